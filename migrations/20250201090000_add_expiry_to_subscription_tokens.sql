@@ -0,0 +1,8 @@
+begin;
+    alter table subscription_tokens
+        add column created_at timestamp not null default (now() at time zone 'utc'),
+        add column expires_at timestamp not null default (now() at time zone 'utc') + interval '1 day',
+        add column used_at timestamp null;
+    alter table subscription_tokens alter column created_at drop default;
+    alter table subscription_tokens alter column expires_at drop default;
+commit;
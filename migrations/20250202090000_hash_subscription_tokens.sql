@@ -0,0 +1,10 @@
+-- Raw tokens of existing rows cannot be hashed without the key of the application, so they are
+-- replaced by their keyed hashes when the application starts (see
+-- SqlxSubscriptionTokenRepository::hash_legacy_tokens). The token column is left nullable until
+-- every environment has been started once after this migration.
+begin;
+    alter table subscription_tokens add column token_hash text null;
+    alter table subscription_tokens drop constraint subscription_tokens_pkey;
+    alter table subscription_tokens alter column token drop not null;
+    alter table subscription_tokens add constraint subscription_tokens_token_hash_key unique (token_hash);
+commit;
@@ -0,0 +1,13 @@
+create table outbox (
+    id uuid primary key,
+    subscriber_id uuid not null,
+    subject text not null,
+    content text not null,
+    created_at timestamp not null,
+    attempts integer not null,
+    next_attempt_at timestamp not null,
+    delivered_at timestamp null,
+    last_error text null
+);
+
+create index outbox_undelivered_next_attempt_at_idx on outbox (next_attempt_at) where delivered_at is null;
@@ -0,0 +1,6 @@
+-- Outbox messages carry both parts of the rendered email. Pending messages written before
+-- templating only have plain text, which is reused as their HTML part.
+alter table outbox rename column content to text_body;
+alter table outbox add column html_body text null;
+update outbox set html_body = text_body;
+alter table outbox alter column html_body set not null;
@@ -0,0 +1,2 @@
+-- Subscribers created before localization keep receiving English emails.
+alter table subscribers add column locale text not null default 'en';
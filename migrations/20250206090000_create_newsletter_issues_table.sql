@@ -0,0 +1,7 @@
+create table newsletter_issues (
+    id uuid primary key,
+    title text not null,
+    html_content text not null,
+    text_content text not null,
+    published_at timestamp not null
+);
@@ -0,0 +1,20 @@
+create table issue_delivery_queue (
+    newsletter_issue_id uuid not null references newsletter_issues (id),
+    subscriber_id uuid not null,
+    attempts integer not null,
+    next_attempt_at timestamp not null,
+    last_error text null,
+    primary key (newsletter_issue_id, subscriber_id)
+);
+
+create index issue_delivery_queue_next_attempt_at_idx on issue_delivery_queue (next_attempt_at);
+
+-- Deliveries that failed permanently or ran out of attempts, kept for inspection
+create table issue_delivery_dead_letters (
+    newsletter_issue_id uuid not null references newsletter_issues (id),
+    subscriber_id uuid not null,
+    attempts integer not null,
+    last_error text null,
+    parked_at timestamp not null,
+    primary key (newsletter_issue_id, subscriber_id)
+);
@@ -0,0 +1,12 @@
+-- The row is claimed before the request is handled, so the response columns stay empty until the
+-- first request completes and its transaction commits
+create table idempotency (
+    idempotency_key text not null,
+    request_path text not null,
+    response_status_code smallint null,
+    response_header_names text[] null,
+    response_header_values bytea[] null,
+    response_body bytea null,
+    created_at timestamp not null,
+    primary key (idempotency_key, request_path)
+);
@@ -0,0 +1,8 @@
+-- Operator accounts allowed to use admin endpoints
+create table users (
+    id uuid not null primary key,
+    username text not null unique,
+    -- PHC string of the Argon2id hash, which carries its own salt and parameters
+    password_hash text not null,
+    created_at timestamp not null
+);
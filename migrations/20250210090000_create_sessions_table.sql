@@ -0,0 +1,11 @@
+-- Browser sessions of the admin UI, looked up by the SHA-256 hash of the cookie value so that a
+-- leaked table does not hand out live sessions
+create table sessions (
+    id_hash text not null primary key,
+    user_id uuid null references users (id) on delete cascade,
+    csrf_token text not null,
+    flash_messages text[] not null,
+    expires_at timestamp not null
+);
+
+create index sessions_expires_at_idx on sessions (expires_at);
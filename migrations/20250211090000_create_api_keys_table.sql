@@ -0,0 +1,11 @@
+-- Keys of machine clients, looked up by the SHA-256 hash of the key presented as a bearer token
+create table api_keys (
+    id uuid not null primary key,
+    client_name text not null,
+    key_hash text not null unique,
+    -- Leading characters of the key, so that operators can tell keys apart without seeing them
+    visible_prefix text not null,
+    scopes text[] not null,
+    created_at timestamp not null,
+    revoked_at timestamp null
+);
@@ -0,0 +1,24 @@
+create table webhook_endpoints (
+    id uuid primary key,
+    url text not null,
+    -- Kept as is rather than hashed, since every delivery is signed with it
+    secret text not null,
+    created_at timestamp not null
+);
+
+-- Doubles as the delivery log, so delivered rows are kept along with their last response
+create table webhook_deliveries (
+    id uuid primary key,
+    endpoint_id uuid not null references webhook_endpoints (id) on delete cascade,
+    event_type text not null,
+    payload text not null,
+    created_at timestamp not null,
+    attempts integer not null,
+    next_attempt_at timestamp not null,
+    delivered_at timestamp null,
+    last_status_code integer null,
+    last_error text null
+);
+
+create index webhook_deliveries_undelivered_next_attempt_at_idx on webhook_deliveries (next_attempt_at) where delivered_at is null;
+create index webhook_deliveries_endpoint_id_idx on webhook_deliveries (endpoint_id, id);
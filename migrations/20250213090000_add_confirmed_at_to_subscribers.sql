@@ -0,0 +1,2 @@
+-- Left empty for subscribers confirmed before the transition was recorded, as its time is unknown
+alter table subscribers add column confirmed_at timestamp null;
@@ -0,0 +1,3 @@
+-- Bumped by every update, so that writers not holding a row lock can tell whether the row has
+-- changed since they read it
+alter table subscribers add column version integer not null default 0;
@@ -0,0 +1,60 @@
+-- Addresses as given by subscribers, while the email column keeps their normalized form
+alter table subscribers add column display_email text null;
+update subscribers set display_email = email;
+alter table subscribers alter column display_email set not null;
+
+-- Addresses differing only by case or surrounding whitespace belong to the same subscriber, so
+-- they are merged before their normalization makes them collide. A confirmed subscriber is kept
+-- over a pending one, and the earliest over later ones.
+create temporary table subscriber_merges as
+select
+    id,
+    first_value(id) over (
+        partition by lower(btrim(email))
+        order by
+            case status when 'Confirmed' then 0 when 'Pending' then 1 else 2 end,
+            subscribed_at,
+            id
+    ) as kept_id,
+    bool_or(status = 'Unsubscribed') over (partition by lower(btrim(email))) as opted_out
+from subscribers;
+
+-- Opting out through any of the addresses is honoured by the subscriber kept
+update subscribers
+set status = 'Unsubscribed', version = version + 1
+from subscriber_merges
+where subscribers.id = subscriber_merges.id
+    and subscriber_merges.id = subscriber_merges.kept_id
+    and subscriber_merges.opted_out
+    and subscribers.status <> 'Unsubscribed';
+
+-- Confirmation links and queued emails of the merged subscribers are dropped along with them, as
+-- is their share of issues being delivered, which the subscriber kept receives in their stead.
+-- Dead letters are left as they are for inspection.
+delete from subscription_tokens
+using subscriber_merges
+where subscription_tokens.subscriber_id = subscriber_merges.id
+    and subscriber_merges.id <> subscriber_merges.kept_id;
+delete from outbox
+using subscriber_merges
+where outbox.subscriber_id = subscriber_merges.id
+    and subscriber_merges.id <> subscriber_merges.kept_id;
+delete from issue_delivery_queue
+using subscriber_merges
+where issue_delivery_queue.subscriber_id = subscriber_merges.id
+    and subscriber_merges.id <> subscriber_merges.kept_id;
+delete from subscribers
+using subscriber_merges
+where subscribers.id = subscriber_merges.id
+    and subscriber_merges.id <> subscriber_merges.kept_id;
+
+drop table subscriber_merges;
+
+-- Addresses stored before normalization differ from their normalized form only by case and
+-- surrounding whitespace, as long as they are plain ASCII. The others are normalized by
+-- SqlxSubscriberRepository::normalize_legacy_emails, as their domains have to be encoded the way
+-- Email::parse does.
+update subscribers set email = lower(btrim(email));
+
+alter table subscribers drop constraint subscribers_email_key;
+create unique index subscribers_email_key on subscribers (email);
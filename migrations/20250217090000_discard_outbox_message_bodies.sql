@@ -0,0 +1,10 @@
+-- Rendered bodies carry confirmation links, so they are discarded once messages are delivered or
+-- dead-lettered. The unsubscribe link announced in headers is kept along with them.
+alter table outbox alter column html_body drop not null;
+alter table outbox alter column text_body drop not null;
+alter table outbox add column unsubscribe_url text null;
+alter table outbox add column dead_lettered_at timestamp null;
+update outbox set html_body = null, text_body = null where delivered_at is not null;
+
+drop index outbox_undelivered_next_attempt_at_idx;
+create index outbox_deliverable_next_attempt_at_idx on outbox (next_attempt_at) where delivered_at is null and dead_lettered_at is null;
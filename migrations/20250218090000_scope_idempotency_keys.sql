@@ -0,0 +1,12 @@
+-- Keys are scoped to the caller presenting them, and a request reusing a key has to match the
+-- fingerprint of the first one. Saved responses are only kept for replaying retries, so the
+-- existing ones, which belong to no caller, are dropped rather than migrated.
+truncate table idempotency;
+
+alter table idempotency drop constraint idempotency_pkey;
+alter table idempotency drop column request_path;
+alter table idempotency add column principal text not null;
+alter table idempotency add column request_fingerprint text not null;
+alter table idempotency add primary key (principal, idempotency_key);
+
+create index idempotency_created_at_idx on idempotency (created_at);
@@ -0,0 +1,16 @@
+-- Raw tokens left by releases storing them have to be hashed with the key of the application
+-- first, by running `task hash-legacy-subscription-tokens` against the database migrated up to
+-- the previous migration. This refuses to run until none is left, so that no pending
+-- confirmation is lost.
+do $$
+begin
+    if exists (select from subscription_tokens where token_hash is null) then
+        raise exception 'Raw subscription tokens are left, run `task hash-legacy-subscription-tokens` first';
+    end if;
+end
+$$;
+
+alter table subscription_tokens drop constraint subscription_tokens_token_hash_key;
+alter table subscription_tokens alter column token_hash set not null;
+alter table subscription_tokens add primary key (token_hash);
+alter table subscription_tokens drop column token;
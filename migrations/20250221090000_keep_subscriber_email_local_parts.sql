@@ -0,0 +1,12 @@
+-- Local parts may be told apart by case by mail servers, so the email column keeps them as given
+-- and only the domain is normalized. Local parts lowercased by the migration normalizing emails
+-- are restored from the addresses as given.
+update subscribers
+set email = regexp_replace(btrim(display_email), '@[^@]*$', '') || substring(email from '@[^@]*$')
+where btrim(display_email) like '%@%'
+    and email like '%@%'
+    and lower(regexp_replace(btrim(display_email), '@[^@]*$', '')) = regexp_replace(email, '@[^@]*$', '');
+
+-- Addresses differing only by the case of their local part still belong to the same subscriber
+drop index subscribers_email_key;
+create unique index subscribers_email_key on subscribers (lower(email));
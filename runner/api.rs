@@ -3,6 +3,7 @@ use std::error::Error;
 use zero2prod::assembly;
 use zero2prod::configuration;
 use zero2prod::interface;
+use zero2prod::newsletter;
 use zero2prod::subscriber;
 use zero2prod::telemetry;
 
@@ -16,7 +17,7 @@ async fn main() -> Result<(), impl Error> {
         .unwrap_or_else(|_| "local".into())
         .try_into()
         .expect("Failed to determine environment");
-    let mut configuration =
+    let configuration =
         configuration::get_configuration(env).expect("Failed to read configuration");
 
     // Set up listener for running this application
@@ -25,22 +26,107 @@ async fn main() -> Result<(), impl Error> {
     // Assemble subscriber aggregate's external dependencies
     let subscriber_database_pool =
         assembly::get_database_pool(&configuration.subscriber.database).await;
+    let unit_of_work = assembly::assemble_unit_of_work(subscriber_database_pool.clone());
     let subscriber_repository =
-        assembly::assemble_subscriber_repository(subscriber_database_pool.clone());
-    let subscription_token_repository =
-        assembly::assemble_subscription_token_repository(subscriber_database_pool.clone());
+        assembly::assemble_subscriber_repository(&configuration.subscriber.concurrency_control);
+    let subscription_token_repository = assembly::assemble_subscription_token_repository(
+        &configuration.subscriber.subscription_token,
+    );
+    let outbox_repository = assembly::assemble_outbox_repository();
 
-    let _ = assembly::assemble_subscription_email_server(&mut configuration.subscriber.email).await;
     let subscription_email_client =
         assembly::assemble_subscription_email_client(&configuration.subscriber.email);
 
+    // Run subscriber aggregate's outbox dispatcher in background
+    tokio::spawn(subscriber::interface::dispatcher::run(
+        unit_of_work.clone(),
+        subscriber_repository.clone(),
+        outbox_repository,
+        subscription_email_client.clone(),
+        assembly::assemble_outbox_retry_policy(&configuration.subscriber.outbox),
+        configuration.subscriber.outbox.batch_size,
+        configuration.subscriber.outbox.interval,
+    ));
+
+    // Run subscriber aggregate's webhook worker in background
+    tokio::spawn(subscriber::interface::webhook_worker::run(
+        unit_of_work.clone(),
+        assembly::assemble_webhook_endpoint_repository(),
+        assembly::assemble_webhook_delivery_repository(),
+        assembly::assemble_webhook_client(&configuration.subscriber.webhook),
+        assembly::assemble_webhook_retry_policy(&configuration.subscriber.webhook),
+        configuration.subscriber.webhook.batch_size,
+        configuration.subscriber.webhook.interval,
+    ));
+
+    // Assemble subscriber aggregate's query executor
+    let subscriber_query_executor = subscriber::domain::service::new_query_executor(
+        unit_of_work.clone(),
+        subscriber_repository.clone(),
+    );
+
     // Assemble subscriber aggregate's command executor
     let subscriber_command_executor = subscriber::domain::service::new_command_executor(
+        unit_of_work,
         subscriber_repository,
         subscription_token_repository,
+        assembly::assemble_event_publisher(&configuration.subscriber, &configuration.application),
+        configuration.subscriber.unsubscribe.key.clone(),
+    );
+
+    // Assemble newsletter aggregate's external dependencies, which share the subscriber database
+    let newsletter_unit_of_work =
+        assembly::assemble_newsletter_unit_of_work(subscriber_database_pool.clone());
+    let newsletter_issue_repository = assembly::assemble_newsletter_issue_repository();
+    let issue_delivery_repository = assembly::assemble_issue_delivery_repository();
+
+    // Run newsletter aggregate's delivery worker in background, sending through the same email
+    // client as subscriptions
+    tokio::spawn(newsletter::interface::worker::run(
+        newsletter_unit_of_work.clone(),
+        newsletter_issue_repository.clone(),
+        issue_delivery_repository.clone(),
+        assembly::assemble_recipient_repository(),
         subscription_email_client,
+        assembly::assemble_issue_delivery_retry_policy(&configuration.newsletter.delivery),
+        assembly::assemble_link_builder(&configuration.application),
+        configuration.subscriber.unsubscribe.key.clone(),
+        configuration.newsletter.delivery.batch_size,
+        configuration.newsletter.delivery.interval,
+    ));
+
+    // Assemble newsletter aggregate's command executor
+    let newsletter_command_executor = newsletter::domain::service::new_command_executor(
+        newsletter_unit_of_work,
+        newsletter_issue_repository,
+        issue_delivery_repository,
+    );
+
+    // Assemble operator authentication, whose users and sessions live in the subscriber database
+    let authenticator = assembly::assemble_authenticator(subscriber_database_pool.clone());
+    let api_key_manager = assembly::assemble_api_key_manager(subscriber_database_pool.clone());
+    let session_store = assembly::assemble_session_store(
+        &configuration.auth.session,
+        subscriber_database_pool.clone(),
+    );
+
+    let webhook_manager = assembly::assemble_webhook_manager(
+        &configuration.subscriber.webhook,
+        subscriber_database_pool.clone(),
     );
 
     // Run this application
-    interface::run(listener, subscriber_command_executor).await
+    interface::run(
+        listener,
+        subscriber_command_executor,
+        subscriber_query_executor,
+        newsletter_command_executor,
+        assembly::assemble_idempotency_store(subscriber_database_pool),
+        authenticator,
+        api_key_manager,
+        session_store,
+        assembly::assemble_session_cookie(&configuration.auth.session),
+        webhook_manager,
+    )
+    .await
 }
@@ -0,0 +1,37 @@
+use secrecy::SecretString;
+use zero2prod::assembly;
+use zero2prod::auth;
+use zero2prod::configuration;
+
+// Registers an operator account: `create-admin <username>`, with the password read from the
+// ADMIN_PASSWORD environment variable so that it stays out of the shell history
+#[tokio::main]
+async fn main() -> Result<(), anyhow::Error> {
+    let username = std::env::args()
+        .nth(1)
+        .ok_or_else(|| anyhow::anyhow!("Usage: create-admin <username>"))?;
+    let password = std::env::var("ADMIN_PASSWORD")
+        .map(SecretString::from)
+        .map_err(|_| anyhow::anyhow!("ADMIN_PASSWORD must be set"))?;
+
+    // Read configuration
+    let env: configuration::Environment = std::env::var("ENVIRONMENT")
+        .unwrap_or_else(|_| "local".into())
+        .try_into()
+        .expect("Failed to determine environment");
+    let configuration =
+        configuration::get_configuration(env).expect("Failed to read configuration");
+
+    // Users live in the subscriber database alongside every other aggregate
+    let pool = assembly::get_database_pool(&configuration.subscriber.database).await;
+    let user_id = auth::domain::service::register_user(
+        &username,
+        password,
+        assembly::assemble_auth_unit_of_work(pool),
+        assembly::assemble_user_repository(),
+    )
+    .await?;
+
+    println!("Registered {} as {}", username, user_id);
+    Ok(())
+}
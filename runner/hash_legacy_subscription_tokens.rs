@@ -0,0 +1,31 @@
+use zero2prod::assembly;
+use zero2prod::configuration;
+use zero2prod::subscriber::domain::infrastructure::UnitOfWork;
+use zero2prod::subscriber::infrastructure::repository::SqlxSubscriptionTokenRepository;
+
+// Replaces raw subscription tokens left by releases storing them with their keyed hashes, so that
+// pending confirmations survive the migration dropping the raw tokens. Run it once after migrating
+// up to that migration, which refuses to run before.
+#[tokio::main]
+async fn main() -> Result<(), anyhow::Error> {
+    // Read configuration
+    let env: configuration::Environment = std::env::var("ENVIRONMENT")
+        .unwrap_or_else(|_| "local".into())
+        .try_into()
+        .expect("Failed to determine environment");
+    let configuration =
+        configuration::get_configuration(env).expect("Failed to read configuration");
+
+    let pool = assembly::get_database_pool(&configuration.subscriber.database).await;
+    let unit_of_work = assembly::assemble_unit_of_work(pool);
+    let mut transaction = unit_of_work.begin().await?;
+    let count = SqlxSubscriptionTokenRepository::new(
+        configuration.subscriber.subscription_token.key.clone(),
+    )
+    .hash_legacy_tokens(&mut transaction)
+    .await?;
+    unit_of_work.commit(transaction).await?;
+
+    println!("Hashed {} legacy subscription tokens", count);
+    Ok(())
+}
@@ -0,0 +1,29 @@
+use zero2prod::assembly;
+use zero2prod::configuration;
+use zero2prod::subscriber::domain::infrastructure::UnitOfWork;
+use zero2prod::subscriber::infrastructure::repository::SqlxSubscriberRepository;
+
+// Encodes the internationalized domains of addresses stored before emails were normalized, merging
+// the subscribers this reveals to share an address. Run it once after migrating, as the merge
+// deletes subscribers and is not to happen whenever the service starts.
+#[tokio::main]
+async fn main() -> Result<(), anyhow::Error> {
+    // Read configuration
+    let env: configuration::Environment = std::env::var("ENVIRONMENT")
+        .unwrap_or_else(|_| "local".into())
+        .try_into()
+        .expect("Failed to determine environment");
+    let configuration =
+        configuration::get_configuration(env).expect("Failed to read configuration");
+
+    let pool = assembly::get_database_pool(&configuration.subscriber.database).await;
+    let unit_of_work = assembly::assemble_unit_of_work(pool);
+    let mut transaction = unit_of_work.begin().await?;
+    let count = SqlxSubscriberRepository::default()
+        .normalize_legacy_emails(&mut transaction)
+        .await?;
+    unit_of_work.commit(transaction).await?;
+
+    println!("Normalized {} legacy subscriber emails", count);
+    Ok(())
+}
@@ -1,27 +1,86 @@
 use std::net::SocketAddrV4;
+use std::time::Duration;
 
-use reqwest::StatusCode;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::transport::smtp::client::Tls;
+use lettre::transport::smtp::client::TlsParameters;
+use lettre::AsyncSmtpTransport;
+use lettre::Tokio1Executor;
 use secrecy::ExposeSecret;
 use secrecy::SecretString;
 use sqlx::postgres::PgPoolOptions;
 use sqlx::Pool;
 use sqlx::Postgres;
 use tokio::net::TcpListener;
-use wiremock::matchers::method;
-use wiremock::matchers::path;
-use wiremock::Mock;
-use wiremock::MockServer;
-use wiremock::ResponseTemplate;
 
+use crate::auth::domain::infrastructure::ApiKeyRepository;
+use crate::auth::domain::infrastructure::UnitOfWork as AuthUnitOfWorkTrait;
+use crate::auth::domain::infrastructure::UserRepository;
+use crate::auth::domain::service::new_api_key_manager;
+use crate::auth::domain::service::new_authenticator;
+use crate::auth::domain::service::ApiKeyManager;
+use crate::auth::domain::service::AuthenticatorFunction;
+use crate::auth::infrastructure::repository::SqlxApiKeyRepository;
+use crate::auth::infrastructure::repository::SqlxUserRepository;
+use crate::auth::infrastructure::session_store::ConfiguredSessionStore;
+use crate::auth::infrastructure::session_store::InMemorySessionStore;
+use crate::auth::infrastructure::session_store::PostgresSessionStore;
+use crate::auth::interface::session::SessionCookie;
+use crate::common::email_client::ConfiguredEmailClient;
+use crate::common::email_client::FileEmailClient;
+use crate::common::email_client::InMemoryEmailClient;
+use crate::common::email_client::InMemoryMailbox;
+use crate::common::email_client::PostmarkEmailClient;
+use crate::common::email_client::SmtpEmailClient;
+use crate::common::email_client::StdoutEmailClient;
+use crate::common::link::LinkBuilder;
+use crate::common::retry::RetryPolicy;
+use crate::common::unit_of_work::SqlxTransaction;
+use crate::common::unit_of_work::SqlxUnitOfWork;
 use crate::configuration::ApplicationConfiguration;
+use crate::configuration::ConcurrencyControlConfiguration;
 use crate::configuration::DatabaseConfiguration;
+use crate::configuration::EmailBackendConfiguration;
 use crate::configuration::EmailConfiguration;
-use crate::subscriber::domain::infrastructure::EmailClient;
+use crate::configuration::IssueDeliveryConfiguration;
+use crate::configuration::OutboxConfiguration;
+use crate::configuration::SessionConfiguration;
+use crate::configuration::SessionStoreConfiguration;
+use crate::configuration::SmtpConfiguration;
+use crate::configuration::SmtpTls;
+use crate::configuration::SubscriberConfiguration;
+use crate::configuration::SubscriptionTokenConfiguration;
+use crate::configuration::WebhookConfiguration;
+use crate::idempotency::SqlxIdempotencyStore;
+use crate::newsletter::domain::infrastructure::IssueDeliveryRepository;
+use crate::newsletter::domain::infrastructure::NewsletterIssueRepository;
+use crate::newsletter::domain::infrastructure::RecipientRepository;
+use crate::newsletter::domain::infrastructure::UnitOfWork as NewsletterUnitOfWorkTrait;
+use crate::newsletter::infrastructure::repository::SqlxIssueDeliveryRepository;
+use crate::newsletter::infrastructure::repository::SqlxNewsletterIssueRepository;
+use crate::newsletter::infrastructure::repository::SqlxRecipientRepository;
+use crate::subscriber::domain::infrastructure::EmailRenderer;
+use crate::subscriber::domain::infrastructure::EventPublisher;
+use crate::subscriber::domain::infrastructure::OutboxRepository;
 use crate::subscriber::domain::infrastructure::SubscriberRepository;
 use crate::subscriber::domain::infrastructure::SubscriptionTokenRepository;
-use crate::subscriber::infrastructure::email_client::FakeEmailClient;
+use crate::subscriber::domain::infrastructure::UnitOfWork;
+use crate::subscriber::domain::infrastructure::WebhookClient;
+use crate::subscriber::domain::infrastructure::WebhookDeliveryRepository;
+use crate::subscriber::domain::infrastructure::WebhookEndpointRepository;
+use crate::subscriber::domain::service::new_webhook_manager;
+use crate::subscriber::domain::service::ConfirmationEmailPublisher;
+use crate::subscriber::domain::service::WebhookEventPublisher;
+use crate::subscriber::domain::service::WebhookManager;
+use crate::subscriber::infrastructure::email_renderer::MinijinjaEmailRenderer;
+use crate::subscriber::infrastructure::event_publisher::TracingEventPublisher;
+use crate::subscriber::infrastructure::repository::ConcurrencyControl;
+use crate::subscriber::infrastructure::repository::SqlxOutboxRepository;
 use crate::subscriber::infrastructure::repository::SqlxSubscriberRepository;
 use crate::subscriber::infrastructure::repository::SqlxSubscriptionTokenRepository;
+use crate::subscriber::infrastructure::repository::SqlxWebhookDeliveryRepository;
+use crate::subscriber::infrastructure::repository::SqlxWebhookEndpointRepository;
+use crate::subscriber::infrastructure::webhook_client::ReqwestWebhookClient;
 
 pub async fn get_application_listener(c: &ApplicationConfiguration) -> TcpListener {
     TcpListener::bind(SocketAddrV4::new(
@@ -53,35 +112,228 @@ pub async fn get_database_pool(c: &DatabaseConfiguration) -> Pool<Postgres> {
         .expect("Failed to create database connection pool")
 }
 
-pub fn assemble_subscriber_repository(pool: Pool<Postgres>) -> impl SubscriberRepository {
-    SqlxSubscriberRepository::new(pool)
+pub fn assemble_unit_of_work(
+    pool: Pool<Postgres>,
+) -> impl UnitOfWork<Transaction = SqlxTransaction> {
+    SqlxUnitOfWork::new(pool)
+}
+
+pub fn assemble_subscriber_repository(
+    c: &ConcurrencyControlConfiguration,
+) -> impl SubscriberRepository<Transaction = SqlxTransaction> {
+    SqlxSubscriberRepository::new(match c {
+        ConcurrencyControlConfiguration::Pessimistic => ConcurrencyControl::Pessimistic,
+        ConcurrencyControlConfiguration::Optimistic => ConcurrencyControl::Optimistic,
+    })
+}
+
+pub fn assemble_outbox_repository() -> impl OutboxRepository<Transaction = SqlxTransaction> {
+    SqlxOutboxRepository::new()
+}
+
+pub fn assemble_event_publisher(
+    c: &SubscriberConfiguration,
+    application: &ApplicationConfiguration,
+) -> impl EventPublisher<Transaction = SqlxTransaction> {
+    (
+        TracingEventPublisher::new(),
+        (
+            WebhookEventPublisher::new(
+                assemble_subscriber_repository(&c.concurrency_control),
+                assemble_webhook_endpoint_repository(),
+                assemble_webhook_delivery_repository(),
+            ),
+            ConfirmationEmailPublisher::new(
+                assemble_subscriber_repository(&c.concurrency_control),
+                assemble_subscription_token_repository(&c.subscription_token),
+                assemble_outbox_repository(),
+                assemble_subscription_email_renderer(&c.email),
+                assemble_link_builder(application),
+                c.unsubscribe.key.clone(),
+            ),
+        ),
+    )
+}
+
+pub fn assemble_webhook_endpoint_repository(
+) -> impl WebhookEndpointRepository<Transaction = SqlxTransaction> {
+    SqlxWebhookEndpointRepository::new()
+}
+
+pub fn assemble_webhook_delivery_repository(
+) -> impl WebhookDeliveryRepository<Transaction = SqlxTransaction> {
+    SqlxWebhookDeliveryRepository::new()
+}
+
+pub fn assemble_webhook_client(c: &WebhookConfiguration) -> impl WebhookClient {
+    ReqwestWebhookClient::new(c.timeout, c.allow_private_targets)
+}
+
+pub fn assemble_webhook_retry_policy(c: &WebhookConfiguration) -> RetryPolicy {
+    RetryPolicy::new(
+        c.retry.max_attempts,
+        c.retry.initial_backoff,
+        c.retry.max_backoff,
+    )
+}
+
+pub fn assemble_webhook_manager(
+    c: &WebhookConfiguration,
+    pool: Pool<Postgres>,
+) -> impl WebhookManager {
+    new_webhook_manager(
+        assemble_unit_of_work(pool),
+        assemble_webhook_endpoint_repository(),
+        assemble_webhook_delivery_repository(),
+        c.allow_private_targets,
+    )
+}
+
+pub fn assemble_outbox_retry_policy(c: &OutboxConfiguration) -> RetryPolicy {
+    RetryPolicy::new(
+        c.retry.max_attempts,
+        c.retry.initial_backoff,
+        c.retry.max_backoff,
+    )
 }
 
 pub fn assemble_subscription_token_repository(
+    c: &SubscriptionTokenConfiguration,
+) -> impl SubscriptionTokenRepository<Transaction = SqlxTransaction> {
+    SqlxSubscriptionTokenRepository::new(c.key.clone())
+}
+
+pub fn assemble_subscription_email_client(c: &EmailConfiguration) -> ConfiguredEmailClient {
+    match &c.backend {
+        EmailBackendConfiguration::HttpApi(backend) => {
+            ConfiguredEmailClient::HttpApi(PostmarkEmailClient::new(
+                reqwest::Client::new(),
+                backend.url.clone(),
+                c.client.sender.clone(),
+                backend.token.clone(),
+                c.client.timeout,
+            ))
+        }
+        EmailBackendConfiguration::Smtp(backend) => {
+            ConfiguredEmailClient::Smtp(SmtpEmailClient::new(
+                assemble_smtp_transport(backend, c.client.timeout),
+                c.client.sender.clone(),
+            ))
+        }
+        EmailBackendConfiguration::Stdout => {
+            ConfiguredEmailClient::Stdout(StdoutEmailClient::new(c.client.sender.clone()))
+        }
+        EmailBackendConfiguration::File(backend) => ConfiguredEmailClient::File(
+            FileEmailClient::new(backend.directory.clone(), c.client.sender.clone()),
+        ),
+        EmailBackendConfiguration::InMemory => ConfiguredEmailClient::InMemory(
+            InMemoryEmailClient::new(InMemoryMailbox::new(), c.client.sender.clone()),
+        ),
+    }
+}
+
+pub fn assemble_subscription_email_renderer(c: &EmailConfiguration) -> impl EmailRenderer {
+    MinijinjaEmailRenderer::new(c.template.sender_name.clone())
+}
+
+pub fn assemble_link_builder(c: &ApplicationConfiguration) -> LinkBuilder {
+    LinkBuilder::new(c.base_url.clone())
+}
+
+pub fn assemble_newsletter_unit_of_work(
     pool: Pool<Postgres>,
-) -> impl SubscriptionTokenRepository {
-    SqlxSubscriptionTokenRepository::new(pool)
-}
-
-pub async fn assemble_subscription_email_server(
-    c: &mut EmailConfiguration,
-) -> wiremock::MockServer {
-    let server = MockServer::start().await;
-    Mock::given(method("POST"))
-        .and(path("/email"))
-        .respond_with(ResponseTemplate::new(StatusCode::OK))
-        .mount(&server)
-        .await;
-    c.server.url = server.uri();
-    server
-}
-
-pub fn assemble_subscription_email_client(c: &EmailConfiguration) -> impl EmailClient {
-    FakeEmailClient::new(
-        reqwest::Client::new(),
-        c.server.url.clone(),
-        c.client.sender.clone(),
-        c.server.token.clone(),
-        c.client.timeout,
+) -> impl NewsletterUnitOfWorkTrait<Transaction = SqlxTransaction> {
+    SqlxUnitOfWork::new(pool)
+}
+
+pub fn assemble_newsletter_issue_repository(
+) -> impl NewsletterIssueRepository<Transaction = SqlxTransaction> {
+    SqlxNewsletterIssueRepository::new()
+}
+
+pub fn assemble_issue_delivery_repository(
+) -> impl IssueDeliveryRepository<Transaction = SqlxTransaction> {
+    SqlxIssueDeliveryRepository::new()
+}
+
+pub fn assemble_issue_delivery_retry_policy(c: &IssueDeliveryConfiguration) -> RetryPolicy {
+    RetryPolicy::new(
+        c.retry.max_attempts,
+        c.retry.initial_backoff,
+        c.retry.max_backoff,
     )
 }
+
+pub fn assemble_recipient_repository() -> impl RecipientRepository<Transaction = SqlxTransaction> {
+    SqlxRecipientRepository::new()
+}
+
+pub fn assemble_auth_unit_of_work(
+    pool: Pool<Postgres>,
+) -> impl AuthUnitOfWorkTrait<Transaction = SqlxTransaction> {
+    SqlxUnitOfWork::new(pool)
+}
+
+pub fn assemble_user_repository() -> impl UserRepository<Transaction = SqlxTransaction> {
+    SqlxUserRepository::new()
+}
+
+pub fn assemble_authenticator(pool: Pool<Postgres>) -> AuthenticatorFunction {
+    new_authenticator(assemble_auth_unit_of_work(pool), assemble_user_repository())
+}
+
+pub fn assemble_api_key_repository() -> impl ApiKeyRepository<Transaction = SqlxTransaction> {
+    SqlxApiKeyRepository::new()
+}
+
+pub fn assemble_api_key_manager(pool: Pool<Postgres>) -> impl ApiKeyManager {
+    new_api_key_manager(
+        assemble_auth_unit_of_work(pool),
+        assemble_api_key_repository(),
+    )
+}
+
+pub fn assemble_session_store(
+    c: &SessionConfiguration,
+    pool: Pool<Postgres>,
+) -> ConfiguredSessionStore {
+    match c.store {
+        SessionStoreConfiguration::Postgres => {
+            ConfiguredSessionStore::Postgres(PostgresSessionStore::new(pool))
+        }
+        SessionStoreConfiguration::InMemory => {
+            ConfiguredSessionStore::InMemory(InMemorySessionStore::new())
+        }
+    }
+}
+
+// Cookies are only sent back over HTTPS once the service is exposed through it
+pub fn assemble_session_cookie(c: &SessionConfiguration) -> SessionCookie {
+    SessionCookie::new(c.ttl, c.secure_cookie)
+}
+
+pub fn assemble_idempotency_store(pool: Pool<Postgres>) -> SqlxIdempotencyStore {
+    SqlxIdempotencyStore::new(pool)
+}
+
+pub fn assemble_smtp_transport(
+    c: &SmtpConfiguration,
+    timeout: Duration,
+) -> AsyncSmtpTransport<Tokio1Executor> {
+    let tls = match c.tls {
+        SmtpTls::StartTls => Tls::Required(
+            TlsParameters::new(c.host.clone()).expect("Failed to set up TLS for SMTP"),
+        ),
+        SmtpTls::None => Tls::None,
+    };
+
+    AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(c.host.as_str())
+        .port(c.port)
+        .tls(tls)
+        .credentials(Credentials::new(
+            c.username.clone(),
+            c.password.expose_secret().into(),
+        ))
+        .timeout(Some(timeout))
+        .build()
+}
@@ -0,0 +1,15 @@
+use uuid::Uuid;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("{0}")]
+    InvariantViolated(String),
+    #[error("Invalid username or password.")]
+    InvalidCredentials,
+    #[error("Failed to find the API key.")]
+    ApiKeyNotFound(Uuid),
+    #[error("Failed to operate on repository.")]
+    RepositoryOperationFailed(#[source] anyhow::Error),
+    #[error("Failed unexpectedly.")]
+    FailedUnexpectedly(#[source] anyhow::Error),
+}
@@ -0,0 +1,63 @@
+use uuid::Uuid;
+
+use crate::auth::domain::error::Error;
+use crate::auth::domain::model::ApiKey;
+use crate::auth::domain::model::Session;
+use crate::auth::domain::model::User;
+
+// Repositories sharing the same transaction type can participate in a single unit of work,
+// so that changes across aggregates are committed all-or-nothing
+#[async_trait::async_trait]
+pub trait UnitOfWork: Send + Sync + Clone + 'static {
+    type Transaction: Send;
+
+    async fn begin(&self) -> Result<Self::Transaction, Error>;
+    async fn commit(&self, transaction: Self::Transaction) -> Result<(), Error>;
+}
+
+#[async_trait::async_trait]
+pub trait UserRepository: Send + Sync + Clone + 'static {
+    type Transaction: Send;
+
+    async fn save(&self, transaction: &mut Self::Transaction, user: &User) -> Result<(), Error>;
+    async fn find_by_username(
+        &self,
+        transaction: &mut Self::Transaction,
+        username: &str,
+    ) -> Result<Option<User>, Error>;
+}
+
+#[async_trait::async_trait]
+pub trait ApiKeyRepository: Send + Sync + Clone + 'static {
+    type Transaction: Send;
+
+    async fn save(
+        &self,
+        transaction: &mut Self::Transaction,
+        api_key: &ApiKey,
+    ) -> Result<(), Error>;
+    async fn find_all(&self, transaction: &mut Self::Transaction) -> Result<Vec<ApiKey>, Error>;
+    async fn find_by_key_hash(
+        &self,
+        transaction: &mut Self::Transaction,
+        key_hash: &str,
+    ) -> Result<Option<ApiKey>, Error>;
+    async fn modify_by_id<F>(
+        &self,
+        transaction: &mut Self::Transaction,
+        id: &Uuid,
+        modifier: F,
+    ) -> Result<(), Error>
+    where
+        F: FnOnce(ApiKey) -> ApiKey + Send + Sync;
+}
+
+// Sessions are short-lived and written on most browser requests, so they are kept out of units of
+// work and the store can live outside of the database
+#[async_trait::async_trait]
+pub trait SessionStore: Send + Sync + 'static {
+    // Expired sessions are treated as missing
+    async fn load(&self, id: &str) -> Result<Option<Session>, Error>;
+    async fn save(&self, session: &Session) -> Result<(), Error>;
+    async fn delete(&self, id: &str) -> Result<(), Error>;
+}
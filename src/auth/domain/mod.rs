@@ -0,0 +1,4 @@
+pub mod error;
+pub mod infrastructure;
+pub mod model;
+pub mod service;
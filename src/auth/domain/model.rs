@@ -0,0 +1,401 @@
+use std::time::Duration;
+
+use chrono::DateTime;
+use chrono::Utc;
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use secrecy::SecretString;
+use sha2::Digest;
+use sha2::Sha256;
+use strum::AsRefStr;
+use strum::EnumString;
+use subtle::ConstantTimeEq;
+use uuid::Uuid;
+
+use crate::auth::domain::error::Error;
+
+const MAX_USERNAME_LENGTH: usize = 256;
+const SESSION_TOKEN_LENGTH: usize = 32;
+const API_KEY_PREFIX: &str = "z2p_";
+const API_KEY_SECRET_LENGTH: usize = 40;
+// Long enough to tell keys apart in listings, far too short to guess the rest of the key
+const API_KEY_VISIBLE_LENGTH: usize = 12;
+
+// Debug output of the password is redacted by `SecretString`, so credentials are safe to carry
+// through instrumented functions
+#[derive(Clone, Debug)]
+pub struct Credentials {
+    username: String,
+    password: SecretString,
+}
+
+impl Credentials {
+    pub fn new(username: String, password: SecretString) -> Self {
+        Self { username, password }
+    }
+
+    pub fn username(&self) -> &str {
+        &self.username
+    }
+
+    pub fn password(&self) -> &SecretString {
+        &self.password
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct User {
+    id: Uuid,
+    username: String,
+    password_hash: SecretString,
+    created_at: DateTime<Utc>,
+}
+
+impl User {
+    pub(crate) fn new(
+        id: Uuid,
+        username: String,
+        password_hash: SecretString,
+        created_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id,
+            username,
+            password_hash,
+            created_at,
+        }
+    }
+
+    pub fn create(username: &str, password_hash: SecretString) -> Result<Self, Error> {
+        let username = username.trim();
+        if username.is_empty() {
+            return Err(Error::InvariantViolated("Username cannot be empty".into()));
+        }
+
+        if username.chars().count() > MAX_USERNAME_LENGTH {
+            return Err(Error::InvariantViolated(format!(
+                "Username cannot be longer than {}",
+                MAX_USERNAME_LENGTH
+            )));
+        }
+
+        Ok(Self {
+            id: Uuid::now_v7(),
+            username: username.into(),
+            password_hash,
+            created_at: Utc::now(),
+        })
+    }
+
+    pub fn id(&self) -> &Uuid {
+        &self.id
+    }
+
+    pub fn username(&self) -> &str {
+        &self.username
+    }
+
+    pub fn password_hash(&self) -> &SecretString {
+        &self.password_hash
+    }
+
+    pub fn created_at(&self) -> &DateTime<Utc> {
+        &self.created_at
+    }
+}
+
+// Browser session, started anonymously so that the login form can carry a CSRF token and failed
+// logins can leave flash messages behind
+#[derive(Clone, Debug)]
+pub struct Session {
+    id: SecretString,
+    user_id: Option<Uuid>,
+    csrf_token: String,
+    flash_messages: Vec<String>,
+    expires_at: DateTime<Utc>,
+}
+
+impl Session {
+    pub(crate) fn new(
+        id: SecretString,
+        user_id: Option<Uuid>,
+        csrf_token: String,
+        flash_messages: Vec<String>,
+        expires_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id,
+            user_id,
+            csrf_token,
+            flash_messages,
+            expires_at,
+        }
+    }
+
+    pub fn start(ttl: Duration) -> Self {
+        Self {
+            id: SecretString::from(generate_token(SESSION_TOKEN_LENGTH)),
+            user_id: None,
+            csrf_token: generate_token(SESSION_TOKEN_LENGTH),
+            flash_messages: Vec::new(),
+            expires_at: Utc::now() + ttl,
+        }
+    }
+
+    // Identifier and CSRF token are replaced on login, so that an identifier planted before
+    // logging in cannot be used to ride the authenticated session
+    pub fn log_in(&self, user_id: Uuid, ttl: Duration) -> Self {
+        Self {
+            user_id: Some(user_id),
+            flash_messages: self.flash_messages.clone(),
+            ..Self::start(ttl)
+        }
+    }
+
+    pub fn verify_csrf_token(&self, csrf_token: &str) -> bool {
+        self.csrf_token
+            .as_bytes()
+            .ct_eq(csrf_token.as_bytes())
+            .into()
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.expires_at <= Utc::now()
+    }
+
+    pub fn add_flash_message(&mut self, message: String) {
+        self.flash_messages.push(message);
+    }
+
+    // Flash messages are shown once, so reading them removes them from the session
+    pub fn take_flash_messages(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.flash_messages)
+    }
+
+    pub fn id(&self) -> &SecretString {
+        &self.id
+    }
+
+    pub fn user_id(&self) -> Option<&Uuid> {
+        self.user_id.as_ref()
+    }
+
+    pub fn csrf_token(&self) -> &str {
+        &self.csrf_token
+    }
+
+    pub fn flash_messages(&self) -> &[String] {
+        &self.flash_messages
+    }
+
+    pub fn expires_at(&self) -> &DateTime<Utc> {
+        &self.expires_at
+    }
+}
+
+// Permission granted to an API key, named as <resource>:<action>
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, EnumString, AsRefStr)]
+pub enum Scope {
+    #[strum(serialize = "subscribers:read")]
+    SubscribersRead,
+    #[strum(serialize = "subscribers:write")]
+    SubscribersWrite,
+    #[strum(serialize = "newsletters:publish")]
+    NewslettersPublish,
+}
+
+// Key of a machine client. Only the hash of the key is kept, so the key itself is shown once
+// when it is minted and can never be recovered afterwards.
+#[derive(Clone, Debug)]
+pub struct ApiKey {
+    id: Uuid,
+    client_name: String,
+    key_hash: String,
+    visible_prefix: String,
+    scopes: Vec<Scope>,
+    created_at: DateTime<Utc>,
+    revoked_at: Option<DateTime<Utc>>,
+}
+
+impl ApiKey {
+    pub(crate) fn new(
+        id: Uuid,
+        client_name: String,
+        key_hash: String,
+        visible_prefix: String,
+        scopes: Vec<Scope>,
+        created_at: DateTime<Utc>,
+        revoked_at: Option<DateTime<Utc>>,
+    ) -> Self {
+        Self {
+            id,
+            client_name,
+            key_hash,
+            visible_prefix,
+            scopes,
+            created_at,
+            revoked_at,
+        }
+    }
+
+    pub fn mint(client_name: &str, scopes: &[Scope]) -> Result<(Self, SecretString), Error> {
+        let client_name = client_name.trim();
+        if client_name.is_empty() {
+            return Err(Error::InvariantViolated(
+                "Client name cannot be empty".into(),
+            ));
+        }
+
+        if scopes.is_empty() {
+            return Err(Error::InvariantViolated(
+                "API key must be granted at least one scope".into(),
+            ));
+        }
+
+        let mut scopes = scopes.to_vec();
+        scopes.sort_by_key(|scope| *scope as u8);
+        scopes.dedup();
+
+        let key = format!(
+            "{}{}",
+            API_KEY_PREFIX,
+            generate_token(API_KEY_SECRET_LENGTH)
+        );
+        let api_key = Self {
+            id: Uuid::now_v7(),
+            client_name: client_name.into(),
+            key_hash: Self::hash(&key),
+            visible_prefix: key[..API_KEY_VISIBLE_LENGTH].into(),
+            scopes,
+            created_at: Utc::now(),
+            revoked_at: None,
+        };
+
+        Ok((api_key, SecretString::from(key)))
+    }
+
+    // Keys are long random strings, so a fast hash is enough to make a leaked table useless
+    pub fn hash(key: &str) -> String {
+        hex::encode(Sha256::digest(key.as_bytes()))
+    }
+
+    pub fn revoke(&mut self) {
+        if self.revoked_at.is_none() {
+            self.revoked_at = Some(Utc::now());
+        }
+    }
+
+    pub fn is_revoked(&self) -> bool {
+        self.revoked_at.is_some()
+    }
+
+    pub fn has_scope(&self, scope: Scope) -> bool {
+        self.scopes.contains(&scope)
+    }
+
+    pub fn id(&self) -> &Uuid {
+        &self.id
+    }
+
+    pub fn client_name(&self) -> &str {
+        &self.client_name
+    }
+
+    pub fn key_hash(&self) -> &str {
+        &self.key_hash
+    }
+
+    pub fn visible_prefix(&self) -> &str {
+        &self.visible_prefix
+    }
+
+    pub fn scopes(&self) -> &[Scope] {
+        &self.scopes
+    }
+
+    pub fn created_at(&self) -> &DateTime<Utc> {
+        &self.created_at
+    }
+
+    pub fn revoked_at(&self) -> Option<&DateTime<Utc>> {
+        self.revoked_at.as_ref()
+    }
+}
+
+// ThreadRng is a cryptographically secure generator, unlike time-ordered UUIDs
+fn generate_token(length: usize) -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(length)
+        .map(char::from)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use secrecy::ExposeSecret;
+
+    use super::*;
+
+    const TTL: Duration = Duration::from_secs(60);
+
+    #[test]
+    fn logging_in_rotates_session_id_and_csrf_token() {
+        let anonymous = Session::start(TTL);
+        let user_id = Uuid::now_v7();
+
+        let logged_in = anonymous.log_in(user_id, TTL);
+
+        assert_ne!(
+            logged_in.id().expose_secret(),
+            anonymous.id().expose_secret()
+        );
+        assert_ne!(logged_in.csrf_token(), anonymous.csrf_token());
+        assert_eq!(logged_in.user_id(), Some(&user_id));
+    }
+
+    #[test]
+    fn minted_api_key_is_kept_only_as_hash() {
+        let (api_key, key) = ApiKey::mint("billing", &[Scope::SubscribersRead]).unwrap();
+
+        assert!(key.expose_secret().starts_with(api_key.visible_prefix()));
+        assert_eq!(api_key.key_hash(), ApiKey::hash(key.expose_secret()));
+        assert_ne!(api_key.key_hash(), key.expose_secret());
+    }
+
+    #[test]
+    fn api_key_without_scopes_cannot_be_minted() {
+        assert!(matches!(
+            ApiKey::mint("billing", &[]),
+            Err(Error::InvariantViolated(_))
+        ));
+    }
+
+    #[rstest::rstest]
+    #[case("subscribers:read", Some(Scope::SubscribersRead))]
+    #[case("subscribers:write", Some(Scope::SubscribersWrite))]
+    #[case("newsletters:publish", Some(Scope::NewslettersPublish))]
+    #[case("subscribers:delete", None)]
+    fn scope_is_parsed_from_its_name(#[case] name: &str, #[case] expected: Option<Scope>) {
+        assert_eq!(name.parse::<Scope>().ok(), expected);
+    }
+
+    #[test]
+    fn flash_messages_are_removed_once_taken() {
+        let mut session = Session::start(TTL);
+        session.add_flash_message("Authentication failed".into());
+
+        assert_eq!(session.take_flash_messages(), vec!["Authentication failed"]);
+        assert!(session.take_flash_messages().is_empty());
+    }
+
+    #[rstest::rstest]
+    #[case("")]
+    #[case("not-the-token")]
+    fn csrf_token_other_than_issued_one_is_rejected(#[case] csrf_token: &str) {
+        let session = Session::start(TTL);
+
+        assert!(!session.verify_csrf_token(csrf_token));
+        assert!(session.verify_csrf_token(session.csrf_token()));
+    }
+}
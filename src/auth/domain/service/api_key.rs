@@ -0,0 +1,100 @@
+use secrecy::ExposeSecret;
+use secrecy::SecretString;
+use uuid::Uuid;
+
+use crate::auth::domain::error::Error;
+use crate::auth::domain::infrastructure::ApiKeyRepository;
+use crate::auth::domain::infrastructure::UnitOfWork;
+use crate::auth::domain::model::ApiKey;
+use crate::auth::domain::model::Scope;
+
+#[async_trait::async_trait]
+pub trait ApiKeyManager: Send + Sync + 'static {
+    // Returns the key itself along with what is stored, as it cannot be recovered afterwards
+    async fn mint(
+        &self,
+        client_name: &str,
+        scopes: &[Scope],
+    ) -> Result<(ApiKey, SecretString), Error>;
+    async fn list(&self) -> Result<Vec<ApiKey>, Error>;
+    async fn revoke(&self, id: &Uuid) -> Result<(), Error>;
+    // Fails with invalid credentials for unknown and revoked keys alike
+    async fn verify(&self, key: &SecretString) -> Result<ApiKey, Error>;
+}
+
+#[derive(Clone)]
+pub struct ApiKeyService<U, R> {
+    unit_of_work: U,
+    api_key_repository: R,
+}
+
+pub fn new_api_key_manager<U, R>(unit_of_work: U, api_key_repository: R) -> ApiKeyService<U, R>
+where
+    U: UnitOfWork,
+    R: ApiKeyRepository<Transaction = U::Transaction>,
+{
+    ApiKeyService {
+        unit_of_work,
+        api_key_repository,
+    }
+}
+
+#[async_trait::async_trait]
+impl<U, R> ApiKeyManager for ApiKeyService<U, R>
+where
+    U: UnitOfWork,
+    R: ApiKeyRepository<Transaction = U::Transaction>,
+{
+    #[tracing::instrument(name = "Minting API key", skip_all, fields(client_name = %client_name))]
+    async fn mint(
+        &self,
+        client_name: &str,
+        scopes: &[Scope],
+    ) -> Result<(ApiKey, SecretString), Error> {
+        let (api_key, key) = ApiKey::mint(client_name, scopes)?;
+
+        let mut transaction = self.unit_of_work.begin().await?;
+        self.api_key_repository
+            .save(&mut transaction, &api_key)
+            .await?;
+        self.unit_of_work.commit(transaction).await?;
+
+        Ok((api_key, key))
+    }
+
+    #[tracing::instrument(name = "Listing API keys", skip_all)]
+    async fn list(&self) -> Result<Vec<ApiKey>, Error> {
+        let mut transaction = self.unit_of_work.begin().await?;
+        let api_keys = self.api_key_repository.find_all(&mut transaction).await?;
+        self.unit_of_work.commit(transaction).await?;
+
+        Ok(api_keys)
+    }
+
+    #[tracing::instrument(name = "Revoking API key", skip_all, fields(id = ?id))]
+    async fn revoke(&self, id: &Uuid) -> Result<(), Error> {
+        let mut transaction = self.unit_of_work.begin().await?;
+        self.api_key_repository
+            .modify_by_id(&mut transaction, id, |mut api_key| {
+                api_key.revoke();
+                api_key
+            })
+            .await?;
+        self.unit_of_work.commit(transaction).await
+    }
+
+    #[tracing::instrument(name = "Verifying API key", skip_all)]
+    async fn verify(&self, key: &SecretString) -> Result<ApiKey, Error> {
+        let mut transaction = self.unit_of_work.begin().await?;
+        let api_key = self
+            .api_key_repository
+            .find_by_key_hash(&mut transaction, &ApiKey::hash(key.expose_secret()))
+            .await?;
+        self.unit_of_work.commit(transaction).await?;
+
+        match api_key {
+            Some(api_key) if !api_key.is_revoked() => Ok(api_key),
+            _ => Err(Error::InvalidCredentials),
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use secrecy::SecretString;
+use uuid::Uuid;
+
+use crate::auth::domain::error::Error;
+use crate::auth::domain::infrastructure::UnitOfWork;
+use crate::auth::domain::infrastructure::UserRepository;
+use crate::auth::domain::model::Credentials;
+use crate::auth::domain::service::verify_password;
+
+// Verified when the username is unknown, so that the response time does not reveal which
+// usernames exist
+const FALLBACK_PASSWORD_HASH: &str = "$argon2id$v=19$m=19456,t=2,p=1$gZiV/M1gPc22ElAH/Jh1Hw$CWOrkoo7oJBQ/iyh7uJ0LO2aLEfrHwTWllSAxT0zRno";
+
+#[async_trait::async_trait]
+pub trait Authenticator: Send + Sync + 'static {
+    async fn authenticate(&self, credentials: Credentials) -> Result<Uuid, Error>;
+}
+
+pub type AuthenticatorFunction = Arc<
+    dyn Fn(Credentials) -> Pin<Box<dyn Future<Output = Result<Uuid, Error>> + Send>> + Send + Sync,
+>;
+
+#[async_trait::async_trait]
+impl Authenticator for AuthenticatorFunction {
+    async fn authenticate(&self, credentials: Credentials) -> Result<Uuid, Error> {
+        self(credentials).await
+    }
+}
+
+pub fn new_authenticator<U: UnitOfWork>(
+    unit_of_work: U,
+    user_repository: impl UserRepository<Transaction = U::Transaction>,
+) -> AuthenticatorFunction {
+    Arc::new(move |credentials: Credentials| {
+        let unit_of_work = unit_of_work.clone();
+        let user_repository = user_repository.clone();
+
+        Box::pin(async move { authenticate(credentials, unit_of_work, user_repository).await })
+    })
+}
+
+#[tracing::instrument(name = "Authenticating user", skip_all, fields(username = %credentials.username()))]
+pub async fn authenticate<U: UnitOfWork>(
+    credentials: Credentials,
+    unit_of_work: U,
+    user_repository: impl UserRepository<Transaction = U::Transaction>,
+) -> Result<Uuid, Error> {
+    let mut transaction = unit_of_work.begin().await?;
+    let user = user_repository
+        .find_by_username(&mut transaction, credentials.username())
+        .await?;
+    unit_of_work.commit(transaction).await?;
+
+    let (user_id, password_hash) = match user {
+        Some(user) => (Some(*user.id()), user.password_hash().clone()),
+        None => (None, SecretString::from(FALLBACK_PASSWORD_HASH)),
+    };
+    verify_password(password_hash, credentials.password().clone()).await?;
+
+    user_id.ok_or(Error::InvalidCredentials)
+}
@@ -0,0 +1,9 @@
+mod api_key;
+mod authentication;
+mod password;
+mod registration;
+
+pub use api_key::*;
+pub use authentication::*;
+pub use password::*;
+pub use registration::*;
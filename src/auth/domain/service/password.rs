@@ -0,0 +1,68 @@
+use anyhow::anyhow;
+use anyhow::Context;
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::SaltString;
+use argon2::Algorithm;
+use argon2::Argon2;
+use argon2::Params;
+use argon2::PasswordHash;
+use argon2::PasswordHasher;
+use argon2::PasswordVerifier;
+use argon2::Version;
+use secrecy::ExposeSecret;
+use secrecy::SecretString;
+
+use crate::auth::domain::error::Error;
+
+// Parameters recommended by OWASP for Argon2id; hashes keep their own parameters, so raising
+// these later does not invalidate existing ones
+fn argon2() -> Argon2<'static> {
+    Argon2::new(
+        Algorithm::Argon2id,
+        Version::V0x13,
+        Params::new(19_456, 2, 1, None).expect("Argon2 parameters must be valid"),
+    )
+}
+
+// Hashing is CPU-bound for tens of milliseconds, so it runs on the blocking pool rather than
+// stalling the async runtime
+#[tracing::instrument(name = "Hashing password", skip_all)]
+pub async fn hash_password(password: SecretString) -> Result<SecretString, Error> {
+    let span = tracing::Span::current();
+    tokio::task::spawn_blocking(move || {
+        span.in_scope(|| {
+            let salt = SaltString::generate(&mut OsRng);
+            argon2()
+                .hash_password(password.expose_secret().as_bytes(), &salt)
+                .map(|hash| SecretString::from(hash.to_string()))
+                .map_err(|e| Error::FailedUnexpectedly(anyhow!("Failed to hash password: {}", e)))
+        })
+    })
+    .await
+    .context("Failed to join password hashing task")
+    .map_err(Error::FailedUnexpectedly)?
+}
+
+#[tracing::instrument(name = "Verifying password", skip_all)]
+pub async fn verify_password(
+    password_hash: SecretString,
+    password: SecretString,
+) -> Result<(), Error> {
+    let span = tracing::Span::current();
+    tokio::task::spawn_blocking(move || {
+        span.in_scope(|| {
+            let password_hash = PasswordHash::new(password_hash.expose_secret()).map_err(|e| {
+                Error::FailedUnexpectedly(anyhow!("Failed to parse password hash: {}", e))
+            })?;
+            argon2()
+                .verify_password(password.expose_secret().as_bytes(), &password_hash)
+                .map_err(|e| match e {
+                    argon2::password_hash::Error::Password => Error::InvalidCredentials,
+                    e => Error::FailedUnexpectedly(anyhow!("Failed to verify password: {}", e)),
+                })
+        })
+    })
+    .await
+    .context("Failed to join password verification task")
+    .map_err(Error::FailedUnexpectedly)?
+}
@@ -0,0 +1,24 @@
+use secrecy::SecretString;
+use uuid::Uuid;
+
+use crate::auth::domain::error::Error;
+use crate::auth::domain::infrastructure::UnitOfWork;
+use crate::auth::domain::infrastructure::UserRepository;
+use crate::auth::domain::model::User;
+use crate::auth::domain::service::hash_password;
+
+#[tracing::instrument(name = "Registering user", skip_all, fields(username = %username))]
+pub async fn register_user<U: UnitOfWork>(
+    username: &str,
+    password: SecretString,
+    unit_of_work: U,
+    user_repository: impl UserRepository<Transaction = U::Transaction>,
+) -> Result<Uuid, Error> {
+    let user = User::create(username, hash_password(password).await?)?;
+
+    let mut transaction = unit_of_work.begin().await?;
+    user_repository.save(&mut transaction, &user).await?;
+    unit_of_work.commit(transaction).await?;
+
+    Ok(*user.id())
+}
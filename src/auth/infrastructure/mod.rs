@@ -0,0 +1,3 @@
+pub mod repository;
+pub mod session_store;
+pub mod unit_of_work;
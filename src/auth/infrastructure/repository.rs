@@ -0,0 +1,294 @@
+use anyhow::Context;
+use chrono::NaiveDateTime;
+use secrecy::ExposeSecret;
+use secrecy::SecretString;
+use uuid::Uuid;
+
+use crate::auth::domain::error::Error;
+use crate::auth::domain::infrastructure::ApiKeyRepository;
+use crate::auth::domain::infrastructure::UserRepository;
+use crate::auth::domain::model::ApiKey;
+use crate::auth::domain::model::Scope;
+use crate::auth::domain::model::User;
+use crate::auth::infrastructure::unit_of_work::SqlxTransaction;
+
+pub struct UserDataModel {
+    id: Uuid,
+    username: String,
+    password_hash: String,
+    created_at: NaiveDateTime,
+}
+
+impl UserDataModel {
+    pub fn new(
+        id: Uuid,
+        username: String,
+        password_hash: String,
+        created_at: NaiveDateTime,
+    ) -> Self {
+        Self {
+            id,
+            username,
+            password_hash,
+            created_at,
+        }
+    }
+}
+
+impl From<UserDataModel> for User {
+    fn from(data_model: UserDataModel) -> Self {
+        User::new(
+            data_model.id,
+            data_model.username,
+            SecretString::from(data_model.password_hash),
+            data_model.created_at.and_utc(),
+        )
+    }
+}
+
+impl From<&User> for UserDataModel {
+    fn from(entity: &User) -> Self {
+        UserDataModel::new(
+            *entity.id(),
+            entity.username().into(),
+            entity.password_hash().expose_secret().into(),
+            entity.created_at().naive_utc(),
+        )
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct SqlxUserRepository;
+
+impl SqlxUserRepository {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait::async_trait]
+impl UserRepository for SqlxUserRepository {
+    type Transaction = SqlxTransaction;
+
+    #[tracing::instrument(name = "Saving user", skip_all, fields(id = ?user.id(), username = %user.username()))]
+    async fn save(&self, transaction: &mut Self::Transaction, user: &User) -> Result<(), Error> {
+        let data_model = UserDataModel::from(user);
+        sqlx::query!(
+            "INSERT INTO users (id, username, password_hash, created_at) VALUES ($1, $2, $3, $4)",
+            data_model.id,
+            data_model.username,
+            data_model.password_hash,
+            data_model.created_at,
+        )
+        .execute(&mut **transaction)
+        .await
+        .context("Failed to save user")
+        .map_err(Error::RepositoryOperationFailed)?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Finding user by username", skip_all, fields(username = %username))]
+    async fn find_by_username(
+        &self,
+        transaction: &mut Self::Transaction,
+        username: &str,
+    ) -> Result<Option<User>, Error> {
+        let data_model = sqlx::query_as!(
+            UserDataModel,
+            "SELECT id, username, password_hash, created_at FROM users WHERE username = $1",
+            username,
+        )
+        .fetch_optional(&mut **transaction)
+        .await
+        .context("Failed to find user by username")
+        .map_err(Error::RepositoryOperationFailed)?;
+
+        Ok(data_model.map(User::from))
+    }
+}
+
+pub struct ApiKeyDataModel {
+    id: Uuid,
+    client_name: String,
+    key_hash: String,
+    visible_prefix: String,
+    scopes: Vec<String>,
+    created_at: NaiveDateTime,
+    revoked_at: Option<NaiveDateTime>,
+}
+
+impl ApiKeyDataModel {
+    pub fn new(
+        id: Uuid,
+        client_name: String,
+        key_hash: String,
+        visible_prefix: String,
+        scopes: Vec<String>,
+        created_at: NaiveDateTime,
+        revoked_at: Option<NaiveDateTime>,
+    ) -> Self {
+        Self {
+            id,
+            client_name,
+            key_hash,
+            visible_prefix,
+            scopes,
+            created_at,
+            revoked_at,
+        }
+    }
+}
+
+impl TryFrom<ApiKeyDataModel> for ApiKey {
+    type Error = Error;
+
+    fn try_from(data_model: ApiKeyDataModel) -> Result<Self, Self::Error> {
+        let scopes = data_model
+            .scopes
+            .iter()
+            .map(|scope| scope.parse::<Scope>())
+            .collect::<Result<Vec<_>, _>>()
+            .context("Failed to parse scopes of API key")
+            .map_err(Error::RepositoryOperationFailed)?;
+
+        Ok(ApiKey::new(
+            data_model.id,
+            data_model.client_name,
+            data_model.key_hash,
+            data_model.visible_prefix,
+            scopes,
+            data_model.created_at.and_utc(),
+            data_model.revoked_at.map(|revoked_at| revoked_at.and_utc()),
+        ))
+    }
+}
+
+impl From<&ApiKey> for ApiKeyDataModel {
+    fn from(entity: &ApiKey) -> Self {
+        ApiKeyDataModel::new(
+            *entity.id(),
+            entity.client_name().into(),
+            entity.key_hash().into(),
+            entity.visible_prefix().into(),
+            entity
+                .scopes()
+                .iter()
+                .map(|scope| scope.as_ref().into())
+                .collect(),
+            entity.created_at().naive_utc(),
+            entity.revoked_at().map(|revoked_at| revoked_at.naive_utc()),
+        )
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct SqlxApiKeyRepository;
+
+impl SqlxApiKeyRepository {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait::async_trait]
+impl ApiKeyRepository for SqlxApiKeyRepository {
+    type Transaction = SqlxTransaction;
+
+    #[tracing::instrument(name = "Saving API key", skip_all, fields(id = ?api_key.id(), client_name = %api_key.client_name()))]
+    async fn save(
+        &self,
+        transaction: &mut Self::Transaction,
+        api_key: &ApiKey,
+    ) -> Result<(), Error> {
+        let data_model = ApiKeyDataModel::from(api_key);
+        sqlx::query!(
+            "INSERT INTO api_keys (id, client_name, key_hash, visible_prefix, scopes, created_at, revoked_at) VALUES ($1, $2, $3, $4, $5, $6, $7)",
+            data_model.id,
+            data_model.client_name,
+            data_model.key_hash,
+            data_model.visible_prefix,
+            &data_model.scopes,
+            data_model.created_at,
+            data_model.revoked_at,
+        )
+        .execute(&mut **transaction)
+        .await
+        .context("Failed to save API key")
+        .map_err(Error::RepositoryOperationFailed)?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Finding all API keys", skip_all)]
+    async fn find_all(&self, transaction: &mut Self::Transaction) -> Result<Vec<ApiKey>, Error> {
+        sqlx::query_as!(
+            ApiKeyDataModel,
+            "SELECT id, client_name, key_hash, visible_prefix, scopes, created_at, revoked_at FROM api_keys ORDER BY created_at, id",
+        )
+        .fetch_all(&mut **transaction)
+        .await
+        .context("Failed to find API keys")
+        .map_err(Error::RepositoryOperationFailed)?
+        .into_iter()
+        .map(ApiKey::try_from)
+        .collect()
+    }
+
+    #[tracing::instrument(name = "Finding API key by key hash", skip_all)]
+    async fn find_by_key_hash(
+        &self,
+        transaction: &mut Self::Transaction,
+        key_hash: &str,
+    ) -> Result<Option<ApiKey>, Error> {
+        sqlx::query_as!(
+            ApiKeyDataModel,
+            "SELECT id, client_name, key_hash, visible_prefix, scopes, created_at, revoked_at FROM api_keys WHERE key_hash = $1",
+            key_hash,
+        )
+        .fetch_optional(&mut **transaction)
+        .await
+        .context("Failed to find API key by key hash")
+        .map_err(Error::RepositoryOperationFailed)?
+        .map(ApiKey::try_from)
+        .transpose()
+    }
+
+    #[tracing::instrument(name = "Modifying API key", skip_all, fields(id = ?id))]
+    async fn modify_by_id<F>(
+        &self,
+        transaction: &mut Self::Transaction,
+        id: &Uuid,
+        modifier: F,
+    ) -> Result<(), Error>
+    where
+        F: FnOnce(ApiKey) -> ApiKey + Send + Sync,
+    {
+        let api_key: ApiKey = sqlx::query_as!(
+            ApiKeyDataModel,
+            "SELECT id, client_name, key_hash, visible_prefix, scopes, created_at, revoked_at FROM api_keys WHERE id = $1 FOR UPDATE",
+            id,
+        )
+        .fetch_optional(&mut **transaction)
+        .await
+        .context("Failed to find API key")
+        .map_err(Error::RepositoryOperationFailed)?
+        .ok_or(Error::ApiKeyNotFound(*id))?
+        .try_into()?;
+
+        let data_model = ApiKeyDataModel::from(&modifier(api_key));
+        sqlx::query!(
+            "UPDATE api_keys SET client_name = $1, scopes = $2, revoked_at = $3 WHERE id = $4",
+            data_model.client_name,
+            &data_model.scopes,
+            data_model.revoked_at,
+            data_model.id,
+        )
+        .execute(&mut **transaction)
+        .await
+        .context("Failed to update API key")
+        .map_err(Error::RepositoryOperationFailed)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anyhow::Context;
+use chrono::NaiveDateTime;
+use secrecy::ExposeSecret;
+use secrecy::SecretString;
+use sha2::Digest;
+use sha2::Sha256;
+use sqlx::Pool;
+use sqlx::Postgres;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::auth::domain::error::Error;
+use crate::auth::domain::infrastructure::SessionStore;
+use crate::auth::domain::model::Session;
+
+pub enum ConfiguredSessionStore {
+    Postgres(PostgresSessionStore),
+    InMemory(InMemorySessionStore),
+}
+
+#[async_trait::async_trait]
+impl SessionStore for ConfiguredSessionStore {
+    async fn load(&self, id: &str) -> Result<Option<Session>, Error> {
+        match self {
+            Self::Postgres(store) => store.load(id).await,
+            Self::InMemory(store) => store.load(id).await,
+        }
+    }
+
+    async fn save(&self, session: &Session) -> Result<(), Error> {
+        match self {
+            Self::Postgres(store) => store.save(session).await,
+            Self::InMemory(store) => store.save(session).await,
+        }
+    }
+
+    async fn delete(&self, id: &str) -> Result<(), Error> {
+        match self {
+            Self::Postgres(store) => store.delete(id).await,
+            Self::InMemory(store) => store.delete(id).await,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct PostgresSessionStore {
+    pool: Pool<Postgres>,
+}
+
+impl PostgresSessionStore {
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self { pool }
+    }
+}
+
+fn hash_session_id(id: &str) -> String {
+    hex::encode(Sha256::digest(id.as_bytes()))
+}
+
+#[async_trait::async_trait]
+impl SessionStore for PostgresSessionStore {
+    #[tracing::instrument(name = "Loading session", skip_all)]
+    async fn load(&self, id: &str) -> Result<Option<Session>, Error> {
+        let row = sqlx::query!(
+            "SELECT user_id, csrf_token, flash_messages, expires_at FROM sessions WHERE id_hash = $1 AND expires_at > (now() AT TIME ZONE 'utc')",
+            hash_session_id(id),
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .context("Failed to load session")
+        .map_err(Error::RepositoryOperationFailed)?;
+
+        Ok(row.map(|row| {
+            Session::new(
+                SecretString::from(id),
+                row.user_id,
+                row.csrf_token,
+                row.flash_messages,
+                row.expires_at.and_utc(),
+            )
+        }))
+    }
+
+    // Expired sessions of other browsers are swept on the way, so the table does not keep every
+    // anonymous visit forever
+    #[tracing::instrument(name = "Saving session", skip_all, fields(user_id = ?session.user_id()))]
+    async fn save(&self, session: &Session) -> Result<(), Error> {
+        let user_id: Option<Uuid> = session.user_id().copied();
+        let expires_at: NaiveDateTime = session.expires_at().naive_utc();
+        sqlx::query!(
+            "INSERT INTO sessions (id_hash, user_id, csrf_token, flash_messages, expires_at) VALUES ($1, $2, $3, $4, $5) ON CONFLICT (id_hash) DO UPDATE SET user_id = EXCLUDED.user_id, csrf_token = EXCLUDED.csrf_token, flash_messages = EXCLUDED.flash_messages, expires_at = EXCLUDED.expires_at",
+            hash_session_id(session.id().expose_secret()),
+            user_id,
+            session.csrf_token(),
+            session.flash_messages(),
+            expires_at,
+        )
+        .execute(&self.pool)
+        .await
+        .context("Failed to save session")
+        .map_err(Error::RepositoryOperationFailed)?;
+
+        sqlx::query!("DELETE FROM sessions WHERE expires_at <= (now() AT TIME ZONE 'utc')")
+            .execute(&self.pool)
+            .await
+            .context("Failed to delete expired sessions")
+            .map_err(Error::RepositoryOperationFailed)?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Deleting session", skip_all)]
+    async fn delete(&self, id: &str) -> Result<(), Error> {
+        sqlx::query!(
+            "DELETE FROM sessions WHERE id_hash = $1",
+            hash_session_id(id)
+        )
+        .execute(&self.pool)
+        .await
+        .context("Failed to delete session")
+        .map_err(Error::RepositoryOperationFailed)?;
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct InMemorySessionStore {
+    sessions: Arc<RwLock<HashMap<String, Session>>>,
+}
+
+impl InMemorySessionStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait::async_trait]
+impl SessionStore for InMemorySessionStore {
+    async fn load(&self, id: &str) -> Result<Option<Session>, Error> {
+        let sessions = self.sessions.read().await;
+        Ok(sessions
+            .get(id)
+            .filter(|session| !session.is_expired())
+            .cloned())
+    }
+
+    async fn save(&self, session: &Session) -> Result<(), Error> {
+        let mut sessions = self.sessions.write().await;
+        sessions.retain(|_, session| !session.is_expired());
+        sessions.insert(session.id().expose_secret().into(), session.clone());
+        Ok(())
+    }
+
+    async fn delete(&self, id: &str) -> Result<(), Error> {
+        self.sessions.write().await.remove(id);
+        Ok(())
+    }
+}
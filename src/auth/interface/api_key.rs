@@ -0,0 +1,157 @@
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use axum::extract::FromRef;
+use axum::extract::FromRequestParts;
+use axum::http::header::AUTHORIZATION;
+use axum::http::header::WWW_AUTHENTICATE;
+use axum::http::request::Parts;
+use axum::http::HeaderValue;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use secrecy::SecretString;
+use uuid::Uuid;
+
+use crate::auth::domain::error::Error;
+use crate::auth::domain::model::Scope;
+use crate::auth::domain::service::ApiKeyManager;
+use crate::auth::domain::service::Authenticator;
+use crate::auth::interface::extractor::AdminUser;
+use crate::auth::interface::response::Response;
+
+// Scope a route demands from the API key of its caller
+pub trait RequiredScope: Send + Sync + 'static {
+    const SCOPE: Scope;
+}
+
+pub struct SubscribersRead;
+
+impl RequiredScope for SubscribersRead {
+    const SCOPE: Scope = Scope::SubscribersRead;
+}
+
+pub struct SubscribersWrite;
+
+impl RequiredScope for SubscribersWrite {
+    const SCOPE: Scope = Scope::SubscribersWrite;
+}
+
+pub struct NewslettersPublish;
+
+impl RequiredScope for NewslettersPublish {
+    const SCOPE: Scope = Scope::NewslettersPublish;
+}
+
+// Machine client calling with a bearer API key, so that routes taking ApiClient<R> reject callers
+// whose key lacks the scope of R
+#[derive(Debug)]
+pub struct ApiClient<R: RequiredScope> {
+    pub api_key_id: Uuid,
+    pub client_name: String,
+    scope: PhantomData<R>,
+}
+
+impl<S, R> FromRequestParts<S> for ApiClient<R>
+where
+    S: Send + Sync,
+    R: RequiredScope,
+    Arc<dyn ApiKeyManager>: FromRef<S>,
+{
+    type Rejection = axum::response::Response;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Some(key) = parts
+            .headers
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .map(|key| SecretString::from(key.trim()))
+        else {
+            return Err(unauthorized("Missing bearer API key"));
+        };
+
+        let api_key_manager = Arc::<dyn ApiKeyManager>::from_ref(state);
+        let api_key = match api_key_manager.verify(&key).await {
+            Ok(api_key) => api_key,
+            Err(Error::InvalidCredentials) => return Err(unauthorized("Invalid API key")),
+            Err(error) => {
+                tracing::error!("{:?}", error);
+                return Err(Response::new(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Some(
+                        "Failed to verify the API key because of the unexpected system issue."
+                            .into(),
+                    ),
+                )
+                .into_response());
+            }
+        };
+
+        if !api_key.has_scope(R::SCOPE) {
+            return Err(Response::new(
+                StatusCode::FORBIDDEN,
+                Some(format!("API key lacks the {} scope", R::SCOPE.as_ref())),
+            )
+            .into_response());
+        }
+
+        Ok(Self {
+            api_key_id: *api_key.id(),
+            client_name: api_key.client_name().into(),
+            scope: PhantomData,
+        })
+    }
+}
+
+// Caller of admin routes open to machine clients as well, i.e. an operator presenting HTTP Basic
+// credentials or a machine client presenting a bearer API key with the scope of R
+pub enum AdminOrApiClient<R: RequiredScope> {
+    Admin(AdminUser),
+    ApiClient(ApiClient<R>),
+}
+
+impl<R: RequiredScope> std::fmt::Display for AdminOrApiClient<R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Admin(admin_user) => write!(f, "admin {}", admin_user.user_id),
+            Self::ApiClient(api_client) => write!(f, "client {}", api_client.client_name),
+        }
+    }
+}
+
+impl<S, R> FromRequestParts<S> for AdminOrApiClient<R>
+where
+    S: Send + Sync,
+    R: RequiredScope,
+    Arc<dyn ApiKeyManager>: FromRef<S>,
+    Arc<dyn Authenticator>: FromRef<S>,
+{
+    type Rejection = axum::response::Response;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let bearer = parts
+            .headers
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.starts_with("Bearer "));
+
+        if bearer {
+            ApiClient::from_request_parts(parts, state)
+                .await
+                .map(Self::ApiClient)
+        } else {
+            AdminUser::from_request_parts(parts, state)
+                .await
+                .map(Self::Admin)
+        }
+    }
+}
+
+fn unauthorized(message: &str) -> axum::response::Response {
+    let mut response =
+        Response::new(StatusCode::UNAUTHORIZED, Some(message.into())).into_response();
+    response
+        .headers_mut()
+        .insert(WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"));
+    response
+}
@@ -0,0 +1,38 @@
+use std::sync::Arc;
+
+use axum::extract::Path;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use uuid::Uuid;
+
+use crate::auth::domain::error::Error;
+use crate::auth::domain::service::ApiKeyManager;
+use crate::auth::interface::extractor::AdminUser;
+use crate::auth::interface::response::Response;
+
+// Revoking keeps the key around, so that listings still show which clients used to have access
+#[tracing::instrument(name = "Revoking an API key", skip_all, fields(admin = %admin_user.user_id, id = %id))]
+pub async fn control(
+    admin_user: AdminUser,
+    State(api_key_manager): State<Arc<dyn ApiKeyManager>>,
+    Path(id): Path<Uuid>,
+) -> impl IntoResponse {
+    match api_key_manager.revoke(&id).await {
+        Ok(_) => Response::new(StatusCode::NO_CONTENT, None),
+        Err(error) => {
+            tracing::error!("{:?}", error);
+            convert_error_to_response(error)
+        }
+    }
+}
+
+fn convert_error_to_response(error: Error) -> Response {
+    match error {
+        Error::ApiKeyNotFound(_) => Response::new(StatusCode::NOT_FOUND, Some(error.to_string())),
+        _ => Response::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Some("Failed to revoke the API key because of the unexpected system issue.".into()),
+        ),
+    }
+}
@@ -0,0 +1,65 @@
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::Json;
+use uuid::Uuid;
+
+use crate::auth::domain::model::ApiKey;
+use crate::auth::domain::service::ApiKeyManager;
+use crate::auth::interface::extractor::AdminUser;
+use crate::auth::interface::response::Response;
+
+#[derive(serde::Serialize)]
+pub struct ApiKeyResponse {
+    id: Uuid,
+    client_name: String,
+    visible_prefix: String,
+    scopes: Vec<String>,
+    created_at: String,
+    revoked_at: Option<String>,
+}
+
+impl From<&ApiKey> for ApiKeyResponse {
+    fn from(api_key: &ApiKey) -> Self {
+        Self {
+            id: *api_key.id(),
+            client_name: api_key.client_name().into(),
+            visible_prefix: api_key.visible_prefix().into(),
+            scopes: api_key
+                .scopes()
+                .iter()
+                .map(|scope| scope.as_ref().into())
+                .collect(),
+            created_at: api_key.created_at().to_rfc3339(),
+            revoked_at: api_key
+                .revoked_at()
+                .map(|revoked_at| revoked_at.to_rfc3339()),
+        }
+    }
+}
+
+#[tracing::instrument(name = "Listing API keys", skip_all, fields(admin = %admin_user.user_id))]
+pub async fn control(
+    admin_user: AdminUser,
+    State(api_key_manager): State<Arc<dyn ApiKeyManager>>,
+) -> axum::response::Response {
+    match api_key_manager.list().await {
+        Ok(api_keys) => Json(
+            api_keys
+                .iter()
+                .map(ApiKeyResponse::from)
+                .collect::<Vec<_>>(),
+        )
+        .into_response(),
+        Err(error) => {
+            tracing::error!("{:?}", error);
+            Response::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Some("Failed to list API keys because of the unexpected system issue.".into()),
+            )
+            .into_response()
+        }
+    }
+}
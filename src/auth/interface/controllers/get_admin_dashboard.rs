@@ -0,0 +1,40 @@
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::response::Response;
+use minijinja::context;
+
+use crate::auth::domain::error::Error;
+use crate::auth::domain::infrastructure::SessionStore;
+use crate::auth::interface::session::AdminSession;
+use crate::common::page::PageRenderer;
+
+#[tracing::instrument(name = "Showing admin dashboard", skip_all, fields(user_id = %admin_session.user_id))]
+pub async fn control(
+    State(session_store): State<Arc<dyn SessionStore>>,
+    State(page_renderer): State<PageRenderer>,
+    mut admin_session: AdminSession,
+) -> Response {
+    let page = match admin_session
+        .take_flash_messages(session_store.as_ref())
+        .await
+    {
+        Ok(flash_messages) => page_renderer
+            .render(
+                "dashboard.html",
+                context! { csrf_token => admin_session.csrf_token, flash_messages },
+            )
+            .map_err(Error::FailedUnexpectedly),
+        Err(error) => Err(error),
+    };
+
+    match page {
+        Ok(page) => page.into_response(),
+        Err(error) => {
+            tracing::error!("{:?}", error);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::response::Redirect;
+use axum::response::Response;
+use axum_extra::extract::CookieJar;
+use minijinja::context;
+
+use crate::auth::domain::infrastructure::SessionStore;
+use crate::auth::domain::model::Session;
+use crate::auth::interface::session::load_session;
+use crate::auth::interface::session::SessionCookie;
+use crate::common::page::PageRenderer;
+
+#[tracing::instrument(name = "Showing login form", skip_all)]
+pub async fn control(
+    State(session_store): State<Arc<dyn SessionStore>>,
+    State(session_cookie): State<SessionCookie>,
+    State(page_renderer): State<PageRenderer>,
+    jar: CookieJar,
+) -> Response {
+    let session = match load_session(&jar, session_store.as_ref()).await {
+        Ok(session) => session,
+        Err(error) => {
+            tracing::error!("{:?}", error);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    // Anonymous browsers get a session as well, which carries the CSRF token of the form
+    let mut session = match session {
+        Some(session) if session.user_id().is_some() => {
+            return Redirect::to("/admin/dashboard").into_response()
+        }
+        Some(session) => session,
+        None => Session::start(session_cookie.ttl()),
+    };
+    let flash_messages = session.take_flash_messages();
+
+    if let Err(error) = session_store.save(&session).await {
+        tracing::error!("{:?}", error);
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    }
+
+    let page = page_renderer.render(
+        "login.html",
+        context! { csrf_token => session.csrf_token(), flash_messages },
+    );
+    match page {
+        Ok(page) => (jar.add(session_cookie.build(&session)), page).into_response(),
+        Err(error) => {
+            tracing::error!("{:?}", error);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
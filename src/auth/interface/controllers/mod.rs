@@ -0,0 +1,7 @@
+pub mod delete_admin_api_keys;
+pub mod get_admin_api_keys;
+pub mod get_admin_dashboard;
+pub mod get_login;
+pub mod post_admin_api_keys;
+pub mod post_login;
+pub mod post_logout;
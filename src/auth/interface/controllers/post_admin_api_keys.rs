@@ -0,0 +1,76 @@
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::Json;
+use secrecy::ExposeSecret;
+
+use crate::auth::domain::error::Error;
+use crate::auth::domain::model::Scope;
+use crate::auth::domain::service::ApiKeyManager;
+use crate::auth::interface::controllers::get_admin_api_keys::ApiKeyResponse;
+use crate::auth::interface::extractor::AdminUser;
+use crate::auth::interface::response::Response;
+
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct Request {
+    client_name: String,
+    scopes: Vec<String>,
+}
+
+#[derive(serde::Serialize)]
+struct MintedApiKeyResponse {
+    #[serde(flatten)]
+    api_key: ApiKeyResponse,
+    // Shown only in this response, as only its hash is kept
+    key: String,
+}
+
+#[tracing::instrument(name = "Minting an API key", skip_all, fields(admin = %admin_user.user_id, client_name = %request.client_name))]
+pub async fn control(
+    admin_user: AdminUser,
+    State(api_key_manager): State<Arc<dyn ApiKeyManager>>,
+    Json(request): Json<Request>,
+) -> axum::response::Response {
+    let scopes = match request
+        .scopes
+        .iter()
+        .map(|scope| {
+            scope
+                .parse::<Scope>()
+                .map_err(|_| format!("Unknown scope {}", scope))
+        })
+        .collect::<Result<Vec<_>, _>>()
+    {
+        Ok(scopes) => scopes,
+        Err(message) => {
+            return Response::new(StatusCode::BAD_REQUEST, Some(message)).into_response()
+        }
+    };
+
+    match api_key_manager.mint(&request.client_name, &scopes).await {
+        Ok((api_key, key)) => (
+            StatusCode::CREATED,
+            Json(MintedApiKeyResponse {
+                api_key: (&api_key).into(),
+                key: key.expose_secret().into(),
+            }),
+        )
+            .into_response(),
+        Err(error) => {
+            tracing::error!("{:?}", error);
+            convert_error_to_response(error).into_response()
+        }
+    }
+}
+
+fn convert_error_to_response(error: Error) -> Response {
+    match error {
+        Error::InvariantViolated(message) => Response::new(StatusCode::BAD_REQUEST, Some(message)),
+        _ => Response::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Some("Failed to mint the API key because of the unexpected system issue.".into()),
+        ),
+    }
+}
@@ -0,0 +1,75 @@
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::response::Redirect;
+use axum::response::Response;
+use axum::Form;
+use axum_extra::extract::CookieJar;
+use secrecy::ExposeSecret;
+use secrecy::SecretString;
+
+use crate::auth::domain::error::Error;
+use crate::auth::domain::infrastructure::SessionStore;
+use crate::auth::domain::model::Credentials;
+use crate::auth::domain::service::Authenticator;
+use crate::auth::interface::session::load_session;
+use crate::auth::interface::session::SessionCookie;
+
+#[derive(Debug, serde::Deserialize)]
+pub struct Request {
+    username: String,
+    password: SecretString,
+    csrf_token: String,
+}
+
+#[tracing::instrument(name = "Logging in", skip_all, fields(username = %request.username))]
+pub async fn control(
+    State(authenticator): State<Arc<dyn Authenticator>>,
+    State(session_store): State<Arc<dyn SessionStore>>,
+    State(session_cookie): State<SessionCookie>,
+    jar: CookieJar,
+    Form(request): Form<Request>,
+) -> Response {
+    let mut session = match load_session(&jar, session_store.as_ref()).await {
+        Ok(Some(session)) if session.verify_csrf_token(&request.csrf_token) => session,
+        Ok(_) => return (StatusCode::FORBIDDEN, "Invalid CSRF token").into_response(),
+        Err(error) => {
+            tracing::error!("{:?}", error);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    let credentials = Credentials::new(request.username, request.password);
+    let result = match authenticator.authenticate(credentials).await {
+        Ok(user_id) => {
+            let logged_in = session.log_in(user_id, session_cookie.ttl());
+            let result = async {
+                session_store.delete(session.id().expose_secret()).await?;
+                session_store.save(&logged_in).await
+            }
+            .await;
+            result.map(|_| {
+                (
+                    jar.add(session_cookie.build(&logged_in)),
+                    Redirect::to("/admin/dashboard"),
+                )
+                    .into_response()
+            })
+        }
+        Err(Error::InvalidCredentials) => {
+            session.add_flash_message("Authentication failed".into());
+            session_store
+                .save(&session)
+                .await
+                .map(|_| Redirect::to("/login").into_response())
+        }
+        Err(error) => Err(error),
+    };
+
+    result.unwrap_or_else(|error| {
+        tracing::error!("{:?}", error);
+        StatusCode::INTERNAL_SERVER_ERROR.into_response()
+    })
+}
@@ -0,0 +1,61 @@
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::response::Redirect;
+use axum::response::Response;
+use axum::Form;
+use axum_extra::extract::CookieJar;
+use secrecy::ExposeSecret;
+
+use crate::auth::domain::infrastructure::SessionStore;
+use crate::auth::domain::model::Session;
+use crate::auth::interface::session::load_session;
+use crate::auth::interface::session::SessionCookie;
+
+#[derive(Debug, serde::Deserialize)]
+pub struct Request {
+    csrf_token: String,
+}
+
+#[tracing::instrument(name = "Logging out", skip_all)]
+pub async fn control(
+    State(session_store): State<Arc<dyn SessionStore>>,
+    State(session_cookie): State<SessionCookie>,
+    jar: CookieJar,
+    Form(request): Form<Request>,
+) -> Response {
+    let session = match load_session(&jar, session_store.as_ref()).await {
+        Ok(Some(session)) if session.user_id().is_some() => session,
+        Ok(_) => return Redirect::to("/login").into_response(),
+        Err(error) => {
+            tracing::error!("{:?}", error);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    if !session.verify_csrf_token(&request.csrf_token) {
+        return (StatusCode::FORBIDDEN, "Invalid CSRF token").into_response();
+    }
+
+    // A fresh anonymous session carries the farewell message to the login form
+    let mut anonymous = Session::start(session_cookie.ttl());
+    anonymous.add_flash_message("You have successfully logged out.".into());
+    let result = async {
+        session_store.delete(session.id().expose_secret()).await?;
+        session_store.save(&anonymous).await
+    }
+    .await;
+
+    match result {
+        Ok(_) => (
+            jar.add(session_cookie.build(&anonymous)),
+            Redirect::to("/login"),
+        )
+            .into_response(),
+        Err(error) => {
+            tracing::error!("{:?}", error);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use std::sync::Arc;
+
+use axum::extract::FromRef;
+use axum::extract::FromRequestParts;
+use axum::http::header::AUTHORIZATION;
+use axum::http::header::WWW_AUTHENTICATE;
+use axum::http::request::Parts;
+use axum::http::HeaderValue;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use secrecy::SecretString;
+use uuid::Uuid;
+
+use crate::auth::domain::error::Error;
+use crate::auth::domain::model::Credentials;
+use crate::auth::domain::service::Authenticator;
+use crate::auth::interface::response::Response;
+
+// Operator authenticated with HTTP Basic credentials, so that routes taking it reject requests
+// without valid credentials of a registered user
+#[derive(Clone, Copy, Debug)]
+pub struct AdminUser {
+    pub user_id: Uuid,
+}
+
+impl<S> FromRequestParts<S> for AdminUser
+where
+    S: Send + Sync,
+    Arc<dyn Authenticator>: FromRef<S>,
+{
+    type Rejection = axum::response::Response;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Some(credentials) = parts
+            .headers
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(parse_basic_credentials)
+        else {
+            return Err(unauthorized(
+                "Missing or malformed basic credentials".into(),
+            ));
+        };
+
+        let authenticator = Arc::<dyn Authenticator>::from_ref(state);
+        match authenticator.authenticate(credentials).await {
+            Ok(user_id) => Ok(Self { user_id }),
+            Err(Error::InvalidCredentials) => {
+                Err(unauthorized(Error::InvalidCredentials.to_string()))
+            }
+            Err(error) => {
+                tracing::error!("{:?}", error);
+                Err(Response::new(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Some("Failed to authenticate because of the unexpected system issue.".into()),
+                )
+                .into_response())
+            }
+        }
+    }
+}
+
+fn parse_basic_credentials(authorization: &str) -> Option<Credentials> {
+    let encoded = authorization.strip_prefix("Basic ")?;
+    let decoded = String::from_utf8(STANDARD.decode(encoded.trim()).ok()?).ok()?;
+    let (username, password) = decoded.split_once(':')?;
+
+    Some(Credentials::new(
+        username.into(),
+        SecretString::from(password),
+    ))
+}
+
+fn unauthorized(message: String) -> axum::response::Response {
+    let mut response = Response::new(StatusCode::UNAUTHORIZED, Some(message)).into_response();
+    response.headers_mut().insert(
+        WWW_AUTHENTICATE,
+        HeaderValue::from_static(r#"Basic realm="admin", charset="UTF-8""#),
+    );
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use secrecy::ExposeSecret;
+
+    use super::*;
+
+    #[rstest::rstest]
+    #[case("Basic YWRtaW46czNjcjN0", Some(("admin", "s3cr3t")))]
+    #[case("Basic YWRtaW46czNjcjN0OndpdGg6Y29sb25z", Some(("admin", "s3cr3t:with:colons")))]
+    #[case("Bearer YWRtaW46czNjcjN0", None)]
+    #[case("Basic not-base64", None)]
+    #[case("Basic YWRtaW4=", None)]
+    fn parse_basic_credentials_splits_username_and_password_on_first_colon(
+        #[case] authorization: &str,
+        #[case] expected: Option<(&str, &str)>,
+    ) {
+        let actual = parse_basic_credentials(authorization);
+
+        assert_eq!(
+            actual
+                .as_ref()
+                .map(|c| (c.username(), c.password().expose_secret())),
+            expected
+        );
+    }
+}
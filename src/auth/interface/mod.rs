@@ -0,0 +1,6 @@
+pub mod api_key;
+pub mod controllers;
+pub mod extractor;
+pub mod response;
+pub mod router;
+pub mod session;
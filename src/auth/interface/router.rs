@@ -0,0 +1,93 @@
+use std::sync::Arc;
+
+use axum::extract::FromRef;
+use axum::routing::delete;
+use axum::routing::get;
+use axum::routing::post;
+use axum::Router;
+
+use crate::auth::domain::infrastructure::SessionStore;
+use crate::auth::domain::service::ApiKeyManager;
+use crate::auth::domain::service::Authenticator;
+use crate::auth::interface::controllers;
+use crate::auth::interface::session::SessionCookie;
+use crate::common::page::PageRenderer;
+
+#[derive(Clone)]
+pub struct Container {
+    authenticator: Arc<dyn Authenticator>,
+    api_key_manager: Arc<dyn ApiKeyManager>,
+    session_store: Arc<dyn SessionStore>,
+    session_cookie: SessionCookie,
+    page_renderer: PageRenderer,
+}
+
+impl Container {
+    pub fn new(
+        authenticator: Arc<dyn Authenticator>,
+        api_key_manager: Arc<dyn ApiKeyManager>,
+        session_store: Arc<dyn SessionStore>,
+        session_cookie: SessionCookie,
+    ) -> Self {
+        Self {
+            authenticator,
+            api_key_manager,
+            session_store,
+            session_cookie,
+            page_renderer: PageRenderer::new(),
+        }
+    }
+}
+
+impl FromRef<Container> for Arc<dyn Authenticator> {
+    fn from_ref(container: &Container) -> Self {
+        container.authenticator.clone()
+    }
+}
+
+impl FromRef<Container> for Arc<dyn ApiKeyManager> {
+    fn from_ref(container: &Container) -> Self {
+        container.api_key_manager.clone()
+    }
+}
+
+impl FromRef<Container> for Arc<dyn SessionStore> {
+    fn from_ref(container: &Container) -> Self {
+        container.session_store.clone()
+    }
+}
+
+impl FromRef<Container> for SessionCookie {
+    fn from_ref(container: &Container) -> Self {
+        container.session_cookie
+    }
+}
+
+impl FromRef<Container> for PageRenderer {
+    fn from_ref(container: &Container) -> Self {
+        container.page_renderer.clone()
+    }
+}
+
+pub async fn get_router(container: Container) -> Router {
+    Router::new()
+        .route(
+            "/login",
+            get(controllers::get_login::control).post(controllers::post_login::control),
+        )
+        .route("/logout", post(controllers::post_logout::control))
+        .route(
+            "/admin/dashboard",
+            get(controllers::get_admin_dashboard::control),
+        )
+        .route(
+            "/admin/api-keys",
+            get(controllers::get_admin_api_keys::control)
+                .post(controllers::post_admin_api_keys::control),
+        )
+        .route(
+            "/admin/api-keys/{id}",
+            delete(controllers::delete_admin_api_keys::control),
+        )
+        .with_state(container)
+}
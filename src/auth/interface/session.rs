@@ -0,0 +1,124 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::extract::FromRef;
+use axum::extract::FromRequestParts;
+use axum::http::request::Parts;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::response::Redirect;
+use axum_extra::extract::cookie::Cookie;
+use axum_extra::extract::cookie::SameSite;
+use axum_extra::extract::CookieJar;
+use secrecy::ExposeSecret;
+use uuid::Uuid;
+
+use crate::auth::domain::error::Error;
+use crate::auth::domain::infrastructure::SessionStore;
+use crate::auth::domain::model::Session;
+
+const SESSION_COOKIE_NAME: &str = "session_id";
+
+// The cookie carrying the session identifier is marked Secure when the service is served over HTTPS
+#[derive(Clone, Copy, Debug)]
+pub struct SessionCookie {
+    ttl: Duration,
+    secure: bool,
+}
+
+impl SessionCookie {
+    pub fn new(ttl: Duration, secure: bool) -> Self {
+        Self { ttl, secure }
+    }
+
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    // Lax keeps the cookie off cross-site form posts, on top of the CSRF token the forms carry
+    pub fn build(&self, session: &Session) -> Cookie<'static> {
+        Cookie::build((SESSION_COOKIE_NAME, session.id().expose_secret().to_owned()))
+            .path("/")
+            .http_only(true)
+            .secure(self.secure)
+            .same_site(SameSite::Lax)
+            .max_age(self.ttl.try_into().unwrap_or_default())
+            .build()
+    }
+}
+
+pub async fn load_session(
+    jar: &CookieJar,
+    session_store: &dyn SessionStore,
+) -> Result<Option<Session>, Error> {
+    match jar.get(SESSION_COOKIE_NAME) {
+        Some(cookie) => session_store.load(cookie.value()).await,
+        None => Ok(None),
+    }
+}
+
+// Operator logged in through the login form, so that pages taking it redirect anonymous browsers
+// to the login form
+#[derive(Clone, Debug)]
+pub struct AdminSession {
+    pub user_id: Uuid,
+    pub csrf_token: String,
+    session: Session,
+}
+
+impl AdminSession {
+    pub fn verify_csrf_token(&self, csrf_token: &str) -> bool {
+        self.session.verify_csrf_token(csrf_token)
+    }
+
+    // Leaves a message for the page the browser is redirected to next
+    pub async fn add_flash_message(
+        &mut self,
+        session_store: &dyn SessionStore,
+        message: String,
+    ) -> Result<(), Error> {
+        self.session.add_flash_message(message);
+        session_store.save(&self.session).await
+    }
+
+    // Flash messages are shown once, so reading them removes them from the session
+    pub async fn take_flash_messages(
+        &mut self,
+        session_store: &dyn SessionStore,
+    ) -> Result<Vec<String>, Error> {
+        let flash_messages = self.session.take_flash_messages();
+        if !flash_messages.is_empty() {
+            session_store.save(&self.session).await?;
+        }
+        Ok(flash_messages)
+    }
+}
+
+impl<S> FromRequestParts<S> for AdminSession
+where
+    S: Send + Sync,
+    Arc<dyn SessionStore>: FromRef<S>,
+{
+    type Rejection = axum::response::Response;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let jar = CookieJar::from_headers(&parts.headers);
+        let session_store = Arc::<dyn SessionStore>::from_ref(state);
+
+        match load_session(&jar, session_store.as_ref()).await {
+            Ok(Some(session)) => match session.user_id() {
+                Some(user_id) => Ok(Self {
+                    user_id: *user_id,
+                    csrf_token: session.csrf_token().into(),
+                    session,
+                }),
+                None => Err(Redirect::to("/login").into_response()),
+            },
+            Ok(None) => Err(Redirect::to("/login").into_response()),
+            Err(error) => {
+                tracing::error!("{:?}", error);
+                Err(StatusCode::INTERNAL_SERVER_ERROR.into_response())
+            }
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use url::Url;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Failed to process Email request.")]
+    OperationFailed(#[source] anyhow::Error),
+    // Retrying cannot help, e.g. the recipient address does not exist or has been deactivated
+    #[error("The email has been rejected permanently.")]
+    Rejected(#[source] anyhow::Error),
+}
+
+#[async_trait::async_trait]
+pub trait EmailClient: Send + Sync + Clone + 'static {
+    async fn send(&self, recipient: &str, message: &EmailMessage) -> Result<(), Error>;
+}
+
+// Rendered email, carrying both parts so that clients can send a multipart alternative
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EmailMessage {
+    subject: String,
+    html_body: String,
+    text_body: String,
+    unsubscribe_url: Option<String>,
+}
+
+impl EmailMessage {
+    pub fn new(subject: String, html_body: String, text_body: String) -> Self {
+        Self {
+            subject,
+            html_body,
+            text_body,
+            unsubscribe_url: None,
+        }
+    }
+
+    // Clients announce the link in List-Unsubscribe headers, so that mail clients can offer
+    // one-click unsubscription (RFC 8058) next to the message
+    pub fn with_unsubscribe_url(mut self, unsubscribe_url: &Url) -> Self {
+        self.unsubscribe_url = Some(unsubscribe_url.as_str().into());
+        self
+    }
+
+    pub fn subject(&self) -> &str {
+        &self.subject
+    }
+
+    pub fn html_body(&self) -> &str {
+        &self.html_body
+    }
+
+    pub fn text_body(&self) -> &str {
+        &self.text_body
+    }
+
+    pub fn unsubscribe_url(&self) -> Option<&str> {
+        self.unsubscribe_url.as_deref()
+    }
+}
@@ -0,0 +1,358 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::anyhow;
+use anyhow::Context;
+use lettre::message::header::HeaderName;
+use lettre::message::header::HeaderValue;
+use lettre::message::Mailbox;
+use lettre::message::MultiPart;
+use lettre::AsyncSmtpTransport;
+use lettre::AsyncTransport;
+use lettre::Message;
+use lettre::Tokio1Executor;
+use reqwest::Client;
+use reqwest::StatusCode;
+use secrecy::ExposeSecret;
+use secrecy::SecretString;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::common::email::EmailClient;
+use crate::common::email::EmailMessage;
+use crate::common::email::Error;
+
+#[derive(Clone)]
+pub struct PostmarkEmailClient {
+    client: Client,
+    host: String,
+    sender: String,
+    token: SecretString,
+    timeout: Duration,
+}
+
+impl PostmarkEmailClient {
+    pub fn new(
+        client: Client,
+        host: String,
+        sender: String,
+        token: SecretString,
+        timeout: Duration,
+    ) -> Self {
+        Self {
+            client,
+            host,
+            sender,
+            token,
+            timeout,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl EmailClient for PostmarkEmailClient {
+    #[tracing::instrument(name = "Sending email through Postmark", skip_all)]
+    async fn send(&self, recipient: &str, message: &EmailMessage) -> Result<(), Error> {
+        let url = format!("{}/email", self.host);
+        let body = PostmarkSendEmailRequest {
+            from: self.sender.as_ref(),
+            to: recipient,
+            subject: message.subject(),
+            html_body: message.html_body(),
+            text_body: message.text_body(),
+            headers: list_unsubscribe_headers(message)
+                .into_iter()
+                .map(|(name, value)| PostmarkHeader { name, value })
+                .collect(),
+        };
+
+        let response = self
+            .client
+            .post(url)
+            .header("Accept", "application/json")
+            .header("X-Postmark-Server-Token", self.token.expose_secret())
+            .json(&body)
+            .timeout(self.timeout)
+            .send()
+            .await
+            .context("Failed to send a email")
+            .map_err(Error::OperationFailed)?;
+
+        let status = response.status();
+        if status.is_success() {
+            return Ok(());
+        }
+
+        // Postmark describes why it rejected the email in the body, which is kept as the cause
+        // so that callers can tell e.g. an inactive recipient apart from a bad server token
+        let error = match response.json::<PostmarkErrorResponse>().await {
+            Ok(body) => anyhow!(PostmarkError {
+                status,
+                error_code: body.error_code,
+                message: body.message,
+            }),
+            Err(error) => anyhow!(error).context(format!(
+                "Postmark responded with {} and an unreadable body",
+                status
+            )),
+        };
+        let error = error.context("Succeed to send a email but response is not 2xx");
+        // Postmark answers 422 when the email itself is unacceptable, e.g. an invalid or inactive
+        // recipient, while other statuses such as rate limits or outages may pass on retry
+        if status == StatusCode::UNPROCESSABLE_ENTITY {
+            return Err(Error::Rejected(error));
+        }
+        Err(Error::OperationFailed(error))
+    }
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct PostmarkSendEmailRequest<'a> {
+    from: &'a str,
+    to: &'a str,
+    subject: &'a str,
+    html_body: &'a str,
+    text_body: &'a str,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    headers: Vec<PostmarkHeader>,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct PostmarkHeader {
+    name: &'static str,
+    value: String,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct PostmarkErrorResponse {
+    error_code: i64,
+    message: String,
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("Postmark rejected the email with error code {error_code} ({status}): {message}")]
+pub struct PostmarkError {
+    pub status: StatusCode,
+    pub error_code: i64,
+    pub message: String,
+}
+
+#[derive(Clone)]
+pub struct SmtpEmailClient {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    sender: String,
+}
+
+impl SmtpEmailClient {
+    pub fn new(transport: AsyncSmtpTransport<Tokio1Executor>, sender: String) -> Self {
+        Self { transport, sender }
+    }
+}
+
+#[async_trait::async_trait]
+impl EmailClient for SmtpEmailClient {
+    #[tracing::instrument(name = "Sending email through SMTP", skip_all)]
+    async fn send(&self, recipient: &str, message: &EmailMessage) -> Result<(), Error> {
+        let message = build_message(&self.sender, recipient, message)?;
+
+        self.transport.send(message).await.map_err(|error| {
+            // 5xx replies such as an unknown mailbox are permanent, 4xx ones are worth retrying
+            if error.is_permanent() {
+                Error::Rejected(anyhow!(error).context("SMTP server rejected a email"))
+            } else {
+                Error::OperationFailed(anyhow!(error).context("Failed to send a email"))
+            }
+        })?;
+
+        Ok(())
+    }
+}
+
+// Writes each email as an .eml file into the directory, so that local runs need no mail server
+#[derive(Clone)]
+pub struct FileEmailClient {
+    directory: PathBuf,
+    sender: String,
+}
+
+impl FileEmailClient {
+    pub fn new(directory: PathBuf, sender: String) -> Self {
+        Self { directory, sender }
+    }
+}
+
+#[async_trait::async_trait]
+impl EmailClient for FileEmailClient {
+    #[tracing::instrument(name = "Writing email to file", skip_all)]
+    async fn send(&self, recipient: &str, message: &EmailMessage) -> Result<(), Error> {
+        let message = build_message(&self.sender, recipient, message)?;
+
+        tokio::fs::create_dir_all(&self.directory)
+            .await
+            .context("Failed to create email directory")
+            .map_err(Error::OperationFailed)?;
+        // UUID v7 keeps the files sorted in the order they were written
+        let path = self.directory.join(format!("{}.eml", Uuid::now_v7()));
+        tokio::fs::write(&path, message.formatted())
+            .await
+            .context("Failed to write a email")
+            .map_err(Error::OperationFailed)?;
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct CapturedEmail {
+    pub sender: String,
+    pub recipient: String,
+    pub message: EmailMessage,
+}
+
+// Emails captured by in-memory clients, shared with whoever needs to inspect them
+#[derive(Clone, Default)]
+pub struct InMemoryMailbox {
+    emails: Arc<RwLock<Vec<CapturedEmail>>>,
+}
+
+impl InMemoryMailbox {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn emails(&self) -> Vec<CapturedEmail> {
+        self.emails.read().await.clone()
+    }
+
+    pub async fn emails_to(&self, recipient: &str) -> Vec<CapturedEmail> {
+        self.emails
+            .read()
+            .await
+            .iter()
+            .filter(|email| email.recipient == recipient)
+            .cloned()
+            .collect()
+    }
+}
+
+#[derive(Clone)]
+pub struct InMemoryEmailClient {
+    mailbox: InMemoryMailbox,
+    sender: String,
+}
+
+impl InMemoryEmailClient {
+    pub fn new(mailbox: InMemoryMailbox, sender: String) -> Self {
+        Self { mailbox, sender }
+    }
+
+    pub fn mailbox(&self) -> &InMemoryMailbox {
+        &self.mailbox
+    }
+}
+
+#[async_trait::async_trait]
+impl EmailClient for InMemoryEmailClient {
+    async fn send(&self, recipient: &str, message: &EmailMessage) -> Result<(), Error> {
+        self.mailbox.emails.write().await.push(CapturedEmail {
+            sender: self.sender.clone(),
+            recipient: recipient.into(),
+            message: message.clone(),
+        });
+        Ok(())
+    }
+}
+
+// Writes emails to stdout instead of delivering them, so that local runs need no mail server
+#[derive(Clone)]
+pub struct StdoutEmailClient {
+    sender: String,
+}
+
+impl StdoutEmailClient {
+    pub fn new(sender: String) -> Self {
+        Self { sender }
+    }
+}
+
+#[async_trait::async_trait]
+impl EmailClient for StdoutEmailClient {
+    async fn send(&self, recipient: &str, message: &EmailMessage) -> Result<(), Error> {
+        println!(
+            "From: {}\nTo: {}\nSubject: {}\n\n{}\n",
+            self.sender,
+            recipient,
+            message.subject(),
+            message.text_body()
+        );
+        Ok(())
+    }
+}
+
+// Email client chosen at runtime by the configured backend
+#[derive(Clone)]
+pub enum ConfiguredEmailClient {
+    HttpApi(PostmarkEmailClient),
+    Smtp(SmtpEmailClient),
+    Stdout(StdoutEmailClient),
+    File(FileEmailClient),
+    InMemory(InMemoryEmailClient),
+}
+
+#[async_trait::async_trait]
+impl EmailClient for ConfiguredEmailClient {
+    async fn send(&self, recipient: &str, message: &EmailMessage) -> Result<(), Error> {
+        match self {
+            ConfiguredEmailClient::HttpApi(client) => client.send(recipient, message).await,
+            ConfiguredEmailClient::Smtp(client) => client.send(recipient, message).await,
+            ConfiguredEmailClient::Stdout(client) => client.send(recipient, message).await,
+            ConfiguredEmailClient::File(client) => client.send(recipient, message).await,
+            ConfiguredEmailClient::InMemory(client) => client.send(recipient, message).await,
+        }
+    }
+}
+
+fn build_message(sender: &str, recipient: &str, message: &EmailMessage) -> Result<Message, Error> {
+    let from: Mailbox = sender
+        .parse()
+        .context("Failed to parse sender address")
+        .map_err(Error::OperationFailed)?;
+    let to: Mailbox = recipient
+        .parse()
+        .context("Failed to parse recipient address")
+        .map_err(Error::Rejected)?;
+
+    let mut builder = Message::builder()
+        .from(from)
+        .to(to)
+        .subject(message.subject());
+    for (name, value) in list_unsubscribe_headers(message) {
+        builder = builder.raw_header(HeaderValue::new(
+            HeaderName::new_from_ascii_str(name),
+            value,
+        ));
+    }
+
+    builder
+        .multipart(MultiPart::alternative_plain_html(
+            message.text_body().to_owned(),
+            message.html_body().to_owned(),
+        ))
+        .context("Failed to build a email")
+        .map_err(Error::OperationFailed)
+}
+
+// Mail clients offer one-click unsubscription (RFC 8058) for messages carrying both headers
+fn list_unsubscribe_headers(message: &EmailMessage) -> Vec<(&'static str, String)> {
+    match message.unsubscribe_url() {
+        Some(url) => vec![
+            ("List-Unsubscribe", format!("<{}>", url)),
+            ("List-Unsubscribe-Post", "List-Unsubscribe=One-Click".into()),
+        ],
+        None => Vec::new(),
+    }
+}
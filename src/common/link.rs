@@ -0,0 +1,136 @@
+use hmac::Hmac;
+use hmac::Mac;
+use secrecy::ExposeSecret;
+use secrecy::SecretString;
+use sha2::Sha256;
+use url::Url;
+use uuid::Uuid;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Failed to verify the token.")]
+    TokenInvalid,
+}
+
+// Builds links to this service from its externally visible base URL
+#[derive(Clone, Debug)]
+pub struct LinkBuilder {
+    base_url: Url,
+}
+
+impl LinkBuilder {
+    pub fn new(mut base_url: Url) -> Self {
+        // Without a trailing slash, joining would replace the last segment of a path prefix
+        if !base_url.path().ends_with('/') {
+            let path = format!("{}/", base_url.path());
+            base_url.set_path(&path);
+        }
+        Self { base_url }
+    }
+
+    pub fn confirm_subscription(&self, token: &str) -> Url {
+        let mut url = self
+            .base_url
+            .join("subscriptions/confirm")
+            .expect("Failed to join relative path to base URL");
+        url.query_pairs_mut().append_pair("token", token);
+        url
+    }
+
+    pub fn unsubscribe(&self, token: &str) -> Url {
+        let mut url = self
+            .base_url
+            .join("subscriptions/unsubscribe")
+            .expect("Failed to join relative path to base URL");
+        url.query_pairs_mut().append_pair("token", token);
+        url
+    }
+}
+
+// Signed proof of the subscriber an unsubscribe link has been issued to, which any context mailing
+// subscribers attaches to its emails
+#[derive(Clone, Debug)]
+pub struct UnsubscribeToken {
+    subscriber_id: Uuid,
+    signature: String,
+}
+
+impl UnsubscribeToken {
+    pub fn issue(subscriber_id: Uuid, key: &SecretString) -> Self {
+        let signature = hex::encode(
+            UnsubscribeToken::mac(&subscriber_id, key)
+                .finalize()
+                .into_bytes(),
+        );
+
+        Self {
+            subscriber_id,
+            signature,
+        }
+    }
+
+    pub fn verify(token: &str, key: &SecretString) -> Result<Self, Error> {
+        let invalid = || Error::TokenInvalid;
+
+        let (subscriber_id, signature) = token.split_once('.').ok_or_else(invalid)?;
+        let subscriber_id = Uuid::parse_str(subscriber_id).map_err(|_| invalid())?;
+        let signature_bytes = hex::decode(signature).map_err(|_| invalid())?;
+
+        // Compare signatures in constant time so that tokens cannot be guessed byte by byte
+        UnsubscribeToken::mac(&subscriber_id, key)
+            .verify_slice(&signature_bytes)
+            .map_err(|_| invalid())?;
+
+        Ok(Self {
+            subscriber_id,
+            signature: signature.into(),
+        })
+    }
+
+    fn mac(subscriber_id: &Uuid, key: &SecretString) -> Hmac<Sha256> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(key.expose_secret().as_bytes())
+            .expect("HMAC can take key of any size");
+        mac.update(subscriber_id.as_bytes());
+        mac
+    }
+
+    pub fn token(&self) -> String {
+        format!("{}.{}", self.subscriber_id, self.signature)
+    }
+
+    pub fn subscriber_id(&self) -> &Uuid {
+        &self.subscriber_id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[rstest::rstest]
+    #[case(
+        "https://example.com",
+        "https://example.com/subscriptions/confirm?token=abc"
+    )]
+    #[case(
+        "https://example.com/newsletter",
+        "https://example.com/newsletter/subscriptions/confirm?token=abc"
+    )]
+    #[case(
+        "https://example.com/newsletter/",
+        "https://example.com/newsletter/subscriptions/confirm?token=abc"
+    )]
+    fn link_builder_keeps_path_prefix_of_base_url(#[case] base_url: &str, #[case] expected: &str) {
+        let link_builder = LinkBuilder::new(Url::parse(base_url).unwrap());
+        assert_eq!(link_builder.confirm_subscription("abc").as_str(), expected);
+    }
+
+    #[test]
+    fn link_builder_builds_unsubscribe_link_with_token() {
+        let link_builder = LinkBuilder::new(Url::parse("https://example.com/newsletter").unwrap());
+        assert_eq!(
+            link_builder.unsubscribe("abc.def").as_str(),
+            "https://example.com/newsletter/subscriptions/unsubscribe?token=abc.def"
+        );
+    }
+}
@@ -0,0 +1,35 @@
+use strum::AsRefStr;
+use strum::EnumString;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, EnumString, AsRefStr)]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+pub enum Locale {
+    #[default]
+    En,
+    Ko,
+}
+
+impl Locale {
+    // Tags such as ko-KR are matched by their primary subtag, so that regional variants share
+    // the same translations
+    pub fn parse(tag: &str) -> Option<Self> {
+        let primary = tag.trim().split(['-', '_']).next()?;
+        primary.parse().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[rstest::rstest]
+    #[case("en", Some(Locale::En))]
+    #[case("ko", Some(Locale::Ko))]
+    #[case("ko-KR", Some(Locale::Ko))]
+    #[case("EN_us", Some(Locale::En))]
+    #[case("fr-FR", None)]
+    #[case("", None)]
+    fn locale_is_parsed_by_primary_subtag(#[case] tag: &str, #[case] expected: Option<Locale>) {
+        assert_eq!(Locale::parse(tag), expected);
+    }
+}
@@ -0,0 +1,7 @@
+pub mod email;
+pub mod email_client;
+pub mod link;
+pub mod locale;
+pub mod page;
+pub mod retry;
+pub mod unit_of_work;
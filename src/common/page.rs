@@ -0,0 +1,74 @@
+use std::sync::Arc;
+
+use anyhow::Context;
+use axum::response::Html;
+use minijinja::Environment;
+use serde::Serialize;
+
+// Templates are embedded into the binary so that rendering does not depend on the working
+// directory. Pages of every context share the admin layout, so they are all kept together.
+const TEMPLATES: [(&str, &str); 6] = [
+    (
+        "layout.html",
+        include_str!("../../templates/admin/layout.html"),
+    ),
+    (
+        "login.html",
+        include_str!("../../templates/admin/login.html"),
+    ),
+    (
+        "dashboard.html",
+        include_str!("../../templates/admin/dashboard.html"),
+    ),
+    (
+        "subscribers.html",
+        include_str!("../../templates/admin/subscribers.html"),
+    ),
+    (
+        "newsletters.html",
+        include_str!("../../templates/admin/newsletters.html"),
+    ),
+    (
+        "unsubscribe.html",
+        include_str!("../../templates/subscription/unsubscribe.html"),
+    ),
+];
+
+#[derive(Clone)]
+pub struct PageRenderer {
+    environment: Arc<Environment<'static>>,
+}
+
+impl PageRenderer {
+    pub fn new() -> Self {
+        // Pages are auto-escaped by their .html extension
+        let mut environment = Environment::new();
+        for (name, source) in TEMPLATES {
+            environment
+                .add_template(name, source)
+                .expect("Failed to parse page template");
+        }
+
+        Self {
+            environment: Arc::new(environment),
+        }
+    }
+
+    pub fn render(
+        &self,
+        name: &str,
+        context: impl Serialize,
+    ) -> Result<Html<String>, anyhow::Error> {
+        self.environment
+            .get_template(name)
+            .and_then(|template| template.render(context))
+            .map(Html)
+            .with_context(|| format!("Failed to render {} page", name))
+    }
+}
+
+impl Default for PageRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,56 @@
+use std::time::Duration;
+
+use chrono::TimeDelta;
+
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+    max_attempts: i32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+}
+
+impl RetryPolicy {
+    pub fn new(max_attempts: i32, initial_backoff: Duration, max_backoff: Duration) -> Self {
+        Self {
+            max_attempts,
+            initial_backoff,
+            max_backoff,
+        }
+    }
+
+    pub fn max_attempts(&self) -> i32 {
+        self.max_attempts
+    }
+
+    // Exponential backoff doubling the delay on each failed attempt, capped by the maximum
+    pub fn backoff(&self, attempts: i32) -> TimeDelta {
+        let exponent = attempts.saturating_sub(1).clamp(0, 31) as u32;
+        let backoff = self
+            .initial_backoff
+            .saturating_mul(2u32.saturating_pow(exponent))
+            .min(self.max_backoff);
+        TimeDelta::from_std(backoff).unwrap_or(TimeDelta::MAX)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[rstest::rstest]
+    #[case(1, 1)]
+    #[case(2, 2)]
+    #[case(3, 4)]
+    #[case(5, 16)]
+    #[case(10, 60)]
+    fn retry_policy_doubles_backoff_until_it_reaches_maximum(
+        #[case] attempts: i32,
+        #[case] expected_seconds: i64,
+    ) {
+        let retry_policy = RetryPolicy::new(10, Duration::from_secs(1), Duration::from_secs(60));
+        assert_eq!(
+            retry_policy.backoff(attempts),
+            TimeDelta::seconds(expected_seconds)
+        );
+    }
+}
@@ -0,0 +1,33 @@
+use anyhow::Context;
+use sqlx::Pool;
+use sqlx::Postgres;
+
+pub type SqlxTransaction = sqlx::Transaction<'static, Postgres>;
+
+// Every context keeps its aggregates in the same database, so each of them implements its own
+// UnitOfWork trait on this one
+#[derive(Clone)]
+pub struct SqlxUnitOfWork {
+    pool: Pool<Postgres>,
+}
+
+impl SqlxUnitOfWork {
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self { pool }
+    }
+
+    pub async fn begin_transaction(&self) -> Result<SqlxTransaction, anyhow::Error> {
+        self.pool
+            .begin()
+            .await
+            .context("Failed to start transaction")
+    }
+
+    // Dropping a transaction without committing rolls it back
+    pub async fn commit_transaction(transaction: SqlxTransaction) -> Result<(), anyhow::Error> {
+        transaction
+            .commit()
+            .await
+            .context("Failed to commit transaction")
+    }
+}
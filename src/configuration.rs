@@ -1,3 +1,4 @@
+use std::path::PathBuf;
 use std::time::Duration;
 
 use config::ConfigError;
@@ -5,23 +6,37 @@ use config::File;
 use config::FileFormat;
 use duration_str::deserialize_duration;
 use secrecy::SecretString;
+use serde::de::Error as _;
+use serde::Deserialize;
+use serde::Deserializer;
+use url::Url;
 
 #[derive(serde::Deserialize)]
 pub struct Configuration {
     pub application: ApplicationConfiguration,
     pub subscriber: SubscriberConfiguration,
+    pub newsletter: NewsletterConfiguration,
+    pub auth: AuthConfiguration,
 }
 
 #[derive(serde::Deserialize)]
 pub struct ApplicationConfiguration {
     pub host: String,
     pub port: u16,
+    // Externally visible URL of this service, used for links sent outside of it
+    #[serde(deserialize_with = "deserialize_base_url")]
+    pub base_url: Url,
 }
 
 #[derive(serde::Deserialize)]
 pub struct SubscriberConfiguration {
     pub database: DatabaseConfiguration,
     pub email: EmailConfiguration,
+    pub outbox: OutboxConfiguration,
+    pub subscription_token: SubscriptionTokenConfiguration,
+    pub unsubscribe: UnsubscribeConfiguration,
+    pub webhook: WebhookConfiguration,
+    pub concurrency_control: ConcurrencyControlConfiguration,
 }
 
 #[derive(serde::Deserialize)]
@@ -49,16 +64,57 @@ pub struct DatabasePoolConfiguration {
 
 #[derive(serde::Deserialize)]
 pub struct EmailConfiguration {
-    pub server: EmailServerConfiguration,
+    pub backend: EmailBackendConfiguration,
     pub client: EmailClientConfiguration,
+    pub template: EmailTemplateConfiguration,
 }
 
+// Transport used for delivering emails, picked by the `type` key of the backend section
 #[derive(serde::Deserialize)]
-pub struct EmailServerConfiguration {
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum EmailBackendConfiguration {
+    // Postmark compatible HTTP API
+    HttpApi(HttpApiConfiguration),
+    // Self-hosted mail server speaking SMTP
+    Smtp(SmtpConfiguration),
+    // Prints emails instead of delivering them, for running the application locally
+    Stdout,
+    // Writes emails as .eml files into a directory instead of delivering them
+    File(FileConfiguration),
+    // Keeps emails in memory so that tests can inspect them
+    InMemory,
+}
+
+#[derive(serde::Deserialize)]
+pub struct HttpApiConfiguration {
     pub url: String,
+    #[serde(deserialize_with = "deserialize_secret")]
     pub token: SecretString,
 }
 
+#[derive(serde::Deserialize)]
+pub struct FileConfiguration {
+    pub directory: PathBuf,
+}
+
+#[derive(serde::Deserialize)]
+pub struct SmtpConfiguration {
+    pub host: String,
+    pub port: u16,
+    pub username: String,
+    pub password: SecretString,
+    pub tls: SmtpTls,
+}
+
+#[derive(serde::Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SmtpTls {
+    // Upgrade the connection with STARTTLS before authenticating, failing if it is not offered
+    StartTls,
+    // Plaintext connection, only for trusted local relays
+    None,
+}
+
 #[derive(serde::Deserialize)]
 pub struct EmailClientConfiguration {
     pub sender: String,
@@ -66,6 +122,105 @@ pub struct EmailClientConfiguration {
     pub timeout: Duration,
 }
 
+#[derive(serde::Deserialize)]
+pub struct EmailTemplateConfiguration {
+    pub sender_name: String,
+}
+
+#[derive(serde::Deserialize)]
+pub struct OutboxConfiguration {
+    pub batch_size: i64,
+    #[serde(deserialize_with = "deserialize_duration")]
+    pub interval: Duration,
+    pub retry: RetryConfiguration,
+}
+
+#[derive(serde::Deserialize)]
+pub struct WebhookConfiguration {
+    pub batch_size: i64,
+    #[serde(deserialize_with = "deserialize_duration")]
+    pub interval: Duration,
+    // How long a receiver may take to respond before the attempt counts as failed
+    #[serde(deserialize_with = "deserialize_duration")]
+    pub timeout: Duration,
+    pub retry: RetryConfiguration,
+    // Lets endpoints target private or loopback addresses, which only receivers running alongside
+    // the service, e.g. in tests, should need
+    pub allow_private_targets: bool,
+}
+
+#[derive(serde::Deserialize)]
+pub struct RetryConfiguration {
+    pub max_attempts: i32,
+    #[serde(deserialize_with = "deserialize_duration")]
+    pub initial_backoff: Duration,
+    #[serde(deserialize_with = "deserialize_duration")]
+    pub max_backoff: Duration,
+}
+
+#[derive(serde::Deserialize)]
+pub struct SubscriptionTokenConfiguration {
+    // Hashes subscription tokens, so that anyone knowing it could compute them offline
+    #[serde(deserialize_with = "deserialize_secret")]
+    pub key: SecretString,
+}
+
+#[derive(serde::Deserialize)]
+pub struct UnsubscribeConfiguration {
+    // Signs unsubscribe links, so that anyone knowing it could unsubscribe anyone
+    #[serde(deserialize_with = "deserialize_secret")]
+    pub key: SecretString,
+}
+
+#[derive(serde::Deserialize)]
+pub struct NewsletterConfiguration {
+    pub delivery: IssueDeliveryConfiguration,
+}
+
+#[derive(serde::Deserialize)]
+pub struct IssueDeliveryConfiguration {
+    pub batch_size: i64,
+    #[serde(deserialize_with = "deserialize_duration")]
+    pub interval: Duration,
+    pub retry: RetryConfiguration,
+}
+
+#[derive(serde::Deserialize)]
+pub struct AuthConfiguration {
+    pub session: SessionConfiguration,
+}
+
+#[derive(serde::Deserialize)]
+pub struct SessionConfiguration {
+    pub store: SessionStoreConfiguration,
+    // Lifetime of a session since it was started or logged in
+    #[serde(deserialize_with = "deserialize_duration")]
+    pub ttl: Duration,
+    // Marks the session cookie to be sent over HTTPS only, which environments served over plain
+    // HTTP have to turn off
+    pub secure_cookie: bool,
+}
+
+// Storage of browser sessions, picked by the `type` key of the store section
+#[derive(serde::Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum SessionStoreConfiguration {
+    // Sessions table in the application database, surviving restarts and shared by replicas
+    Postgres,
+    // Keeps sessions in process memory, for tests and single-instance local runs
+    InMemory,
+}
+
+// Protection of subscribers against concurrent modifications
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ConcurrencyControlConfiguration {
+    // Locks subscribers while they are being modified
+    Pessimistic,
+    // Detects conflicting modifications by version and retries the losing command
+    Optimistic,
+}
+
 pub enum Environment {
     Local,
     Test,
@@ -98,14 +253,147 @@ impl TryFrom<String> for Environment {
     }
 }
 
+// Environment variables override the files, e.g. APP__APPLICATION__BASE_URL sets
+// application.base_url, so that deployments provide their own URLs and secrets
 pub fn get_configuration(env: Environment) -> Result<Configuration, ConfigError> {
+    get_configuration_from(env, None)
+}
+
+// Reads the given variables in place of the process environment when there are any
+fn get_configuration_from(
+    env: Environment,
+    variables: Option<config::Map<String, String>>,
+) -> Result<Configuration, ConfigError> {
     let configuration = config::Config::builder()
         .add_source(File::new("configuration/default.yaml", FileFormat::Yaml))
         .add_source(File::new(
             format!("configuration/{}.yaml", env.as_str()).as_str(),
             FileFormat::Yaml,
         ))
+        .add_source(
+            config::Environment::with_prefix("APP")
+                .prefix_separator("__")
+                .separator("__")
+                .try_parsing(true)
+                .source(variables),
+        )
         .build()?;
 
     configuration.try_deserialize::<Configuration>()
 }
+
+fn deserialize_base_url<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Url, D::Error> {
+    let raw = String::deserialize(deserializer)?;
+    let base_url = Url::parse(&raw)
+        .map_err(|error| D::Error::custom(format!("{} is not an absolute URL: {}", raw, error)))?;
+
+    if !matches!(base_url.scheme(), "http" | "https") || base_url.cannot_be_a_base() {
+        return Err(D::Error::custom(format!(
+            "{} is not an absolute http(s) URL",
+            raw
+        )));
+    }
+
+    Ok(base_url)
+}
+
+// Keys and tokens have no default outside local and test environments, and placeholders standing in
+// for them are refused, so that no deployment runs with a secret known to everyone
+fn deserialize_secret<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<SecretString, D::Error> {
+    let raw = String::deserialize(deserializer)?;
+    if raw.trim().is_empty() || raw.starts_with("SECRET_") {
+        return Err(D::Error::custom(
+            "Secret is missing or still a placeholder, and has to be provided through environment variables",
+        ));
+    }
+
+    Ok(raw.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::de::value::Error;
+    use serde::de::value::StrDeserializer;
+    use serde::de::IntoDeserializer;
+
+    use super::*;
+
+    #[rstest::rstest]
+    #[case("http://127.0.0.1:8080")]
+    #[case("https://example.com/newsletter/")]
+    fn absolute_base_urls_are_accepted(#[case] raw: &str) {
+        let deserializer: StrDeserializer<Error> = raw.into_deserializer();
+        assert!(deserialize_base_url(deserializer).is_ok());
+    }
+
+    const PRODUCTION_VARIABLES: [(&str, &str); 4] = [
+        (
+            "APP__APPLICATION__BASE_URL",
+            "https://example.com/newsletter/",
+        ),
+        (
+            "APP__SUBSCRIBER__SUBSCRIPTION_TOKEN__KEY",
+            "subscription-token-key",
+        ),
+        ("APP__SUBSCRIBER__UNSUBSCRIBE__KEY", "unsubscribe-key"),
+        (
+            "APP__SUBSCRIBER__EMAIL__BACKEND__TOKEN",
+            "postmark-server-token",
+        ),
+    ];
+
+    fn production_variables_without(missing: Option<&str>) -> config::Map<String, String> {
+        PRODUCTION_VARIABLES
+            .into_iter()
+            .filter(|(name, _)| Some(*name) != missing)
+            .map(|(name, value)| (name.into(), value.into()))
+            .collect()
+    }
+
+    #[test]
+    fn base_url_and_secrets_of_production_are_provided_through_environment_variables() {
+        let variables = production_variables_without(None);
+
+        let configuration = get_configuration_from(Environment::Production, Some(variables));
+
+        assert_eq!(
+            configuration.unwrap().application.base_url.as_str(),
+            "https://example.com/newsletter/"
+        );
+    }
+
+    #[rstest::rstest]
+    #[case("APP__APPLICATION__BASE_URL")]
+    #[case("APP__SUBSCRIBER__SUBSCRIPTION_TOKEN__KEY")]
+    #[case("APP__SUBSCRIBER__UNSUBSCRIBE__KEY")]
+    #[case("APP__SUBSCRIBER__EMAIL__BACKEND__TOKEN")]
+    fn production_is_refused_when_base_url_or_any_secret_is_not_provided(#[case] missing: &str) {
+        let variables = production_variables_without(Some(missing));
+
+        let configuration = get_configuration_from(Environment::Production, Some(variables));
+
+        assert!(configuration.is_err());
+    }
+
+    #[rstest::rstest]
+    #[case("")]
+    #[case("SECRET_SUBSCRIPTION_TOKEN_KEY")]
+    #[case("SECRET_UNSUBSCRIBE_KEY")]
+    #[case("SECRET_TOKEN")]
+    fn missing_or_placeholder_secrets_are_rejected(#[case] raw: &str) {
+        let deserializer: StrDeserializer<Error> = raw.into_deserializer();
+        assert!(deserialize_secret(deserializer).is_err());
+    }
+
+    #[rstest::rstest]
+    #[case("/subscriptions")]
+    #[case("example.com")]
+    #[case("mailto:newsletter@example.com")]
+    #[case("ftp://example.com")]
+    fn relative_or_non_http_base_urls_are_rejected(#[case] raw: &str) {
+        let deserializer: StrDeserializer<Error> = raw.into_deserializer();
+        assert!(deserialize_base_url(deserializer).is_err());
+    }
+}
@@ -0,0 +1,373 @@
+use std::future::Future;
+use std::net::IpAddr;
+use std::time::Duration;
+
+use anyhow::anyhow;
+use anyhow::Context;
+use chrono::TimeDelta;
+use chrono::Utc;
+use sha2::Digest;
+use sha2::Sha256;
+use sqlx::Pool;
+use sqlx::Postgres;
+use uuid::Uuid;
+
+const MAX_IDEMPOTENCY_KEY_LENGTH: usize = 255;
+
+// Saved responses are replayed to retries within this period, and purged afterwards
+const IDEMPOTENCY_KEY_TTL: TimeDelta = TimeDelta::hours(24);
+
+// A key claimed by a request that never completed, e.g. because the process stopped while
+// handling it, can be claimed again by a retry after this period. The lease is renewed while the
+// request is being handled, so that a slow request is never handled twice.
+const IDEMPOTENCY_KEY_LEASE: TimeDelta = TimeDelta::minutes(5);
+const IDEMPOTENCY_KEY_LEASE_RENEWAL_INTERVAL: Duration = Duration::from_secs(60);
+
+// A concurrent duplicate waits this long at most for the first request to complete, polling for
+// its response, before it is told to retry later
+const IDEMPOTENCY_KEY_WAIT_TIMEOUT: Duration = Duration::from_secs(10);
+const IDEMPOTENCY_KEY_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Clone, Debug)]
+pub struct IdempotencyKey(String);
+
+impl IdempotencyKey {
+    pub fn parse(key: &str) -> Result<Self, String> {
+        if key.trim().is_empty() {
+            return Err("Idempotency-Key cannot be empty".into());
+        }
+
+        if key.len() > MAX_IDEMPOTENCY_KEY_LENGTH {
+            return Err(format!(
+                "Idempotency-Key cannot be longer than {}",
+                MAX_IDEMPOTENCY_KEY_LENGTH
+            ));
+        }
+
+        Ok(Self(key.into()))
+    }
+}
+
+impl AsRef<str> for IdempotencyKey {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+// Caller a key belongs to, derived from the identity the caller authenticates as, so that one
+// caller can neither replay nor block the responses of another while logging in again keeps the
+// replay. Anonymous callers are told apart by their address instead.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Principal(String);
+
+impl Principal {
+    pub fn operator(user_id: &Uuid) -> Self {
+        Self::digest("operator", &user_id.to_string())
+    }
+
+    pub fn api_client(api_key_id: &Uuid) -> Self {
+        Self::digest("api_client", &api_key_id.to_string())
+    }
+
+    pub fn anonymous(address: IpAddr) -> Self {
+        Self::digest("address", &address.to_string())
+    }
+
+    fn digest(kind: &str, value: &str) -> Self {
+        let mut hasher = Sha256::new();
+        hasher.update(kind.as_bytes());
+        hasher.update([0]);
+        hasher.update(value.as_bytes());
+        Self(hex::encode(hasher.finalize()))
+    }
+}
+
+impl AsRef<str> for Principal {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+// Digest of the request a key has been used with, so that reusing the key for another request is
+// told apart from retrying the same one
+#[derive(Clone, Debug, PartialEq)]
+pub struct RequestFingerprint(String);
+
+impl RequestFingerprint {
+    pub fn new(method: &str, path: &str, body: &[u8]) -> Self {
+        let mut hasher = Sha256::new();
+        hasher.update(method.as_bytes());
+        hasher.update([0]);
+        hasher.update(path.as_bytes());
+        hasher.update([0]);
+        hasher.update(body);
+        Self(hex::encode(hasher.finalize()))
+    }
+}
+
+impl AsRef<str> for RequestFingerprint {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SavedResponse {
+    pub status_code: u16,
+    pub headers: Vec<(String, Vec<u8>)>,
+    pub body: Vec<u8>,
+}
+
+pub enum Reservation {
+    // The key is new, and has to be either completed or released once the request is handled
+    Acquired,
+    // The key is being used by another request which has not completed in time
+    InProgress,
+    // The key has been used with another request
+    Mismatched,
+    // The key has been used already, and this is the response to replay
+    Completed(SavedResponse),
+}
+
+// Every operation runs in a short transaction of its own, so that no connection is held while
+// requests are being handled
+#[derive(Clone)]
+pub struct SqlxIdempotencyStore {
+    pool: Pool<Postgres>,
+}
+
+impl SqlxIdempotencyStore {
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self { pool }
+    }
+
+    // A concurrent duplicate is held back until the first request either completes, so that its
+    // response is replayed, or is released, so that the duplicate is handled instead
+    #[tracing::instrument(name = "Reserving idempotency key", skip_all, fields(key = ?key))]
+    pub async fn reserve(
+        &self,
+        principal: &Principal,
+        key: &IdempotencyKey,
+        fingerprint: &RequestFingerprint,
+    ) -> Result<Reservation, anyhow::Error> {
+        self.purge(principal, key).await?;
+
+        let deadline = tokio::time::Instant::now() + IDEMPOTENCY_KEY_WAIT_TIMEOUT;
+        loop {
+            let reservation = self.try_reserve(principal, key, fingerprint).await?;
+            if !matches!(reservation, Reservation::InProgress)
+                || tokio::time::Instant::now() >= deadline
+            {
+                return Ok(reservation);
+            }
+            tokio::time::sleep(IDEMPOTENCY_KEY_POLL_INTERVAL).await;
+        }
+    }
+
+    // Runs the request holding the key, renewing its lease until the request is handled
+    pub async fn hold<F: Future>(
+        &self,
+        principal: &Principal,
+        key: &IdempotencyKey,
+        handling: F,
+    ) -> F::Output {
+        tokio::pin!(handling);
+        let mut renewal = tokio::time::interval_at(
+            tokio::time::Instant::now() + IDEMPOTENCY_KEY_LEASE_RENEWAL_INTERVAL,
+            IDEMPOTENCY_KEY_LEASE_RENEWAL_INTERVAL,
+        );
+        loop {
+            tokio::select! {
+                output = &mut handling => return output,
+                _ = renewal.tick() => {
+                    if let Err(error) = self.renew(principal, key).await {
+                        tracing::error!("Failed to renew idempotency key: {:?}", error);
+                    }
+                }
+            }
+        }
+    }
+
+    // Expired keys of every caller are purged once per reservation, so the table only keeps the
+    // responses which may still be replayed, along with the lease of this key if it has lapsed
+    async fn purge(
+        &self,
+        principal: &Principal,
+        key: &IdempotencyKey,
+    ) -> Result<(), anyhow::Error> {
+        let now = Utc::now().naive_utc();
+        sqlx::query!(
+            "DELETE FROM idempotency WHERE created_at < $1 OR (principal = $2 AND idempotency_key = $3 AND response_status_code IS NULL AND created_at < $4)",
+            now - IDEMPOTENCY_KEY_TTL,
+            principal.as_ref(),
+            key.as_ref(),
+            now - IDEMPOTENCY_KEY_LEASE,
+        )
+        .execute(&self.pool)
+        .await
+        .context("Failed to purge expired idempotency keys")?;
+
+        Ok(())
+    }
+
+    async fn try_reserve(
+        &self,
+        principal: &Principal,
+        key: &IdempotencyKey,
+        fingerprint: &RequestFingerprint,
+    ) -> Result<Reservation, anyhow::Error> {
+        let now = Utc::now().naive_utc();
+        // A concurrent duplicate blocks on the uncommitted row only until the insert commits
+        let inserted = sqlx::query!(
+            "INSERT INTO idempotency (principal, idempotency_key, request_fingerprint, created_at) VALUES ($1, $2, $3, $4) ON CONFLICT DO NOTHING",
+            principal.as_ref(),
+            key.as_ref(),
+            fingerprint.as_ref(),
+            now,
+        )
+        .execute(&self.pool)
+        .await
+        .context("Failed to claim idempotency key")?
+        .rows_affected();
+
+        if inserted > 0 {
+            return Ok(Reservation::Acquired);
+        }
+
+        let Some(saved) = sqlx::query!(
+            "SELECT request_fingerprint, response_status_code, response_header_names, response_header_values, response_body FROM idempotency WHERE principal = $1 AND idempotency_key = $2",
+            principal.as_ref(),
+            key.as_ref(),
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .context("Failed to find saved response")?
+        else {
+            // The first request has been released in the meantime, so this one claims the key again
+            return Ok(Reservation::InProgress);
+        };
+
+        if saved.request_fingerprint != fingerprint.as_ref() {
+            return Ok(Reservation::Mismatched);
+        }
+
+        match (
+            saved.response_status_code,
+            saved.response_header_names,
+            saved.response_header_values,
+            saved.response_body,
+        ) {
+            (Some(status_code), Some(names), Some(values), Some(body)) => {
+                Ok(Reservation::Completed(SavedResponse {
+                    status_code: status_code as u16,
+                    headers: names.into_iter().zip(values).collect(),
+                    body,
+                }))
+            }
+            (None, None, None, None) => Ok(Reservation::InProgress),
+            _ => Err(anyhow!("Saved response of idempotency key is incomplete")),
+        }
+    }
+
+    #[tracing::instrument(name = "Completing idempotency key", skip_all, fields(key = ?key))]
+    pub async fn complete(
+        &self,
+        principal: &Principal,
+        key: &IdempotencyKey,
+        response: &SavedResponse,
+    ) -> Result<(), anyhow::Error> {
+        let (names, values): (Vec<String>, Vec<Vec<u8>>) = response.headers.iter().cloned().unzip();
+
+        sqlx::query!(
+            "UPDATE idempotency SET response_status_code = $1, response_header_names = $2, response_header_values = $3, response_body = $4 WHERE principal = $5 AND idempotency_key = $6",
+            response.status_code as i16,
+            &names,
+            &values,
+            response.body,
+            principal.as_ref(),
+            key.as_ref(),
+        )
+        .execute(&self.pool)
+        .await
+        .context("Failed to save response")?;
+
+        Ok(())
+    }
+
+    // Saved responses are purged by when they were claimed, which only moves while they are not
+    // completed yet
+    #[tracing::instrument(name = "Renewing idempotency key", skip_all, fields(key = ?key))]
+    async fn renew(
+        &self,
+        principal: &Principal,
+        key: &IdempotencyKey,
+    ) -> Result<(), anyhow::Error> {
+        sqlx::query!(
+            "UPDATE idempotency SET created_at = $1 WHERE principal = $2 AND idempotency_key = $3 AND response_status_code IS NULL",
+            Utc::now().naive_utc(),
+            principal.as_ref(),
+            key.as_ref(),
+        )
+        .execute(&self.pool)
+        .await
+        .context("Failed to renew idempotency key")?;
+
+        Ok(())
+    }
+
+    // Gives the key up without saving a response, so that a retry is handled again
+    #[tracing::instrument(name = "Releasing idempotency key", skip_all, fields(key = ?key))]
+    pub async fn release(
+        &self,
+        principal: &Principal,
+        key: &IdempotencyKey,
+    ) -> Result<(), anyhow::Error> {
+        sqlx::query!(
+            "DELETE FROM idempotency WHERE principal = $1 AND idempotency_key = $2 AND response_status_code IS NULL",
+            principal.as_ref(),
+            key.as_ref(),
+        )
+        .execute(&self.pool)
+        .await
+        .context("Failed to release idempotency key")?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::IpAddr;
+    use std::net::Ipv4Addr;
+
+    use uuid::Uuid;
+
+    use super::Principal;
+
+    #[test]
+    fn sut_scopes_anonymous_callers_by_their_address() {
+        // Arrange
+        let address = IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1));
+        let another_address = IpAddr::V4(Ipv4Addr::new(192, 0, 2, 2));
+
+        // Act
+        let actual = Principal::anonymous(address);
+
+        // Assert
+        assert_eq!(actual, Principal::anonymous(address));
+        assert_ne!(actual, Principal::anonymous(another_address));
+    }
+
+    #[test]
+    fn sut_never_scopes_operator_together_with_api_client_sharing_id() {
+        // Arrange
+        let id = Uuid::now_v7();
+
+        // Act
+        let actual = Principal::operator(&id);
+
+        // Assert
+        assert_eq!(actual, Principal::operator(&id));
+        assert_ne!(actual, Principal::api_client(&id));
+    }
+}
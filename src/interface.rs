@@ -1,26 +1,104 @@
 use std::error::Error;
+use std::net::SocketAddr;
+use std::sync::Arc;
 
+use axum::body::Body;
+use axum::extract::ConnectInfo;
+use axum::extract::FromRef;
+use axum::extract::FromRequestParts;
 use axum::extract::MatchedPath;
+use axum::extract::State;
+use axum::http::header::AUTHORIZATION;
+use axum::http::header::SET_COOKIE;
+use axum::http::request::Parts;
+use axum::http::HeaderName;
+use axum::http::HeaderValue;
+use axum::http::Method;
 use axum::http::Request;
 use axum::http::StatusCode;
+use axum::middleware::from_fn_with_state;
+use axum::middleware::Next;
+use axum::response::IntoResponse;
+use axum::response::Response;
 use axum::routing::get;
+use axum::Json;
 use axum::Router;
+use axum_extra::extract::CookieJar;
+use secrecy::SecretString;
 use tokio::net::TcpListener;
 use tower_http::trace::TraceLayer;
 use uuid::Uuid;
 
+use crate::auth;
+use crate::auth::interface::extractor::AdminUser;
+use crate::idempotency::IdempotencyKey;
+use crate::idempotency::Principal;
+use crate::idempotency::RequestFingerprint;
+use crate::idempotency::Reservation;
+use crate::idempotency::SavedResponse;
+use crate::idempotency::SqlxIdempotencyStore;
+use crate::newsletter;
 use crate::subscriber;
 
+#[allow(clippy::too_many_arguments)]
 pub async fn run(
     listener: TcpListener,
     subscriber_command_executor: impl subscriber::domain::service::CommandExecutor,
+    subscriber_query_executor: impl subscriber::domain::service::QueryExecutor,
+    newsletter_command_executor: impl newsletter::domain::service::CommandExecutor,
+    idempotency_store: SqlxIdempotencyStore,
+    authenticator: impl auth::domain::service::Authenticator,
+    api_key_manager: impl auth::domain::service::ApiKeyManager,
+    session_store: impl auth::domain::infrastructure::SessionStore,
+    session_cookie: auth::interface::session::SessionCookie,
+    webhook_manager: impl subscriber::domain::service::WebhookManager,
 ) -> Result<(), impl Error> {
-    let subscriber_container =
-        subscriber::interface::router::Container::new(subscriber_command_executor);
+    // API keys are minted by the auth context and checked by every context serving machine clients
+    let api_key_manager: Arc<dyn auth::domain::service::ApiKeyManager> = Arc::new(api_key_manager);
+    // Operators are authenticated by the auth context for admin routes served by other contexts too
+    let authenticator: Arc<dyn auth::domain::service::Authenticator> = Arc::new(authenticator);
+    // Operators logged in through the auth context browse management pages served by other
+    // contexts too
+    let session_store: Arc<dyn auth::domain::infrastructure::SessionStore> =
+        Arc::new(session_store);
+
+    let subscriber_container = subscriber::interface::router::Container::new(
+        subscriber_command_executor,
+        subscriber_query_executor,
+        api_key_manager.clone(),
+        authenticator.clone(),
+        session_store.clone(),
+        webhook_manager,
+    );
     let subscriber_router = subscriber::interface::router::get_router(subscriber_container).await;
 
+    let newsletter_container = newsletter::interface::router::Container::new(
+        newsletter_command_executor,
+        api_key_manager.clone(),
+        session_store.clone(),
+    );
+    let newsletter_router = newsletter::interface::router::get_router(newsletter_container).await;
+
+    let idempotency_container = IdempotencyContainer {
+        idempotency_store,
+        api_key_manager: api_key_manager.clone(),
+        authenticator: authenticator.clone(),
+        session_store: session_store.clone(),
+    };
+
+    let auth_container = auth::interface::router::Container::new(
+        authenticator,
+        api_key_manager,
+        session_store,
+        session_cookie,
+    );
+    let auth_router = auth::interface::router::get_router(auth_container).await;
+
     let app = Router::new()
         .merge(subscriber_router)
+        .merge(newsletter_router)
+        .merge(auth_router)
+        .layer(from_fn_with_state(idempotency_container, idempotency))
         .layer(
             // Refer to https://github.com/tokio-rs/axum/blob/main/examples/tracing-aka-logging/Cargo.toml
             TraceLayer::new_for_http().make_span_with(|request: &Request<_>| {
@@ -39,5 +117,211 @@ pub async fn run(
         )
         .route("/healthz", get(|| async { StatusCode::OK }));
 
-    axum::serve(listener, app).await
+    // Anonymous callers are told apart by their address when reusing idempotency keys
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await
+}
+
+const IDEMPOTENCY_KEY: HeaderName = HeaderName::from_static("idempotency-key");
+const IDEMPOTENT_REPLAYED: HeaderName = HeaderName::from_static("idempotent-replayed");
+
+// Requests are buffered to fingerprint them, up to the same size axum accepts by default
+const MAX_IDEMPOTENT_REQUEST_BODY_SIZE: usize = 2 * 1024 * 1024;
+
+// Routes authenticating operators or minting secrets are never replayed, so that saved responses
+// never hold credentials
+const UNREPLAYABLE_PATHS: [&str; 4] = ["/login", "/logout", "/admin/api-keys", "/admin/webhooks"];
+
+// Keys are scoped to the identity callers authenticate as, which is verified with the same services
+// the routes use
+#[derive(Clone)]
+struct IdempotencyContainer {
+    idempotency_store: SqlxIdempotencyStore,
+    api_key_manager: Arc<dyn auth::domain::service::ApiKeyManager>,
+    authenticator: Arc<dyn auth::domain::service::Authenticator>,
+    session_store: Arc<dyn auth::domain::infrastructure::SessionStore>,
+}
+
+impl FromRef<IdempotencyContainer> for Arc<dyn auth::domain::service::Authenticator> {
+    fn from_ref(container: &IdempotencyContainer) -> Self {
+        container.authenticator.clone()
+    }
+}
+
+#[derive(serde::Serialize)]
+struct ErrorBody {
+    message: String,
+}
+
+fn error_response(status_code: StatusCode, message: &str) -> Response {
+    (
+        status_code,
+        Json(ErrorBody {
+            message: message.into(),
+        }),
+    )
+        .into_response()
+}
+
+// Retried POST requests carrying the same Idempotency-Key get the first successful response
+// replayed instead of being handled again. Requests without the header are handled as usual.
+async fn idempotency(
+    State(container): State<IdempotencyContainer>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    if request.method() != Method::POST || UNREPLAYABLE_PATHS.contains(&request.uri().path()) {
+        return next.run(request).await;
+    }
+    let Some(key) = request.headers().get(IDEMPOTENCY_KEY) else {
+        return next.run(request).await;
+    };
+    let key = match key
+        .to_str()
+        .map_err(|e| e.to_string())
+        .and_then(IdempotencyKey::parse)
+    {
+        Ok(key) => key,
+        Err(message) => return error_response(StatusCode::BAD_REQUEST, &message),
+    };
+    let (mut parts, body) = request.into_parts();
+    let Some(principal) = principal(&mut parts, &container).await else {
+        return next.run(Request::from_parts(parts, body)).await;
+    };
+
+    let idempotency_store = &container.idempotency_store;
+    let body = match axum::body::to_bytes(body, MAX_IDEMPOTENT_REQUEST_BODY_SIZE).await {
+        Ok(body) => body,
+        Err(_) => {
+            return error_response(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                "The request body is too large.",
+            )
+        }
+    };
+    let fingerprint = RequestFingerprint::new(parts.method.as_str(), parts.uri.path(), &body);
+
+    match idempotency_store
+        .reserve(&principal, &key, &fingerprint)
+        .await
+    {
+        Ok(Reservation::Acquired) => {}
+        Ok(Reservation::Completed(saved)) => return replay(saved),
+        Ok(Reservation::InProgress) => return error_response(
+            StatusCode::CONFLICT,
+            "A request with the same Idempotency-Key is still being processed. Try again later.",
+        ),
+        Ok(Reservation::Mismatched) => {
+            return error_response(
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "The Idempotency-Key has been used with another request.",
+            )
+        }
+        Err(error) => {
+            tracing::error!("Failed to reserve idempotency key: {:?}", error);
+            return error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to process the idempotency key because of the unexpected system issue.",
+            );
+        }
+    };
+
+    let response = idempotency_store
+        .hold(
+            &principal,
+            &key,
+            next.run(Request::from_parts(parts, Body::from(body))),
+        )
+        .await;
+    // Errors are not saved, so that a retry fixing the request or outlasting a transient issue is
+    // handled again
+    if response.status().is_client_error() || response.status().is_server_error() {
+        if let Err(error) = idempotency_store.release(&principal, &key).await {
+            tracing::error!("Failed to release idempotency key: {:?}", error);
+        }
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+    let body = match axum::body::to_bytes(body, usize::MAX).await {
+        Ok(body) => body,
+        Err(error) => {
+            tracing::error!("Failed to read response body: {:?}", error);
+            if let Err(error) = idempotency_store.release(&principal, &key).await {
+                tracing::error!("Failed to release idempotency key: {:?}", error);
+            }
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let saved = SavedResponse {
+        status_code: parts.status.as_u16(),
+        headers: parts
+            .headers
+            .iter()
+            .filter(|(name, _)| *name != SET_COOKIE)
+            .map(|(name, value)| (name.as_str().to_owned(), value.as_bytes().to_owned()))
+            .collect(),
+        body: body.to_vec(),
+    };
+    // The request has been handled already, so failing to save only loses the replay
+    if let Err(error) = idempotency_store.complete(&principal, &key, &saved).await {
+        tracing::error!("Failed to save idempotent response: {:?}", error);
+    }
+
+    Response::from_parts(parts, Body::from(body))
+}
+
+// Machine clients are scoped to their API key, and operators to their user whether they present
+// HTTP Basic credentials or a session cookie. Anonymous callers are scoped to their address, and
+// requests whose caller cannot be told apart, e.g. because their credentials are invalid, are
+// handled without idempotency.
+async fn principal(parts: &mut Parts, container: &IdempotencyContainer) -> Option<Principal> {
+    let authorization = parts
+        .headers
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok());
+    if let Some(key) = authorization.and_then(|value| value.strip_prefix("Bearer ")) {
+        let key = SecretString::from(key.trim());
+        return match container.api_key_manager.verify(&key).await {
+            Ok(api_key) => Some(Principal::api_client(api_key.id())),
+            Err(_) => None,
+        };
+    }
+    if authorization.is_some() {
+        return AdminUser::from_request_parts(parts, container)
+            .await
+            .ok()
+            .map(|admin_user| Principal::operator(&admin_user.user_id));
+    }
+
+    let jar = CookieJar::from_headers(&parts.headers);
+    let session = auth::interface::session::load_session(&jar, container.session_store.as_ref())
+        .await
+        .ok()?;
+    if let Some(user_id) = session.as_ref().and_then(|session| session.user_id()) {
+        return Some(Principal::operator(user_id));
+    }
+
+    parts
+        .extensions
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(address)| Principal::anonymous(address.ip()))
+}
+
+fn replay(saved: SavedResponse) -> Response {
+    let mut response = Response::new(Body::from(saved.body));
+    *response.status_mut() =
+        StatusCode::from_u16(saved.status_code).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    let headers = response.headers_mut();
+    for (name, value) in saved.headers {
+        if let (Ok(name), Ok(value)) = (HeaderName::try_from(name), HeaderValue::from_bytes(&value))
+        {
+            headers.append(name, value);
+        }
+    }
+    headers.insert(IDEMPOTENT_REPLAYED, HeaderValue::from_static("true"));
+    response
 }
@@ -1,5 +1,9 @@
 pub mod assembly;
+pub mod auth;
+pub mod common;
 pub mod configuration;
+pub mod idempotency;
 pub mod interface;
+pub mod newsletter;
 pub mod subscriber;
 pub mod telemetry;
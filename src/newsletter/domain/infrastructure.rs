@@ -0,0 +1,80 @@
+use uuid::Uuid;
+
+use crate::newsletter::domain::error::Error;
+use crate::newsletter::domain::model::IssueDelivery;
+use crate::newsletter::domain::model::NewsletterIssue;
+use crate::newsletter::domain::model::Recipient;
+
+// Repositories sharing the same transaction type can participate in a single unit of work,
+// so that changes across aggregates are committed all-or-nothing
+#[async_trait::async_trait]
+pub trait UnitOfWork: Send + Sync + Clone + 'static {
+    type Transaction: Send;
+
+    async fn begin(&self) -> Result<Self::Transaction, Error>;
+    async fn commit(&self, transaction: Self::Transaction) -> Result<(), Error>;
+}
+
+#[async_trait::async_trait]
+pub trait NewsletterIssueRepository: Send + Sync + Clone + 'static {
+    type Transaction: Send;
+
+    async fn save(
+        &self,
+        transaction: &mut Self::Transaction,
+        newsletter_issue: &NewsletterIssue,
+    ) -> Result<(), Error>;
+    async fn find_by_id(
+        &self,
+        transaction: &mut Self::Transaction,
+        id: &Uuid,
+    ) -> Result<Option<NewsletterIssue>, Error>;
+}
+
+#[async_trait::async_trait]
+pub trait IssueDeliveryRepository: Send + Sync + Clone + 'static {
+    type Transaction: Send;
+
+    // Queues a delivery for everyone confirmed at the moment of publishing
+    async fn enqueue_for_confirmed_subscribers(
+        &self,
+        transaction: &mut Self::Transaction,
+        newsletter_issue_id: &Uuid,
+    ) -> Result<u64, Error>;
+    // Deliveries being sent by another worker are skipped rather than waited for
+    async fn find_due(
+        &self,
+        transaction: &mut Self::Transaction,
+        limit: i64,
+    ) -> Result<Vec<IssueDelivery>, Error>;
+    async fn update(
+        &self,
+        transaction: &mut Self::Transaction,
+        issue_delivery: &IssueDelivery,
+    ) -> Result<(), Error>;
+    async fn remove(
+        &self,
+        transaction: &mut Self::Transaction,
+        issue_delivery: &IssueDelivery,
+    ) -> Result<(), Error>;
+    // Moves the delivery out of the queue into the dead letters, keeping its last error
+    async fn park(
+        &self,
+        transaction: &mut Self::Transaction,
+        issue_delivery: &IssueDelivery,
+    ) -> Result<(), Error>;
+}
+
+// Read-only view on subscribers owned by the subscriber context
+#[async_trait::async_trait]
+pub trait RecipientRepository: Send + Sync + Clone + 'static {
+    type Transaction: Send;
+
+    // Subscribers who are not confirmed, e.g. those who left after an issue was published, are
+    // not recipients anymore
+    async fn find_confirmed_by_id(
+        &self,
+        transaction: &mut Self::Transaction,
+        id: &Uuid,
+    ) -> Result<Option<Recipient>, Error>;
+}
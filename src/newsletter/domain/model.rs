@@ -0,0 +1,190 @@
+use chrono::DateTime;
+use chrono::TimeDelta;
+use chrono::Utc;
+use url::Url;
+use uuid::Uuid;
+
+use crate::common::email::EmailMessage;
+use crate::common::locale::Locale;
+use crate::common::retry::RetryPolicy;
+use crate::newsletter::domain::error::Error;
+
+#[derive(Clone, Debug)]
+pub struct NewsletterIssue {
+    id: Uuid,
+    title: String,
+    html_content: String,
+    text_content: String,
+    published_at: DateTime<Utc>,
+}
+
+impl NewsletterIssue {
+    pub(crate) fn new(
+        id: Uuid,
+        title: String,
+        html_content: String,
+        text_content: String,
+        published_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id,
+            title,
+            html_content,
+            text_content,
+            published_at,
+        }
+    }
+
+    pub fn create(title: &str, html_content: &str, text_content: &str) -> Result<Self, Error> {
+        if title.trim().is_empty() {
+            return Err(Error::InvariantViolated("Title cannot be empty".into()));
+        }
+
+        if html_content.trim().is_empty() || text_content.trim().is_empty() {
+            return Err(Error::InvariantViolated(
+                "Content must have both HTML and text parts".into(),
+            ));
+        }
+
+        Ok(Self {
+            id: Uuid::now_v7(),
+            title: title.into(),
+            html_content: html_content.into(),
+            text_content: text_content.into(),
+            published_at: Utc::now(),
+        })
+    }
+
+    // Every recipient receives the same content, followed by a link unsubscribing only them
+    pub fn email_message(&self, locale: Locale, unsubscribe_url: &Url) -> EmailMessage {
+        let unsubscribe = match locale {
+            Locale::En => "Unsubscribe",
+            Locale::Ko => "구독 해지",
+        };
+        // Query values of the link are percent-encoded, which leaves only ampersands to escape
+        let html_footer = format!(
+            "<p><a href=\"{}\">{}</a></p>",
+            unsubscribe_url.as_str().replace('&', "&amp;"),
+            unsubscribe,
+        );
+        let text_footer = format!("{}: {}", unsubscribe, unsubscribe_url);
+
+        EmailMessage::new(
+            self.title.clone(),
+            format!("{}\n{}", self.html_content, html_footer),
+            format!("{}\n\n{}", self.text_content, text_footer),
+        )
+        .with_unsubscribe_url(unsubscribe_url)
+    }
+
+    pub fn id(&self) -> &Uuid {
+        &self.id
+    }
+
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    pub fn html_content(&self) -> &str {
+        &self.html_content
+    }
+
+    pub fn text_content(&self) -> &str {
+        &self.text_content
+    }
+
+    pub fn published_at(&self) -> &DateTime<Utc> {
+        &self.published_at
+    }
+}
+
+// Pending delivery of an issue to a single subscriber, queued when the issue is published
+#[derive(Clone, Debug)]
+pub struct IssueDelivery {
+    newsletter_issue_id: Uuid,
+    subscriber_id: Uuid,
+    attempts: i32,
+    next_attempt_at: DateTime<Utc>,
+    last_error: Option<String>,
+}
+
+impl IssueDelivery {
+    pub(crate) fn new(
+        newsletter_issue_id: Uuid,
+        subscriber_id: Uuid,
+        attempts: i32,
+        next_attempt_at: DateTime<Utc>,
+        last_error: Option<String>,
+    ) -> Self {
+        Self {
+            newsletter_issue_id,
+            subscriber_id,
+            attempts,
+            next_attempt_at,
+            last_error,
+        }
+    }
+
+    // Postpones the next attempt for as long as the lease, so that other workers leave the
+    // delivery alone while it is being sent without a transaction held open
+    pub fn claim(&mut self, lease: TimeDelta) {
+        self.next_attempt_at = Utc::now() + lease;
+    }
+
+    pub fn mark_as_failed(&mut self, error: String, retry_policy: &RetryPolicy) {
+        self.attempts += 1;
+        self.next_attempt_at = Utc::now() + retry_policy.backoff(self.attempts);
+        self.last_error = Some(error);
+    }
+
+    pub fn is_exhausted(&self, retry_policy: &RetryPolicy) -> bool {
+        self.attempts >= retry_policy.max_attempts()
+    }
+
+    pub fn newsletter_issue_id(&self) -> &Uuid {
+        &self.newsletter_issue_id
+    }
+
+    pub fn subscriber_id(&self) -> &Uuid {
+        &self.subscriber_id
+    }
+
+    pub fn attempts(&self) -> i32 {
+        self.attempts
+    }
+
+    pub fn next_attempt_at(&self) -> &DateTime<Utc> {
+        &self.next_attempt_at
+    }
+
+    pub fn last_error(&self) -> Option<&str> {
+        self.last_error.as_deref()
+    }
+}
+
+// Confirmed subscriber an issue is mailed to, as much of the subscriber context's records as
+// delivering needs
+#[derive(Clone, Debug)]
+pub struct Recipient {
+    id: Uuid,
+    email: String,
+    locale: Locale,
+}
+
+impl Recipient {
+    pub(crate) fn new(id: Uuid, email: String, locale: Locale) -> Self {
+        Self { id, email, locale }
+    }
+
+    pub fn id(&self) -> &Uuid {
+        &self.id
+    }
+
+    pub fn email(&self) -> &str {
+        &self.email
+    }
+
+    pub fn locale(&self) -> Locale {
+        self.locale
+    }
+}
@@ -0,0 +1 @@
+pub mod publish_issue;
@@ -0,0 +1,60 @@
+use crate::newsletter::domain::error::Error;
+use crate::newsletter::domain::infrastructure::IssueDeliveryRepository;
+use crate::newsletter::domain::infrastructure::NewsletterIssueRepository;
+use crate::newsletter::domain::infrastructure::UnitOfWork;
+use crate::newsletter::domain::model::NewsletterIssue;
+
+#[derive(Clone, Debug)]
+pub struct Command {
+    title: String,
+    html_content: String,
+    text_content: String,
+}
+
+impl Command {
+    pub fn new(title: String, html_content: String, text_content: String) -> Self {
+        Self {
+            title,
+            html_content,
+            text_content,
+        }
+    }
+
+    pub fn title(&self) -> &str {
+        self.title.as_str()
+    }
+
+    pub fn html_content(&self) -> &str {
+        self.html_content.as_str()
+    }
+
+    pub fn text_content(&self) -> &str {
+        self.text_content.as_str()
+    }
+}
+
+#[tracing::instrument(name = "Executing publish issue command", skip_all, fields(title = %command.title))]
+pub async fn execute<U: UnitOfWork>(
+    command: Command,
+    unit_of_work: U,
+    newsletter_issue_repository: impl NewsletterIssueRepository<Transaction = U::Transaction>,
+    issue_delivery_repository: impl IssueDeliveryRepository<Transaction = U::Transaction>,
+) -> Result<(), Error> {
+    let newsletter_issue =
+        NewsletterIssue::create(&command.title, &command.html_content, &command.text_content)?;
+
+    // Emails are sent by the delivery worker, so publishing only has to queue them along with
+    // the issue itself
+    let mut transaction = unit_of_work.begin().await?;
+    newsletter_issue_repository
+        .save(&mut transaction, &newsletter_issue)
+        .await?;
+    let queued = issue_delivery_repository
+        .enqueue_for_confirmed_subscribers(&mut transaction, newsletter_issue.id())
+        .await?;
+    unit_of_work.commit(transaction).await?;
+
+    tracing::info!(id = ?newsletter_issue.id(), queued, "Queued newsletter issue deliveries");
+
+    Ok(())
+}
@@ -0,0 +1,63 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use enum_as_inner::EnumAsInner;
+
+use crate::newsletter::domain::error::Error;
+use crate::newsletter::domain::infrastructure::IssueDeliveryRepository;
+use crate::newsletter::domain::infrastructure::NewsletterIssueRepository;
+use crate::newsletter::domain::infrastructure::UnitOfWork;
+use crate::newsletter::domain::service::command::executors;
+
+#[derive(Clone, EnumAsInner)]
+pub enum Command {
+    PublishIssue(executors::publish_issue::Command),
+}
+
+impl From<executors::publish_issue::Command> for Command {
+    fn from(command: executors::publish_issue::Command) -> Self {
+        Self::PublishIssue(command)
+    }
+}
+
+#[async_trait::async_trait]
+pub trait CommandExecutor: Send + Sync + 'static {
+    async fn execute(&self, command: Command) -> Result<(), Error>;
+}
+
+pub type CommandExecutorFuncion =
+    Arc<dyn Fn(Command) -> Pin<Box<dyn Future<Output = Result<(), Error>> + Send>> + Send + Sync>;
+
+#[async_trait::async_trait]
+impl CommandExecutor for CommandExecutorFuncion {
+    async fn execute(&self, command: Command) -> Result<(), Error> {
+        self(command).await
+    }
+}
+
+pub fn new_command_executor<U: UnitOfWork>(
+    unit_of_work: U,
+    newsletter_issue_repository: impl NewsletterIssueRepository<Transaction = U::Transaction>,
+    issue_delivery_repository: impl IssueDeliveryRepository<Transaction = U::Transaction>,
+) -> CommandExecutorFuncion {
+    Arc::new(move |command: Command| {
+        let unit_of_work = unit_of_work.clone();
+        let newsletter_issue_repository = newsletter_issue_repository.clone();
+        let issue_delivery_repository = issue_delivery_repository.clone();
+
+        Box::pin(async move {
+            match command {
+                Command::PublishIssue(command) => {
+                    executors::publish_issue::execute(
+                        command,
+                        unit_of_work,
+                        newsletter_issue_repository,
+                        issue_delivery_repository,
+                    )
+                    .await
+                }
+            }
+        })
+    })
+}
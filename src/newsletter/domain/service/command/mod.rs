@@ -0,0 +1,7 @@
+mod executors;
+mod interface;
+
+pub use executors::publish_issue::Command as PublishIssueCommand;
+pub use interface::new_command_executor;
+pub use interface::Command;
+pub use interface::CommandExecutor;
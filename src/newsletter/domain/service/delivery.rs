@@ -0,0 +1,117 @@
+use anyhow::anyhow;
+use chrono::TimeDelta;
+use secrecy::SecretString;
+
+use crate::common::email;
+use crate::common::email::EmailClient;
+use crate::common::link::LinkBuilder;
+use crate::common::link::UnsubscribeToken;
+use crate::common::retry::RetryPolicy;
+use crate::newsletter::domain::error::Error;
+use crate::newsletter::domain::infrastructure::IssueDeliveryRepository;
+use crate::newsletter::domain::infrastructure::NewsletterIssueRepository;
+use crate::newsletter::domain::infrastructure::RecipientRepository;
+use crate::newsletter::domain::infrastructure::UnitOfWork;
+
+// Claimed deliveries are retried by any worker once their lease expires, e.g. when the one having
+// claimed them stopped before recording the results of sending them
+const ISSUE_DELIVERY_LEASE: TimeDelta = TimeDelta::minutes(10);
+
+// Delivers a batch of queued issues and returns how many of them have been attempted
+#[allow(clippy::too_many_arguments)]
+#[tracing::instrument(name = "Delivering newsletter issues", skip_all)]
+pub async fn deliver_newsletter_issues<U: UnitOfWork>(
+    unit_of_work: &U,
+    newsletter_issue_repository: &impl NewsletterIssueRepository<Transaction = U::Transaction>,
+    issue_delivery_repository: &impl IssueDeliveryRepository<Transaction = U::Transaction>,
+    recipient_repository: &impl RecipientRepository<Transaction = U::Transaction>,
+    email_client: &impl EmailClient,
+    retry_policy: &RetryPolicy,
+    link_builder: &LinkBuilder,
+    unsubscribe_key: &SecretString,
+    batch_size: i64,
+) -> Result<usize, Error> {
+    // Deliveries are claimed in a transaction of their own, so that no row stays locked while
+    // emails are being sent
+    let mut transaction = unit_of_work.begin().await?;
+
+    let issue_deliveries = issue_delivery_repository
+        .find_due(&mut transaction, batch_size)
+        .await?;
+
+    let mut deliveries = Vec::with_capacity(issue_deliveries.len());
+    for mut issue_delivery in issue_deliveries.iter().cloned() {
+        let recipient = recipient_repository
+            .find_confirmed_by_id(&mut transaction, issue_delivery.subscriber_id())
+            .await?;
+        // Subscribers who left after the issue was published are not mailed anymore
+        let Some(recipient) = recipient else {
+            issue_delivery_repository
+                .remove(&mut transaction, &issue_delivery)
+                .await?;
+            continue;
+        };
+        let newsletter_issue = newsletter_issue_repository
+            .find_by_id(&mut transaction, issue_delivery.newsletter_issue_id())
+            .await?
+            .ok_or_else(|| {
+                Error::FailedUnexpectedly(anyhow!(
+                    "Newsletter issue {} of the delivery does not exist",
+                    issue_delivery.newsletter_issue_id()
+                ))
+            })?;
+
+        issue_delivery.claim(ISSUE_DELIVERY_LEASE);
+        issue_delivery_repository
+            .update(&mut transaction, &issue_delivery)
+            .await?;
+        deliveries.push((issue_delivery, recipient, newsletter_issue));
+    }
+
+    unit_of_work.commit(transaction).await?;
+
+    for (mut issue_delivery, recipient, newsletter_issue) in deliveries {
+        let unsubscribe_url = link_builder
+            .unsubscribe(&UnsubscribeToken::issue(*recipient.id(), unsubscribe_key).token());
+        let result = email_client
+            .send(
+                recipient.email(),
+                &newsletter_issue.email_message(recipient.locale(), &unsubscribe_url),
+            )
+            .await;
+
+        // Each result is recorded on its own, so that a failure to record one does not send the
+        // issue again to recipients already recorded
+        let mut transaction = unit_of_work.begin().await?;
+        match result {
+            Ok(_) => {
+                issue_delivery_repository
+                    .remove(&mut transaction, &issue_delivery)
+                    .await?
+            }
+            Err(error) => {
+                tracing::warn!(
+                    newsletter_issue_id = ?issue_delivery.newsletter_issue_id(),
+                    subscriber_id = ?issue_delivery.subscriber_id(),
+                    "Failed to deliver newsletter issue: {:?}",
+                    error,
+                );
+                let permanent = matches!(error, email::Error::Rejected(_));
+                issue_delivery.mark_as_failed(format!("{:?}", anyhow!(error)), retry_policy);
+
+                if permanent || issue_delivery.is_exhausted(retry_policy) {
+                    issue_delivery_repository
+                        .park(&mut transaction, &issue_delivery)
+                        .await?;
+                } else {
+                    issue_delivery_repository
+                        .update(&mut transaction, &issue_delivery)
+                        .await?;
+                }
+            }
+        }
+        unit_of_work.commit(transaction).await?;
+    }
+
+    Ok(issue_deliveries.len())
+}
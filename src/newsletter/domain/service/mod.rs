@@ -0,0 +1,5 @@
+mod command;
+mod delivery;
+
+pub use command::*;
+pub use delivery::*;
@@ -0,0 +1,358 @@
+use anyhow::Context;
+use chrono::NaiveDateTime;
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::common::locale::Locale;
+use crate::newsletter::domain::error::Error;
+use crate::newsletter::domain::infrastructure::IssueDeliveryRepository;
+use crate::newsletter::domain::infrastructure::NewsletterIssueRepository;
+use crate::newsletter::domain::infrastructure::RecipientRepository;
+use crate::newsletter::domain::model::IssueDelivery;
+use crate::newsletter::domain::model::NewsletterIssue;
+use crate::newsletter::domain::model::Recipient;
+use crate::newsletter::infrastructure::unit_of_work::SqlxTransaction;
+
+#[derive(Debug)]
+pub struct NewsletterIssueDataModel {
+    id: Uuid,
+    title: String,
+    html_content: String,
+    text_content: String,
+    published_at: NaiveDateTime,
+}
+
+impl NewsletterIssueDataModel {
+    pub fn new(
+        id: Uuid,
+        title: String,
+        html_content: String,
+        text_content: String,
+        published_at: NaiveDateTime,
+    ) -> Self {
+        Self {
+            id,
+            title,
+            html_content,
+            text_content,
+            published_at,
+        }
+    }
+}
+
+impl From<NewsletterIssueDataModel> for NewsletterIssue {
+    fn from(data_model: NewsletterIssueDataModel) -> Self {
+        NewsletterIssue::new(
+            data_model.id,
+            data_model.title,
+            data_model.html_content,
+            data_model.text_content,
+            data_model.published_at.and_utc(),
+        )
+    }
+}
+
+impl From<&NewsletterIssue> for NewsletterIssueDataModel {
+    fn from(entity: &NewsletterIssue) -> Self {
+        NewsletterIssueDataModel::new(
+            *entity.id(),
+            entity.title().into(),
+            entity.html_content().into(),
+            entity.text_content().into(),
+            entity.published_at().naive_utc(),
+        )
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct SqlxNewsletterIssueRepository;
+
+impl SqlxNewsletterIssueRepository {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait::async_trait]
+impl NewsletterIssueRepository for SqlxNewsletterIssueRepository {
+    type Transaction = SqlxTransaction;
+
+    #[tracing::instrument(name = "Saving newsletter issue", skip_all, fields(id = ?newsletter_issue.id()))]
+    async fn save(
+        &self,
+        transaction: &mut Self::Transaction,
+        newsletter_issue: &NewsletterIssue,
+    ) -> Result<(), Error> {
+        let data_model: NewsletterIssueDataModel = newsletter_issue.into();
+        sqlx::query!(
+            "INSERT INTO newsletter_issues (id, title, html_content, text_content, published_at) VALUES ($1, $2, $3, $4, $5)",
+            data_model.id,
+            data_model.title,
+            data_model.html_content,
+            data_model.text_content,
+            data_model.published_at,
+        )
+        .execute(&mut **transaction)
+        .await
+        .context("Failed to save newsletter issue")
+        .map_err(Error::RepositoryOperationFailed)?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Finding newsletter issue by id", skip_all, fields(id = ?id))]
+    async fn find_by_id(
+        &self,
+        transaction: &mut Self::Transaction,
+        id: &Uuid,
+    ) -> Result<Option<NewsletterIssue>, Error> {
+        Ok(sqlx::query!(
+            "SELECT id, title, html_content, text_content, published_at FROM newsletter_issues WHERE id = $1",
+            id,
+        )
+        .fetch_optional(&mut **transaction)
+        .await
+        .context("Failed to find newsletter issue by id")
+        .map_err(Error::RepositoryOperationFailed)?
+        .map(|r| {
+            NewsletterIssueDataModel::new(
+                r.id,
+                r.title,
+                r.html_content,
+                r.text_content,
+                r.published_at,
+            )
+            .into()
+        }))
+    }
+}
+
+#[derive(Debug)]
+pub struct IssueDeliveryDataModel {
+    newsletter_issue_id: Uuid,
+    subscriber_id: Uuid,
+    attempts: i32,
+    next_attempt_at: NaiveDateTime,
+    last_error: Option<String>,
+}
+
+impl IssueDeliveryDataModel {
+    pub fn new(
+        newsletter_issue_id: Uuid,
+        subscriber_id: Uuid,
+        attempts: i32,
+        next_attempt_at: NaiveDateTime,
+        last_error: Option<String>,
+    ) -> Self {
+        Self {
+            newsletter_issue_id,
+            subscriber_id,
+            attempts,
+            next_attempt_at,
+            last_error,
+        }
+    }
+}
+
+impl From<IssueDeliveryDataModel> for IssueDelivery {
+    fn from(data_model: IssueDeliveryDataModel) -> Self {
+        IssueDelivery::new(
+            data_model.newsletter_issue_id,
+            data_model.subscriber_id,
+            data_model.attempts,
+            data_model.next_attempt_at.and_utc(),
+            data_model.last_error,
+        )
+    }
+}
+
+impl From<&IssueDelivery> for IssueDeliveryDataModel {
+    fn from(entity: &IssueDelivery) -> Self {
+        IssueDeliveryDataModel::new(
+            *entity.newsletter_issue_id(),
+            *entity.subscriber_id(),
+            entity.attempts(),
+            entity.next_attempt_at().naive_utc(),
+            entity.last_error().map(Into::into),
+        )
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct SqlxIssueDeliveryRepository;
+
+impl SqlxIssueDeliveryRepository {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait::async_trait]
+impl IssueDeliveryRepository for SqlxIssueDeliveryRepository {
+    type Transaction = SqlxTransaction;
+
+    #[tracing::instrument(name = "Enqueueing issue deliveries", skip_all, fields(newsletter_issue_id = ?newsletter_issue_id))]
+    async fn enqueue_for_confirmed_subscribers(
+        &self,
+        transaction: &mut Self::Transaction,
+        newsletter_issue_id: &Uuid,
+    ) -> Result<u64, Error> {
+        Ok(sqlx::query!(
+            "INSERT INTO issue_delivery_queue (newsletter_issue_id, subscriber_id, attempts, next_attempt_at) SELECT $1, id, 0, (now() AT TIME ZONE 'utc') FROM subscribers WHERE status = 'Confirmed'",
+            newsletter_issue_id,
+        )
+        .execute(&mut **transaction)
+        .await
+        .context("Failed to enqueue issue deliveries")
+        .map_err(Error::RepositoryOperationFailed)?
+        .rows_affected())
+    }
+
+    #[tracing::instrument(name = "Finding due issue deliveries", skip_all)]
+    async fn find_due(
+        &self,
+        transaction: &mut Self::Transaction,
+        limit: i64,
+    ) -> Result<Vec<IssueDelivery>, Error> {
+        Ok(sqlx::query!(
+            "SELECT newsletter_issue_id, subscriber_id, attempts, next_attempt_at, last_error FROM issue_delivery_queue WHERE next_attempt_at <= (now() AT TIME ZONE 'utc') ORDER BY next_attempt_at LIMIT $1 FOR UPDATE SKIP LOCKED",
+            limit,
+        )
+        .fetch_all(&mut **transaction)
+        .await
+        .context("Failed to find due issue deliveries")
+        .map_err(Error::RepositoryOperationFailed)?
+        .into_iter()
+        .map(|r| {
+            IssueDeliveryDataModel::new(
+                r.newsletter_issue_id,
+                r.subscriber_id,
+                r.attempts,
+                r.next_attempt_at,
+                r.last_error,
+            )
+            .into()
+        })
+        .collect())
+    }
+
+    #[tracing::instrument(name = "Updating issue delivery", skip_all)]
+    async fn update(
+        &self,
+        transaction: &mut Self::Transaction,
+        issue_delivery: &IssueDelivery,
+    ) -> Result<(), Error> {
+        let data_model: IssueDeliveryDataModel = issue_delivery.into();
+        sqlx::query!(
+            "UPDATE issue_delivery_queue SET attempts = $1, next_attempt_at = $2, last_error = $3 WHERE newsletter_issue_id = $4 AND subscriber_id = $5",
+            data_model.attempts,
+            data_model.next_attempt_at,
+            data_model.last_error,
+            data_model.newsletter_issue_id,
+            data_model.subscriber_id,
+        )
+        .execute(&mut **transaction)
+        .await
+        .context("Failed to update issue delivery")
+        .map_err(Error::RepositoryOperationFailed)?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Removing issue delivery", skip_all)]
+    async fn remove(
+        &self,
+        transaction: &mut Self::Transaction,
+        issue_delivery: &IssueDelivery,
+    ) -> Result<(), Error> {
+        sqlx::query!(
+            "DELETE FROM issue_delivery_queue WHERE newsletter_issue_id = $1 AND subscriber_id = $2",
+            issue_delivery.newsletter_issue_id(),
+            issue_delivery.subscriber_id(),
+        )
+        .execute(&mut **transaction)
+        .await
+        .context("Failed to remove issue delivery")
+        .map_err(Error::RepositoryOperationFailed)?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Parking issue delivery", skip_all)]
+    async fn park(
+        &self,
+        transaction: &mut Self::Transaction,
+        issue_delivery: &IssueDelivery,
+    ) -> Result<(), Error> {
+        self.remove(transaction, issue_delivery).await?;
+
+        let data_model: IssueDeliveryDataModel = issue_delivery.into();
+        sqlx::query!(
+            "INSERT INTO issue_delivery_dead_letters (newsletter_issue_id, subscriber_id, attempts, last_error, parked_at) VALUES ($1, $2, $3, $4, $5)",
+            data_model.newsletter_issue_id,
+            data_model.subscriber_id,
+            data_model.attempts,
+            data_model.last_error,
+            Utc::now().naive_utc(),
+        )
+        .execute(&mut **transaction)
+        .await
+        .context("Failed to park issue delivery")
+        .map_err(Error::RepositoryOperationFailed)?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct RecipientDataModel {
+    id: Uuid,
+    email: String,
+    locale: String,
+}
+
+impl RecipientDataModel {
+    pub fn new(id: Uuid, email: String, locale: String) -> Self {
+        Self { id, email, locale }
+    }
+}
+
+impl From<RecipientDataModel> for Recipient {
+    fn from(data_model: RecipientDataModel) -> Self {
+        let locale = Locale::parse(data_model.locale.as_str()).unwrap_or_default();
+        Recipient::new(data_model.id, data_model.email, locale)
+    }
+}
+
+// Reads subscribers straight from the table of the subscriber context, without going through its
+// repositories
+#[derive(Clone, Default)]
+pub struct SqlxRecipientRepository;
+
+impl SqlxRecipientRepository {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait::async_trait]
+impl RecipientRepository for SqlxRecipientRepository {
+    type Transaction = SqlxTransaction;
+
+    #[tracing::instrument(name = "Finding confirmed recipient by id", skip_all, fields(id = ?id))]
+    async fn find_confirmed_by_id(
+        &self,
+        transaction: &mut Self::Transaction,
+        id: &Uuid,
+    ) -> Result<Option<Recipient>, Error> {
+        Ok(sqlx::query!(
+            "SELECT id, display_email, locale FROM subscribers WHERE id = $1 AND status = 'Confirmed'",
+            id,
+        )
+        .fetch_optional(&mut **transaction)
+        .await
+        .context("Failed to find recipient by id")
+        .map_err(Error::RepositoryOperationFailed)?
+        .map(|r| RecipientDataModel::new(r.id, r.display_email, r.locale).into()))
+    }
+}
@@ -0,0 +1,21 @@
+pub use crate::common::unit_of_work::SqlxTransaction;
+pub use crate::common::unit_of_work::SqlxUnitOfWork;
+use crate::newsletter::domain::error::Error;
+use crate::newsletter::domain::infrastructure::UnitOfWork;
+
+#[async_trait::async_trait]
+impl UnitOfWork for SqlxUnitOfWork {
+    type Transaction = SqlxTransaction;
+
+    async fn begin(&self) -> Result<Self::Transaction, Error> {
+        self.begin_transaction()
+            .await
+            .map_err(Error::RepositoryOperationFailed)
+    }
+
+    async fn commit(&self, transaction: Self::Transaction) -> Result<(), Error> {
+        SqlxUnitOfWork::commit_transaction(transaction)
+            .await
+            .map_err(Error::RepositoryOperationFailed)
+    }
+}
@@ -0,0 +1,42 @@
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::response::Response;
+use minijinja::context;
+
+use crate::auth::domain::infrastructure::SessionStore;
+use crate::auth::interface::session::AdminSession;
+use crate::common::page::PageRenderer;
+
+#[tracing::instrument(name = "Showing newsletter issue form", skip_all, fields(user_id = %admin_session.user_id))]
+pub async fn control(
+    State(session_store): State<Arc<dyn SessionStore>>,
+    State(page_renderer): State<PageRenderer>,
+    mut admin_session: AdminSession,
+) -> Response {
+    let flash_messages = match admin_session
+        .take_flash_messages(session_store.as_ref())
+        .await
+    {
+        Ok(flash_messages) => flash_messages,
+        Err(error) => {
+            tracing::error!("{:?}", error);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    let page = page_renderer.render(
+        "newsletters.html",
+        context! { csrf_token => admin_session.csrf_token, flash_messages },
+    );
+
+    match page {
+        Ok(page) => page.into_response(),
+        Err(error) => {
+            tracing::error!("{:?}", error);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
@@ -0,0 +1,3 @@
+pub mod get_admin_dashboard_newsletters;
+pub mod post_admin_dashboard_newsletters;
+pub mod post_newsletters;
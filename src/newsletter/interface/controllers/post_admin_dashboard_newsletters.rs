@@ -0,0 +1,58 @@
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::response::Redirect;
+use axum::response::Response;
+use axum::Form;
+
+use crate::auth::domain::infrastructure::SessionStore;
+use crate::auth::interface::session::AdminSession;
+use crate::newsletter::domain::error::Error;
+use crate::newsletter::domain::service::CommandExecutor;
+use crate::newsletter::domain::service::PublishIssueCommand;
+
+#[derive(Debug, serde::Deserialize)]
+pub struct Request {
+    title: String,
+    html_content: String,
+    text_content: String,
+    csrf_token: String,
+}
+
+// The outcome is reported through a flash message on the form, so that reloading the page does
+// not publish the issue again
+#[tracing::instrument(name = "Publishing a newsletter issue from form", skip_all, fields(user_id = %admin_session.user_id, title = %request.title))]
+pub async fn control(
+    State(session_store): State<Arc<dyn SessionStore>>,
+    State(command_executor): State<Arc<dyn CommandExecutor>>,
+    mut admin_session: AdminSession,
+    Form(request): Form<Request>,
+) -> Response {
+    if !admin_session.verify_csrf_token(&request.csrf_token) {
+        return (StatusCode::FORBIDDEN, "Invalid CSRF token").into_response();
+    }
+
+    let command =
+        PublishIssueCommand::new(request.title, request.html_content, request.text_content).into();
+    let message = match command_executor.execute(command).await {
+        Ok(_) => "The newsletter issue has been published.".into(),
+        Err(Error::InvariantViolated(message)) => message,
+        Err(error) => {
+            tracing::error!("{:?}", error);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    match admin_session
+        .add_flash_message(session_store.as_ref(), message)
+        .await
+    {
+        Ok(_) => Redirect::to("/admin/dashboard/newsletters").into_response(),
+        Err(error) => {
+            tracing::error!("{:?}", error);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
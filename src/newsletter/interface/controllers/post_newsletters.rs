@@ -0,0 +1,56 @@
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::Json;
+
+use crate::auth::interface::api_key::ApiClient;
+use crate::auth::interface::api_key::NewslettersPublish;
+use crate::newsletter::domain::error::Error;
+use crate::newsletter::domain::service::CommandExecutor;
+use crate::newsletter::domain::service::PublishIssueCommand;
+use crate::newsletter::interface::response::Response;
+
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct Request {
+    title: String,
+    content: Content,
+}
+
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct Content {
+    html: String,
+    text: String,
+}
+
+#[tracing::instrument(name = "Publishing a newsletter issue", skip_all, fields(client_name = %api_client.client_name, title = %request.title))]
+pub async fn control(
+    api_client: ApiClient<NewslettersPublish>,
+    State(command_executor): State<Arc<dyn CommandExecutor>>,
+    Json(request): Json<Request>,
+) -> impl IntoResponse {
+    let command =
+        PublishIssueCommand::new(request.title, request.content.html, request.content.text).into();
+
+    match command_executor.execute(command).await {
+        Ok(_) => Response::new(StatusCode::OK, None),
+        Err(error) => {
+            tracing::error!("{:?}", error);
+            convert_error_to_response(error)
+        }
+    }
+}
+
+fn convert_error_to_response(error: Error) -> Response {
+    match error {
+        Error::InvariantViolated(message) => Response::new(StatusCode::BAD_REQUEST, Some(message)),
+        _ => Response::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Some(
+                "Failed to publish the newsletter issue because of the unexpected system issue."
+                    .into(),
+            ),
+        ),
+    }
+}
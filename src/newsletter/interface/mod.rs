@@ -0,0 +1,4 @@
+mod controllers;
+mod response;
+pub mod router;
+pub mod worker;
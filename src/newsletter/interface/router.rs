@@ -0,0 +1,70 @@
+use std::sync::Arc;
+
+use axum::extract::FromRef;
+use axum::routing::get;
+use axum::routing::post;
+use axum::Router;
+
+use crate::auth::domain::infrastructure::SessionStore;
+use crate::auth::domain::service::ApiKeyManager;
+use crate::common::page::PageRenderer;
+use crate::newsletter::domain::service::CommandExecutor;
+use crate::newsletter::interface::controllers;
+
+#[derive(Clone)]
+pub struct Container {
+    command_executor: Arc<dyn CommandExecutor>,
+    api_key_manager: Arc<dyn ApiKeyManager>,
+    session_store: Arc<dyn SessionStore>,
+    page_renderer: PageRenderer,
+}
+
+impl Container {
+    pub fn new(
+        command_executor: impl CommandExecutor,
+        api_key_manager: Arc<dyn ApiKeyManager>,
+        session_store: Arc<dyn SessionStore>,
+    ) -> Self {
+        Self {
+            command_executor: Arc::new(command_executor),
+            api_key_manager,
+            session_store,
+            page_renderer: PageRenderer::new(),
+        }
+    }
+}
+
+impl FromRef<Container> for Arc<dyn CommandExecutor> {
+    fn from_ref(container: &Container) -> Self {
+        container.command_executor.clone()
+    }
+}
+
+impl FromRef<Container> for Arc<dyn ApiKeyManager> {
+    fn from_ref(container: &Container) -> Self {
+        container.api_key_manager.clone()
+    }
+}
+
+impl FromRef<Container> for Arc<dyn SessionStore> {
+    fn from_ref(container: &Container) -> Self {
+        container.session_store.clone()
+    }
+}
+
+impl FromRef<Container> for PageRenderer {
+    fn from_ref(container: &Container) -> Self {
+        container.page_renderer.clone()
+    }
+}
+
+pub async fn get_router(container: Container) -> Router {
+    Router::new()
+        .route("/newsletters", post(controllers::post_newsletters::control))
+        .route(
+            "/admin/dashboard/newsletters",
+            get(controllers::get_admin_dashboard_newsletters::control)
+                .post(controllers::post_admin_dashboard_newsletters::control),
+        )
+        .with_state(container)
+}
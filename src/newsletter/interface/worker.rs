@@ -0,0 +1,50 @@
+use std::time::Duration;
+
+use secrecy::SecretString;
+
+use crate::common::email::EmailClient;
+use crate::common::link::LinkBuilder;
+use crate::common::retry::RetryPolicy;
+use crate::newsletter::domain::infrastructure::IssueDeliveryRepository;
+use crate::newsletter::domain::infrastructure::NewsletterIssueRepository;
+use crate::newsletter::domain::infrastructure::RecipientRepository;
+use crate::newsletter::domain::infrastructure::UnitOfWork;
+use crate::newsletter::domain::service::deliver_newsletter_issues;
+
+// Drains the delivery queue continuously, and waits for the interval only when there was nothing
+// to deliver
+#[allow(clippy::too_many_arguments)]
+pub async fn run<U: UnitOfWork>(
+    unit_of_work: U,
+    newsletter_issue_repository: impl NewsletterIssueRepository<Transaction = U::Transaction>,
+    issue_delivery_repository: impl IssueDeliveryRepository<Transaction = U::Transaction>,
+    recipient_repository: impl RecipientRepository<Transaction = U::Transaction>,
+    email_client: impl EmailClient,
+    retry_policy: RetryPolicy,
+    link_builder: LinkBuilder,
+    unsubscribe_key: SecretString,
+    batch_size: i64,
+    interval: Duration,
+) {
+    loop {
+        match deliver_newsletter_issues(
+            &unit_of_work,
+            &newsletter_issue_repository,
+            &issue_delivery_repository,
+            &recipient_repository,
+            &email_client,
+            &retry_policy,
+            &link_builder,
+            &unsubscribe_key,
+            batch_size,
+        )
+        .await
+        {
+            Ok(count) if count > 0 => continue,
+            Ok(_) => {}
+            Err(error) => tracing::error!("Failed to deliver newsletter issues: {:?}", error),
+        }
+
+        tokio::time::sleep(interval).await;
+    }
+}
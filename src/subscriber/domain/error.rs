@@ -1,17 +1,66 @@
 use uuid::Uuid;
 
+use crate::common::email;
+use crate::common::link;
+
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
     #[error("{0}")]
     InvariantViolated(String),
+    #[error("Name cannot be empty.")]
+    NameEmpty,
+    #[error("Name cannot be longer than 256 characters.")]
+    NameTooLong,
+    #[error("Name cannot have forbidden characters.")]
+    NameHasForbiddenCharacters,
+    #[error("Email address must be valid.")]
+    EmailInvalid,
     #[error("Failed to find the token.")]
-    TokenNotFound(String),
+    TokenNotFound,
+    #[error("The token has expired.")]
+    TokenExpired,
+    #[error("The token has already been used.")]
+    TokenAlreadyUsed,
+    #[error("Failed to verify the token.")]
+    TokenInvalid,
     #[error("Failed to find the subscriber.")]
     SubscriberNotFound(Uuid),
+    // Another subscription of the same address has saved it first
+    #[error("The subscriber already exists.")]
+    SubscriberAlreadyExists(String),
+    // Another writer has updated the subscriber since it was read, so the change may be retried
+    #[error("The subscriber has been modified concurrently.")]
+    ConcurrencyConflict(Uuid),
+    #[error("Failed to find the webhook endpoint.")]
+    WebhookEndpointNotFound(Uuid),
+    #[error("Failed to verify the signature.")]
+    SignatureInvalid(String),
     #[error("Failed to operate on repository.")]
     RepositoryOperationFailed(#[source] anyhow::Error),
     #[error("Failed to process Email request.")]
     EmailOperationFailed(#[source] anyhow::Error),
+    // Retrying cannot help, e.g. the recipient address does not exist or has been deactivated
+    #[error("The email has been rejected permanently.")]
+    EmailRejected(#[source] anyhow::Error),
+    #[error("Failed to process webhook request.")]
+    WebhookOperationFailed(#[source] anyhow::Error),
     #[error("Failed unexpectedly.")]
     FailedUnexpectedly(#[source] anyhow::Error),
 }
+
+impl From<email::Error> for Error {
+    fn from(error: email::Error) -> Self {
+        match error {
+            email::Error::OperationFailed(e) => Error::EmailOperationFailed(e),
+            email::Error::Rejected(e) => Error::EmailRejected(e),
+        }
+    }
+}
+
+impl From<link::Error> for Error {
+    fn from(error: link::Error) -> Self {
+        match error {
+            link::Error::TokenInvalid => Error::TokenInvalid,
+        }
+    }
+}
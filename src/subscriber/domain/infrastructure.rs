@@ -1,25 +1,238 @@
+use chrono::DateTime;
+use chrono::Utc;
+use url::Url;
 use uuid::Uuid;
 
+use crate::common::email::EmailMessage;
 use crate::subscriber::domain::error::Error;
+use crate::subscriber::domain::model::OutboxMessage;
 use crate::subscriber::domain::model::Subscriber;
+use crate::subscriber::domain::model::SubscriberEvent;
+use crate::subscriber::domain::model::SubscriberFilter;
 use crate::subscriber::domain::model::SubscriptionToken;
+use crate::subscriber::domain::model::WebhookDelivery;
+use crate::subscriber::domain::model::WebhookEndpoint;
+use crate::subscriber::domain::model::WebhookSignature;
+
+// Repositories sharing the same transaction type can participate in a single unit of work,
+// so that changes across aggregates are committed all-or-nothing
+#[async_trait::async_trait]
+pub trait UnitOfWork: Send + Sync + Clone + 'static {
+    type Transaction: Send;
+
+    async fn begin(&self) -> Result<Self::Transaction, Error>;
+    async fn commit(&self, transaction: Self::Transaction) -> Result<(), Error>;
+}
 
 #[async_trait::async_trait]
 pub trait SubscriberRepository: Send + Sync + Clone + 'static {
-    async fn save(&self, subscriber: &Subscriber) -> Result<(), Error>;
-    async fn modify_by_id<F>(&self, id: &Uuid, modifier: F) -> Result<(), Error>
+    type Transaction: Send;
+
+    // Fails with SubscriberAlreadyExists rather than aborting the transaction if the address has
+    // been taken in the meantime
+    async fn save(
+        &self,
+        transaction: &mut Self::Transaction,
+        subscriber: &Subscriber,
+    ) -> Result<(), Error>;
+    async fn find_by_id(
+        &self,
+        transaction: &mut Self::Transaction,
+        id: &Uuid,
+    ) -> Result<Option<Subscriber>, Error>;
+    // Expects the normalized address, which is what identifies subscribers
+    async fn find_by_email(
+        &self,
+        transaction: &mut Self::Transaction,
+        email: &str,
+    ) -> Result<Option<Subscriber>, Error>;
+    // Subscribers matching the filter whose ids come after the cursor, in the order of their ids
+    async fn find_page(
+        &self,
+        transaction: &mut Self::Transaction,
+        filter: &SubscriberFilter,
+        cursor: Option<&Uuid>,
+        limit: i64,
+    ) -> Result<Vec<Subscriber>, Error>;
+    async fn modify_by_id<F>(
+        &self,
+        transaction: &mut Self::Transaction,
+        id: &Uuid,
+        modifier: F,
+    ) -> Result<(), Error>
     where
         F: FnOnce(Subscriber) -> Subscriber + Send + Sync;
 }
 
 #[async_trait::async_trait]
 pub trait SubscriptionTokenRepository: Send + Sync + Clone + 'static {
-    async fn save(&self, subscription_token: &SubscriptionToken) -> Result<(), Error>;
-    async fn find_by_token(&self, token: &str) -> Result<Option<SubscriptionToken>, Error>;
+    type Transaction: Send;
+
+    async fn save(
+        &self,
+        transaction: &mut Self::Transaction,
+        subscription_token: &SubscriptionToken,
+    ) -> Result<(), Error>;
+    async fn find_by_token(
+        &self,
+        transaction: &mut Self::Transaction,
+        token: &str,
+    ) -> Result<Option<SubscriptionToken>, Error>;
+    // Only when the latest token has been issued is found, as raw tokens are never stored
+    async fn find_latest_issued_at_by_subscriber_id(
+        &self,
+        transaction: &mut Self::Transaction,
+        subscriber_id: &Uuid,
+    ) -> Result<Option<DateTime<Utc>>, Error>;
+    async fn modify_by_token<F>(
+        &self,
+        transaction: &mut Self::Transaction,
+        token: &str,
+        modifier: F,
+    ) -> Result<(), Error>
+    where
+        F: FnOnce(SubscriptionToken) -> SubscriptionToken + Send + Sync;
+    // Waits for tokens being consumed concurrently, which are then left as they are
+    async fn remove_unused_by_subscriber_id(
+        &self,
+        transaction: &mut Self::Transaction,
+        subscriber_id: &Uuid,
+    ) -> Result<(), Error>;
+}
+
+#[async_trait::async_trait]
+pub trait OutboxRepository: Send + Sync + Clone + 'static {
+    type Transaction: Send;
+
+    async fn save(
+        &self,
+        transaction: &mut Self::Transaction,
+        outbox_message: &OutboxMessage,
+    ) -> Result<(), Error>;
+    // Messages being delivered by another dispatcher are skipped rather than waited for
+    async fn find_deliverable(
+        &self,
+        transaction: &mut Self::Transaction,
+        max_attempts: i32,
+        limit: i64,
+    ) -> Result<Vec<OutboxMessage>, Error>;
+    async fn modify_by_id<F>(
+        &self,
+        transaction: &mut Self::Transaction,
+        id: &Uuid,
+        modifier: F,
+    ) -> Result<(), Error>
+    where
+        F: FnOnce(OutboxMessage) -> OutboxMessage + Send + Sync;
+}
+
+// Events are published within the unit of work that produced them, so that publishers persisting
+// them for later delivery do so all-or-nothing with the state change
+#[async_trait::async_trait]
+pub trait EventPublisher: Send + Sync + Clone + 'static {
+    type Transaction: Send;
+
+    async fn publish(
+        &self,
+        transaction: &mut Self::Transaction,
+        event: &SubscriberEvent,
+    ) -> Result<(), Error>;
 }
 
+// Publishes every event through both publishers, which lets them be stacked in pairs
 #[async_trait::async_trait]
-pub trait EmailClient: Send + Sync + Clone + 'static {
-    async fn send(&self, recipient: &Subscriber, subject: &str, content: &str)
-        -> Result<(), Error>;
+impl<A, B> EventPublisher for (A, B)
+where
+    A: EventPublisher,
+    B: EventPublisher<Transaction = A::Transaction>,
+{
+    type Transaction = A::Transaction;
+
+    async fn publish(
+        &self,
+        transaction: &mut Self::Transaction,
+        event: &SubscriberEvent,
+    ) -> Result<(), Error> {
+        self.0.publish(transaction, event).await?;
+        self.1.publish(transaction, event).await
+    }
+}
+
+#[async_trait::async_trait]
+pub trait WebhookEndpointRepository: Send + Sync + Clone + 'static {
+    type Transaction: Send;
+
+    async fn save(
+        &self,
+        transaction: &mut Self::Transaction,
+        webhook_endpoint: &WebhookEndpoint,
+    ) -> Result<(), Error>;
+    async fn find_all(
+        &self,
+        transaction: &mut Self::Transaction,
+    ) -> Result<Vec<WebhookEndpoint>, Error>;
+    async fn find_by_id(
+        &self,
+        transaction: &mut Self::Transaction,
+        id: &Uuid,
+    ) -> Result<Option<WebhookEndpoint>, Error>;
+    // Deliveries queued for the endpoint are removed along with it
+    async fn remove_by_id(
+        &self,
+        transaction: &mut Self::Transaction,
+        id: &Uuid,
+    ) -> Result<(), Error>;
+}
+
+#[async_trait::async_trait]
+pub trait WebhookDeliveryRepository: Send + Sync + Clone + 'static {
+    type Transaction: Send;
+
+    async fn save(
+        &self,
+        transaction: &mut Self::Transaction,
+        webhook_delivery: &WebhookDelivery,
+    ) -> Result<(), Error>;
+    // Deliveries being sent by another worker are skipped rather than waited for
+    async fn find_deliverable(
+        &self,
+        transaction: &mut Self::Transaction,
+        max_attempts: i32,
+        limit: i64,
+    ) -> Result<Vec<WebhookDelivery>, Error>;
+    // Most recent deliveries first
+    async fn find_by_endpoint_id(
+        &self,
+        transaction: &mut Self::Transaction,
+        endpoint_id: &Uuid,
+        limit: i64,
+    ) -> Result<Vec<WebhookDelivery>, Error>;
+    async fn modify_by_id<F>(
+        &self,
+        transaction: &mut Self::Transaction,
+        id: &Uuid,
+        modifier: F,
+    ) -> Result<(), Error>
+    where
+        F: FnOnce(WebhookDelivery) -> WebhookDelivery + Send + Sync;
+}
+
+#[async_trait::async_trait]
+pub trait WebhookClient: Send + Sync + Clone + 'static {
+    // Returns the status code of any response, and fails only when no response has been received
+    async fn send(
+        &self,
+        url: &Url,
+        delivery: &WebhookDelivery,
+        signature: &WebhookSignature,
+    ) -> Result<u16, Error>;
+}
+
+pub trait EmailRenderer: Send + Sync + Clone + 'static {
+    fn render_confirmation(
+        &self,
+        recipient: &Subscriber,
+        confirmation_url: &str,
+        unsubscribe_url: &str,
+    ) -> Result<EmailMessage, Error>;
 }
@@ -1,10 +1,25 @@
+use std::net::IpAddr;
+
 use chrono::DateTime;
+use chrono::TimeDelta;
 use chrono::Utc;
+use hmac::Hmac;
+use hmac::Mac;
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use secrecy::ExposeSecret;
+use secrecy::SecretString;
+use sha2::Sha256;
 use strum::AsRefStr;
 use strum::EnumString;
+use url::Host;
+use url::Url;
 use uuid::Uuid;
 use validator::ValidateEmail;
 
+use crate::common::email::EmailMessage;
+use crate::common::locale::Locale;
+use crate::common::retry::RetryPolicy;
 use crate::subscriber::domain::error::Error;
 
 #[derive(Clone, Debug)]
@@ -14,6 +29,10 @@ pub struct Subscriber {
     email: Email,
     subscribed_at: DateTime<Utc>,
     status: Status,
+    confirmed_at: Option<DateTime<Utc>>,
+    locale: Locale,
+    // Recorded by state changes until command executors take them for publishing
+    events: Vec<SubscriberEvent>,
 }
 
 impl Subscriber {
@@ -23,6 +42,8 @@ impl Subscriber {
         email: Email,
         subscribed_at: DateTime<Utc>,
         status: Status,
+        confirmed_at: Option<DateTime<Utc>>,
+        locale: Locale,
     ) -> Self {
         Self {
             id,
@@ -30,24 +51,87 @@ impl Subscriber {
             email,
             subscribed_at,
             status,
+            confirmed_at,
+            locale,
+            events: Vec::new(),
         }
     }
 
-    pub fn create(name: &str, email: &str) -> Result<Self, Error> {
+    pub fn create(name: &str, email: &str, locale: Locale) -> Result<Self, Error> {
         let name: Name = name.try_into()?;
         let email: Email = email.try_into()?;
 
+        let id = Uuid::now_v7();
+        let subscribed_at = Utc::now();
         Ok(Self {
-            id: Uuid::now_v7(),
+            id,
             name,
             email,
-            subscribed_at: Utc::now(),
+            subscribed_at,
             status: Status::Pending,
+            confirmed_at: None,
+            locale,
+            events: vec![SubscriberEvent::SubscriberRegistered {
+                subscriber_id: id,
+                occurred_at: subscribed_at,
+            }],
         })
     }
 
-    pub fn confirm(&mut self) {
-        self.status = Status::Confirmed;
+    // Registering a pending or unsubscribed address again leaves its status as it is, and records
+    // that a fresh confirmation is asked for
+    pub fn register_again(&mut self) {
+        self.events.push(SubscriberEvent::ConfirmationReissued {
+            subscriber_id: self.id,
+            occurred_at: Utc::now(),
+        });
+    }
+
+    // Confirming again leaves the subscriber as it is and records nothing
+    pub fn confirm(&mut self) -> Result<(), Error> {
+        if matches!(self.status, Status::Confirmed) {
+            return Ok(());
+        }
+        self.transition_to(Status::Confirmed)?;
+
+        let confirmed_at = Utc::now();
+        self.confirmed_at = Some(confirmed_at);
+        self.events.push(SubscriberEvent::SubscriptionConfirmed {
+            subscriber_id: self.id,
+            occurred_at: confirmed_at,
+        });
+        Ok(())
+    }
+
+    // Unsubscribing again leaves the subscriber as it is and records nothing
+    pub fn unsubscribe(&mut self) -> Result<(), Error> {
+        if matches!(self.status, Status::Unsubscribed) {
+            return Ok(());
+        }
+        self.transition_to(Status::Unsubscribed)?;
+
+        self.events.push(SubscriberEvent::SubscriberUnsubscribed {
+            subscriber_id: self.id,
+            occurred_at: Utc::now(),
+        });
+        Ok(())
+    }
+
+    fn transition_to(&mut self, next: Status) -> Result<(), Error> {
+        if !self.status.can_transition_to(&next) {
+            return Err(Error::InvariantViolated(format!(
+                "Subscriber cannot become {} while {}",
+                next.as_ref(),
+                self.status.as_ref(),
+            )));
+        }
+
+        self.status = next;
+        Ok(())
+    }
+
+    pub fn take_events(&mut self) -> Vec<SubscriberEvent> {
+        std::mem::take(&mut self.events)
     }
 
     pub fn id(&self) -> &Uuid {
@@ -62,6 +146,10 @@ impl Subscriber {
         self.email.as_ref()
     }
 
+    pub fn display_email(&self) -> &str {
+        self.email.display()
+    }
+
     pub fn subscribed_at(&self) -> &DateTime<Utc> {
         &self.subscribed_at
     }
@@ -69,6 +157,60 @@ impl Subscriber {
     pub fn status(&self) -> &Status {
         &self.status
     }
+
+    pub fn confirmed_at(&self) -> Option<&DateTime<Utc>> {
+        self.confirmed_at.as_ref()
+    }
+
+    pub fn locale(&self) -> Locale {
+        self.locale
+    }
+}
+
+// Facts about a subscriber that other parts of the system may react to, named after what has
+// happened rather than what should happen next
+#[derive(Clone, Debug, PartialEq, Eq, AsRefStr)]
+pub enum SubscriberEvent {
+    #[strum(serialize = "subscriber.registered")]
+    SubscriberRegistered {
+        subscriber_id: Uuid,
+        occurred_at: DateTime<Utc>,
+    },
+    #[strum(serialize = "confirmation.reissued")]
+    ConfirmationReissued {
+        subscriber_id: Uuid,
+        occurred_at: DateTime<Utc>,
+    },
+    #[strum(serialize = "subscription.confirmed")]
+    SubscriptionConfirmed {
+        subscriber_id: Uuid,
+        occurred_at: DateTime<Utc>,
+    },
+    #[strum(serialize = "subscriber.unsubscribed")]
+    SubscriberUnsubscribed {
+        subscriber_id: Uuid,
+        occurred_at: DateTime<Utc>,
+    },
+}
+
+impl SubscriberEvent {
+    pub fn subscriber_id(&self) -> &Uuid {
+        match self {
+            Self::SubscriberRegistered { subscriber_id, .. }
+            | Self::ConfirmationReissued { subscriber_id, .. }
+            | Self::SubscriptionConfirmed { subscriber_id, .. }
+            | Self::SubscriberUnsubscribed { subscriber_id, .. } => subscriber_id,
+        }
+    }
+
+    pub fn occurred_at(&self) -> &DateTime<Utc> {
+        match self {
+            Self::SubscriberRegistered { occurred_at, .. }
+            | Self::ConfirmationReissued { occurred_at, .. }
+            | Self::SubscriptionConfirmed { occurred_at, .. }
+            | Self::SubscriberUnsubscribed { occurred_at, .. } => occurred_at,
+        }
+    }
 }
 
 const FORBIDDEN_CHARACTERS: [char; 11] = ['/', '(', ')', '\"', '<', '>', '\\', '{', '}', '?', '%'];
@@ -89,19 +231,15 @@ impl Name {
 
     pub fn parse(name: &str) -> Result<Self, Error> {
         if name.trim().is_empty() {
-            return Err(Error::InvariantViolated("Name cannot be empty".into()));
+            return Err(Error::NameEmpty);
         }
 
         if name.len() >= 256 {
-            return Err(Error::InvariantViolated(
-                "Name cannot be longer than 256".into(),
-            ));
+            return Err(Error::NameTooLong);
         }
 
         if name.chars().any(|c| FORBIDDEN_CHARACTERS.contains(&c)) {
-            return Err(Error::InvariantViolated(
-                "Name cannot have forbidden characters".into(),
-            ));
+            return Err(Error::NameHasForbiddenCharacters);
         }
 
         Ok(Name(name.into()))
@@ -122,8 +260,12 @@ impl AsRef<str> for Name {
     }
 }
 
+// Identified by its normalized address, while the address as given is kept for showing it back
 #[derive(Clone, Debug)]
-pub struct Email(String);
+pub struct Email {
+    address: String,
+    display: String,
+}
 
 impl Email {
     /// .
@@ -132,17 +274,33 @@ impl Email {
     /// This constructor creates Email without validation. Be careful to use it.
     /// For now, this is only for repository to create Email without validation.
     /// .
-    pub unsafe fn new_unchecked(email: &str) -> Self {
-        Self(email.into())
+    pub unsafe fn new_unchecked(address: &str, display: &str) -> Self {
+        Self {
+            address: address.into(),
+            display: display.into(),
+        }
     }
 
+    // Addresses differing only by the case or the encoding of their domain are the same address.
+    // The local part is kept as given, as mail servers may tell local parts apart by case.
     pub fn parse(email: &str) -> Result<Self, Error> {
-        email
+        let display = email.trim();
+        let (local_part, domain) = display.rsplit_once('@').ok_or(Error::EmailInvalid)?;
+        // Internationalized domains are kept in their ASCII form, which every mail server accepts
+        let domain = idna::domain_to_ascii(domain).map_err(|_| Error::EmailInvalid)?;
+        let address = format!("{}@{}", local_part, domain);
+
+        address
             .validate_email()
-            .then_some(Email(email.into()))
-            .ok_or(Error::InvariantViolated(
-                "Email address must be valid".into(),
-            ))
+            .then(|| Email {
+                address,
+                display: display.into(),
+            })
+            .ok_or(Error::EmailInvalid)
+    }
+
+    pub fn display(&self) -> &str {
+        &self.display
     }
 }
 
@@ -156,36 +314,107 @@ impl TryFrom<&str> for Email {
 
 impl AsRef<str> for Email {
     fn as_ref(&self) -> &str {
-        &self.0
+        &self.address
     }
 }
 
 #[derive(Clone, Debug, EnumString, AsRefStr)]
+#[strum(ascii_case_insensitive)]
 pub enum Status {
     Unexpected,
     Pending,
     Confirmed,
+    Unsubscribed,
+}
+
+impl Status {
+    pub fn can_transition_to(&self, next: &Status) -> bool {
+        match (self, next) {
+            // Unsubscribed addresses are sent a fresh confirmation when they subscribe again, as
+            // links sent before opting out are revoked on unsubscribing. Subscribers in an unknown
+            // status are sent one too, and following it settles their status.
+            (Status::Unexpected | Status::Pending | Status::Unsubscribed, Status::Confirmed) => {
+                true
+            }
+            // Opting out is honoured whatever the subscriber has been in, even an unknown status
+            (Status::Unexpected | Status::Pending | Status::Confirmed, Status::Unsubscribed) => {
+                true
+            }
+            _ => false,
+        }
+    }
 }
 
+const SUBSCRIPTION_TOKEN_LENGTH: usize = 32;
+const SUBSCRIPTION_TOKEN_LIFETIME: TimeDelta = TimeDelta::days(1);
+// Subscribing again reissues the confirmation no more often than this, so that repeated requests
+// cannot flood the address with confirmation emails
+const SUBSCRIPTION_TOKEN_REISSUE_INTERVAL: TimeDelta = TimeDelta::minutes(5);
+
 #[derive(Clone, Debug)]
 pub struct SubscriptionToken {
     token: String,
     subscriber_id: Uuid,
+    created_at: DateTime<Utc>,
+    expires_at: DateTime<Utc>,
+    used_at: Option<DateTime<Utc>>,
 }
 
 impl SubscriptionToken {
-    pub(crate) fn new(token: String, subscriber_id: Uuid) -> Self {
+    pub(crate) fn new(
+        token: String,
+        subscriber_id: Uuid,
+        created_at: DateTime<Utc>,
+        expires_at: DateTime<Utc>,
+        used_at: Option<DateTime<Utc>>,
+    ) -> Self {
         Self {
             token,
             subscriber_id,
+            created_at,
+            expires_at,
+            used_at,
         }
     }
 
     pub fn create(subscriber_id: Uuid) -> Self {
+        let created_at = Utc::now();
+        // ThreadRng is a cryptographically secure generator, unlike time-ordered UUIDs
+        let token = rand::thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(SUBSCRIPTION_TOKEN_LENGTH)
+            .map(char::from)
+            .collect();
+
         Self {
             subscriber_id,
-            token: Uuid::now_v7().into(),
+            token,
+            created_at,
+            expires_at: created_at + SUBSCRIPTION_TOKEN_LIFETIME,
+            used_at: None,
+        }
+    }
+
+    pub fn ensure_usable(&self) -> Result<(), Error> {
+        if self.used_at.is_some() {
+            return Err(Error::TokenAlreadyUsed);
         }
+
+        if self.expires_at <= Utc::now() {
+            return Err(Error::TokenExpired);
+        }
+
+        Ok(())
+    }
+
+    pub fn consume(&mut self) {
+        self.used_at = Some(Utc::now());
+    }
+
+    // Whether another token may be issued to the subscriber whose latest one has been issued at
+    // the given time
+    pub fn allows_reissuing(latest_issued_at: &DateTime<Utc>) -> bool {
+        *latest_issued_at + SUBSCRIPTION_TOKEN_REISSUE_INTERVAL <= Utc::now()
     }
 
     pub fn token(&self) -> &str {
@@ -195,6 +424,550 @@ impl SubscriptionToken {
     pub fn subscriber_id(&self) -> &Uuid {
         &self.subscriber_id
     }
+
+    pub fn created_at(&self) -> &DateTime<Utc> {
+        &self.created_at
+    }
+
+    pub fn expires_at(&self) -> &DateTime<Utc> {
+        &self.expires_at
+    }
+
+    pub fn used_at(&self) -> Option<&DateTime<Utc>> {
+        self.used_at.as_ref()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct OutboxMessage {
+    id: Uuid,
+    subscriber_id: Uuid,
+    message: Option<EmailMessage>,
+    created_at: DateTime<Utc>,
+    attempts: i32,
+    next_attempt_at: DateTime<Utc>,
+    delivered_at: Option<DateTime<Utc>>,
+    dead_lettered_at: Option<DateTime<Utc>>,
+    last_error: Option<String>,
+}
+
+impl OutboxMessage {
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn new(
+        id: Uuid,
+        subscriber_id: Uuid,
+        message: Option<EmailMessage>,
+        created_at: DateTime<Utc>,
+        attempts: i32,
+        next_attempt_at: DateTime<Utc>,
+        delivered_at: Option<DateTime<Utc>>,
+        dead_lettered_at: Option<DateTime<Utc>>,
+        last_error: Option<String>,
+    ) -> Self {
+        Self {
+            id,
+            subscriber_id,
+            message,
+            created_at,
+            attempts,
+            next_attempt_at,
+            delivered_at,
+            dead_lettered_at,
+            last_error,
+        }
+    }
+
+    pub fn create(subscriber_id: Uuid, message: EmailMessage) -> Self {
+        let created_at = Utc::now();
+
+        Self {
+            id: Uuid::now_v7(),
+            subscriber_id,
+            message: Some(message),
+            created_at,
+            attempts: 0,
+            next_attempt_at: created_at,
+            delivered_at: None,
+            dead_lettered_at: None,
+            last_error: None,
+        }
+    }
+
+    // Postpones the next attempt for as long as the lease, so that other dispatchers leave the
+    // message alone while it is being sent without a transaction held open
+    pub fn claim(&mut self, lease: TimeDelta) {
+        self.next_attempt_at = Utc::now() + lease;
+    }
+
+    // The message carries a confirmation link, so it is discarded once no longer needed
+    pub fn mark_as_delivered(&mut self) {
+        self.attempts += 1;
+        self.delivered_at = Some(Utc::now());
+        self.last_error = None;
+        self.message = None;
+    }
+
+    pub fn mark_as_failed(&mut self, error: String, retry_policy: &RetryPolicy) {
+        self.attempts += 1;
+        self.next_attempt_at = Utc::now() + retry_policy.backoff(self.attempts);
+        self.last_error = Some(error);
+        if self.attempts >= retry_policy.max_attempts() {
+            self.dead_lettered_at = Some(Utc::now());
+            self.message = None;
+        }
+    }
+
+    // Retrying cannot help once the email has been rejected permanently, so the message is
+    // dead-lettered on the first attempt
+    pub fn mark_as_rejected(&mut self, error: String) {
+        self.attempts += 1;
+        self.last_error = Some(error);
+        self.dead_lettered_at = Some(Utc::now());
+        self.message = None;
+    }
+
+    pub fn id(&self) -> &Uuid {
+        &self.id
+    }
+
+    pub fn subscriber_id(&self) -> &Uuid {
+        &self.subscriber_id
+    }
+
+    pub fn message(&self) -> Option<&EmailMessage> {
+        self.message.as_ref()
+    }
+
+    pub fn created_at(&self) -> &DateTime<Utc> {
+        &self.created_at
+    }
+
+    pub fn attempts(&self) -> i32 {
+        self.attempts
+    }
+
+    pub fn next_attempt_at(&self) -> &DateTime<Utc> {
+        &self.next_attempt_at
+    }
+
+    pub fn delivered_at(&self) -> Option<&DateTime<Utc>> {
+        self.delivered_at.as_ref()
+    }
+
+    pub fn dead_lettered_at(&self) -> Option<&DateTime<Utc>> {
+        self.dead_lettered_at.as_ref()
+    }
+
+    pub fn last_error(&self) -> Option<&str> {
+        self.last_error.as_deref()
+    }
+}
+
+const WEBHOOK_SECRET_LENGTH: usize = 32;
+const WEBHOOK_SECRET_PREFIX: &str = "whsec_";
+// Secrets supplied by operators, e.g. ones already shared with a receiver, have to be long enough
+// to resist guessing
+const MIN_WEBHOOK_SECRET_LENGTH: usize = 24;
+const MAX_WEBHOOK_SECRET_LENGTH: usize = 256;
+// Receivers should reject deliveries signed longer ago than this, so that captured requests
+// cannot be replayed later
+pub const WEBHOOK_SIGNATURE_TOLERANCE: TimeDelta = TimeDelta::minutes(5);
+
+// Receiver of subscriber events, which verifies deliveries with the secret shared on registration
+#[derive(Clone, Debug)]
+pub struct WebhookEndpoint {
+    id: Uuid,
+    url: Url,
+    secret: SecretString,
+    created_at: DateTime<Utc>,
+}
+
+impl WebhookEndpoint {
+    pub(crate) fn new(id: Uuid, url: Url, secret: SecretString, created_at: DateTime<Utc>) -> Self {
+        Self {
+            id,
+            url,
+            secret,
+            created_at,
+        }
+    }
+
+    // A secret is generated unless the operator supplies one
+    pub fn register(url: &str, secret: Option<SecretString>) -> Result<Self, Error> {
+        let url = Url::parse(url)
+            .map_err(|error| Error::InvariantViolated(format!("Invalid webhook URL: {}", error)))?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(Error::InvariantViolated(
+                "Webhook URL must use http or https".into(),
+            ));
+        }
+
+        let secret = match secret {
+            Some(secret) => {
+                let length = secret.expose_secret().chars().count();
+                if !(MIN_WEBHOOK_SECRET_LENGTH..=MAX_WEBHOOK_SECRET_LENGTH).contains(&length) {
+                    return Err(Error::InvariantViolated(format!(
+                        "Webhook secret must be between {} and {} characters long",
+                        MIN_WEBHOOK_SECRET_LENGTH, MAX_WEBHOOK_SECRET_LENGTH
+                    )));
+                }
+                secret
+            }
+            None => {
+                let secret: String = rand::thread_rng()
+                    .sample_iter(&Alphanumeric)
+                    .take(WEBHOOK_SECRET_LENGTH)
+                    .map(char::from)
+                    .collect();
+                SecretString::from(format!("{}{}", WEBHOOK_SECRET_PREFIX, secret))
+            }
+        };
+
+        Ok(Self {
+            id: Uuid::now_v7(),
+            url,
+            secret,
+            created_at: Utc::now(),
+        })
+    }
+
+    // Only tells about the host as written, since names are resolved again on every delivery
+    pub fn targets_private_address(&self) -> bool {
+        match self.url.host() {
+            Some(Host::Domain(domain)) => {
+                let domain = domain.trim_end_matches('.');
+                domain == "localhost" || domain.ends_with(".localhost")
+            }
+            Some(Host::Ipv4(ip)) => !is_public_address(&IpAddr::V4(ip)),
+            Some(Host::Ipv6(ip)) => !is_public_address(&IpAddr::V6(ip)),
+            None => true,
+        }
+    }
+
+    pub fn id(&self) -> &Uuid {
+        &self.id
+    }
+
+    pub fn url(&self) -> &Url {
+        &self.url
+    }
+
+    pub fn secret(&self) -> &SecretString {
+        &self.secret
+    }
+
+    pub fn created_at(&self) -> &DateTime<Utc> {
+        &self.created_at
+    }
+}
+
+// Whether an address is reachable on the public internet, so that webhooks cannot be pointed at
+// the service itself or at the network it runs in
+pub fn is_public_address(ip: &IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, ..] = ip.octets();
+            !(ip.is_unspecified()
+                || ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_broadcast()
+                || ip.is_documentation()
+                || ip.is_multicast()
+                || a == 0
+                // Shared address space of carrier-grade NAT
+                || (a == 100 && (64..128).contains(&b))
+                // Benchmarking networks
+                || (a == 198 && (18..20).contains(&b))
+                // Reserved for future use
+                || a >= 240)
+        }
+        IpAddr::V6(ip) => {
+            if let Some(ip) = ip.to_ipv4_mapped() {
+                return is_public_address(&IpAddr::V4(ip));
+            }
+            let first = ip.segments()[0];
+            !(ip.is_unspecified()
+                || ip.is_loopback()
+                || ip.is_multicast()
+                // Unique local addresses
+                || (first & 0xfe00) == 0xfc00
+                // Link-local addresses
+                || (first & 0xffc0) == 0xfe80)
+        }
+    }
+}
+
+// Event queued for a webhook endpoint, whose payload is rendered when the event occurs so that
+// retries send the same content
+#[derive(Clone, Debug)]
+pub struct WebhookDelivery {
+    id: Uuid,
+    endpoint_id: Uuid,
+    event_type: String,
+    payload: String,
+    created_at: DateTime<Utc>,
+    attempts: i32,
+    next_attempt_at: DateTime<Utc>,
+    delivered_at: Option<DateTime<Utc>>,
+    last_status_code: Option<i32>,
+    last_error: Option<String>,
+}
+
+impl WebhookDelivery {
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn new(
+        id: Uuid,
+        endpoint_id: Uuid,
+        event_type: String,
+        payload: String,
+        created_at: DateTime<Utc>,
+        attempts: i32,
+        next_attempt_at: DateTime<Utc>,
+        delivered_at: Option<DateTime<Utc>>,
+        last_status_code: Option<i32>,
+        last_error: Option<String>,
+    ) -> Self {
+        Self {
+            id,
+            endpoint_id,
+            event_type,
+            payload,
+            created_at,
+            attempts,
+            next_attempt_at,
+            delivered_at,
+            last_status_code,
+            last_error,
+        }
+    }
+
+    pub fn create(endpoint_id: Uuid, event: &SubscriberEvent, subscriber: &Subscriber) -> Self {
+        let id = Uuid::now_v7();
+        let created_at = Utc::now();
+        // The delivery id lets receivers drop deliveries they have already processed
+        let payload = serde_json::json!({
+            "id": id,
+            "type": event.as_ref(),
+            "occurred_at": event.occurred_at().to_rfc3339(),
+            "data": {
+                "subscriber_id": subscriber.id(),
+                "name": subscriber.name(),
+                "email": subscriber.email(),
+                "status": subscriber.status().as_ref(),
+            },
+        })
+        .to_string();
+
+        Self {
+            id,
+            endpoint_id,
+            event_type: event.as_ref().into(),
+            payload,
+            created_at,
+            attempts: 0,
+            next_attempt_at: created_at,
+            delivered_at: None,
+            last_status_code: None,
+            last_error: None,
+        }
+    }
+
+    // Postpones the next attempt for as long as the lease, so that other workers leave the
+    // delivery alone while it is being sent without a transaction held open
+    pub fn claim(&mut self, lease: TimeDelta) {
+        self.next_attempt_at = Utc::now() + lease;
+    }
+
+    pub fn mark_as_delivered(&mut self, status_code: i32) {
+        self.attempts += 1;
+        self.delivered_at = Some(Utc::now());
+        self.last_status_code = Some(status_code);
+        self.last_error = None;
+    }
+
+    pub fn mark_as_failed(
+        &mut self,
+        status_code: Option<i32>,
+        error: String,
+        retry_policy: &RetryPolicy,
+    ) {
+        self.attempts += 1;
+        self.next_attempt_at = Utc::now() + retry_policy.backoff(self.attempts);
+        self.last_status_code = status_code;
+        self.last_error = Some(error);
+    }
+
+    pub fn id(&self) -> &Uuid {
+        &self.id
+    }
+
+    pub fn endpoint_id(&self) -> &Uuid {
+        &self.endpoint_id
+    }
+
+    pub fn event_type(&self) -> &str {
+        &self.event_type
+    }
+
+    pub fn payload(&self) -> &str {
+        &self.payload
+    }
+
+    pub fn created_at(&self) -> &DateTime<Utc> {
+        &self.created_at
+    }
+
+    pub fn attempts(&self) -> i32 {
+        self.attempts
+    }
+
+    pub fn next_attempt_at(&self) -> &DateTime<Utc> {
+        &self.next_attempt_at
+    }
+
+    pub fn delivered_at(&self) -> Option<&DateTime<Utc>> {
+        self.delivered_at.as_ref()
+    }
+
+    pub fn last_status_code(&self) -> Option<i32> {
+        self.last_status_code
+    }
+
+    pub fn last_error(&self) -> Option<&str> {
+        self.last_error.as_deref()
+    }
+}
+
+// HMAC-SHA256 over the delivery id, the unix timestamp of sending and the payload. Covering the
+// timestamp keeps it from being altered to make an old delivery look fresh.
+#[derive(Clone, Debug)]
+pub struct WebhookSignature {
+    timestamp: i64,
+    signature: String,
+}
+
+impl WebhookSignature {
+    pub fn sign(secret: &SecretString, id: &Uuid, timestamp: i64, payload: &str) -> Self {
+        let signature = hex::encode(
+            WebhookSignature::mac(secret, id, timestamp, payload)
+                .finalize()
+                .into_bytes(),
+        );
+
+        Self {
+            timestamp,
+            signature: format!("v1={}", signature),
+        }
+    }
+
+    // What receivers are expected to do, kept here to pin down the scheme
+    pub fn verify(
+        secret: &SecretString,
+        id: &Uuid,
+        timestamp: i64,
+        payload: &str,
+        signature: &str,
+        now: DateTime<Utc>,
+    ) -> Result<(), Error> {
+        let invalid = || Error::SignatureInvalid(signature.into());
+
+        let signed_at = DateTime::from_timestamp(timestamp, 0).ok_or_else(invalid)?;
+        if (now - signed_at).abs() > WEBHOOK_SIGNATURE_TOLERANCE {
+            return Err(invalid());
+        }
+
+        let signature_bytes = signature
+            .strip_prefix("v1=")
+            .and_then(|signature| hex::decode(signature).ok())
+            .ok_or_else(invalid)?;
+        WebhookSignature::mac(secret, id, timestamp, payload)
+            .verify_slice(&signature_bytes)
+            .map_err(|_| invalid())
+    }
+
+    fn mac(secret: &SecretString, id: &Uuid, timestamp: i64, payload: &str) -> Hmac<Sha256> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.expose_secret().as_bytes())
+            .expect("HMAC can take key of any size");
+        mac.update(format!("{}.{}.", id, timestamp).as_bytes());
+        mac.update(payload.as_bytes());
+        mac
+    }
+
+    pub fn timestamp(&self) -> i64 {
+        self.timestamp
+    }
+
+    pub fn signature(&self) -> &str {
+        &self.signature
+    }
+}
+
+// Criteria for listing subscribers, where each criterion left out matches every subscriber
+#[derive(Clone, Debug, Default)]
+pub struct SubscriberFilter {
+    status: Option<Status>,
+    email: Option<String>,
+    subscribed_since: Option<DateTime<Utc>>,
+    subscribed_before: Option<DateTime<Utc>>,
+}
+
+impl SubscriberFilter {
+    pub fn new(
+        status: Option<Status>,
+        email: Option<String>,
+        subscribed_since: Option<DateTime<Utc>>,
+        subscribed_before: Option<DateTime<Utc>>,
+    ) -> Self {
+        Self {
+            status,
+            email,
+            subscribed_since,
+            subscribed_before,
+        }
+    }
+
+    pub fn status(&self) -> Option<&Status> {
+        self.status.as_ref()
+    }
+
+    // Matched as a case-insensitive substring
+    pub fn email(&self) -> Option<&str> {
+        self.email.as_deref()
+    }
+
+    pub fn subscribed_since(&self) -> Option<&DateTime<Utc>> {
+        self.subscribed_since.as_ref()
+    }
+
+    pub fn subscribed_before(&self) -> Option<&DateTime<Utc>> {
+        self.subscribed_before.as_ref()
+    }
+}
+
+// Subscribers are paged in the order of their ids, which are UUIDv7 and so sorted by creation time
+#[derive(Clone, Debug)]
+pub struct SubscriberPage {
+    subscribers: Vec<Subscriber>,
+    next_cursor: Option<Uuid>,
+}
+
+impl SubscriberPage {
+    pub fn new(subscribers: Vec<Subscriber>, next_cursor: Option<Uuid>) -> Self {
+        Self {
+            subscribers,
+            next_cursor,
+        }
+    }
+
+    pub fn subscribers(&self) -> &[Subscriber] {
+        &self.subscribers
+    }
+
+    // Id of the last subscriber on this page, absent on the last page
+    pub fn next_cursor(&self) -> Option<&Uuid> {
+        self.next_cursor.as_ref()
+    }
 }
 
 #[cfg(test)]
@@ -217,9 +990,166 @@ mod tests {
         }
     }
 
+    #[test]
+    fn subscriber_records_events_until_they_are_taken() {
+        let mut subscriber = Subscriber::create("Ada", "ada@example.com", Locale::En).unwrap();
+        subscriber.confirm().unwrap();
+
+        let events = subscriber.take_events();
+
+        assert_eq!(
+            events.iter().map(AsRef::as_ref).collect::<Vec<&str>>(),
+            vec!["subscriber.registered", "subscription.confirmed"]
+        );
+        assert!(events
+            .iter()
+            .all(|event| event.subscriber_id() == subscriber.id()));
+        assert!(subscriber.take_events().is_empty());
+    }
+
+    #[rstest::rstest]
+    #[case(Status::Pending, Status::Confirmed, true)]
+    #[case(Status::Unsubscribed, Status::Confirmed, true)]
+    #[case(Status::Unexpected, Status::Confirmed, true)]
+    #[case(Status::Pending, Status::Unsubscribed, true)]
+    #[case(Status::Confirmed, Status::Unsubscribed, true)]
+    #[case(Status::Unexpected, Status::Unsubscribed, true)]
+    #[case(Status::Confirmed, Status::Pending, false)]
+    #[case(Status::Pending, Status::Unexpected, false)]
+    fn status_allows_only_known_transitions(
+        #[case] current: Status,
+        #[case] next: Status,
+        #[case] expected: bool,
+    ) {
+        assert_eq!(current.can_transition_to(&next), expected);
+    }
+
+    #[test]
+    fn subscriber_is_confirmed_once_even_if_confirmed_again() {
+        let mut subscriber = Subscriber::create("Ada", "ada@example.com", Locale::En).unwrap();
+        subscriber.take_events();
+        subscriber.confirm().unwrap();
+        let confirmed_at = *subscriber.confirmed_at().unwrap();
+
+        let actual = subscriber.confirm();
+
+        assert!(actual.is_ok());
+        assert_eq!(subscriber.confirmed_at(), Some(&confirmed_at));
+        assert_eq!(subscriber.take_events().len(), 1);
+    }
+
+    #[test]
+    fn subscriber_in_unexpected_status_is_confirmed_through_confirmation_sent_to_them() {
+        let mut subscriber = Subscriber::new(
+            Uuid::now_v7(),
+            Name::try_from("Ada").unwrap(),
+            Email::try_from("ada@example.com").unwrap(),
+            Utc::now(),
+            Status::Unexpected,
+            None,
+            Locale::En,
+        );
+
+        let actual = subscriber.confirm();
+
+        assert!(actual.is_ok());
+        assert!(matches!(subscriber.status(), Status::Confirmed));
+        assert!(subscriber.confirmed_at().is_some());
+        assert_eq!(subscriber.take_events().len(), 1);
+    }
+
+    #[test]
+    fn webhook_signature_is_verified_only_for_untampered_fresh_delivery() {
+        let secret = SecretString::from("whsec_test");
+        let id = Uuid::now_v7();
+        let now = Utc::now();
+        let signature = WebhookSignature::sign(&secret, &id, now.timestamp(), "{}");
+
+        let verify = |payload: &str, now: DateTime<Utc>| {
+            WebhookSignature::verify(
+                &secret,
+                &id,
+                signature.timestamp(),
+                payload,
+                signature.signature(),
+                now,
+            )
+        };
+
+        assert!(verify("{}", now).is_ok());
+        assert!(verify(r#"{"tampered":true}"#, now).is_err());
+        assert!(verify("{}", now + TimeDelta::minutes(6)).is_err());
+    }
+
+    #[rstest::rstest]
+    #[case("ftp://example.com/hook")]
+    #[case("not a url")]
+    fn webhook_endpoint_requires_http_url(#[case] url: &str) {
+        assert!(matches!(
+            WebhookEndpoint::register(url, None),
+            Err(Error::InvariantViolated(_))
+        ));
+    }
+
+    #[rstest::rstest]
+    #[case("short", false)]
+    #[case("a-secret-shared-with-the-receiver", true)]
+    fn webhook_endpoint_accepts_only_long_enough_supplied_secret(
+        #[case] secret: &str,
+        #[case] expected: bool,
+    ) {
+        let actual =
+            WebhookEndpoint::register("https://example.com/hook", Some(SecretString::from(secret)));
+
+        assert_eq!(actual.is_ok(), expected);
+        if let Ok(webhook_endpoint) = actual {
+            assert_eq!(webhook_endpoint.secret().expose_secret(), secret);
+        }
+    }
+
+    #[rstest::rstest]
+    #[case("http://localhost:8080/hook", true)]
+    #[case("http://127.0.0.1/hook", true)]
+    #[case("http://10.0.0.1/hook", true)]
+    #[case("http://169.254.169.254/latest/meta-data", true)]
+    #[case("http://[::1]/hook", true)]
+    #[case("http://[::ffff:192.168.0.1]/hook", true)]
+    #[case("http://[fd00::1]/hook", true)]
+    #[case("https://crm.example.com/hooks", false)]
+    #[case("https://93.184.216.34/hook", false)]
+    fn webhook_endpoint_tells_whether_it_targets_private_address(
+        #[case] url: &str,
+        #[case] expected: bool,
+    ) {
+        let webhook_endpoint = WebhookEndpoint::register(url, None).unwrap();
+
+        assert_eq!(webhook_endpoint.targets_private_address(), expected);
+    }
+
     #[quickcheck_macros::quickcheck]
     fn valid_emails_are_parsed_correctly(email: ValidEmailFixture) -> bool {
         dbg!(&email.0);
         Email::parse(email.0.as_str()).is_ok()
     }
+
+    #[rstest::rstest]
+    #[case("alice@example.com", "alice@example.com")]
+    #[case("  Alice@Example.COM ", "Alice@example.com")]
+    #[case("ALICE@example.com", "ALICE@example.com")]
+    #[case("alice@Bücher.example", "alice@xn--bcher-kva.example")]
+    fn email_is_identified_by_normalized_address(#[case] email: &str, #[case] expected: &str) {
+        let actual = Email::parse(email).unwrap();
+
+        assert_eq!(actual.as_ref(), expected);
+        assert_eq!(actual.display(), email.trim());
+    }
+
+    #[rstest::rstest]
+    #[case("alice")]
+    #[case("alice@")]
+    #[case("@example.com")]
+    #[case("alice@exa mple.com")]
+    fn invalid_emails_are_rejected(#[case] email: &str) {
+        assert!(matches!(Email::parse(email), Err(Error::EmailInvalid)));
+    }
 }
@@ -1,12 +1,22 @@
 use crate::subscriber::domain::error::Error;
+use crate::subscriber::domain::infrastructure::EventPublisher;
 use crate::subscriber::domain::infrastructure::SubscriberRepository;
 use crate::subscriber::domain::infrastructure::SubscriptionTokenRepository;
+use crate::subscriber::domain::infrastructure::UnitOfWork;
+use crate::subscriber::domain::service::command::executors::publish_events;
 
-#[derive(Clone, Debug)]
+#[derive(Clone)]
 pub struct Command {
     token: String,
 }
 
+// The token alone is enough to act on behalf of the subscriber, so it is kept out of logs
+impl std::fmt::Debug for Command {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Command").finish_non_exhaustive()
+    }
+}
+
 impl Command {
     pub fn new(token: String) -> Self {
         Self { token }
@@ -17,21 +27,48 @@ impl Command {
     }
 }
 
-#[tracing::instrument(name = "Executing confirm subscription command", skip_all, fields(command = ?command))]
-pub async fn execute(
+#[tracing::instrument(name = "Executing confirm subscription command", skip_all)]
+pub async fn execute<U: UnitOfWork>(
     command: Command,
-    subscriber_repository: impl SubscriberRepository,
-    subscription_token_repository: impl SubscriptionTokenRepository,
+    unit_of_work: U,
+    subscriber_repository: impl SubscriberRepository<Transaction = U::Transaction>,
+    subscription_token_repository: impl SubscriptionTokenRepository<Transaction = U::Transaction>,
+    event_publisher: impl EventPublisher<Transaction = U::Transaction>,
 ) -> Result<(), Error> {
-    let subscription_token = subscription_token_repository
-        .find_by_token(command.token())
-        .await?
-        .ok_or(Error::TokenNotFound(command.token().into()))?;
+    let mut transaction = unit_of_work.begin().await?;
 
+    // The token stays locked from being checked until being consumed, so that concurrent
+    // confirmations cannot both find it usable
+    let mut usable = Ok(());
+    let mut subscriber_id = None;
+    subscription_token_repository
+        .modify_by_token(
+            &mut transaction,
+            command.token(),
+            |mut subscription_token| {
+                usable = subscription_token.ensure_usable();
+                if usable.is_ok() {
+                    subscription_token.consume();
+                }
+                subscriber_id = Some(*subscription_token.subscriber_id());
+                subscription_token
+            },
+        )
+        .await?;
+    usable?;
+    let subscriber_id = subscriber_id.expect("Modifier has been called for the token");
+
+    let mut confirmed = Ok(());
+    let mut events = Vec::new();
     subscriber_repository
-        .modify_by_id(subscription_token.subscriber_id(), |mut subscriber| {
-            subscriber.confirm();
+        .modify_by_id(&mut transaction, &subscriber_id, |mut subscriber| {
+            confirmed = subscriber.confirm();
+            events = subscriber.take_events();
             subscriber
         })
-        .await
+        .await?;
+    confirmed?;
+    publish_events(&mut transaction, &event_publisher, events).await?;
+
+    unit_of_work.commit(transaction).await
 }
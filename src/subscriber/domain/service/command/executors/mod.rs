@@ -1,2 +1,18 @@
 pub mod confirm_subscription;
 pub mod subscribe;
+pub mod unsubscribe;
+
+use crate::subscriber::domain::error::Error;
+use crate::subscriber::domain::infrastructure::EventPublisher;
+use crate::subscriber::domain::model::SubscriberEvent;
+
+async fn publish_events<P: EventPublisher>(
+    transaction: &mut P::Transaction,
+    event_publisher: &P,
+    events: Vec<SubscriberEvent>,
+) -> Result<(), Error> {
+    for event in events.iter() {
+        event_publisher.publish(transaction, event).await?;
+    }
+    Ok(())
+}
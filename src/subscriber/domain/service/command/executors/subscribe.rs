@@ -1,19 +1,28 @@
+use crate::common::locale::Locale;
 use crate::subscriber::domain::error::Error;
-use crate::subscriber::domain::infrastructure::EmailClient;
+use crate::subscriber::domain::infrastructure::EventPublisher;
 use crate::subscriber::domain::infrastructure::SubscriberRepository;
 use crate::subscriber::domain::infrastructure::SubscriptionTokenRepository;
+use crate::subscriber::domain::infrastructure::UnitOfWork;
+use crate::subscriber::domain::model::Status;
 use crate::subscriber::domain::model::Subscriber;
 use crate::subscriber::domain::model::SubscriptionToken;
+use crate::subscriber::domain::service::command::executors::publish_events;
 
 #[derive(Clone, Debug)]
 pub struct Command {
     name: String,
     email: String,
+    locale: Locale,
 }
 
 impl Command {
-    pub fn new(name: String, email: String) -> Self {
-        Self { name, email }
+    pub fn new(name: String, email: String, locale: Locale) -> Self {
+        Self {
+            name,
+            email,
+            locale,
+        }
     }
 
     pub fn name(&self) -> &str {
@@ -23,28 +32,61 @@ impl Command {
     pub fn email(&self) -> &str {
         self.email.as_str()
     }
+
+    pub fn locale(&self) -> Locale {
+        self.locale
+    }
 }
 
 #[tracing::instrument(name = "Executing subscribe command", skip_all, fields(command = ?command))]
-pub async fn execute(
+pub async fn execute<U: UnitOfWork>(
     command: Command,
-    subscriber_repository: impl SubscriberRepository,
-    subscription_token_repository: impl SubscriptionTokenRepository,
-    email_client: impl EmailClient,
+    unit_of_work: U,
+    subscriber_repository: impl SubscriberRepository<Transaction = U::Transaction>,
+    subscription_token_repository: impl SubscriptionTokenRepository<Transaction = U::Transaction>,
+    event_publisher: impl EventPublisher<Transaction = U::Transaction>,
 ) -> Result<(), Error> {
-    let subscriber = Subscriber::create(&command.name, &command.email)?;
-    subscriber_repository.save(&subscriber).await?;
-
-    let subscription_token = SubscriptionToken::create(*subscriber.id());
-    subscription_token_repository
-        .save(&subscription_token)
-        .await?;
-
-    email_client
-        .send(
-            &subscriber,
-            "hello!",
-            format!("click this link: .. {} ..", subscription_token.token()).as_ref(),
-        )
-        .await
+    let mut subscriber = Subscriber::create(&command.name, &command.email, command.locale)?;
+
+    let mut transaction = unit_of_work.begin().await?;
+    match subscriber_repository
+        .find_by_email(&mut transaction, subscriber.email())
+        .await?
+    {
+        // Report success without sending anything, so that the response does not reveal
+        // whether the address is already subscribed
+        Some(existing) if matches!(existing.status(), Status::Confirmed) => {
+            return unit_of_work.commit(transaction).await;
+        }
+        // A pending or unsubscribed address gets a fresh confirmation, which confirms it anew,
+        // unless one has just been issued
+        Some(mut existing) => {
+            let latest_issued_at = subscription_token_repository
+                .find_latest_issued_at_by_subscriber_id(&mut transaction, existing.id())
+                .await?;
+            if latest_issued_at
+                .is_some_and(|issued_at| !SubscriptionToken::allows_reissuing(&issued_at))
+            {
+                return unit_of_work.commit(transaction).await;
+            }
+            existing.register_again();
+            publish_events(&mut transaction, &event_publisher, existing.take_events()).await?;
+        }
+        None => {
+            match subscriber_repository
+                .save(&mut transaction, &subscriber)
+                .await
+            {
+                // The concurrent subscription having saved the address sends its confirmation
+                Err(Error::SubscriberAlreadyExists(_)) => {
+                    return unit_of_work.commit(transaction).await;
+                }
+                result => result?,
+            }
+            publish_events(&mut transaction, &event_publisher, subscriber.take_events()).await?;
+        }
+    }
+
+    // The confirmation email itself is queued by the publisher reacting to the registration
+    unit_of_work.commit(transaction).await
 }
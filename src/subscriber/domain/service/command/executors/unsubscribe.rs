@@ -0,0 +1,66 @@
+use secrecy::SecretString;
+
+use crate::common::link::UnsubscribeToken;
+use crate::subscriber::domain::error::Error;
+use crate::subscriber::domain::infrastructure::EventPublisher;
+use crate::subscriber::domain::infrastructure::SubscriberRepository;
+use crate::subscriber::domain::infrastructure::SubscriptionTokenRepository;
+use crate::subscriber::domain::infrastructure::UnitOfWork;
+use crate::subscriber::domain::service::command::executors::publish_events;
+
+#[derive(Clone)]
+pub struct Command {
+    token: String,
+}
+
+// The token alone is enough to act on behalf of the subscriber, so it is kept out of logs
+impl std::fmt::Debug for Command {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Command").finish_non_exhaustive()
+    }
+}
+
+impl Command {
+    pub fn new(token: String) -> Self {
+        Self { token }
+    }
+
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+}
+
+#[tracing::instrument(name = "Executing unsubscribe command", skip_all)]
+pub async fn execute<U: UnitOfWork>(
+    command: Command,
+    unit_of_work: U,
+    subscriber_repository: impl SubscriberRepository<Transaction = U::Transaction>,
+    subscription_token_repository: impl SubscriptionTokenRepository<Transaction = U::Transaction>,
+    event_publisher: impl EventPublisher<Transaction = U::Transaction>,
+    unsubscribe_key: SecretString,
+) -> Result<(), Error> {
+    let unsubscribe_token = UnsubscribeToken::verify(command.token(), &unsubscribe_key)?;
+
+    let mut transaction = unit_of_work.begin().await?;
+    // Confirmation links sent before opting out must not subscribe the address again. Tokens are
+    // locked before the subscriber, in the same order as confirmations, so the two cannot deadlock
+    subscription_token_repository
+        .remove_unused_by_subscriber_id(&mut transaction, unsubscribe_token.subscriber_id())
+        .await?;
+    let mut unsubscribed = Ok(());
+    let mut events = Vec::new();
+    subscriber_repository
+        .modify_by_id(
+            &mut transaction,
+            unsubscribe_token.subscriber_id(),
+            |mut subscriber| {
+                unsubscribed = subscriber.unsubscribe();
+                events = subscriber.take_events();
+                subscriber
+            },
+        )
+        .await?;
+    unsubscribed?;
+    publish_events(&mut transaction, &event_publisher, events).await?;
+    unit_of_work.commit(transaction).await
+}
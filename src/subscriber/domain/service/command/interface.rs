@@ -1,19 +1,24 @@
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
+use std::time::Duration;
 
 use enum_as_inner::EnumAsInner;
+use rand::Rng;
+use secrecy::SecretString;
 
 use crate::subscriber::domain::error::Error;
-use crate::subscriber::domain::infrastructure::EmailClient;
+use crate::subscriber::domain::infrastructure::EventPublisher;
 use crate::subscriber::domain::infrastructure::SubscriberRepository;
 use crate::subscriber::domain::infrastructure::SubscriptionTokenRepository;
+use crate::subscriber::domain::infrastructure::UnitOfWork;
 use crate::subscriber::domain::service::command::executors;
 
 #[derive(Clone, EnumAsInner)]
 pub enum Command {
     Subscribe(executors::subscribe::Command),
     ConfirmSubscription(executors::confirm_subscription::Command),
+    Unsubscribe(executors::unsubscribe::Command),
 }
 
 // TODO: Maybe good chance to learn macros with EnumAsInner and From
@@ -29,6 +34,19 @@ impl From<executors::confirm_subscription::Command> for Command {
     }
 }
 
+impl From<executors::unsubscribe::Command> for Command {
+    fn from(command: executors::unsubscribe::Command) -> Self {
+        Self::Unsubscribe(command)
+    }
+}
+
+// Commands losing a race against concurrent modifications are given up after this many runs
+pub const MAX_ATTEMPTS_ON_CONCURRENCY_CONFLICT: u32 = 3;
+
+// Upper bound of the random pause before the first retry, doubled for every later one, so that
+// commands which have just conflicted do not collide again right away
+const CONCURRENCY_CONFLICT_BACKOFF: Duration = Duration::from_millis(20);
+
 #[async_trait::async_trait]
 pub trait CommandExecutor: Send + Sync + 'static {
     async fn execute(&self, command: Command) -> Result<(), Error>;
@@ -44,34 +62,75 @@ impl CommandExecutor for CommandExecutorFuncion {
     }
 }
 
-pub fn new_command_executor(
-    subscriber_repository: impl SubscriberRepository,
-    subscription_token_repository: impl SubscriptionTokenRepository,
-    email_client: impl EmailClient,
+pub fn new_command_executor<U: UnitOfWork>(
+    unit_of_work: U,
+    subscriber_repository: impl SubscriberRepository<Transaction = U::Transaction>,
+    subscription_token_repository: impl SubscriptionTokenRepository<Transaction = U::Transaction>,
+    event_publisher: impl EventPublisher<Transaction = U::Transaction>,
+    unsubscribe_key: SecretString,
 ) -> CommandExecutorFuncion {
     Arc::new(move |command: Command| {
+        let unit_of_work = unit_of_work.clone();
         let subscriber_repository = subscriber_repository.clone();
         let subscription_token_repository = subscription_token_repository.clone();
-        let email_client = email_client.clone();
+        let event_publisher = event_publisher.clone();
+        let unsubscribe_key = unsubscribe_key.clone();
 
         Box::pin(async move {
-            match command {
-                Command::Subscribe(command) => {
-                    executors::subscribe::execute(
-                        command,
-                        subscriber_repository,
-                        subscription_token_repository,
-                        email_client,
-                    )
-                    .await
-                }
-                Command::ConfirmSubscription(command) => {
-                    executors::confirm_subscription::execute(
-                        command,
-                        subscriber_repository,
-                        subscription_token_repository,
-                    )
-                    .await
+            let mut attempt = 1;
+            loop {
+                let result = match command.clone() {
+                    Command::Subscribe(command) => {
+                        executors::subscribe::execute(
+                            command,
+                            unit_of_work.clone(),
+                            subscriber_repository.clone(),
+                            subscription_token_repository.clone(),
+                            event_publisher.clone(),
+                        )
+                        .await
+                    }
+                    Command::ConfirmSubscription(command) => {
+                        executors::confirm_subscription::execute(
+                            command,
+                            unit_of_work.clone(),
+                            subscriber_repository.clone(),
+                            subscription_token_repository.clone(),
+                            event_publisher.clone(),
+                        )
+                        .await
+                    }
+                    Command::Unsubscribe(command) => {
+                        executors::unsubscribe::execute(
+                            command,
+                            unit_of_work.clone(),
+                            subscriber_repository.clone(),
+                            subscription_token_repository.clone(),
+                            event_publisher.clone(),
+                            unsubscribe_key.clone(),
+                        )
+                        .await
+                    }
+                };
+
+                match result {
+                    // Another command has modified the same subscriber in the meantime, so the
+                    // whole command is run again against the fresh state
+                    Err(Error::ConcurrencyConflict(id))
+                        if attempt < MAX_ATTEMPTS_ON_CONCURRENCY_CONFLICT =>
+                    {
+                        tracing::warn!(
+                            subscriber_id = %id,
+                            attempt,
+                            "Retrying command after concurrent modification of subscriber"
+                        );
+                        let backoff = rand::thread_rng().gen_range(
+                            Duration::ZERO..=CONCURRENCY_CONFLICT_BACKOFF * 2u32.pow(attempt - 1),
+                        );
+                        tokio::time::sleep(backoff).await;
+                        attempt += 1;
+                    }
+                    result => return result,
                 }
             }
         })
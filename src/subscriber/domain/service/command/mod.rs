@@ -4,6 +4,9 @@ mod interface;
 pub use interface::new_command_executor;
 pub use interface::Command;
 pub use interface::CommandExecutor;
+pub use interface::CommandExecutorFuncion;
+pub use interface::MAX_ATTEMPTS_ON_CONCURRENCY_CONFLICT;
 
 pub use executors::confirm_subscription::Command as ConfirmSubscriptionCommand;
 pub use executors::subscribe::Command as SubscribeCommand;
+pub use executors::unsubscribe::Command as UnsubscribeCommand;
@@ -0,0 +1,104 @@
+use secrecy::SecretString;
+
+use crate::common::link::LinkBuilder;
+use crate::common::link::UnsubscribeToken;
+use crate::subscriber::domain::error::Error;
+use crate::subscriber::domain::infrastructure::EmailRenderer;
+use crate::subscriber::domain::infrastructure::EventPublisher;
+use crate::subscriber::domain::infrastructure::OutboxRepository;
+use crate::subscriber::domain::infrastructure::SubscriberRepository;
+use crate::subscriber::domain::infrastructure::SubscriptionTokenRepository;
+use crate::subscriber::domain::model::OutboxMessage;
+use crate::subscriber::domain::model::SubscriberEvent;
+use crate::subscriber::domain::model::SubscriptionToken;
+
+// Queues a confirmation email for every registration, within the transaction of the command that
+// registered the subscriber, so that the email is sent if and only if the registration is stored
+#[derive(Clone)]
+pub struct ConfirmationEmailPublisher<S, T, O, R> {
+    subscriber_repository: S,
+    subscription_token_repository: T,
+    outbox_repository: O,
+    email_renderer: R,
+    link_builder: LinkBuilder,
+    unsubscribe_key: SecretString,
+}
+
+impl<S, T, O, R> ConfirmationEmailPublisher<S, T, O, R> {
+    pub fn new(
+        subscriber_repository: S,
+        subscription_token_repository: T,
+        outbox_repository: O,
+        email_renderer: R,
+        link_builder: LinkBuilder,
+        unsubscribe_key: SecretString,
+    ) -> Self {
+        Self {
+            subscriber_repository,
+            subscription_token_repository,
+            outbox_repository,
+            email_renderer,
+            link_builder,
+            unsubscribe_key,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<S, T, O, R> EventPublisher for ConfirmationEmailPublisher<S, T, O, R>
+where
+    S: SubscriberRepository,
+    T: SubscriptionTokenRepository<Transaction = S::Transaction>,
+    O: OutboxRepository<Transaction = S::Transaction>,
+    R: EmailRenderer,
+{
+    type Transaction = S::Transaction;
+
+    #[tracing::instrument(name = "Queueing confirmation email", skip_all, fields(event = event.as_ref()))]
+    async fn publish(
+        &self,
+        transaction: &mut Self::Transaction,
+        event: &SubscriberEvent,
+    ) -> Result<(), Error> {
+        if !matches!(
+            event,
+            SubscriberEvent::SubscriberRegistered { .. }
+                | SubscriberEvent::ConfirmationReissued { .. }
+        ) {
+            return Ok(());
+        }
+
+        let subscriber = self
+            .subscriber_repository
+            .find_by_id(transaction, event.subscriber_id())
+            .await?
+            .ok_or(Error::SubscriberNotFound(*event.subscriber_id()))?;
+
+        let subscription_token = SubscriptionToken::create(*subscriber.id());
+        let confirmation_url = self
+            .link_builder
+            .confirm_subscription(subscription_token.token());
+        let unsubscribe_url = self
+            .link_builder
+            .unsubscribe(&UnsubscribeToken::issue(*subscriber.id(), &self.unsubscribe_key).token());
+        // The confirmation email is delivered later by the outbox dispatcher, so that email server
+        // outages do not fail the subscription itself
+        let outbox_message = OutboxMessage::create(
+            *subscriber.id(),
+            self.email_renderer
+                .render_confirmation(
+                    &subscriber,
+                    confirmation_url.as_str(),
+                    unsubscribe_url.as_str(),
+                )?
+                .with_unsubscribe_url(&unsubscribe_url),
+        );
+
+        self.subscription_token_repository
+            .save(transaction, &subscription_token)
+            .await?;
+        self.outbox_repository
+            .save(transaction, &outbox_message)
+            .await
+    }
+}
@@ -1,3 +1,11 @@
 mod command;
+mod confirmation;
+mod outbox;
+mod query;
+mod webhook;
 
 pub use command::*;
+pub use confirmation::*;
+pub use outbox::*;
+pub use query::*;
+pub use webhook::*;
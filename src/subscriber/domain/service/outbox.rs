@@ -0,0 +1,98 @@
+use anyhow::anyhow;
+use chrono::TimeDelta;
+
+use crate::common::email::EmailClient;
+use crate::common::retry::RetryPolicy;
+use crate::subscriber::domain::error::Error;
+use crate::subscriber::domain::infrastructure::OutboxRepository;
+use crate::subscriber::domain::infrastructure::SubscriberRepository;
+use crate::subscriber::domain::infrastructure::UnitOfWork;
+
+// Claimed messages are retried by any dispatcher once their lease expires, e.g. when the one
+// having claimed them stopped before recording the results of sending them
+const OUTBOX_MESSAGE_LEASE: TimeDelta = TimeDelta::minutes(10);
+
+// Delivers a batch of outbox messages and returns how many of them have been attempted
+#[tracing::instrument(name = "Dispatching outbox messages", skip_all)]
+pub async fn dispatch_outbox_messages<U: UnitOfWork>(
+    unit_of_work: &U,
+    subscriber_repository: &impl SubscriberRepository<Transaction = U::Transaction>,
+    outbox_repository: &impl OutboxRepository<Transaction = U::Transaction>,
+    email_client: &impl EmailClient,
+    retry_policy: &RetryPolicy,
+    batch_size: i64,
+) -> Result<usize, Error> {
+    // Messages are claimed in a transaction of their own, so that no row stays locked while
+    // emails are being sent
+    let mut transaction = unit_of_work.begin().await?;
+
+    let outbox_messages = outbox_repository
+        .find_deliverable(&mut transaction, retry_policy.max_attempts(), batch_size)
+        .await?;
+
+    let mut deliveries = Vec::with_capacity(outbox_messages.len());
+    for outbox_message in outbox_messages {
+        let recipient = subscriber_repository
+            .find_by_id(&mut transaction, outbox_message.subscriber_id())
+            .await?;
+        outbox_repository
+            .modify_by_id(
+                &mut transaction,
+                outbox_message.id(),
+                |mut outbox_message| {
+                    outbox_message.claim(OUTBOX_MESSAGE_LEASE);
+                    outbox_message
+                },
+            )
+            .await?;
+        deliveries.push((outbox_message, recipient));
+    }
+
+    unit_of_work.commit(transaction).await?;
+
+    for (outbox_message, recipient) in deliveries.iter() {
+        let result = match (recipient, outbox_message.message()) {
+            (Some(recipient), Some(message)) => email_client
+                .send(recipient.display_email(), message)
+                .await
+                .map_err(Error::from),
+            (None, _) => Err(Error::SubscriberNotFound(*outbox_message.subscriber_id())),
+            (Some(_), None) => Err(Error::FailedUnexpectedly(anyhow!(
+                "Deliverable outbox message has no body"
+            ))),
+        };
+
+        if let Err(error) = &result {
+            tracing::warn!(id = ?outbox_message.id(), "Failed to deliver outbox message: {:?}", error);
+        }
+
+        let mut transaction = unit_of_work.begin().await?;
+        let mut dead_lettered = false;
+        outbox_repository
+            .modify_by_id(
+                &mut transaction,
+                outbox_message.id(),
+                |mut outbox_message| {
+                    match result {
+                        Ok(_) => outbox_message.mark_as_delivered(),
+                        Err(error @ Error::EmailRejected(_)) => outbox_message
+                            .mark_as_rejected(format!("{:?}", anyhow::Error::from(error))),
+                        Err(error) => outbox_message.mark_as_failed(
+                            format!("{:?}", anyhow::Error::from(error)),
+                            retry_policy,
+                        ),
+                    }
+                    dead_lettered = outbox_message.dead_lettered_at().is_some();
+                    outbox_message
+                },
+            )
+            .await?;
+        unit_of_work.commit(transaction).await?;
+
+        if dead_lettered {
+            tracing::error!(id = ?outbox_message.id(), "Gave up delivering outbox message");
+        }
+    }
+
+    Ok(deliveries.len())
+}
@@ -0,0 +1,38 @@
+use uuid::Uuid;
+
+use crate::subscriber::domain::error::Error;
+use crate::subscriber::domain::infrastructure::SubscriberRepository;
+use crate::subscriber::domain::infrastructure::UnitOfWork;
+use crate::subscriber::domain::model::Subscriber;
+
+#[derive(Clone, Debug)]
+pub struct Query {
+    id: Uuid,
+}
+
+impl Query {
+    pub fn new(id: Uuid) -> Self {
+        Self { id }
+    }
+
+    pub fn id(&self) -> &Uuid {
+        &self.id
+    }
+}
+
+#[tracing::instrument(name = "Executing get subscriber query", skip_all, fields(query = ?query))]
+pub async fn execute<U: UnitOfWork>(
+    query: Query,
+    unit_of_work: U,
+    subscriber_repository: impl SubscriberRepository<Transaction = U::Transaction>,
+) -> Result<Subscriber, Error> {
+    let mut transaction = unit_of_work.begin().await?;
+
+    let subscriber = subscriber_repository
+        .find_by_id(&mut transaction, query.id())
+        .await?
+        .ok_or(Error::SubscriberNotFound(*query.id()))?;
+
+    unit_of_work.commit(transaction).await?;
+    Ok(subscriber)
+}
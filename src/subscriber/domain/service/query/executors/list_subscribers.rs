@@ -0,0 +1,73 @@
+use uuid::Uuid;
+
+use crate::subscriber::domain::error::Error;
+use crate::subscriber::domain::infrastructure::SubscriberRepository;
+use crate::subscriber::domain::infrastructure::UnitOfWork;
+use crate::subscriber::domain::model::SubscriberFilter;
+use crate::subscriber::domain::model::SubscriberPage;
+
+pub const MAX_LIMIT: i64 = 100;
+
+#[derive(Clone, Debug)]
+pub struct Query {
+    filter: SubscriberFilter,
+    cursor: Option<Uuid>,
+    limit: i64,
+}
+
+impl Query {
+    pub fn new(filter: SubscriberFilter, cursor: Option<Uuid>, limit: i64) -> Self {
+        Self {
+            filter,
+            cursor,
+            limit,
+        }
+    }
+
+    pub fn filter(&self) -> &SubscriberFilter {
+        &self.filter
+    }
+
+    pub fn cursor(&self) -> Option<&Uuid> {
+        self.cursor.as_ref()
+    }
+
+    pub fn limit(&self) -> i64 {
+        self.limit
+    }
+}
+
+#[tracing::instrument(name = "Executing list subscribers query", skip_all, fields(query = ?query))]
+pub async fn execute<U: UnitOfWork>(
+    query: Query,
+    unit_of_work: U,
+    subscriber_repository: impl SubscriberRepository<Transaction = U::Transaction>,
+) -> Result<SubscriberPage, Error> {
+    if !(1..=MAX_LIMIT).contains(&query.limit()) {
+        return Err(Error::InvariantViolated(format!(
+            "Limit must be between 1 and {}",
+            MAX_LIMIT
+        )));
+    }
+
+    let mut transaction = unit_of_work.begin().await?;
+
+    // One more subscriber than asked for tells whether another page follows
+    let mut subscribers = subscriber_repository
+        .find_page(
+            &mut transaction,
+            query.filter(),
+            query.cursor(),
+            query.limit() + 1,
+        )
+        .await?;
+
+    unit_of_work.commit(transaction).await?;
+
+    let mut next_cursor = None;
+    if subscribers.len() as i64 > query.limit() {
+        subscribers.truncate(query.limit() as usize);
+        next_cursor = subscribers.last().map(|subscriber| *subscriber.id());
+    }
+    Ok(SubscriberPage::new(subscribers, next_cursor))
+}
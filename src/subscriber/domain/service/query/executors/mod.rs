@@ -0,0 +1,2 @@
+pub mod get_subscriber;
+pub mod list_subscribers;
@@ -0,0 +1,71 @@
+use crate::subscriber::domain::error::Error;
+use crate::subscriber::domain::infrastructure::SubscriberRepository;
+use crate::subscriber::domain::infrastructure::UnitOfWork;
+use crate::subscriber::domain::model::Subscriber;
+use crate::subscriber::domain::model::SubscriberPage;
+use crate::subscriber::domain::service::query::executors;
+
+// Queries only read subscribers, so each of them answers with its own type rather than through
+// a single enum as commands do
+#[async_trait::async_trait]
+pub trait QueryExecutor: Send + Sync + 'static {
+    async fn get_subscriber(
+        &self,
+        query: executors::get_subscriber::Query,
+    ) -> Result<Subscriber, Error>;
+    async fn list_subscribers(
+        &self,
+        query: executors::list_subscribers::Query,
+    ) -> Result<SubscriberPage, Error>;
+}
+
+#[derive(Clone)]
+pub struct SubscriberQueryExecutor<U, R> {
+    unit_of_work: U,
+    subscriber_repository: R,
+}
+
+pub fn new_query_executor<U, R>(
+    unit_of_work: U,
+    subscriber_repository: R,
+) -> SubscriberQueryExecutor<U, R>
+where
+    U: UnitOfWork,
+    R: SubscriberRepository<Transaction = U::Transaction>,
+{
+    SubscriberQueryExecutor {
+        unit_of_work,
+        subscriber_repository,
+    }
+}
+
+#[async_trait::async_trait]
+impl<U, R> QueryExecutor for SubscriberQueryExecutor<U, R>
+where
+    U: UnitOfWork,
+    R: SubscriberRepository<Transaction = U::Transaction>,
+{
+    async fn get_subscriber(
+        &self,
+        query: executors::get_subscriber::Query,
+    ) -> Result<Subscriber, Error> {
+        executors::get_subscriber::execute(
+            query,
+            self.unit_of_work.clone(),
+            self.subscriber_repository.clone(),
+        )
+        .await
+    }
+
+    async fn list_subscribers(
+        &self,
+        query: executors::list_subscribers::Query,
+    ) -> Result<SubscriberPage, Error> {
+        executors::list_subscribers::execute(
+            query,
+            self.unit_of_work.clone(),
+            self.subscriber_repository.clone(),
+        )
+        .await
+    }
+}
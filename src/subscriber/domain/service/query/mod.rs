@@ -0,0 +1,8 @@
+mod executors;
+mod interface;
+
+pub use executors::get_subscriber::Query as GetSubscriberQuery;
+pub use executors::list_subscribers::Query as ListSubscribersQuery;
+pub use interface::new_query_executor;
+pub use interface::QueryExecutor;
+pub use interface::SubscriberQueryExecutor;
@@ -0,0 +1,305 @@
+use chrono::TimeDelta;
+use chrono::Utc;
+use secrecy::SecretString;
+use uuid::Uuid;
+
+use crate::common::retry::RetryPolicy;
+use crate::subscriber::domain::error::Error;
+use crate::subscriber::domain::infrastructure::EventPublisher;
+use crate::subscriber::domain::infrastructure::SubscriberRepository;
+use crate::subscriber::domain::infrastructure::UnitOfWork;
+use crate::subscriber::domain::infrastructure::WebhookClient;
+use crate::subscriber::domain::infrastructure::WebhookDeliveryRepository;
+use crate::subscriber::domain::infrastructure::WebhookEndpointRepository;
+use crate::subscriber::domain::model::SubscriberEvent;
+use crate::subscriber::domain::model::WebhookDelivery;
+use crate::subscriber::domain::model::WebhookEndpoint;
+use crate::subscriber::domain::model::WebhookSignature;
+
+// Most recent deliveries shown in the log of an endpoint
+const DELIVERY_LOG_LIMIT: i64 = 100;
+
+// Claimed deliveries are retried by any worker once their lease expires, e.g. when the one having
+// claimed them stopped before recording the results of sending them
+const WEBHOOK_DELIVERY_LEASE: TimeDelta = TimeDelta::minutes(10);
+
+// Queues a delivery of each event for every registered endpoint, within the transaction of the
+// command that produced the event
+#[derive(Clone)]
+pub struct WebhookEventPublisher<S, E, D> {
+    subscriber_repository: S,
+    webhook_endpoint_repository: E,
+    webhook_delivery_repository: D,
+}
+
+impl<S, E, D> WebhookEventPublisher<S, E, D> {
+    pub fn new(
+        subscriber_repository: S,
+        webhook_endpoint_repository: E,
+        webhook_delivery_repository: D,
+    ) -> Self {
+        Self {
+            subscriber_repository,
+            webhook_endpoint_repository,
+            webhook_delivery_repository,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<S, E, D> EventPublisher for WebhookEventPublisher<S, E, D>
+where
+    S: SubscriberRepository,
+    E: WebhookEndpointRepository<Transaction = S::Transaction>,
+    D: WebhookDeliveryRepository<Transaction = S::Transaction>,
+{
+    type Transaction = S::Transaction;
+
+    #[tracing::instrument(name = "Queueing webhook deliveries", skip_all, fields(event = event.as_ref()))]
+    async fn publish(
+        &self,
+        transaction: &mut Self::Transaction,
+        event: &SubscriberEvent,
+    ) -> Result<(), Error> {
+        // Receivers are only told about subscribers joining for now, and not about confirmations
+        // being asked for again
+        if !matches!(
+            event,
+            SubscriberEvent::SubscriberRegistered { .. }
+                | SubscriberEvent::SubscriptionConfirmed { .. }
+        ) {
+            return Ok(());
+        }
+
+        let webhook_endpoints = self
+            .webhook_endpoint_repository
+            .find_all(transaction)
+            .await?;
+        if webhook_endpoints.is_empty() {
+            return Ok(());
+        }
+
+        let subscriber = self
+            .subscriber_repository
+            .find_by_id(transaction, event.subscriber_id())
+            .await?
+            .ok_or(Error::SubscriberNotFound(*event.subscriber_id()))?;
+        for webhook_endpoint in webhook_endpoints.iter() {
+            self.webhook_delivery_repository
+                .save(
+                    transaction,
+                    &WebhookDelivery::create(*webhook_endpoint.id(), event, &subscriber),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+// Sends a batch of webhook deliveries and returns how many of them have been attempted
+#[tracing::instrument(name = "Dispatching webhook deliveries", skip_all)]
+pub async fn dispatch_webhook_deliveries<U: UnitOfWork>(
+    unit_of_work: &U,
+    webhook_endpoint_repository: &impl WebhookEndpointRepository<Transaction = U::Transaction>,
+    webhook_delivery_repository: &impl WebhookDeliveryRepository<Transaction = U::Transaction>,
+    webhook_client: &impl WebhookClient,
+    retry_policy: &RetryPolicy,
+    batch_size: i64,
+) -> Result<usize, Error> {
+    // Deliveries are claimed in a transaction of their own, so that no row stays locked while
+    // endpoints are being called
+    let mut transaction = unit_of_work.begin().await?;
+
+    let webhook_deliveries = webhook_delivery_repository
+        .find_deliverable(&mut transaction, retry_policy.max_attempts(), batch_size)
+        .await?;
+
+    let mut deliveries = Vec::with_capacity(webhook_deliveries.len());
+    for webhook_delivery in webhook_deliveries {
+        let webhook_endpoint = webhook_endpoint_repository
+            .find_by_id(&mut transaction, webhook_delivery.endpoint_id())
+            .await?;
+        webhook_delivery_repository
+            .modify_by_id(
+                &mut transaction,
+                webhook_delivery.id(),
+                |mut webhook_delivery| {
+                    webhook_delivery.claim(WEBHOOK_DELIVERY_LEASE);
+                    webhook_delivery
+                },
+            )
+            .await?;
+        deliveries.push((webhook_delivery, webhook_endpoint));
+    }
+
+    unit_of_work.commit(transaction).await?;
+
+    for (webhook_delivery, webhook_endpoint) in deliveries.iter() {
+        let result = match webhook_endpoint {
+            Some(webhook_endpoint) => {
+                // Signed right before sending, so that the timestamp tells receivers how fresh it is
+                let signature = WebhookSignature::sign(
+                    webhook_endpoint.secret(),
+                    webhook_delivery.id(),
+                    Utc::now().timestamp(),
+                    webhook_delivery.payload(),
+                );
+                webhook_client
+                    .send(webhook_endpoint.url(), webhook_delivery, &signature)
+                    .await
+            }
+            None => Err(Error::WebhookEndpointNotFound(
+                *webhook_delivery.endpoint_id(),
+            )),
+        };
+
+        match &result {
+            Ok(status_code) if !(200..300).contains(status_code) => {
+                tracing::warn!(id = ?webhook_delivery.id(), "Webhook endpoint responded with {}", status_code)
+            }
+            Err(error) => {
+                tracing::warn!(id = ?webhook_delivery.id(), "Failed to deliver webhook: {:?}", error)
+            }
+            _ => {}
+        }
+
+        let mut transaction = unit_of_work.begin().await?;
+        webhook_delivery_repository
+            .modify_by_id(
+                &mut transaction,
+                webhook_delivery.id(),
+                |mut webhook_delivery| {
+                    match result {
+                        Ok(status_code) if (200..300).contains(&status_code) => {
+                            webhook_delivery.mark_as_delivered(status_code.into())
+                        }
+                        Ok(status_code) => webhook_delivery.mark_as_failed(
+                            Some(status_code.into()),
+                            format!("Endpoint responded with {}", status_code),
+                            retry_policy,
+                        ),
+                        Err(error) => webhook_delivery.mark_as_failed(
+                            None,
+                            format!("{:?}", anyhow::Error::from(error)),
+                            retry_policy,
+                        ),
+                    }
+                    webhook_delivery
+                },
+            )
+            .await?;
+        unit_of_work.commit(transaction).await?;
+    }
+
+    Ok(deliveries.len())
+}
+
+#[async_trait::async_trait]
+pub trait WebhookManager: Send + Sync + 'static {
+    // The secret of the endpoint is only shown once, right after registration
+    async fn register(
+        &self,
+        url: &str,
+        secret: Option<SecretString>,
+    ) -> Result<WebhookEndpoint, Error>;
+    async fn list(&self) -> Result<Vec<WebhookEndpoint>, Error>;
+    async fn remove(&self, id: &Uuid) -> Result<(), Error>;
+    async fn list_deliveries(&self, endpoint_id: &Uuid) -> Result<Vec<WebhookDelivery>, Error>;
+}
+
+#[derive(Clone)]
+pub struct WebhookService<U, E, D> {
+    unit_of_work: U,
+    webhook_endpoint_repository: E,
+    webhook_delivery_repository: D,
+    allow_private_targets: bool,
+}
+
+// Endpoints on private or loopback addresses are only accepted where receivers run alongside the
+// service, e.g. in tests
+pub fn new_webhook_manager<U, E, D>(
+    unit_of_work: U,
+    webhook_endpoint_repository: E,
+    webhook_delivery_repository: D,
+    allow_private_targets: bool,
+) -> WebhookService<U, E, D>
+where
+    U: UnitOfWork,
+    E: WebhookEndpointRepository<Transaction = U::Transaction>,
+    D: WebhookDeliveryRepository<Transaction = U::Transaction>,
+{
+    WebhookService {
+        unit_of_work,
+        webhook_endpoint_repository,
+        webhook_delivery_repository,
+        allow_private_targets,
+    }
+}
+
+#[async_trait::async_trait]
+impl<U, E, D> WebhookManager for WebhookService<U, E, D>
+where
+    U: UnitOfWork,
+    E: WebhookEndpointRepository<Transaction = U::Transaction>,
+    D: WebhookDeliveryRepository<Transaction = U::Transaction>,
+{
+    #[tracing::instrument(name = "Registering webhook endpoint", skip_all, fields(url = %url))]
+    async fn register(
+        &self,
+        url: &str,
+        secret: Option<SecretString>,
+    ) -> Result<WebhookEndpoint, Error> {
+        let webhook_endpoint = WebhookEndpoint::register(url, secret)?;
+        if !self.allow_private_targets && webhook_endpoint.targets_private_address() {
+            return Err(Error::InvariantViolated(
+                "Webhook URL must not target a private or loopback address".into(),
+            ));
+        }
+
+        let mut transaction = self.unit_of_work.begin().await?;
+        self.webhook_endpoint_repository
+            .save(&mut transaction, &webhook_endpoint)
+            .await?;
+        self.unit_of_work.commit(transaction).await?;
+
+        Ok(webhook_endpoint)
+    }
+
+    #[tracing::instrument(name = "Listing webhook endpoints", skip_all)]
+    async fn list(&self) -> Result<Vec<WebhookEndpoint>, Error> {
+        let mut transaction = self.unit_of_work.begin().await?;
+        let webhook_endpoints = self
+            .webhook_endpoint_repository
+            .find_all(&mut transaction)
+            .await?;
+        self.unit_of_work.commit(transaction).await?;
+
+        Ok(webhook_endpoints)
+    }
+
+    #[tracing::instrument(name = "Removing webhook endpoint", skip_all, fields(id = ?id))]
+    async fn remove(&self, id: &Uuid) -> Result<(), Error> {
+        let mut transaction = self.unit_of_work.begin().await?;
+        self.webhook_endpoint_repository
+            .remove_by_id(&mut transaction, id)
+            .await?;
+        self.unit_of_work.commit(transaction).await
+    }
+
+    #[tracing::instrument(name = "Listing webhook deliveries", skip_all, fields(endpoint_id = ?endpoint_id))]
+    async fn list_deliveries(&self, endpoint_id: &Uuid) -> Result<Vec<WebhookDelivery>, Error> {
+        let mut transaction = self.unit_of_work.begin().await?;
+        self.webhook_endpoint_repository
+            .find_by_id(&mut transaction, endpoint_id)
+            .await?
+            .ok_or(Error::WebhookEndpointNotFound(*endpoint_id))?;
+        let webhook_deliveries = self
+            .webhook_delivery_repository
+            .find_by_endpoint_id(&mut transaction, endpoint_id, DELIVERY_LOG_LIMIT)
+            .await?;
+        self.unit_of_work.commit(transaction).await?;
+
+        Ok(webhook_deliveries)
+    }
+}
@@ -0,0 +1,105 @@
+use std::sync::Arc;
+
+use anyhow::Context;
+use minijinja::context;
+use minijinja::Environment;
+use serde::Serialize;
+
+use crate::common::email::EmailMessage;
+use crate::common::locale::Locale;
+use crate::subscriber::domain::error::Error;
+use crate::subscriber::domain::infrastructure::EmailRenderer;
+use crate::subscriber::domain::model::Subscriber;
+
+// Templates are embedded into the binary so that rendering does not depend on the working directory.
+// Every supported locale has its own directory with the same set of templates.
+const TEMPLATES: [(&str, &str); 6] = [
+    (
+        "en/confirmation.subject.txt",
+        include_str!("../../../templates/email/en/confirmation.subject.txt"),
+    ),
+    (
+        "en/confirmation.html",
+        include_str!("../../../templates/email/en/confirmation.html"),
+    ),
+    (
+        "en/confirmation.txt",
+        include_str!("../../../templates/email/en/confirmation.txt"),
+    ),
+    (
+        "ko/confirmation.subject.txt",
+        include_str!("../../../templates/email/ko/confirmation.subject.txt"),
+    ),
+    (
+        "ko/confirmation.html",
+        include_str!("../../../templates/email/ko/confirmation.html"),
+    ),
+    (
+        "ko/confirmation.txt",
+        include_str!("../../../templates/email/ko/confirmation.txt"),
+    ),
+];
+
+#[derive(Clone)]
+pub struct MinijinjaEmailRenderer {
+    environment: Arc<Environment<'static>>,
+    sender_name: String,
+}
+
+impl MinijinjaEmailRenderer {
+    pub fn new(sender_name: String) -> Self {
+        // HTML templates are auto-escaped by their .html extension, text templates are not
+        let mut environment = Environment::new();
+        for (name, source) in TEMPLATES {
+            environment
+                .add_template(name, source)
+                .expect("Failed to parse email template");
+        }
+
+        Self {
+            environment: Arc::new(environment),
+            sender_name,
+        }
+    }
+
+    fn render(&self, name: &str, context: impl Serialize) -> Result<String, Error> {
+        self.environment
+            .get_template(name)
+            .and_then(|template| template.render(context))
+            .with_context(|| format!("Failed to render email template {}", name))
+            .map_err(Error::EmailOperationFailed)
+    }
+
+    fn render_message(
+        &self,
+        name: &str,
+        locale: Locale,
+        context: impl Serialize + Copy,
+    ) -> Result<EmailMessage, Error> {
+        let name = format!("{}/{}", locale.as_ref(), name);
+        Ok(EmailMessage::new(
+            self.render(format!("{}.subject.txt", name).as_str(), context)?
+                .trim()
+                .into(),
+            self.render(format!("{}.html", name).as_str(), context)?,
+            self.render(format!("{}.txt", name).as_str(), context)?,
+        ))
+    }
+}
+
+impl EmailRenderer for MinijinjaEmailRenderer {
+    fn render_confirmation(
+        &self,
+        recipient: &Subscriber,
+        confirmation_url: &str,
+        unsubscribe_url: &str,
+    ) -> Result<EmailMessage, Error> {
+        let context = context! {
+            subscriber_name => recipient.name(),
+            sender_name => self.sender_name,
+            confirmation_url => confirmation_url,
+            unsubscribe_url => unsubscribe_url,
+        };
+        self.render_message("confirmation", recipient.locale(), &context)
+    }
+}
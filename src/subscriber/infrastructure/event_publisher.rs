@@ -0,0 +1,33 @@
+use crate::subscriber::domain::error::Error;
+use crate::subscriber::domain::infrastructure::EventPublisher;
+use crate::subscriber::domain::model::SubscriberEvent;
+use crate::subscriber::infrastructure::unit_of_work::SqlxTransaction;
+
+// Records events in the structured logs, where analytics can pick them up
+#[derive(Clone, Default)]
+pub struct TracingEventPublisher;
+
+impl TracingEventPublisher {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait::async_trait]
+impl EventPublisher for TracingEventPublisher {
+    type Transaction = SqlxTransaction;
+
+    async fn publish(
+        &self,
+        _: &mut Self::Transaction,
+        event: &SubscriberEvent,
+    ) -> Result<(), Error> {
+        tracing::info!(
+            event = event.as_ref(),
+            subscriber_id = %event.subscriber_id(),
+            occurred_at = %event.occurred_at(),
+            "Subscriber event has occurred"
+        );
+        Ok(())
+    }
+}
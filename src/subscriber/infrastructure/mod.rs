@@ -1,2 +1,5 @@
-pub mod email_client;
+pub mod email_renderer;
+pub mod event_publisher;
 pub mod repository;
+pub mod unit_of_work;
+pub mod webhook_client;
@@ -2,44 +2,69 @@ use std::str::FromStr;
 
 use anyhow::anyhow;
 use anyhow::Context;
+use chrono::DateTime;
 use chrono::NaiveDateTime;
-use sqlx::Pool;
-use sqlx::Postgres;
-use sqlx::Transaction;
+use chrono::Utc;
+use hmac::Hmac;
+use hmac::Mac;
+use secrecy::ExposeSecret;
+use secrecy::SecretString;
+use sha2::Sha256;
+use url::Url;
 use uuid::Uuid;
 
+use crate::common::email::EmailMessage;
+use crate::common::locale::Locale;
 use crate::subscriber::domain::error::Error;
+use crate::subscriber::domain::infrastructure::OutboxRepository;
 use crate::subscriber::domain::infrastructure::SubscriberRepository;
 use crate::subscriber::domain::infrastructure::SubscriptionTokenRepository;
+use crate::subscriber::domain::infrastructure::WebhookDeliveryRepository;
+use crate::subscriber::domain::infrastructure::WebhookEndpointRepository;
 use crate::subscriber::domain::model::Email;
 use crate::subscriber::domain::model::Name;
+use crate::subscriber::domain::model::OutboxMessage;
 use crate::subscriber::domain::model::Status;
 use crate::subscriber::domain::model::Subscriber;
+use crate::subscriber::domain::model::SubscriberFilter;
 use crate::subscriber::domain::model::SubscriptionToken;
+use crate::subscriber::domain::model::WebhookDelivery;
+use crate::subscriber::domain::model::WebhookEndpoint;
+use crate::subscriber::infrastructure::unit_of_work::SqlxTransaction;
 
-#[derive(Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct SubscriberDataModel {
     id: Uuid,
     name: String,
     email: String,
+    display_email: String,
     subscribed_at: NaiveDateTime,
     status: String,
+    confirmed_at: Option<NaiveDateTime>,
+    locale: String,
 }
 
 impl SubscriberDataModel {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         id: Uuid,
         name: String,
         email: String,
+        display_email: String,
         subscribed_at: NaiveDateTime,
         status: String,
+        confirmed_at: Option<NaiveDateTime>,
+        locale: String,
     ) -> Self {
         Self {
             id,
             name,
             email,
+            display_email,
             subscribed_at,
             status,
+            confirmed_at,
+            locale,
         }
     }
 }
@@ -47,10 +72,22 @@ impl SubscriberDataModel {
 impl From<SubscriberDataModel> for Subscriber {
     fn from(data_model: SubscriberDataModel) -> Self {
         let name = unsafe { Name::new_unchecked(&data_model.name) };
-        let email = unsafe { Email::new_unchecked(&data_model.email) };
+        let email = unsafe { Email::new_unchecked(&data_model.email, &data_model.display_email) };
         let subscribed_at = data_model.subscribed_at.and_utc();
         let status = Status::from_str(data_model.status.as_str()).unwrap_or(Status::Unexpected);
-        Subscriber::new(data_model.id, name, email, subscribed_at, status)
+        let confirmed_at = data_model
+            .confirmed_at
+            .map(|confirmed_at| confirmed_at.and_utc());
+        let locale = Locale::parse(data_model.locale.as_str()).unwrap_or_default();
+        Subscriber::new(
+            data_model.id,
+            name,
+            email,
+            subscribed_at,
+            status,
+            confirmed_at,
+            locale,
+        )
     }
 }
 
@@ -60,179 +97,1282 @@ impl From<&Subscriber> for SubscriberDataModel {
             id: *entity.id(),
             name: entity.name().into(),
             email: entity.email().into(),
+            display_email: entity.display_email().into(),
             subscribed_at: entity.subscribed_at().naive_utc(),
             status: entity.status().as_ref().into(),
+            confirmed_at: entity
+                .confirmed_at()
+                .map(|confirmed_at| confirmed_at.naive_utc()),
+            locale: entity.locale().as_ref().into(),
         }
     }
 }
 
-#[derive(Clone)]
+// How concurrent modifications of the same subscriber are kept from overwriting each other
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ConcurrencyControl {
+    // Locks the row when reading it, so that other writers wait until the transaction ends
+    #[default]
+    Pessimistic,
+    // Reads without locking and fails with a conflict if another writer has updated the row
+    // in the meantime, leaving retries to the caller
+    Optimistic,
+}
+
+#[derive(Clone, Default)]
 pub struct SqlxSubscriberRepository {
-    pool: Pool<Postgres>,
+    concurrency_control: ConcurrencyControl,
 }
 
 impl SqlxSubscriberRepository {
-    pub fn new(pool: Pool<Postgres>) -> Self {
-        Self { pool }
+    pub fn new(concurrency_control: ConcurrencyControl) -> Self {
+        Self {
+            concurrency_control,
+        }
     }
 
-    async fn find_by_id_with_exclusive_lock(
-        transaction: &mut Transaction<'_, Postgres>,
-        id: &Uuid,
-    ) -> Result<SubscriberDataModel, Error> {
+    // Addresses stored before normalization which are not plain ASCII are normalized here rather
+    // than in migrations, so that their domains are encoded exactly as Email::parse does. The
+    // subscribers this reveals to share an address are merged the way the migration normalizing
+    // the others merged them. Addresses which do not parse are left as they are.
+    #[tracing::instrument(name = "Normalizing legacy subscriber emails", skip_all)]
+    pub async fn normalize_legacy_emails(
+        &self,
+        transaction: &mut SqlxTransaction,
+    ) -> Result<u64, Error> {
+        // Addresses holding anything but printable ASCII, which is told apart by bytes whatever the
+        // server encoding is
+        let legacy_emails = sqlx::query!(
+            "SELECT id, email, display_email FROM subscribers WHERE email !~ '^[ -~]*$' FOR UPDATE",
+        )
+        .fetch_all(&mut **transaction)
+        .await
+        .context("Failed to find legacy subscriber emails")
+        .map_err(Error::RepositoryOperationFailed)?;
+
+        let (ids, emails): (Vec<Uuid>, Vec<String>) = legacy_emails
+            .into_iter()
+            .filter_map(|r| {
+                let email = Email::parse(&r.display_email).ok()?;
+                (email.as_ref() != r.email).then(|| (r.id, email.as_ref().to_owned()))
+            })
+            .unzip();
+        if ids.is_empty() {
+            return Ok(0);
+        }
+
+        // A confirmed subscriber is kept over a pending one, and the earliest over later ones,
+        // while opting out through any of the addresses is honoured by the subscriber kept.
+        // Confirmation links and queued emails of the merged subscribers are dropped along with
+        // them, as is their share of issues being delivered.
         sqlx::query!(
-                "SELECT id, name, email, subscribed_at, status FROM subscribers WHERE id = $1 FOR UPDATE",
+            r#"
+            WITH normalized AS (
+                SELECT * FROM unnest($1::UUID[], $2::TEXT[]) AS n(id, email)
+            ), merges AS (
+                SELECT
+                    s.id,
+                    first_value(s.id) OVER (
+                        PARTITION BY lower(coalesce(n.email, s.email))
+                        ORDER BY
+                            CASE s.status WHEN 'Confirmed' THEN 0 WHEN 'Pending' THEN 1 ELSE 2 END,
+                            s.subscribed_at,
+                            s.id
+                    ) AS kept_id,
+                    bool_or(s.status = 'Unsubscribed') OVER (
+                        PARTITION BY lower(coalesce(n.email, s.email))
+                    ) AS opted_out
+                FROM subscribers s LEFT JOIN normalized n ON n.id = s.id
+                WHERE lower(coalesce(n.email, s.email)) IN (SELECT lower(email) FROM normalized)
+            ), opt_outs AS (
+                UPDATE subscribers SET status = 'Unsubscribed', version = version + 1
+                FROM merges
+                WHERE subscribers.id = merges.id
+                    AND merges.id = merges.kept_id
+                    AND merges.opted_out
+                    AND subscribers.status <> 'Unsubscribed'
+            ), tokens AS (
+                DELETE FROM subscription_tokens USING merges
+                WHERE subscription_tokens.subscriber_id = merges.id AND merges.id <> merges.kept_id
+            ), outbox_messages AS (
+                DELETE FROM outbox USING merges
+                WHERE outbox.subscriber_id = merges.id AND merges.id <> merges.kept_id
+            ), issue_deliveries AS (
+                DELETE FROM issue_delivery_queue USING merges
+                WHERE issue_delivery_queue.subscriber_id = merges.id AND merges.id <> merges.kept_id
+            )
+            DELETE FROM subscribers USING merges
+            WHERE subscribers.id = merges.id AND merges.id <> merges.kept_id
+            "#,
+            &ids,
+            &emails,
+        )
+        .execute(&mut **transaction)
+        .await
+        .context("Failed to merge subscribers sharing legacy emails")
+        .map_err(Error::RepositoryOperationFailed)?;
+
+        let result = sqlx::query!(
+            "UPDATE subscribers SET email = n.email, version = version + 1 FROM unnest($1::UUID[], $2::TEXT[]) AS n(id, email) WHERE subscribers.id = n.id",
+            &ids,
+            &emails,
+        )
+        .execute(&mut **transaction)
+        .await
+        .context("Failed to normalize legacy subscriber emails")
+        .map_err(Error::RepositoryOperationFailed)?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn find_by_id_with_version(
+        &self,
+        transaction: &mut SqlxTransaction,
+        id: &Uuid,
+    ) -> Result<(SubscriberDataModel, i32), Error> {
+        let result = match self.concurrency_control {
+            ConcurrencyControl::Pessimistic => sqlx::query!(
+                "SELECT id, name, email, display_email, subscribed_at, status, confirmed_at, locale, version FROM subscribers WHERE id = $1 FOR UPDATE",
                 id,
             )
             .fetch_one(&mut **transaction)
             .await
-            .map(|r| SubscriberDataModel::new(
-                r.id,
-                r.name,
-                r.email,
-                r.subscribed_at,
-                r.status,
-            ))
-            .map_err(|error| {
-                match error {
-                    sqlx::Error::RowNotFound => Error::SubscriberNotFound(*id),
-                    _ => Error::RepositoryOperationFailed(anyhow!(error).context("Failed to find subscriber")),
-                }
-            })
+            .map(|r| (
+                SubscriberDataModel::new(
+                    r.id,
+                    r.name,
+                    r.email,
+                    r.display_email,
+                    r.subscribed_at,
+                    r.status,
+                    r.confirmed_at,
+                    r.locale,
+                ),
+                r.version,
+            )),
+            ConcurrencyControl::Optimistic => sqlx::query!(
+                "SELECT id, name, email, display_email, subscribed_at, status, confirmed_at, locale, version FROM subscribers WHERE id = $1",
+                id,
+            )
+            .fetch_one(&mut **transaction)
+            .await
+            .map(|r| (
+                SubscriberDataModel::new(
+                    r.id,
+                    r.name,
+                    r.email,
+                    r.display_email,
+                    r.subscribed_at,
+                    r.status,
+                    r.confirmed_at,
+                    r.locale,
+                ),
+                r.version,
+            )),
+        };
+
+        result.map_err(|error| match error {
+            sqlx::Error::RowNotFound => Error::SubscriberNotFound(*id),
+            _ => Error::RepositoryOperationFailed(
+                anyhow!(error).context("Failed to find subscriber"),
+            ),
+        })
     }
 
+    // Writes only if the row still has the version it has been read with, which always holds
+    // for rows locked when read
     async fn update(
-        transaction: &mut Transaction<'_, Postgres>,
+        transaction: &mut SqlxTransaction,
         data_model: SubscriberDataModel,
+        version: i32,
     ) -> Result<(), Error> {
-        sqlx::query!(
-            "UPDATE subscribers SET name = $1, email = $2, status = $3 WHERE id = $4",
+        let result = sqlx::query!(
+            "UPDATE subscribers SET name = $1, email = $2, display_email = $3, status = $4, confirmed_at = $5, locale = $6, version = version + 1 WHERE id = $7 AND version = $8",
             data_model.name,
             data_model.email,
+            data_model.display_email,
             data_model.status,
+            data_model.confirmed_at,
+            data_model.locale,
             data_model.id,
+            version,
         )
         .execute(&mut **transaction)
         .await
         .context("Failed to update subscriber")
         .map_err(Error::RepositoryOperationFailed)?;
 
+        if result.rows_affected() == 0 {
+            return Err(Error::ConcurrencyConflict(data_model.id));
+        }
+
         Ok(())
     }
 }
 
 #[async_trait::async_trait]
 impl SubscriberRepository for SqlxSubscriberRepository {
+    type Transaction = SqlxTransaction;
+
     #[tracing::instrument(name = "Saving subscriber", skip_all, fields(subscriber = ?subscriber))]
-    async fn save(&self, subscriber: &Subscriber) -> Result<(), Error> {
+    async fn save(
+        &self,
+        transaction: &mut Self::Transaction,
+        subscriber: &Subscriber,
+    ) -> Result<(), Error> {
         let data_model: SubscriberDataModel = subscriber.into();
-        sqlx::query!(
-            "INSERT INTO subscribers (id, name, email, subscribed_at, status) VALUES ($1, $2, $3, $4, $5)",
+        let result = sqlx::query!(
+            "INSERT INTO subscribers (id, name, email, display_email, subscribed_at, status, confirmed_at, locale) VALUES ($1, $2, $3, $4, $5, $6, $7, $8) ON CONFLICT DO NOTHING",
             data_model.id,
             data_model.name,
             data_model.email,
+            data_model.display_email,
             data_model.subscribed_at,
             data_model.status,
+            data_model.confirmed_at,
+            data_model.locale,
         )
-        .execute(&self.pool)
+        .execute(&mut **transaction)
         .await
         .context("Failed to save subscriber")
         .map_err(Error::RepositoryOperationFailed)?;
 
+        // Only the unique address can conflict, as ids are generated afresh
+        if result.rows_affected() == 0 {
+            return Err(Error::SubscriberAlreadyExists(data_model.email));
+        }
+
         Ok(())
     }
 
+    #[tracing::instrument(name = "Finding subscriber by id", skip_all, fields(id = ?id))]
+    async fn find_by_id(
+        &self,
+        transaction: &mut Self::Transaction,
+        id: &Uuid,
+    ) -> Result<Option<Subscriber>, Error> {
+        Ok(sqlx::query!(
+            "SELECT id, name, email, display_email, subscribed_at, status, confirmed_at, locale FROM subscribers WHERE id = $1",
+            id,
+        )
+        .fetch_optional(&mut **transaction)
+        .await
+        .context("Failed to find subscriber by id")
+        .map_err(Error::RepositoryOperationFailed)?
+        .map(|r| {
+            SubscriberDataModel::new(
+                r.id,
+                r.name,
+                r.email,
+                r.display_email,
+                r.subscribed_at,
+                r.status,
+                r.confirmed_at,
+                r.locale,
+            )
+                .into()
+        }))
+    }
+
+    #[tracing::instrument(name = "Finding subscriber by email", skip_all, fields(email = ?email))]
+    async fn find_by_email(
+        &self,
+        transaction: &mut Self::Transaction,
+        email: &str,
+    ) -> Result<Option<Subscriber>, Error> {
+        Ok(sqlx::query!(
+            "SELECT id, name, email, display_email, subscribed_at, status, confirmed_at, locale FROM subscribers WHERE lower(email) = lower($1) FOR UPDATE",
+            email,
+        )
+        .fetch_optional(&mut **transaction)
+        .await
+        .context("Failed to find subscriber by email")
+        .map_err(Error::RepositoryOperationFailed)?
+        .map(|r| SubscriberDataModel::new(
+            r.id,
+            r.name,
+            r.email,
+            r.display_email,
+            r.subscribed_at,
+            r.status,
+            r.confirmed_at,
+            r.locale,
+        )
+        .into()))
+    }
+
+    #[tracing::instrument(name = "Finding page of subscribers", skip_all, fields(filter = ?filter, cursor = ?cursor))]
+    async fn find_page(
+        &self,
+        transaction: &mut Self::Transaction,
+        filter: &SubscriberFilter,
+        cursor: Option<&Uuid>,
+        limit: i64,
+    ) -> Result<Vec<Subscriber>, Error> {
+        Ok(sqlx::query!(
+            "SELECT id, name, email, display_email, subscribed_at, status, confirmed_at, locale FROM subscribers WHERE ($1::TEXT IS NULL OR status = $1) AND ($2::TEXT IS NULL OR strpos(lower(email), lower($2)) > 0) AND ($3::TIMESTAMP IS NULL OR subscribed_at >= $3) AND ($4::TIMESTAMP IS NULL OR subscribed_at < $4) AND ($5::UUID IS NULL OR id > $5) ORDER BY id LIMIT $6",
+            filter.status().map(|status| status.as_ref()),
+            filter.email(),
+            filter.subscribed_since().map(|since| since.naive_utc()),
+            filter.subscribed_before().map(|before| before.naive_utc()),
+            cursor,
+            limit,
+        )
+        .fetch_all(&mut **transaction)
+        .await
+        .context("Failed to find page of subscribers")
+        .map_err(Error::RepositoryOperationFailed)?
+        .into_iter()
+        .map(|r| {
+            SubscriberDataModel::new(
+                r.id,
+                r.name,
+                r.email,
+                r.display_email,
+                r.subscribed_at,
+                r.status,
+                r.confirmed_at,
+                r.locale,
+            )
+                .into()
+        })
+        .collect())
+    }
+
     #[tracing::instrument(name = "Modifying subscriber", skip_all, fields(id = ?id))]
-    async fn modify_by_id<F>(&self, id: &Uuid, modifier: F) -> Result<(), Error>
+    async fn modify_by_id<F>(
+        &self,
+        transaction: &mut Self::Transaction,
+        id: &Uuid,
+        modifier: F,
+    ) -> Result<(), Error>
     where
         F: FnOnce(Subscriber) -> Subscriber + Send + Sync,
     {
-        let mut transaction = self
-            .pool
-            .begin()
-            .await
-            .context("Failed to start transaction")
-            .map_err(Error::RepositoryOperationFailed)?;
-
-        let subscriber =
-            SqlxSubscriberRepository::find_by_id_with_exclusive_lock(&mut transaction, id)
-                .await?
-                .into();
-        let data_model: SubscriberDataModel = (&modifier(subscriber)).into();
-        SqlxSubscriberRepository::update(&mut transaction, data_model).await?;
-
-        transaction
-            .commit()
-            .await
-            .context("Failed to commit transaction")
-            .map_err(Error::RepositoryOperationFailed)
+        let (original, version) = self.find_by_id_with_version(transaction, id).await?;
+        let data_model: SubscriberDataModel = (&modifier(original.clone().into())).into();
+        // Subscribers left as they were are not written again
+        if data_model == original {
+            return Ok(());
+        }
+        SqlxSubscriberRepository::update(transaction, data_model, version).await
     }
 }
 
 pub struct SubscriptionTokenDataModel {
     token: String,
     subscriber_id: Uuid,
+    created_at: NaiveDateTime,
+    expires_at: NaiveDateTime,
+    used_at: Option<NaiveDateTime>,
 }
 
 impl SubscriptionTokenDataModel {
-    pub fn new(token: String, subscriber_id: Uuid) -> Self {
+    pub fn new(
+        token: String,
+        subscriber_id: Uuid,
+        created_at: NaiveDateTime,
+        expires_at: NaiveDateTime,
+        used_at: Option<NaiveDateTime>,
+    ) -> Self {
         Self {
             token,
             subscriber_id,
+            created_at,
+            expires_at,
+            used_at,
         }
     }
 }
 
 impl From<SubscriptionTokenDataModel> for SubscriptionToken {
     fn from(data_model: SubscriptionTokenDataModel) -> Self {
-        SubscriptionToken::new(data_model.token, data_model.subscriber_id)
+        SubscriptionToken::new(
+            data_model.token,
+            data_model.subscriber_id,
+            data_model.created_at.and_utc(),
+            data_model.expires_at.and_utc(),
+            data_model.used_at.map(|used_at| used_at.and_utc()),
+        )
     }
 }
 
 impl From<&SubscriptionToken> for SubscriptionTokenDataModel {
     fn from(entity: &SubscriptionToken) -> Self {
-        SubscriptionTokenDataModel::new(entity.token().into(), *entity.subscriber_id())
+        SubscriptionTokenDataModel::new(
+            entity.token().into(),
+            *entity.subscriber_id(),
+            entity.created_at().naive_utc(),
+            entity.expires_at().naive_utc(),
+            entity.used_at().map(|used_at| used_at.naive_utc()),
+        )
     }
 }
 
 #[derive(Clone)]
 pub struct SqlxSubscriptionTokenRepository {
-    pool: Pool<Postgres>,
+    key: SecretString,
 }
 
 impl SqlxSubscriptionTokenRepository {
-    pub fn new(pool: Pool<Postgres>) -> Self {
-        Self { pool }
+    pub fn new(key: SecretString) -> Self {
+        Self { key }
+    }
+
+    // Only keyed hashes of tokens are stored, so read access to the table is not enough to confirm
+    // subscriptions on behalf of subscribers
+    fn hash(&self, token: &str) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(self.key.expose_secret().as_bytes())
+            .expect("HMAC can take key of any size");
+        mac.update(token.as_bytes());
+        hex::encode(mac.finalize().into_bytes())
+    }
+
+    // Tokens saved before only their hashes were stored are hashed here rather than in migrations,
+    // which do not know the key. The raw token column is dropped once every token has been hashed,
+    // so it is queried without compile-time checks.
+    #[tracing::instrument(name = "Hashing legacy subscription tokens", skip_all)]
+    pub async fn hash_legacy_tokens(
+        &self,
+        transaction: &mut SqlxTransaction,
+    ) -> Result<u64, Error> {
+        let legacy_tokens: Vec<String> = sqlx::query_scalar(
+            "SELECT token FROM subscription_tokens WHERE token_hash IS NULL FOR UPDATE",
+        )
+        .fetch_all(&mut **transaction)
+        .await
+        .context("Failed to find legacy subscription tokens")
+        .map_err(Error::RepositoryOperationFailed)?;
+
+        let mut count = 0;
+        for token in legacy_tokens {
+            count += sqlx::query(
+                "UPDATE subscription_tokens SET token_hash = $1, token = NULL WHERE token = $2",
+            )
+            .bind(self.hash(&token))
+            .bind(&token)
+            .execute(&mut **transaction)
+            .await
+            .context("Failed to hash legacy subscription token")
+            .map_err(Error::RepositoryOperationFailed)?
+            .rows_affected();
+        }
+
+        Ok(count)
+    }
+
+    async fn find_by_token_hash_with_exclusive_lock(
+        transaction: &mut SqlxTransaction,
+        token: &str,
+        token_hash: &str,
+    ) -> Result<SubscriptionTokenDataModel, Error> {
+        sqlx::query!(
+            "SELECT subscriber_id, created_at, expires_at, used_at FROM subscription_tokens WHERE token_hash = $1 FOR UPDATE",
+            token_hash,
+        )
+        .fetch_one(&mut **transaction)
+        .await
+        .map(|r| {
+            SubscriptionTokenDataModel::new(
+                token.into(),
+                r.subscriber_id,
+                r.created_at,
+                r.expires_at,
+                r.used_at,
+            )
+        })
+        .map_err(|error| match error {
+            sqlx::Error::RowNotFound => Error::TokenNotFound,
+            _ => Error::RepositoryOperationFailed(
+                anyhow!(error).context("Failed to find subscription token"),
+            ),
+        })
+    }
+
+    async fn update(
+        transaction: &mut SqlxTransaction,
+        token_hash: &str,
+        data_model: SubscriptionTokenDataModel,
+    ) -> Result<(), Error> {
+        sqlx::query!(
+            "UPDATE subscription_tokens SET expires_at = $1, used_at = $2 WHERE token_hash = $3",
+            data_model.expires_at,
+            data_model.used_at,
+            token_hash,
+        )
+        .execute(&mut **transaction)
+        .await
+        .context("Failed to update subscription token")
+        .map_err(Error::RepositoryOperationFailed)?;
+
+        Ok(())
     }
 }
 
 #[async_trait::async_trait]
 impl SubscriptionTokenRepository for SqlxSubscriptionTokenRepository {
-    #[tracing::instrument(name = "Saving subscription token", skip_all, fields(subscription_token = ?subscription_token))]
-    async fn save(&self, subscription_token: &SubscriptionToken) -> Result<(), Error> {
+    type Transaction = SqlxTransaction;
+
+    #[tracing::instrument(name = "Saving subscription token", skip_all, fields(subscriber_id = ?subscription_token.subscriber_id()))]
+    async fn save(
+        &self,
+        transaction: &mut Self::Transaction,
+        subscription_token: &SubscriptionToken,
+    ) -> Result<(), Error> {
         let data_model: SubscriptionTokenDataModel = subscription_token.into();
         sqlx::query!(
-            "INSERT INTO subscription_tokens (token, subscriber_id) VALUES ($1, $2)",
-            data_model.token,
+            "INSERT INTO subscription_tokens (token_hash, subscriber_id, created_at, expires_at, used_at) VALUES ($1, $2, $3, $4, $5)",
+            self.hash(&data_model.token),
             data_model.subscriber_id,
+            data_model.created_at,
+            data_model.expires_at,
+            data_model.used_at,
         )
-        .execute(&self.pool)
+        .execute(&mut **transaction)
         .await
         .context("Failed to save subscription token")
         .map_err(Error::RepositoryOperationFailed)?;
         Ok(())
     }
 
-    #[tracing::instrument(name = "Finding subscription token by token", skip_all, fields(token = ?token))]
-    async fn find_by_token(&self, token: &str) -> Result<Option<SubscriptionToken>, Error> {
+    #[tracing::instrument(name = "Finding subscription token by token", skip_all)]
+    async fn find_by_token(
+        &self,
+        transaction: &mut Self::Transaction,
+        token: &str,
+    ) -> Result<Option<SubscriptionToken>, Error> {
         Ok(sqlx::query!(
-            "SELECT token, subscriber_id FROM subscription_tokens WHERE token = $1",
-            token,
+            "SELECT subscriber_id, created_at, expires_at, used_at FROM subscription_tokens WHERE token_hash = $1",
+            self.hash(token),
         )
-        .fetch_optional(&self.pool)
+        .fetch_optional(&mut **transaction)
         .await
         .context("Failed to find subscription token by token")
         .map_err(Error::RepositoryOperationFailed)?
-        .map(|r| SubscriptionTokenDataModel::new(r.token, r.subscriber_id).into()))
+        .map(|r| {
+            SubscriptionTokenDataModel::new(
+                token.into(),
+                r.subscriber_id,
+                r.created_at,
+                r.expires_at,
+                r.used_at,
+            )
+            .into()
+        }))
+    }
+
+    #[tracing::instrument(name = "Finding when latest subscription token has been issued", skip_all, fields(subscriber_id = ?subscriber_id))]
+    async fn find_latest_issued_at_by_subscriber_id(
+        &self,
+        transaction: &mut Self::Transaction,
+        subscriber_id: &Uuid,
+    ) -> Result<Option<DateTime<Utc>>, Error> {
+        Ok(sqlx::query_scalar!(
+            "SELECT max(created_at) FROM subscription_tokens WHERE subscriber_id = $1",
+            subscriber_id,
+        )
+        .fetch_one(&mut **transaction)
+        .await
+        .context("Failed to find when latest subscription token has been issued")
+        .map_err(Error::RepositoryOperationFailed)?
+        .map(|created_at| created_at.and_utc()))
+    }
+
+    #[tracing::instrument(name = "Modifying subscription token", skip_all)]
+    async fn modify_by_token<F>(
+        &self,
+        transaction: &mut Self::Transaction,
+        token: &str,
+        modifier: F,
+    ) -> Result<(), Error>
+    where
+        F: FnOnce(SubscriptionToken) -> SubscriptionToken + Send + Sync,
+    {
+        let token_hash = self.hash(token);
+        let subscription_token =
+            SqlxSubscriptionTokenRepository::find_by_token_hash_with_exclusive_lock(
+                transaction,
+                token,
+                &token_hash,
+            )
+            .await?
+            .into();
+        let data_model: SubscriptionTokenDataModel = (&modifier(subscription_token)).into();
+        SqlxSubscriptionTokenRepository::update(transaction, &token_hash, data_model).await
+    }
+
+    #[tracing::instrument(name = "Removing unused subscription tokens by subscriber id", skip_all, fields(subscriber_id = ?subscriber_id))]
+    async fn remove_unused_by_subscriber_id(
+        &self,
+        transaction: &mut Self::Transaction,
+        subscriber_id: &Uuid,
+    ) -> Result<(), Error> {
+        sqlx::query!(
+            "DELETE FROM subscription_tokens WHERE subscriber_id = $1 AND used_at IS NULL",
+            subscriber_id,
+        )
+        .execute(&mut **transaction)
+        .await
+        .context("Failed to remove unused subscription tokens")
+        .map_err(Error::RepositoryOperationFailed)?;
+
+        Ok(())
+    }
+}
+
+pub struct OutboxDataModel {
+    id: Uuid,
+    subscriber_id: Uuid,
+    subject: String,
+    html_body: Option<String>,
+    text_body: Option<String>,
+    unsubscribe_url: Option<String>,
+    created_at: NaiveDateTime,
+    attempts: i32,
+    next_attempt_at: NaiveDateTime,
+    delivered_at: Option<NaiveDateTime>,
+    dead_lettered_at: Option<NaiveDateTime>,
+    last_error: Option<String>,
+}
+
+impl OutboxDataModel {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        id: Uuid,
+        subscriber_id: Uuid,
+        subject: String,
+        html_body: Option<String>,
+        text_body: Option<String>,
+        unsubscribe_url: Option<String>,
+        created_at: NaiveDateTime,
+        attempts: i32,
+        next_attempt_at: NaiveDateTime,
+        delivered_at: Option<NaiveDateTime>,
+        dead_lettered_at: Option<NaiveDateTime>,
+        last_error: Option<String>,
+    ) -> Self {
+        Self {
+            id,
+            subscriber_id,
+            subject,
+            html_body,
+            text_body,
+            unsubscribe_url,
+            created_at,
+            attempts,
+            next_attempt_at,
+            delivered_at,
+            dead_lettered_at,
+            last_error,
+        }
+    }
+}
+
+impl From<OutboxDataModel> for OutboxMessage {
+    fn from(data_model: OutboxDataModel) -> Self {
+        // Bodies have been discarded from messages that are no longer to be delivered
+        let message = match (data_model.html_body, data_model.text_body) {
+            (Some(html_body), Some(text_body)) => {
+                let message = EmailMessage::new(data_model.subject, html_body, text_body);
+                match data_model
+                    .unsubscribe_url
+                    .and_then(|unsubscribe_url| Url::parse(&unsubscribe_url).ok())
+                {
+                    Some(unsubscribe_url) => Some(message.with_unsubscribe_url(&unsubscribe_url)),
+                    None => Some(message),
+                }
+            }
+            _ => None,
+        };
+
+        OutboxMessage::new(
+            data_model.id,
+            data_model.subscriber_id,
+            message,
+            data_model.created_at.and_utc(),
+            data_model.attempts,
+            data_model.next_attempt_at.and_utc(),
+            data_model
+                .delivered_at
+                .map(|delivered_at| delivered_at.and_utc()),
+            data_model
+                .dead_lettered_at
+                .map(|dead_lettered_at| dead_lettered_at.and_utc()),
+            data_model.last_error,
+        )
+    }
+}
+
+impl From<&OutboxMessage> for OutboxDataModel {
+    fn from(entity: &OutboxMessage) -> Self {
+        OutboxDataModel::new(
+            *entity.id(),
+            *entity.subscriber_id(),
+            entity
+                .message()
+                .map(|message| message.subject().into())
+                .unwrap_or_default(),
+            entity.message().map(|message| message.html_body().into()),
+            entity.message().map(|message| message.text_body().into()),
+            entity
+                .message()
+                .and_then(|message| message.unsubscribe_url())
+                .map(String::from),
+            entity.created_at().naive_utc(),
+            entity.attempts(),
+            entity.next_attempt_at().naive_utc(),
+            entity
+                .delivered_at()
+                .map(|delivered_at| delivered_at.naive_utc()),
+            entity
+                .dead_lettered_at()
+                .map(|dead_lettered_at| dead_lettered_at.naive_utc()),
+            entity.last_error().map(String::from),
+        )
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct SqlxOutboxRepository;
+
+impl SqlxOutboxRepository {
+    pub fn new() -> Self {
+        Self
+    }
+
+    async fn find_by_id_with_exclusive_lock(
+        transaction: &mut SqlxTransaction,
+        id: &Uuid,
+    ) -> Result<OutboxDataModel, Error> {
+        sqlx::query!(
+            "SELECT id, subscriber_id, subject, html_body, text_body, unsubscribe_url, created_at, attempts, next_attempt_at, delivered_at, dead_lettered_at, last_error FROM outbox WHERE id = $1 FOR UPDATE",
+            id,
+        )
+        .fetch_one(&mut **transaction)
+        .await
+        .map(|r| {
+            OutboxDataModel::new(
+                r.id,
+                r.subscriber_id,
+                r.subject,
+                r.html_body,
+                r.text_body,
+                r.unsubscribe_url,
+                r.created_at,
+                r.attempts,
+                r.next_attempt_at,
+                r.delivered_at,
+                r.dead_lettered_at,
+                r.last_error,
+            )
+        })
+        .context("Failed to find outbox message")
+        .map_err(Error::RepositoryOperationFailed)
+    }
+
+    async fn update(
+        transaction: &mut SqlxTransaction,
+        data_model: OutboxDataModel,
+    ) -> Result<(), Error> {
+        sqlx::query!(
+            "UPDATE outbox SET html_body = $1, text_body = $2, unsubscribe_url = $3, attempts = $4, next_attempt_at = $5, delivered_at = $6, dead_lettered_at = $7, last_error = $8 WHERE id = $9",
+            data_model.html_body,
+            data_model.text_body,
+            data_model.unsubscribe_url,
+            data_model.attempts,
+            data_model.next_attempt_at,
+            data_model.delivered_at,
+            data_model.dead_lettered_at,
+            data_model.last_error,
+            data_model.id,
+        )
+        .execute(&mut **transaction)
+        .await
+        .context("Failed to update outbox message")
+        .map_err(Error::RepositoryOperationFailed)?;
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl OutboxRepository for SqlxOutboxRepository {
+    type Transaction = SqlxTransaction;
+
+    #[tracing::instrument(name = "Saving outbox message", skip_all, fields(id = ?outbox_message.id()))]
+    async fn save(
+        &self,
+        transaction: &mut Self::Transaction,
+        outbox_message: &OutboxMessage,
+    ) -> Result<(), Error> {
+        let data_model: OutboxDataModel = outbox_message.into();
+        sqlx::query!(
+            "INSERT INTO outbox (id, subscriber_id, subject, html_body, text_body, unsubscribe_url, created_at, attempts, next_attempt_at, delivered_at, dead_lettered_at, last_error) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)",
+            data_model.id,
+            data_model.subscriber_id,
+            data_model.subject,
+            data_model.html_body,
+            data_model.text_body,
+            data_model.unsubscribe_url,
+            data_model.created_at,
+            data_model.attempts,
+            data_model.next_attempt_at,
+            data_model.delivered_at,
+            data_model.dead_lettered_at,
+            data_model.last_error,
+        )
+        .execute(&mut **transaction)
+        .await
+        .context("Failed to save outbox message")
+        .map_err(Error::RepositoryOperationFailed)?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Finding deliverable outbox messages", skip_all)]
+    async fn find_deliverable(
+        &self,
+        transaction: &mut Self::Transaction,
+        max_attempts: i32,
+        limit: i64,
+    ) -> Result<Vec<OutboxMessage>, Error> {
+        Ok(sqlx::query!(
+            "SELECT id, subscriber_id, subject, html_body, text_body, unsubscribe_url, created_at, attempts, next_attempt_at, delivered_at, dead_lettered_at, last_error FROM outbox WHERE delivered_at IS NULL AND dead_lettered_at IS NULL AND attempts < $1 AND next_attempt_at <= (now() AT TIME ZONE 'utc') ORDER BY next_attempt_at LIMIT $2 FOR UPDATE SKIP LOCKED",
+            max_attempts,
+            limit,
+        )
+        .fetch_all(&mut **transaction)
+        .await
+        .context("Failed to find deliverable outbox messages")
+        .map_err(Error::RepositoryOperationFailed)?
+        .into_iter()
+        .map(|r| {
+            OutboxDataModel::new(
+                r.id,
+                r.subscriber_id,
+                r.subject,
+                r.html_body,
+                r.text_body,
+                r.unsubscribe_url,
+                r.created_at,
+                r.attempts,
+                r.next_attempt_at,
+                r.delivered_at,
+                r.dead_lettered_at,
+                r.last_error,
+            )
+            .into()
+        })
+        .collect())
+    }
+
+    #[tracing::instrument(name = "Modifying outbox message", skip_all, fields(id = ?id))]
+    async fn modify_by_id<F>(
+        &self,
+        transaction: &mut Self::Transaction,
+        id: &Uuid,
+        modifier: F,
+    ) -> Result<(), Error>
+    where
+        F: FnOnce(OutboxMessage) -> OutboxMessage + Send + Sync,
+    {
+        let outbox_message = SqlxOutboxRepository::find_by_id_with_exclusive_lock(transaction, id)
+            .await?
+            .into();
+        let data_model: OutboxDataModel = (&modifier(outbox_message)).into();
+        SqlxOutboxRepository::update(transaction, data_model).await
+    }
+}
+
+pub struct WebhookEndpointDataModel {
+    id: Uuid,
+    url: String,
+    secret: String,
+    created_at: NaiveDateTime,
+}
+
+impl WebhookEndpointDataModel {
+    pub fn new(id: Uuid, url: String, secret: String, created_at: NaiveDateTime) -> Self {
+        Self {
+            id,
+            url,
+            secret,
+            created_at,
+        }
+    }
+}
+
+impl TryFrom<WebhookEndpointDataModel> for WebhookEndpoint {
+    type Error = Error;
+
+    fn try_from(data_model: WebhookEndpointDataModel) -> Result<Self, Self::Error> {
+        Ok(WebhookEndpoint::new(
+            data_model.id,
+            Url::parse(&data_model.url)
+                .context("Failed to parse webhook URL")
+                .map_err(Error::RepositoryOperationFailed)?,
+            SecretString::from(data_model.secret),
+            data_model.created_at.and_utc(),
+        ))
+    }
+}
+
+impl From<&WebhookEndpoint> for WebhookEndpointDataModel {
+    fn from(entity: &WebhookEndpoint) -> Self {
+        WebhookEndpointDataModel::new(
+            *entity.id(),
+            entity.url().to_string(),
+            entity.secret().expose_secret().into(),
+            entity.created_at().naive_utc(),
+        )
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct SqlxWebhookEndpointRepository;
+
+impl SqlxWebhookEndpointRepository {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait::async_trait]
+impl WebhookEndpointRepository for SqlxWebhookEndpointRepository {
+    type Transaction = SqlxTransaction;
+
+    #[tracing::instrument(name = "Saving webhook endpoint", skip_all, fields(id = ?webhook_endpoint.id()))]
+    async fn save(
+        &self,
+        transaction: &mut Self::Transaction,
+        webhook_endpoint: &WebhookEndpoint,
+    ) -> Result<(), Error> {
+        let data_model: WebhookEndpointDataModel = webhook_endpoint.into();
+        sqlx::query!(
+            "INSERT INTO webhook_endpoints (id, url, secret, created_at) VALUES ($1, $2, $3, $4)",
+            data_model.id,
+            data_model.url,
+            data_model.secret,
+            data_model.created_at,
+        )
+        .execute(&mut **transaction)
+        .await
+        .context("Failed to save webhook endpoint")
+        .map_err(Error::RepositoryOperationFailed)?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Finding webhook endpoints", skip_all)]
+    async fn find_all(
+        &self,
+        transaction: &mut Self::Transaction,
+    ) -> Result<Vec<WebhookEndpoint>, Error> {
+        sqlx::query!(
+            "SELECT id, url, secret, created_at FROM webhook_endpoints ORDER BY created_at"
+        )
+        .fetch_all(&mut **transaction)
+        .await
+        .context("Failed to find webhook endpoints")
+        .map_err(Error::RepositoryOperationFailed)?
+        .into_iter()
+        .map(|r| WebhookEndpointDataModel::new(r.id, r.url, r.secret, r.created_at).try_into())
+        .collect()
+    }
+
+    #[tracing::instrument(name = "Finding webhook endpoint by id", skip_all, fields(id = ?id))]
+    async fn find_by_id(
+        &self,
+        transaction: &mut Self::Transaction,
+        id: &Uuid,
+    ) -> Result<Option<WebhookEndpoint>, Error> {
+        sqlx::query!(
+            "SELECT id, url, secret, created_at FROM webhook_endpoints WHERE id = $1",
+            id,
+        )
+        .fetch_optional(&mut **transaction)
+        .await
+        .context("Failed to find webhook endpoint")
+        .map_err(Error::RepositoryOperationFailed)?
+        .map(|r| WebhookEndpointDataModel::new(r.id, r.url, r.secret, r.created_at).try_into())
+        .transpose()
+    }
+
+    #[tracing::instrument(name = "Removing webhook endpoint", skip_all, fields(id = ?id))]
+    async fn remove_by_id(
+        &self,
+        transaction: &mut Self::Transaction,
+        id: &Uuid,
+    ) -> Result<(), Error> {
+        let result = sqlx::query!("DELETE FROM webhook_endpoints WHERE id = $1", id)
+            .execute(&mut **transaction)
+            .await
+            .context("Failed to remove webhook endpoint")
+            .map_err(Error::RepositoryOperationFailed)?;
+
+        if result.rows_affected() == 0 {
+            return Err(Error::WebhookEndpointNotFound(*id));
+        }
+
+        Ok(())
+    }
+}
+
+pub struct WebhookDeliveryDataModel {
+    id: Uuid,
+    endpoint_id: Uuid,
+    event_type: String,
+    payload: String,
+    created_at: NaiveDateTime,
+    attempts: i32,
+    next_attempt_at: NaiveDateTime,
+    delivered_at: Option<NaiveDateTime>,
+    last_status_code: Option<i32>,
+    last_error: Option<String>,
+}
+
+impl WebhookDeliveryDataModel {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        id: Uuid,
+        endpoint_id: Uuid,
+        event_type: String,
+        payload: String,
+        created_at: NaiveDateTime,
+        attempts: i32,
+        next_attempt_at: NaiveDateTime,
+        delivered_at: Option<NaiveDateTime>,
+        last_status_code: Option<i32>,
+        last_error: Option<String>,
+    ) -> Self {
+        Self {
+            id,
+            endpoint_id,
+            event_type,
+            payload,
+            created_at,
+            attempts,
+            next_attempt_at,
+            delivered_at,
+            last_status_code,
+            last_error,
+        }
+    }
+}
+
+impl From<WebhookDeliveryDataModel> for WebhookDelivery {
+    fn from(data_model: WebhookDeliveryDataModel) -> Self {
+        WebhookDelivery::new(
+            data_model.id,
+            data_model.endpoint_id,
+            data_model.event_type,
+            data_model.payload,
+            data_model.created_at.and_utc(),
+            data_model.attempts,
+            data_model.next_attempt_at.and_utc(),
+            data_model
+                .delivered_at
+                .map(|delivered_at| delivered_at.and_utc()),
+            data_model.last_status_code,
+            data_model.last_error,
+        )
+    }
+}
+
+impl From<&WebhookDelivery> for WebhookDeliveryDataModel {
+    fn from(entity: &WebhookDelivery) -> Self {
+        WebhookDeliveryDataModel::new(
+            *entity.id(),
+            *entity.endpoint_id(),
+            entity.event_type().into(),
+            entity.payload().into(),
+            entity.created_at().naive_utc(),
+            entity.attempts(),
+            entity.next_attempt_at().naive_utc(),
+            entity
+                .delivered_at()
+                .map(|delivered_at| delivered_at.naive_utc()),
+            entity.last_status_code(),
+            entity.last_error().map(String::from),
+        )
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct SqlxWebhookDeliveryRepository;
+
+impl SqlxWebhookDeliveryRepository {
+    pub fn new() -> Self {
+        Self
+    }
+
+    async fn find_by_id_with_exclusive_lock(
+        transaction: &mut SqlxTransaction,
+        id: &Uuid,
+    ) -> Result<WebhookDeliveryDataModel, Error> {
+        sqlx::query!(
+            "SELECT id, endpoint_id, event_type, payload, created_at, attempts, next_attempt_at, delivered_at, last_status_code, last_error FROM webhook_deliveries WHERE id = $1 FOR UPDATE",
+            id,
+        )
+        .fetch_one(&mut **transaction)
+        .await
+        .map(|r| {
+            WebhookDeliveryDataModel::new(
+                r.id,
+                r.endpoint_id,
+                r.event_type,
+                r.payload,
+                r.created_at,
+                r.attempts,
+                r.next_attempt_at,
+                r.delivered_at,
+                r.last_status_code,
+                r.last_error,
+            )
+        })
+        .context("Failed to find webhook delivery")
+        .map_err(Error::RepositoryOperationFailed)
+    }
+
+    async fn update(
+        transaction: &mut SqlxTransaction,
+        data_model: WebhookDeliveryDataModel,
+    ) -> Result<(), Error> {
+        sqlx::query!(
+            "UPDATE webhook_deliveries SET attempts = $1, next_attempt_at = $2, delivered_at = $3, last_status_code = $4, last_error = $5 WHERE id = $6",
+            data_model.attempts,
+            data_model.next_attempt_at,
+            data_model.delivered_at,
+            data_model.last_status_code,
+            data_model.last_error,
+            data_model.id,
+        )
+        .execute(&mut **transaction)
+        .await
+        .context("Failed to update webhook delivery")
+        .map_err(Error::RepositoryOperationFailed)?;
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl WebhookDeliveryRepository for SqlxWebhookDeliveryRepository {
+    type Transaction = SqlxTransaction;
+
+    #[tracing::instrument(name = "Saving webhook delivery", skip_all, fields(id = ?webhook_delivery.id()))]
+    async fn save(
+        &self,
+        transaction: &mut Self::Transaction,
+        webhook_delivery: &WebhookDelivery,
+    ) -> Result<(), Error> {
+        let data_model: WebhookDeliveryDataModel = webhook_delivery.into();
+        sqlx::query!(
+            "INSERT INTO webhook_deliveries (id, endpoint_id, event_type, payload, created_at, attempts, next_attempt_at, delivered_at, last_status_code, last_error) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)",
+            data_model.id,
+            data_model.endpoint_id,
+            data_model.event_type,
+            data_model.payload,
+            data_model.created_at,
+            data_model.attempts,
+            data_model.next_attempt_at,
+            data_model.delivered_at,
+            data_model.last_status_code,
+            data_model.last_error,
+        )
+        .execute(&mut **transaction)
+        .await
+        .context("Failed to save webhook delivery")
+        .map_err(Error::RepositoryOperationFailed)?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Finding deliverable webhook deliveries", skip_all)]
+    async fn find_deliverable(
+        &self,
+        transaction: &mut Self::Transaction,
+        max_attempts: i32,
+        limit: i64,
+    ) -> Result<Vec<WebhookDelivery>, Error> {
+        Ok(sqlx::query!(
+            "SELECT id, endpoint_id, event_type, payload, created_at, attempts, next_attempt_at, delivered_at, last_status_code, last_error FROM webhook_deliveries WHERE delivered_at IS NULL AND attempts < $1 AND next_attempt_at <= (now() AT TIME ZONE 'utc') ORDER BY next_attempt_at LIMIT $2 FOR UPDATE SKIP LOCKED",
+            max_attempts,
+            limit,
+        )
+        .fetch_all(&mut **transaction)
+        .await
+        .context("Failed to find deliverable webhook deliveries")
+        .map_err(Error::RepositoryOperationFailed)?
+        .into_iter()
+        .map(|r| {
+            WebhookDeliveryDataModel::new(
+                r.id,
+                r.endpoint_id,
+                r.event_type,
+                r.payload,
+                r.created_at,
+                r.attempts,
+                r.next_attempt_at,
+                r.delivered_at,
+                r.last_status_code,
+                r.last_error,
+            )
+            .into()
+        })
+        .collect())
+    }
+
+    #[tracing::instrument(name = "Finding webhook deliveries by endpoint id", skip_all, fields(endpoint_id = ?endpoint_id))]
+    async fn find_by_endpoint_id(
+        &self,
+        transaction: &mut Self::Transaction,
+        endpoint_id: &Uuid,
+        limit: i64,
+    ) -> Result<Vec<WebhookDelivery>, Error> {
+        Ok(sqlx::query!(
+            "SELECT id, endpoint_id, event_type, payload, created_at, attempts, next_attempt_at, delivered_at, last_status_code, last_error FROM webhook_deliveries WHERE endpoint_id = $1 ORDER BY id DESC LIMIT $2",
+            endpoint_id,
+            limit,
+        )
+        .fetch_all(&mut **transaction)
+        .await
+        .context("Failed to find webhook deliveries")
+        .map_err(Error::RepositoryOperationFailed)?
+        .into_iter()
+        .map(|r| {
+            WebhookDeliveryDataModel::new(
+                r.id,
+                r.endpoint_id,
+                r.event_type,
+                r.payload,
+                r.created_at,
+                r.attempts,
+                r.next_attempt_at,
+                r.delivered_at,
+                r.last_status_code,
+                r.last_error,
+            )
+            .into()
+        })
+        .collect())
+    }
+
+    #[tracing::instrument(name = "Modifying webhook delivery", skip_all, fields(id = ?id))]
+    async fn modify_by_id<F>(
+        &self,
+        transaction: &mut Self::Transaction,
+        id: &Uuid,
+        modifier: F,
+    ) -> Result<(), Error>
+    where
+        F: FnOnce(WebhookDelivery) -> WebhookDelivery + Send + Sync,
+    {
+        let webhook_delivery =
+            SqlxWebhookDeliveryRepository::find_by_id_with_exclusive_lock(transaction, id)
+                .await?
+                .into();
+        let data_model: WebhookDeliveryDataModel = (&modifier(webhook_delivery)).into();
+        SqlxWebhookDeliveryRepository::update(transaction, data_model).await
     }
 }
@@ -0,0 +1,106 @@
+use std::net::IpAddr;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::anyhow;
+use anyhow::Context;
+use reqwest::dns::Addrs;
+use reqwest::dns::Name;
+use reqwest::dns::Resolve;
+use reqwest::dns::Resolving;
+use reqwest::redirect::Policy;
+use reqwest::Client;
+use url::Host;
+use url::Url;
+
+use crate::subscriber::domain::error::Error;
+use crate::subscriber::domain::infrastructure::WebhookClient;
+use crate::subscriber::domain::model::is_public_address;
+use crate::subscriber::domain::model::WebhookDelivery;
+use crate::subscriber::domain::model::WebhookSignature;
+
+#[derive(Clone)]
+pub struct ReqwestWebhookClient {
+    client: Client,
+    timeout: Duration,
+    allow_private_targets: bool,
+}
+
+impl ReqwestWebhookClient {
+    // Redirects are not followed, so that receivers cannot send deliveries on to other hosts, and
+    // names resolving to private or loopback addresses are refused unless allowed
+    pub fn new(timeout: Duration, allow_private_targets: bool) -> Self {
+        let mut builder = Client::builder().redirect(Policy::none());
+        if !allow_private_targets {
+            builder = builder.dns_resolver(Arc::new(PublicAddressResolver));
+        }
+
+        Self {
+            client: builder
+                .build()
+                .expect("Failed to build webhook HTTP client"),
+            timeout,
+            allow_private_targets,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl WebhookClient for ReqwestWebhookClient {
+    #[tracing::instrument(name = "Sending webhook", skip_all, fields(id = ?delivery.id()))]
+    async fn send(
+        &self,
+        url: &Url,
+        delivery: &WebhookDelivery,
+        signature: &WebhookSignature,
+    ) -> Result<u16, Error> {
+        // Addresses written in the URL are never resolved, so they are checked here instead
+        let ip = match url.host() {
+            Some(Host::Ipv4(ip)) => Some(IpAddr::V4(ip)),
+            Some(Host::Ipv6(ip)) => Some(IpAddr::V6(ip)),
+            _ => None,
+        };
+        if !self.allow_private_targets && ip.is_some_and(|ip| !is_public_address(&ip)) {
+            return Err(Error::WebhookOperationFailed(anyhow!(
+                "Webhook URL targets a private or loopback address"
+            )));
+        }
+
+        let response = self
+            .client
+            .post(url.clone())
+            .header("Content-Type", "application/json")
+            .header("Webhook-Id", delivery.id().to_string())
+            .header("Webhook-Timestamp", signature.timestamp().to_string())
+            .header("Webhook-Signature", signature.signature())
+            .body(delivery.payload().to_string())
+            .timeout(self.timeout)
+            .send()
+            .await
+            .context("Failed to send a webhook")
+            .map_err(Error::WebhookOperationFailed)?;
+
+        Ok(response.status().as_u16())
+    }
+}
+
+// Resolves names as usual but leaves out private and loopback addresses, so that a name cannot be
+// pointed at internal services after the endpoint has been registered
+struct PublicAddressResolver;
+
+impl Resolve for PublicAddressResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let addresses: Vec<SocketAddr> = tokio::net::lookup_host((name.as_str(), 0))
+                .await?
+                .filter(|address| is_public_address(&address.ip()))
+                .collect();
+            if addresses.is_empty() {
+                return Err(format!("{} resolves to no public address", name.as_str()).into());
+            }
+
+            Ok(Box::new(addresses.into_iter()) as Addrs)
+        })
+    }
+}
@@ -0,0 +1,43 @@
+use std::sync::Arc;
+
+use axum::extract::Path;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use uuid::Uuid;
+
+use crate::auth::interface::extractor::AdminUser;
+use crate::subscriber::domain::error::Error;
+use crate::subscriber::domain::service::WebhookManager;
+use crate::subscriber::interface::response::Response;
+
+// Deliveries still queued for the endpoint are dropped along with it
+#[tracing::instrument(name = "Removing a webhook endpoint", skip_all, fields(admin = %admin_user.user_id, id = %id))]
+pub async fn control(
+    admin_user: AdminUser,
+    State(webhook_manager): State<Arc<dyn WebhookManager>>,
+    Path(id): Path<Uuid>,
+) -> impl IntoResponse {
+    match webhook_manager.remove(&id).await {
+        Ok(_) => Response::new(StatusCode::NO_CONTENT, None),
+        Err(error) => {
+            tracing::error!("{:?}", error);
+            convert_error_to_response(error)
+        }
+    }
+}
+
+fn convert_error_to_response(error: Error) -> Response {
+    match error {
+        Error::WebhookEndpointNotFound(_) => {
+            Response::new(StatusCode::NOT_FOUND, Some(error.to_string()))
+        }
+        _ => Response::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Some(
+                "Failed to remove the webhook endpoint because of the unexpected system issue."
+                    .into(),
+            ),
+        ),
+    }
+}
@@ -0,0 +1,95 @@
+use std::str::FromStr;
+use std::sync::Arc;
+
+use axum::extract::Query;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::response::Response;
+use minijinja::context;
+use uuid::Uuid;
+
+use crate::auth::domain::infrastructure::SessionStore;
+use crate::auth::interface::session::AdminSession;
+use crate::common::page::PageRenderer;
+use crate::subscriber::domain::model::Status;
+use crate::subscriber::domain::model::SubscriberFilter;
+use crate::subscriber::domain::service::ListSubscribersQuery;
+use crate::subscriber::domain::service::QueryExecutor;
+use crate::subscriber::interface::controllers::get_admin_subscribers::SubscriberResponse;
+
+const PAGE_SIZE: i64 = 50;
+
+// The search form submits empty fields for filters left blank
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct Request {
+    status: Option<String>,
+    email: Option<String>,
+    cursor: Option<Uuid>,
+}
+
+#[tracing::instrument(name = "Showing subscribers", skip_all, fields(user_id = %admin_session.user_id, request = ?request))]
+pub async fn control(
+    State(session_store): State<Arc<dyn SessionStore>>,
+    State(query_executor): State<Arc<dyn QueryExecutor>>,
+    State(page_renderer): State<PageRenderer>,
+    mut admin_session: AdminSession,
+    Query(request): Query<Request>,
+) -> Response {
+    let status = request.status.filter(|status| !status.is_empty());
+    let email = request.email.filter(|email| !email.is_empty());
+    let filter = match status.as_deref().map(Status::from_str).transpose() {
+        Ok(parsed) => SubscriberFilter::new(parsed, email.clone(), None, None),
+        Err(_) => return (StatusCode::BAD_REQUEST, "Unknown status").into_response(),
+    };
+
+    let page = match query_executor
+        .list_subscribers(ListSubscribersQuery::new(filter, request.cursor, PAGE_SIZE))
+        .await
+    {
+        Ok(page) => page,
+        Err(error) => {
+            tracing::error!("{:?}", error);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let flash_messages = match admin_session
+        .take_flash_messages(session_store.as_ref())
+        .await
+    {
+        Ok(flash_messages) => flash_messages,
+        Err(error) => {
+            tracing::error!("{:?}", error);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    let subscribers: Vec<SubscriberResponse> = page
+        .subscribers()
+        .iter()
+        .map(SubscriberResponse::from)
+        .collect();
+    let next_page_url = page.next_cursor().map(|cursor| {
+        let mut query = url::form_urlencoded::Serializer::new(String::new());
+        query.append_pair("cursor", &cursor.to_string());
+        if let Some(status) = &status {
+            query.append_pair("status", status);
+        }
+        if let Some(email) = &email {
+            query.append_pair("email", email);
+        }
+        format!("/admin/dashboard/subscribers?{}", query.finish())
+    });
+    let page = page_renderer.render(
+        "subscribers.html",
+        context! { subscribers, next_page_url, status, email, flash_messages },
+    );
+
+    match page {
+        Ok(page) => page.into_response(),
+        Err(error) => {
+            tracing::error!("{:?}", error);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
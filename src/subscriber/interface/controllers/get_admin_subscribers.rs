@@ -0,0 +1,149 @@
+use std::str::FromStr;
+use std::sync::Arc;
+
+use axum::extract::Query;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::Json;
+use chrono::DateTime;
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::auth::interface::api_key::AdminOrApiClient;
+use crate::auth::interface::api_key::SubscribersRead;
+use crate::subscriber::domain::error::Error;
+use crate::subscriber::domain::model::Status;
+use crate::subscriber::domain::model::Subscriber;
+use crate::subscriber::domain::model::SubscriberFilter;
+use crate::subscriber::domain::model::SubscriberPage;
+use crate::subscriber::domain::service::ListSubscribersQuery;
+use crate::subscriber::domain::service::QueryExecutor;
+use crate::subscriber::interface::response::Response;
+
+const DEFAULT_LIMIT: i64 = 50;
+
+// Timestamps are taken as RFC 3339, and the range includes its start but not its end
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct Request {
+    status: Option<String>,
+    email: Option<String>,
+    subscribed_since: Option<String>,
+    subscribed_before: Option<String>,
+    cursor: Option<Uuid>,
+    limit: Option<i64>,
+}
+
+#[derive(serde::Serialize)]
+pub struct SubscriberResponse {
+    id: Uuid,
+    name: String,
+    email: String,
+    display_email: String,
+    status: String,
+    locale: String,
+    subscribed_at: String,
+    confirmed_at: Option<String>,
+}
+
+impl From<&Subscriber> for SubscriberResponse {
+    fn from(subscriber: &Subscriber) -> Self {
+        Self {
+            id: *subscriber.id(),
+            name: subscriber.name().into(),
+            email: subscriber.email().into(),
+            display_email: subscriber.display_email().into(),
+            status: subscriber.status().as_ref().into(),
+            locale: subscriber.locale().as_ref().into(),
+            subscribed_at: subscriber.subscribed_at().to_rfc3339(),
+            confirmed_at: subscriber
+                .confirmed_at()
+                .map(|confirmed_at| confirmed_at.to_rfc3339()),
+        }
+    }
+}
+
+#[derive(serde::Serialize)]
+struct SubscriberPageResponse {
+    subscribers: Vec<SubscriberResponse>,
+    next_cursor: Option<Uuid>,
+}
+
+impl From<&SubscriberPage> for SubscriberPageResponse {
+    fn from(page: &SubscriberPage) -> Self {
+        Self {
+            subscribers: page
+                .subscribers()
+                .iter()
+                .map(SubscriberResponse::from)
+                .collect(),
+            next_cursor: page.next_cursor().copied(),
+        }
+    }
+}
+
+#[tracing::instrument(name = "Listing subscribers", skip_all, fields(caller = %caller, request = ?request))]
+pub async fn control(
+    caller: AdminOrApiClient<SubscribersRead>,
+    State(query_executor): State<Arc<dyn QueryExecutor>>,
+    Query(request): Query<Request>,
+) -> axum::response::Response {
+    let query = match parse_query(request) {
+        Ok(query) => query,
+        Err(error) => return convert_error_to_response(error).into_response(),
+    };
+
+    match query_executor.list_subscribers(query).await {
+        Ok(page) => Json(SubscriberPageResponse::from(&page)).into_response(),
+        Err(error) => {
+            tracing::error!("{:?}", error);
+            convert_error_to_response(error).into_response()
+        }
+    }
+}
+
+fn parse_query(request: Request) -> Result<ListSubscribersQuery, Error> {
+    let status = request
+        .status
+        .map(|status| {
+            Status::from_str(&status)
+                .map_err(|_| Error::InvariantViolated(format!("Unknown status: {}", status)))
+        })
+        .transpose()?;
+    let filter = SubscriberFilter::new(
+        status,
+        request.email,
+        request
+            .subscribed_since
+            .as_deref()
+            .map(parse_timestamp)
+            .transpose()?,
+        request
+            .subscribed_before
+            .as_deref()
+            .map(parse_timestamp)
+            .transpose()?,
+    );
+
+    Ok(ListSubscribersQuery::new(
+        filter,
+        request.cursor,
+        request.limit.unwrap_or(DEFAULT_LIMIT),
+    ))
+}
+
+fn parse_timestamp(timestamp: &str) -> Result<DateTime<Utc>, Error> {
+    DateTime::parse_from_rfc3339(timestamp)
+        .map(|timestamp| timestamp.to_utc())
+        .map_err(|_| Error::InvariantViolated(format!("Invalid RFC 3339 timestamp: {}", timestamp)))
+}
+
+fn convert_error_to_response(error: Error) -> Response {
+    match error {
+        Error::InvariantViolated(message) => Response::new(StatusCode::BAD_REQUEST, Some(message)),
+        _ => Response::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Some("Failed to list subscribers because of the unexpected system issue.".into()),
+        ),
+    }
+}
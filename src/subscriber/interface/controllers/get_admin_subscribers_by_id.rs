@@ -0,0 +1,51 @@
+use std::sync::Arc;
+
+use axum::extract::Path;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::Json;
+use uuid::Uuid;
+
+use crate::auth::interface::api_key::AdminOrApiClient;
+use crate::auth::interface::api_key::SubscribersRead;
+use crate::common::locale::Locale;
+use crate::subscriber::domain::error::Error;
+use crate::subscriber::domain::service::GetSubscriberQuery;
+use crate::subscriber::domain::service::QueryExecutor;
+use crate::subscriber::interface::controllers::get_admin_subscribers::SubscriberResponse;
+use crate::subscriber::interface::locale::AcceptedLocale;
+use crate::subscriber::interface::message::Message;
+use crate::subscriber::interface::response::Response;
+
+#[tracing::instrument(name = "Fetching subscriber", skip_all, fields(caller = %caller, id = %id))]
+pub async fn control(
+    caller: AdminOrApiClient<SubscribersRead>,
+    State(query_executor): State<Arc<dyn QueryExecutor>>,
+    AcceptedLocale(locale): AcceptedLocale,
+    Path(id): Path<Uuid>,
+) -> axum::response::Response {
+    match query_executor
+        .get_subscriber(GetSubscriberQuery::new(id))
+        .await
+    {
+        Ok(subscriber) => Json(SubscriberResponse::from(&subscriber)).into_response(),
+        Err(error) => {
+            tracing::error!("{:?}", error);
+            convert_error_to_response(error, locale).into_response()
+        }
+    }
+}
+
+fn convert_error_to_response(error: Error, locale: Locale) -> Response {
+    match error {
+        Error::SubscriberNotFound(_) => Response::new(
+            StatusCode::NOT_FOUND,
+            Some(Message::SubscriberNotFound.localize(locale)),
+        ),
+        _ => Response::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Some(Message::SubscriberFetchFailedUnexpectedly.localize(locale)),
+        ),
+    }
+}
@@ -0,0 +1,56 @@
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::Json;
+use uuid::Uuid;
+
+use crate::auth::interface::extractor::AdminUser;
+use crate::subscriber::domain::model::WebhookEndpoint;
+use crate::subscriber::domain::service::WebhookManager;
+use crate::subscriber::interface::response::Response;
+
+#[derive(serde::Serialize)]
+pub struct WebhookEndpointResponse {
+    id: Uuid,
+    url: String,
+    created_at: String,
+}
+
+impl From<&WebhookEndpoint> for WebhookEndpointResponse {
+    fn from(webhook_endpoint: &WebhookEndpoint) -> Self {
+        Self {
+            id: *webhook_endpoint.id(),
+            url: webhook_endpoint.url().to_string(),
+            created_at: webhook_endpoint.created_at().to_rfc3339(),
+        }
+    }
+}
+
+#[tracing::instrument(name = "Listing webhook endpoints", skip_all, fields(admin = %admin_user.user_id))]
+pub async fn control(
+    admin_user: AdminUser,
+    State(webhook_manager): State<Arc<dyn WebhookManager>>,
+) -> axum::response::Response {
+    match webhook_manager.list().await {
+        Ok(webhook_endpoints) => Json(
+            webhook_endpoints
+                .iter()
+                .map(WebhookEndpointResponse::from)
+                .collect::<Vec<_>>(),
+        )
+        .into_response(),
+        Err(error) => {
+            tracing::error!("{:?}", error);
+            Response::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Some(
+                    "Failed to list webhook endpoints because of the unexpected system issue."
+                        .into(),
+                ),
+            )
+            .into_response()
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use std::sync::Arc;
+
+use axum::extract::Path;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::Json;
+use uuid::Uuid;
+
+use crate::auth::interface::extractor::AdminUser;
+use crate::subscriber::domain::error::Error;
+use crate::subscriber::domain::model::WebhookDelivery;
+use crate::subscriber::domain::service::WebhookManager;
+use crate::subscriber::interface::response::Response;
+
+#[derive(serde::Serialize)]
+struct WebhookDeliveryResponse {
+    id: Uuid,
+    event_type: String,
+    created_at: String,
+    attempts: i32,
+    next_attempt_at: Option<String>,
+    delivered_at: Option<String>,
+    last_status_code: Option<i32>,
+    last_error: Option<String>,
+}
+
+impl From<&WebhookDelivery> for WebhookDeliveryResponse {
+    fn from(webhook_delivery: &WebhookDelivery) -> Self {
+        Self {
+            id: *webhook_delivery.id(),
+            event_type: webhook_delivery.event_type().into(),
+            created_at: webhook_delivery.created_at().to_rfc3339(),
+            attempts: webhook_delivery.attempts(),
+            // Only meaningful while the delivery is still pending
+            next_attempt_at: webhook_delivery
+                .delivered_at()
+                .is_none()
+                .then(|| webhook_delivery.next_attempt_at().to_rfc3339()),
+            delivered_at: webhook_delivery
+                .delivered_at()
+                .map(|delivered_at| delivered_at.to_rfc3339()),
+            last_status_code: webhook_delivery.last_status_code(),
+            last_error: webhook_delivery.last_error().map(String::from),
+        }
+    }
+}
+
+#[tracing::instrument(name = "Listing webhook deliveries", skip_all, fields(admin = %admin_user.user_id, id = %id))]
+pub async fn control(
+    admin_user: AdminUser,
+    State(webhook_manager): State<Arc<dyn WebhookManager>>,
+    Path(id): Path<Uuid>,
+) -> axum::response::Response {
+    match webhook_manager.list_deliveries(&id).await {
+        Ok(webhook_deliveries) => Json(
+            webhook_deliveries
+                .iter()
+                .map(WebhookDeliveryResponse::from)
+                .collect::<Vec<_>>(),
+        )
+        .into_response(),
+        Err(error) => {
+            tracing::error!("{:?}", error);
+            convert_error_to_response(error).into_response()
+        }
+    }
+}
+
+fn convert_error_to_response(error: Error) -> Response {
+    match error {
+        Error::WebhookEndpointNotFound(_) => {
+            Response::new(StatusCode::NOT_FOUND, Some(error.to_string()))
+        }
+        _ => Response::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Some(
+                "Failed to list webhook deliveries because of the unexpected system issue.".into(),
+            ),
+        ),
+    }
+}
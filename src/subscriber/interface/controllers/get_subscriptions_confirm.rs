@@ -5,19 +5,30 @@ use axum::extract::State;
 use axum::http::StatusCode;
 use axum::response::IntoResponse;
 
+use crate::common::locale::Locale;
 use crate::subscriber::domain::error::Error;
 use crate::subscriber::domain::service::CommandExecutor;
 use crate::subscriber::domain::service::ConfirmSubscriptionCommand;
+use crate::subscriber::interface::locale::AcceptedLocale;
+use crate::subscriber::interface::message::Message;
 use crate::subscriber::interface::response::Response;
 
-#[derive(Clone, Debug, serde::Deserialize)]
+#[derive(Clone, serde::Deserialize)]
 pub struct Request {
     token: String,
 }
 
-#[tracing::instrument(name = "Confirming subscription", skip_all, fields(request = ?request))]
+// The token alone is enough to act on behalf of the subscriber, so it is kept out of logs
+impl std::fmt::Debug for Request {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Request").finish_non_exhaustive()
+    }
+}
+
+#[tracing::instrument(name = "Confirming subscription", skip_all)]
 pub async fn control(
     State(command_executor): State<Arc<dyn CommandExecutor>>,
+    AcceptedLocale(locale): AcceptedLocale,
     Query(request): Query<Request>,
 ) -> impl IntoResponse {
     let command = ConfirmSubscriptionCommand::new(request.token).into();
@@ -26,28 +37,40 @@ pub async fn control(
         Ok(_) => Response::new(StatusCode::OK, None),
         Err(error) => {
             tracing::error!("{}", error);
-            convert_error_to_response(error)
+            convert_error_to_response(error, locale)
         }
     }
 }
 
-fn convert_error_to_response(error: Error) -> Response {
+fn convert_error_to_response(error: Error, locale: Locale) -> Response {
     match error {
         Error::InvariantViolated(_) => Response::new(
             StatusCode::BAD_REQUEST,
-            Some("Failed to load the subscriber having the token.".into()),
+            Some(Message::SubscriberOfTokenUnloadable.localize(locale)),
         ),
-        Error::TokenNotFound(_) => Response::new(
+        Error::TokenNotFound => Response::new(
             StatusCode::NOT_FOUND,
-            Some("Failed to find the token.".into()),
+            Some(Message::TokenNotFound.localize(locale)),
+        ),
+        Error::TokenExpired => Response::new(
+            StatusCode::GONE,
+            Some(Message::TokenExpired.localize(locale)),
+        ),
+        Error::TokenAlreadyUsed => Response::new(
+            StatusCode::GONE,
+            Some(Message::TokenAlreadyUsed.localize(locale)),
         ),
         Error::SubscriberNotFound(_) => Response::new(
             StatusCode::NOT_FOUND,
-            Some("Failed to find a subscriber with the token.".into()),
+            Some(Message::SubscriberOfTokenNotFound.localize(locale)),
+        ),
+        Error::ConcurrencyConflict(_) => Response::new(
+            StatusCode::CONFLICT,
+            Some(Message::SubscriberModifiedConcurrently.localize(locale)),
         ),
         _ => Response::new(
             StatusCode::INTERNAL_SERVER_ERROR,
-            Some("Failed to confirm subscription because of the unexpected system issue.".into()),
+            Some(Message::ConfirmationFailedUnexpectedly.localize(locale)),
         ),
     }
 }
@@ -0,0 +1,44 @@
+use axum::extract::Query;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::response::Response;
+use minijinja::context;
+
+use crate::common::page::PageRenderer;
+use crate::subscriber::interface::locale::AcceptedLocale;
+use crate::subscriber::interface::message::Message;
+
+#[derive(Clone, serde::Deserialize)]
+pub struct Request {
+    // Only required here, as the form submits to this very URL with the token in its query
+    #[allow(dead_code)]
+    token: String,
+}
+
+// Link scanners and prefetchers follow links in emails, so following the link only asks for
+// confirmation, and unsubscribing is left to the POST the page submits
+#[tracing::instrument(name = "Showing unsubscription confirmation", skip_all)]
+pub async fn control(
+    State(page_renderer): State<PageRenderer>,
+    AcceptedLocale(locale): AcceptedLocale,
+    Query(_): Query<Request>,
+) -> Response {
+    let page = page_renderer.render(
+        "unsubscribe.html",
+        context! {
+            locale => locale.as_ref(),
+            title => Message::UnsubscriptionConfirmationTitle.localize(locale),
+            prompt => Message::UnsubscriptionConfirmationPrompt.localize(locale),
+            button => Message::UnsubscriptionConfirmationButton.localize(locale),
+        },
+    );
+
+    match page {
+        Ok(page) => page.into_response(),
+        Err(error) => {
+            tracing::error!("{:?}", error);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
@@ -1,2 +1,12 @@
+pub mod delete_admin_webhooks;
+pub mod get_admin_dashboard_subscribers;
+pub mod get_admin_subscribers;
+pub mod get_admin_subscribers_by_id;
+pub mod get_admin_webhooks;
+pub mod get_admin_webhooks_deliveries;
 pub mod get_subscriptions_confirm;
+pub mod get_subscriptions_unsubscribe;
+pub mod post_admin_subscribers;
+pub mod post_admin_webhooks;
 pub mod post_subscriptions;
+pub mod post_subscriptions_unsubscribe;
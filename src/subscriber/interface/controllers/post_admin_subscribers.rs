@@ -0,0 +1,82 @@
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::Json;
+
+use crate::auth::interface::api_key::AdminOrApiClient;
+use crate::auth::interface::api_key::SubscribersWrite;
+use crate::common::locale::Locale;
+use crate::subscriber::domain::error::Error;
+use crate::subscriber::domain::service::CommandExecutor;
+use crate::subscriber::domain::service::SubscribeCommand;
+use crate::subscriber::interface::locale::AcceptedLocale;
+use crate::subscriber::interface::message::Message;
+use crate::subscriber::interface::response::Response;
+
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct Request {
+    name: String,
+    email: String,
+    locale: Option<String>,
+}
+
+// Subscribers registered on behalf of someone still have to confirm through the emailed link,
+// exactly as if they had submitted the subscription form themselves
+#[tracing::instrument(name = "Registering a new subscriber on behalf of someone", skip_all, fields(caller = %caller, request = ?request))]
+pub async fn control(
+    caller: AdminOrApiClient<SubscribersWrite>,
+    State(command_executor): State<Arc<dyn CommandExecutor>>,
+    AcceptedLocale(accepted_locale): AcceptedLocale,
+    Json(request): Json<Request>,
+) -> impl IntoResponse {
+    let locale = request
+        .locale
+        .as_deref()
+        .and_then(Locale::parse)
+        .unwrap_or(accepted_locale);
+    let command = SubscribeCommand::new(request.name, request.email, locale).into();
+
+    match command_executor.execute(command).await {
+        Ok(_) => Response::new(StatusCode::ACCEPTED, None),
+        Err(error) => {
+            tracing::error!("{:?}", error);
+            convert_error_to_response(error, accepted_locale)
+        }
+    }
+}
+
+// Errors are told in the locale the caller accepts, which the subscriber registered may not share
+fn convert_error_to_response(error: Error, locale: Locale) -> Response {
+    match error {
+        Error::NameEmpty => Response::new(
+            StatusCode::BAD_REQUEST,
+            Some(Message::NameEmpty.localize(locale)),
+        ),
+        Error::NameTooLong => Response::new(
+            StatusCode::BAD_REQUEST,
+            Some(Message::NameTooLong.localize(locale)),
+        ),
+        Error::NameHasForbiddenCharacters => Response::new(
+            StatusCode::BAD_REQUEST,
+            Some(Message::NameHasForbiddenCharacters.localize(locale)),
+        ),
+        Error::EmailInvalid => Response::new(
+            StatusCode::BAD_REQUEST,
+            Some(Message::EmailInvalid.localize(locale)),
+        ),
+        Error::InvariantViolated(_) => Response::new(
+            StatusCode::BAD_REQUEST,
+            Some(Message::SubscriptionInvalid.localize(locale)),
+        ),
+        Error::ConcurrencyConflict(_) => Response::new(
+            StatusCode::CONFLICT,
+            Some(Message::SubscriberModifiedConcurrently.localize(locale)),
+        ),
+        _ => Response::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Some(Message::SubscriptionFailedUnexpectedly.localize(locale)),
+        ),
+    }
+}
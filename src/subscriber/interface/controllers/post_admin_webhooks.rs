@@ -0,0 +1,64 @@
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::Json;
+use secrecy::ExposeSecret;
+use secrecy::SecretString;
+
+use crate::auth::interface::extractor::AdminUser;
+use crate::subscriber::domain::error::Error;
+use crate::subscriber::domain::service::WebhookManager;
+use crate::subscriber::interface::controllers::get_admin_webhooks::WebhookEndpointResponse;
+use crate::subscriber::interface::response::Response;
+
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct Request {
+    url: String,
+    // Lets operators reuse a secret already shared with the receiver
+    secret: Option<SecretString>,
+}
+
+#[derive(serde::Serialize)]
+struct RegisteredWebhookEndpointResponse {
+    #[serde(flatten)]
+    webhook_endpoint: WebhookEndpointResponse,
+    // Shown only in this response, so that receivers are told once how to verify deliveries
+    secret: String,
+}
+
+#[tracing::instrument(name = "Registering a webhook endpoint", skip_all, fields(admin = %admin_user.user_id, url = %request.url))]
+pub async fn control(
+    admin_user: AdminUser,
+    State(webhook_manager): State<Arc<dyn WebhookManager>>,
+    Json(request): Json<Request>,
+) -> axum::response::Response {
+    match webhook_manager.register(&request.url, request.secret).await {
+        Ok(webhook_endpoint) => (
+            StatusCode::CREATED,
+            Json(RegisteredWebhookEndpointResponse {
+                webhook_endpoint: (&webhook_endpoint).into(),
+                secret: webhook_endpoint.secret().expose_secret().into(),
+            }),
+        )
+            .into_response(),
+        Err(error) => {
+            tracing::error!("{:?}", error);
+            convert_error_to_response(error).into_response()
+        }
+    }
+}
+
+fn convert_error_to_response(error: Error) -> Response {
+    match error {
+        Error::InvariantViolated(message) => Response::new(StatusCode::BAD_REQUEST, Some(message)),
+        _ => Response::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Some(
+                "Failed to register the webhook endpoint because of the unexpected system issue."
+                    .into(),
+            ),
+        ),
+    }
+}
@@ -5,42 +5,73 @@ use axum::http::StatusCode;
 use axum::response::IntoResponse;
 use axum::Form;
 
+use crate::common::locale::Locale;
 use crate::subscriber::domain::error::Error;
 use crate::subscriber::domain::service::CommandExecutor;
 use crate::subscriber::domain::service::SubscribeCommand;
+use crate::subscriber::interface::locale::AcceptedLocale;
+use crate::subscriber::interface::message::Message;
 use crate::subscriber::interface::response::Response;
 
 #[derive(Clone, Debug, serde::Deserialize)]
 pub struct Request {
     name: String,
     email: String,
+    locale: Option<String>,
 }
 
 #[tracing::instrument(name = "Registering a new subscriber", skip_all, fields(request = ?request))]
 pub async fn control(
     State(command_executor): State<Arc<dyn CommandExecutor>>,
+    AcceptedLocale(accepted_locale): AcceptedLocale,
     Form(request): Form<Request>,
 ) -> impl IntoResponse {
-    let command = SubscribeCommand::new(request.name, request.email).into();
+    // A locale chosen explicitly on the form wins over the one the browser advertises
+    let locale = request
+        .locale
+        .as_deref()
+        .and_then(Locale::parse)
+        .unwrap_or(accepted_locale);
+    let command = SubscribeCommand::new(request.name, request.email, locale).into();
 
     match command_executor.execute(command).await {
         Ok(_) => Response::new(StatusCode::OK, None),
         Err(error) => {
             tracing::error!("{:?}", error);
-            convert_error_to_response(error)
+            convert_error_to_response(error, locale)
         }
     }
 }
 
-fn convert_error_to_response(error: Error) -> Response {
+fn convert_error_to_response(error: Error, locale: Locale) -> Response {
     match error {
-        Error::InvariantViolated(message) => Response::new(StatusCode::BAD_REQUEST, Some(message)),
+        Error::NameEmpty => Response::new(
+            StatusCode::BAD_REQUEST,
+            Some(Message::NameEmpty.localize(locale)),
+        ),
+        Error::NameTooLong => Response::new(
+            StatusCode::BAD_REQUEST,
+            Some(Message::NameTooLong.localize(locale)),
+        ),
+        Error::NameHasForbiddenCharacters => Response::new(
+            StatusCode::BAD_REQUEST,
+            Some(Message::NameHasForbiddenCharacters.localize(locale)),
+        ),
+        Error::EmailInvalid => Response::new(
+            StatusCode::BAD_REQUEST,
+            Some(Message::EmailInvalid.localize(locale)),
+        ),
+        Error::InvariantViolated(_) => Response::new(
+            StatusCode::BAD_REQUEST,
+            Some(Message::SubscriptionInvalid.localize(locale)),
+        ),
+        Error::ConcurrencyConflict(_) => Response::new(
+            StatusCode::CONFLICT,
+            Some(Message::SubscriberModifiedConcurrently.localize(locale)),
+        ),
         _ => Response::new(
             StatusCode::INTERNAL_SERVER_ERROR,
-            Some(
-                "Failed to register a new subscriber because of the unexpected system issue."
-                    .into(),
-            ),
+            Some(Message::SubscriptionFailedUnexpectedly.localize(locale)),
         ),
     }
 }
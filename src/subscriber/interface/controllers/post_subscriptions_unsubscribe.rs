@@ -0,0 +1,67 @@
+use std::sync::Arc;
+
+use axum::extract::Query;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+
+use crate::common::locale::Locale;
+use crate::subscriber::domain::error::Error;
+use crate::subscriber::domain::service::CommandExecutor;
+use crate::subscriber::domain::service::UnsubscribeCommand;
+use crate::subscriber::interface::locale::AcceptedLocale;
+use crate::subscriber::interface::message::Message;
+use crate::subscriber::interface::response::Response;
+
+// Mail clients supporting one-click unsubscription (RFC 8058) send POST to the link itself, as
+// does the confirmation page behind it, so the token is read from the query string rather than
+// the form body
+#[derive(Clone, serde::Deserialize)]
+pub struct Request {
+    token: String,
+}
+
+// The token alone is enough to act on behalf of the subscriber, so it is kept out of logs
+impl std::fmt::Debug for Request {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Request").finish_non_exhaustive()
+    }
+}
+
+#[tracing::instrument(name = "Unsubscribing subscriber by one-click", skip_all)]
+pub async fn control(
+    State(command_executor): State<Arc<dyn CommandExecutor>>,
+    AcceptedLocale(locale): AcceptedLocale,
+    Query(request): Query<Request>,
+) -> impl IntoResponse {
+    let command = UnsubscribeCommand::new(request.token).into();
+
+    match command_executor.execute(command).await {
+        Ok(_) => Response::new(StatusCode::OK, None),
+        Err(error) => {
+            tracing::error!("{}", error);
+            convert_error_to_response(error, locale)
+        }
+    }
+}
+
+fn convert_error_to_response(error: Error, locale: Locale) -> Response {
+    match error {
+        Error::TokenInvalid => Response::new(
+            StatusCode::BAD_REQUEST,
+            Some(Message::TokenInvalid.localize(locale)),
+        ),
+        Error::SubscriberNotFound(_) => Response::new(
+            StatusCode::NOT_FOUND,
+            Some(Message::SubscriberOfTokenNotFound.localize(locale)),
+        ),
+        Error::ConcurrencyConflict(_) => Response::new(
+            StatusCode::CONFLICT,
+            Some(Message::SubscriberModifiedConcurrently.localize(locale)),
+        ),
+        _ => Response::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Some(Message::UnsubscriptionFailedUnexpectedly.localize(locale)),
+        ),
+    }
+}
@@ -0,0 +1,38 @@
+use std::time::Duration;
+
+use crate::common::email::EmailClient;
+use crate::common::retry::RetryPolicy;
+use crate::subscriber::domain::infrastructure::OutboxRepository;
+use crate::subscriber::domain::infrastructure::SubscriberRepository;
+use crate::subscriber::domain::infrastructure::UnitOfWork;
+use crate::subscriber::domain::service::dispatch_outbox_messages;
+
+// Drains the outbox continuously, and waits for the interval only when there was nothing to deliver
+pub async fn run<U: UnitOfWork>(
+    unit_of_work: U,
+    subscriber_repository: impl SubscriberRepository<Transaction = U::Transaction>,
+    outbox_repository: impl OutboxRepository<Transaction = U::Transaction>,
+    email_client: impl EmailClient,
+    retry_policy: RetryPolicy,
+    batch_size: i64,
+    interval: Duration,
+) {
+    loop {
+        match dispatch_outbox_messages(
+            &unit_of_work,
+            &subscriber_repository,
+            &outbox_repository,
+            &email_client,
+            &retry_policy,
+            batch_size,
+        )
+        .await
+        {
+            Ok(count) if count > 0 => continue,
+            Ok(_) => {}
+            Err(error) => tracing::error!("Failed to dispatch outbox messages: {:?}", error),
+        }
+
+        tokio::time::sleep(interval).await;
+    }
+}
@@ -0,0 +1,65 @@
+use std::convert::Infallible;
+
+use axum::extract::FromRequestParts;
+use axum::http::header::ACCEPT_LANGUAGE;
+use axum::http::request::Parts;
+
+use crate::common::locale::Locale;
+
+// Negotiated from the Accept-Language header, falling back to the default locale when the header
+// is missing or names nothing supported
+#[derive(Clone, Copy, Debug)]
+pub struct AcceptedLocale(pub Locale);
+
+impl<S: Send + Sync> FromRequestParts<S> for AcceptedLocale {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let locale = parts
+            .headers
+            .get(ACCEPT_LANGUAGE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(negotiate)
+            .unwrap_or_default();
+
+        Ok(Self(locale))
+    }
+}
+
+fn negotiate(accept_language: &str) -> Option<Locale> {
+    let mut candidates: Vec<(f32, Locale)> = accept_language
+        .split(',')
+        .filter_map(|range| {
+            let mut parameters = range.split(';');
+            let locale = Locale::parse(parameters.next()?)?;
+            let quality = match parameters.find_map(|p| p.trim().strip_prefix("q=")) {
+                Some(quality) => quality.trim().parse().ok()?,
+                None => 1.0,
+            };
+            (quality > 0.0).then_some((quality, locale))
+        })
+        .collect();
+
+    // Sorting is stable, so ranges with the same weight keep the order the client sent
+    candidates.sort_by(|a, b| b.0.total_cmp(&a.0));
+    candidates.first().map(|(_, locale)| *locale)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[rstest::rstest]
+    #[case("ko-KR,ko;q=0.9,en-US;q=0.8,en;q=0.7", Some(Locale::Ko))]
+    #[case("fr-FR,en;q=0.5,ko;q=0.8", Some(Locale::Ko))]
+    #[case("en, ko", Some(Locale::En))]
+    #[case("ko;q=0, en;q=0.1", Some(Locale::En))]
+    #[case("fr, de", None)]
+    #[case("*", None)]
+    fn negotiate_picks_supported_locale_with_highest_weight(
+        #[case] accept_language: &str,
+        #[case] expected: Option<Locale>,
+    ) {
+        assert_eq!(negotiate(accept_language), expected);
+    }
+}
@@ -0,0 +1,101 @@
+use crate::common::locale::Locale;
+
+// Translated for every supported locale
+#[derive(Clone, Copy, Debug)]
+pub enum Message {
+    NameEmpty,
+    NameTooLong,
+    NameHasForbiddenCharacters,
+    EmailInvalid,
+    SubscriptionInvalid,
+    SubscriptionFailedUnexpectedly,
+    SubscriberOfTokenUnloadable,
+    TokenNotFound,
+    TokenExpired,
+    TokenAlreadyUsed,
+    TokenInvalid,
+    SubscriberOfTokenNotFound,
+    ConfirmationFailedUnexpectedly,
+    UnsubscriptionFailedUnexpectedly,
+    SubscriberModifiedConcurrently,
+    SubscriberNotFound,
+    SubscriberFetchFailedUnexpectedly,
+    UnsubscriptionConfirmationTitle,
+    UnsubscriptionConfirmationPrompt,
+    UnsubscriptionConfirmationButton,
+}
+
+impl Message {
+    pub fn localize(self, locale: Locale) -> String {
+        let (en, ko) = match self {
+            Message::NameEmpty => ("Name cannot be empty.", "이름을 입력해 주세요."),
+            Message::NameTooLong => (
+                "Name cannot be longer than 256 characters.",
+                "이름은 256자보다 길 수 없습니다.",
+            ),
+            Message::NameHasForbiddenCharacters => (
+                "Name cannot have forbidden characters.",
+                "이름에 사용할 수 없는 문자가 포함되어 있습니다.",
+            ),
+            Message::EmailInvalid => (
+                "Email address must be valid.",
+                "올바른 이메일 주소를 입력해 주세요.",
+            ),
+            Message::SubscriptionInvalid => (
+                "The subscription request is invalid.",
+                "구독 요청이 올바르지 않습니다.",
+            ),
+            Message::SubscriptionFailedUnexpectedly => (
+                "Failed to register a new subscriber because of the unexpected system issue.",
+                "예기치 않은 시스템 문제로 구독자를 등록하지 못했습니다.",
+            ),
+            Message::SubscriberOfTokenUnloadable => (
+                "Failed to load the subscriber having the token.",
+                "토큰에 해당하는 구독자 정보를 불러오지 못했습니다.",
+            ),
+            Message::TokenNotFound => ("Failed to find the token.", "토큰을 찾을 수 없습니다."),
+            Message::TokenExpired => ("The token has expired.", "토큰이 만료되었습니다."),
+            Message::TokenAlreadyUsed => (
+                "The token has already been used.",
+                "이미 사용된 토큰입니다.",
+            ),
+            Message::TokenInvalid => ("Failed to verify the token.", "토큰을 검증하지 못했습니다."),
+            Message::SubscriberOfTokenNotFound => (
+                "Failed to find a subscriber with the token.",
+                "토큰에 해당하는 구독자를 찾을 수 없습니다.",
+            ),
+            Message::ConfirmationFailedUnexpectedly => (
+                "Failed to confirm subscription because of the unexpected system issue.",
+                "예기치 않은 시스템 문제로 구독을 확인하지 못했습니다.",
+            ),
+            Message::UnsubscriptionFailedUnexpectedly => (
+                "Failed to unsubscribe because of the unexpected system issue.",
+                "예기치 않은 시스템 문제로 구독을 해지하지 못했습니다.",
+            ),
+            Message::SubscriberModifiedConcurrently => (
+                "The subscriber is being changed by another request. Please try again.",
+                "다른 요청이 구독자 정보를 변경하고 있습니다. 다시 시도해 주세요.",
+            ),
+            Message::SubscriberNotFound => (
+                "Failed to find the subscriber.",
+                "구독자를 찾을 수 없습니다.",
+            ),
+            Message::SubscriberFetchFailedUnexpectedly => (
+                "Failed to fetch the subscriber because of the unexpected system issue.",
+                "예기치 않은 시스템 문제로 구독자 정보를 불러오지 못했습니다.",
+            ),
+            Message::UnsubscriptionConfirmationTitle => ("Unsubscribe", "구독 해지"),
+            Message::UnsubscriptionConfirmationPrompt => (
+                "Do you want to stop receiving emails from us?",
+                "더 이상 이메일을 받지 않으시겠습니까?",
+            ),
+            Message::UnsubscriptionConfirmationButton => ("Unsubscribe", "구독 해지하기"),
+        };
+
+        match locale {
+            Locale::En => en,
+            Locale::Ko => ko,
+        }
+        .into()
+    }
+}
@@ -1,4 +1,8 @@
 mod controllers;
+pub mod dispatcher;
+mod locale;
+mod message;
 mod response;
 pub mod router;
 pub mod runner;
+pub mod webhook_worker;
@@ -1,22 +1,48 @@
 use std::sync::Arc;
 
 use axum::extract::FromRef;
+use axum::routing::delete;
 use axum::routing::get;
 use axum::routing::post;
 use axum::Router;
 
+use crate::auth::domain::infrastructure::SessionStore;
+use crate::auth::domain::service::ApiKeyManager;
+use crate::auth::domain::service::Authenticator;
+use crate::common::page::PageRenderer;
 use crate::subscriber::domain::service::CommandExecutor;
+use crate::subscriber::domain::service::QueryExecutor;
+use crate::subscriber::domain::service::WebhookManager;
 use crate::subscriber::interface::controllers;
 
 #[derive(Clone)]
 pub struct Container {
     command_executor: Arc<dyn CommandExecutor>,
+    query_executor: Arc<dyn QueryExecutor>,
+    api_key_manager: Arc<dyn ApiKeyManager>,
+    authenticator: Arc<dyn Authenticator>,
+    session_store: Arc<dyn SessionStore>,
+    webhook_manager: Arc<dyn WebhookManager>,
+    page_renderer: PageRenderer,
 }
 
 impl Container {
-    pub fn new(command_executor: impl CommandExecutor) -> Self {
+    pub fn new(
+        command_executor: impl CommandExecutor,
+        query_executor: impl QueryExecutor,
+        api_key_manager: Arc<dyn ApiKeyManager>,
+        authenticator: Arc<dyn Authenticator>,
+        session_store: Arc<dyn SessionStore>,
+        webhook_manager: impl WebhookManager,
+    ) -> Self {
         Self {
             command_executor: Arc::new(command_executor),
+            query_executor: Arc::new(query_executor),
+            api_key_manager,
+            authenticator,
+            session_store,
+            webhook_manager: Arc::new(webhook_manager),
+            page_renderer: PageRenderer::new(),
         }
     }
 }
@@ -27,6 +53,42 @@ impl FromRef<Container> for Arc<dyn CommandExecutor> {
     }
 }
 
+impl FromRef<Container> for Arc<dyn QueryExecutor> {
+    fn from_ref(container: &Container) -> Self {
+        container.query_executor.clone()
+    }
+}
+
+impl FromRef<Container> for Arc<dyn ApiKeyManager> {
+    fn from_ref(container: &Container) -> Self {
+        container.api_key_manager.clone()
+    }
+}
+
+impl FromRef<Container> for Arc<dyn Authenticator> {
+    fn from_ref(container: &Container) -> Self {
+        container.authenticator.clone()
+    }
+}
+
+impl FromRef<Container> for Arc<dyn SessionStore> {
+    fn from_ref(container: &Container) -> Self {
+        container.session_store.clone()
+    }
+}
+
+impl FromRef<Container> for Arc<dyn WebhookManager> {
+    fn from_ref(container: &Container) -> Self {
+        container.webhook_manager.clone()
+    }
+}
+
+impl FromRef<Container> for PageRenderer {
+    fn from_ref(container: &Container) -> Self {
+        container.page_renderer.clone()
+    }
+}
+
 pub async fn get_router(container: Container) -> Router {
     Router::new()
         .route(
@@ -37,5 +99,36 @@ pub async fn get_router(container: Container) -> Router {
             "/subscriptions/confirm",
             get(controllers::get_subscriptions_confirm::control),
         )
+        .route(
+            "/subscriptions/unsubscribe",
+            get(controllers::get_subscriptions_unsubscribe::control)
+                .post(controllers::post_subscriptions_unsubscribe::control),
+        )
+        .route(
+            "/admin/dashboard/subscribers",
+            get(controllers::get_admin_dashboard_subscribers::control),
+        )
+        .route(
+            "/admin/subscribers",
+            get(controllers::get_admin_subscribers::control)
+                .post(controllers::post_admin_subscribers::control),
+        )
+        .route(
+            "/admin/subscribers/{id}",
+            get(controllers::get_admin_subscribers_by_id::control),
+        )
+        .route(
+            "/admin/webhooks",
+            get(controllers::get_admin_webhooks::control)
+                .post(controllers::post_admin_webhooks::control),
+        )
+        .route(
+            "/admin/webhooks/{id}",
+            delete(controllers::delete_admin_webhooks::control),
+        )
+        .route(
+            "/admin/webhooks/{id}/deliveries",
+            get(controllers::get_admin_webhooks_deliveries::control),
+        )
         .with_state(container)
 }
@@ -1,16 +1,34 @@
 use std::error::Error;
+use std::sync::Arc;
 
 use tokio::net::TcpListener;
 
+use crate::auth::domain::infrastructure::SessionStore;
+use crate::auth::domain::service::ApiKeyManager;
+use crate::auth::domain::service::Authenticator;
 use crate::subscriber::domain::service::CommandExecutor as SubscriberCommandExecutor;
+use crate::subscriber::domain::service::QueryExecutor as SubscriberQueryExecutor;
+use crate::subscriber::domain::service::WebhookManager;
 use crate::subscriber::interface::router::get_router;
 use crate::subscriber::interface::router::Container;
 
 pub async fn run(
     listener: TcpListener,
     subscriber_command_executor: impl SubscriberCommandExecutor,
+    subscriber_query_executor: impl SubscriberQueryExecutor,
+    api_key_manager: impl ApiKeyManager,
+    authenticator: impl Authenticator,
+    session_store: impl SessionStore,
+    webhook_manager: impl WebhookManager,
 ) -> Result<(), impl Error> {
-    let container = Container::new(subscriber_command_executor);
+    let container = Container::new(
+        subscriber_command_executor,
+        subscriber_query_executor,
+        Arc::new(api_key_manager),
+        Arc::new(authenticator),
+        Arc::new(session_store),
+        webhook_manager,
+    );
     let app = get_router(container).await;
 
     axum::serve(listener, app).await
@@ -0,0 +1,39 @@
+use std::time::Duration;
+
+use crate::common::retry::RetryPolicy;
+use crate::subscriber::domain::infrastructure::UnitOfWork;
+use crate::subscriber::domain::infrastructure::WebhookClient;
+use crate::subscriber::domain::infrastructure::WebhookDeliveryRepository;
+use crate::subscriber::domain::infrastructure::WebhookEndpointRepository;
+use crate::subscriber::domain::service::dispatch_webhook_deliveries;
+
+// Sends queued webhook deliveries continuously, and waits for the interval only when there was
+// nothing to send
+pub async fn run<U: UnitOfWork>(
+    unit_of_work: U,
+    webhook_endpoint_repository: impl WebhookEndpointRepository<Transaction = U::Transaction>,
+    webhook_delivery_repository: impl WebhookDeliveryRepository<Transaction = U::Transaction>,
+    webhook_client: impl WebhookClient,
+    retry_policy: RetryPolicy,
+    batch_size: i64,
+    interval: Duration,
+) {
+    loop {
+        match dispatch_webhook_deliveries(
+            &unit_of_work,
+            &webhook_endpoint_repository,
+            &webhook_delivery_repository,
+            &webhook_client,
+            &retry_policy,
+            batch_size,
+        )
+        .await
+        {
+            Ok(count) if count > 0 => continue,
+            Ok(_) => {}
+            Err(error) => tracing::error!("Failed to dispatch webhook deliveries: {:?}", error),
+        }
+
+        tokio::time::sleep(interval).await;
+    }
+}
@@ -0,0 +1,4 @@
+pub mod model;
+mod specs_for_api_key_manager;
+mod specs_for_authenticator;
+mod specs_for_user_registration;
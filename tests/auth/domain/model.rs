@@ -0,0 +1,15 @@
+use fake::faker::internet::en::Password;
+use fake::Fake;
+use secrecy::SecretString;
+use uuid::Uuid;
+
+// Usernames are unique across the shared test database, so they are prefixed to avoid collisions
+#[rstest::fixture]
+pub fn username() -> String {
+    format!("admin-{}", Uuid::now_v7())
+}
+
+#[rstest::fixture]
+pub fn password() -> SecretString {
+    SecretString::from(Password(16..32).fake::<String>())
+}
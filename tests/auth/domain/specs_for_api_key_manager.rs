@@ -0,0 +1,84 @@
+use secrecy::SecretString;
+use sqlx::Pool;
+use sqlx::Postgres;
+use uuid::Uuid;
+use zero2prod::auth::domain::error::Error;
+use zero2prod::auth::domain::model::Scope;
+use zero2prod::auth::domain::service::new_api_key_manager;
+use zero2prod::auth::domain::service::ApiKeyManager;
+use zero2prod::auth::infrastructure::repository::SqlxApiKeyRepository;
+use zero2prod::auth::infrastructure::unit_of_work::SqlxUnitOfWork;
+
+use crate::subscriber::infrastructure::repository::pool;
+
+fn api_key_manager(pool: Pool<Postgres>) -> impl ApiKeyManager {
+    new_api_key_manager(SqlxUnitOfWork::new(pool), SqlxApiKeyRepository::new())
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_verifies_minted_key_with_its_scopes(#[future(awt)] pool: Pool<Postgres>) {
+    // Arrange
+    let sut = api_key_manager(pool);
+    let (minted, key) = sut
+        .mint("billing", &[Scope::SubscribersRead, Scope::SubscribersRead])
+        .await
+        .unwrap();
+
+    // Act
+    let actual = sut.verify(&key).await.unwrap();
+
+    // Assert
+    assert_eq!(actual.id(), minted.id());
+    assert_eq!(actual.client_name(), "billing");
+    assert_eq!(actual.scopes(), &[Scope::SubscribersRead]);
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_raises_invalid_credentials_error_for_revoked_key(#[future(awt)] pool: Pool<Postgres>) {
+    // Arrange
+    let sut = api_key_manager(pool);
+    let (minted, key) = sut
+        .mint("billing", &[Scope::SubscribersRead])
+        .await
+        .unwrap();
+    sut.revoke(minted.id()).await.unwrap();
+
+    // Act
+    let actual = sut.verify(&key).await;
+
+    // Assert
+    assert!(matches!(actual, Err(Error::InvalidCredentials)));
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_raises_invalid_credentials_error_for_unknown_key(#[future(awt)] pool: Pool<Postgres>) {
+    // Arrange
+    let sut = api_key_manager(pool);
+
+    // Act
+    let actual = sut
+        .verify(&SecretString::from("z2p_not-a-minted-key"))
+        .await;
+
+    // Assert
+    assert!(matches!(actual, Err(Error::InvalidCredentials)));
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_raises_api_key_not_found_error_when_revoking_unknown_key(
+    #[future(awt)] pool: Pool<Postgres>,
+) {
+    // Arrange
+    let sut = api_key_manager(pool);
+    let id = Uuid::now_v7();
+
+    // Act
+    let actual = sut.revoke(&id).await;
+
+    // Assert
+    assert!(matches!(actual, Err(Error::ApiKeyNotFound(actual)) if actual == id));
+}
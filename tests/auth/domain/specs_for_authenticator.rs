@@ -0,0 +1,76 @@
+use secrecy::SecretString;
+use sqlx::Pool;
+use sqlx::Postgres;
+use zero2prod::auth::domain::error::Error;
+use zero2prod::auth::domain::model::Credentials;
+use zero2prod::auth::domain::service::new_authenticator;
+use zero2prod::auth::domain::service::Authenticator;
+use zero2prod::auth::infrastructure::repository::SqlxUserRepository;
+use zero2prod::auth::infrastructure::unit_of_work::SqlxUnitOfWork;
+
+use crate::auth::domain::model::password;
+use crate::auth::domain::model::username;
+use crate::auth::infrastructure::repository::save_user;
+use crate::auth::infrastructure::repository::user_repository;
+use crate::subscriber::infrastructure::repository::pool;
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_returns_user_id_for_valid_credentials(
+    #[future(awt)] pool: Pool<Postgres>,
+    user_repository: SqlxUserRepository,
+    username: String,
+    password: SecretString,
+) {
+    // Arrange
+    let user_id = save_user(&pool, &username, &password).await;
+    let sut = new_authenticator(SqlxUnitOfWork::new(pool), user_repository);
+
+    // Act
+    let actual = sut.authenticate(Credentials::new(username, password)).await;
+
+    // Assert
+    assert_eq!(actual.unwrap(), user_id);
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_raises_invalid_credentials_error_for_wrong_password(
+    #[future(awt)] pool: Pool<Postgres>,
+    user_repository: SqlxUserRepository,
+    username: String,
+    password: SecretString,
+) {
+    // Arrange
+    save_user(&pool, &username, &password).await;
+    let sut = new_authenticator(SqlxUnitOfWork::new(pool), user_repository);
+
+    // Act
+    let actual = sut
+        .authenticate(Credentials::new(
+            username,
+            SecretString::from("wrong-password"),
+        ))
+        .await;
+
+    // Assert
+    assert!(matches!(actual, Err(Error::InvalidCredentials)));
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_raises_invalid_credentials_error_for_unknown_username(
+    #[future(awt)] pool: Pool<Postgres>,
+    user_repository: SqlxUserRepository,
+    username: String,
+    password: SecretString,
+) {
+    // Arrange
+    let sut = new_authenticator(SqlxUnitOfWork::new(pool), user_repository);
+
+    // Act
+    let actual = sut.authenticate(Credentials::new(username, password)).await;
+
+    // Assert
+    assert!(matches!(actual, Err(Error::InvalidCredentials)));
+}
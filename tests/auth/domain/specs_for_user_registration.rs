@@ -0,0 +1,89 @@
+use secrecy::ExposeSecret;
+use secrecy::SecretString;
+use sqlx::Pool;
+use sqlx::Postgres;
+use zero2prod::auth::domain::error::Error;
+use zero2prod::auth::domain::service::register_user;
+use zero2prod::auth::infrastructure::repository::SqlxUserRepository;
+use zero2prod::auth::infrastructure::unit_of_work::SqlxUnitOfWork;
+
+use crate::auth::domain::model::password;
+use crate::auth::domain::model::username;
+use crate::auth::infrastructure::repository::find_password_hash_by_username;
+use crate::auth::infrastructure::repository::save_user;
+use crate::auth::infrastructure::repository::user_repository;
+use crate::subscriber::infrastructure::repository::pool;
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_stores_argon2id_hash_instead_of_password(
+    #[future(awt)] pool: Pool<Postgres>,
+    username: String,
+    password: SecretString,
+) {
+    // Act
+    save_user(&pool, &username, &password).await;
+
+    // Assert
+    let actual = find_password_hash_by_username(&pool, &username).await;
+    assert!(actual.starts_with("$argon2id$"));
+    assert!(!actual.contains(password.expose_secret()));
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_salts_each_hash_so_same_passwords_hash_differently(
+    #[future(awt)] pool: Pool<Postgres>,
+    password: SecretString,
+) {
+    // Arrange
+    let (first, second) = (username(), username());
+
+    // Act
+    save_user(&pool, &first, &password).await;
+    save_user(&pool, &second, &password).await;
+
+    // Assert
+    assert_ne!(
+        find_password_hash_by_username(&pool, &first).await,
+        find_password_hash_by_username(&pool, &second).await
+    );
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_raises_repository_operation_failed_error_if_username_is_taken(
+    #[future(awt)] pool: Pool<Postgres>,
+    user_repository: SqlxUserRepository,
+    username: String,
+    password: SecretString,
+) {
+    // Arrange
+    save_user(&pool, &username, &password).await;
+
+    // Act
+    let actual = register_user(
+        &username,
+        password,
+        SqlxUnitOfWork::new(pool),
+        user_repository,
+    )
+    .await;
+
+    // Assert
+    assert!(matches!(actual, Err(Error::RepositoryOperationFailed(_))));
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_raises_invariant_violated_error_if_username_is_blank(
+    #[future(awt)] pool: Pool<Postgres>,
+    user_repository: SqlxUserRepository,
+    password: SecretString,
+) {
+    // Act
+    let actual = register_user("  ", password, SqlxUnitOfWork::new(pool), user_repository).await;
+
+    // Assert
+    assert!(matches!(actual, Err(Error::InvariantViolated(_))));
+}
@@ -0,0 +1,2 @@
+pub mod repository;
+mod specs_for_session_store;
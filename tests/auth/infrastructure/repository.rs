@@ -0,0 +1,33 @@
+use secrecy::SecretString;
+use sqlx::Pool;
+use sqlx::Postgres;
+use uuid::Uuid;
+use zero2prod::auth::domain::service::register_user;
+use zero2prod::auth::infrastructure::repository::SqlxUserRepository;
+use zero2prod::auth::infrastructure::unit_of_work::SqlxUnitOfWork;
+
+#[rstest::fixture]
+pub fn user_repository() -> SqlxUserRepository {
+    SqlxUserRepository::new()
+}
+
+pub async fn save_user(pool: &Pool<Postgres>, username: &str, password: &SecretString) -> Uuid {
+    register_user(
+        username,
+        password.clone(),
+        SqlxUnitOfWork::new(pool.clone()),
+        user_repository(),
+    )
+    .await
+    .unwrap()
+}
+
+pub async fn find_password_hash_by_username(pool: &Pool<Postgres>, username: &str) -> String {
+    sqlx::query_scalar!(
+        "select password_hash from users where username = $1",
+        username
+    )
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
@@ -0,0 +1,130 @@
+use std::time::Duration;
+
+use secrecy::ExposeSecret;
+use sqlx::Pool;
+use sqlx::Postgres;
+use uuid::Uuid;
+use zero2prod::auth::domain::infrastructure::SessionStore;
+use zero2prod::auth::domain::model::Session;
+use zero2prod::auth::infrastructure::session_store::InMemorySessionStore;
+use zero2prod::auth::infrastructure::session_store::PostgresSessionStore;
+
+use crate::auth::domain::model::password;
+use crate::auth::domain::model::username;
+use crate::auth::infrastructure::repository::save_user;
+use crate::subscriber::infrastructure::repository::pool;
+
+const TTL: Duration = Duration::from_secs(60);
+
+// Every store has to behave the same, so each spec runs against all of them
+fn session_stores(pool: &Pool<Postgres>) -> Vec<Box<dyn SessionStore>> {
+    vec![
+        Box::new(PostgresSessionStore::new(pool.clone())),
+        Box::new(InMemorySessionStore::new()),
+    ]
+}
+
+async fn logged_in_session(pool: &Pool<Postgres>) -> Session {
+    let user_id: Uuid = save_user(pool, &username(), &password()).await;
+    Session::start(TTL).log_in(user_id, TTL)
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_loads_saved_session(#[future(awt)] pool: Pool<Postgres>) {
+    for sut in session_stores(&pool) {
+        // Arrange
+        let mut session = logged_in_session(&pool).await;
+        session.add_flash_message("Welcome back".into());
+
+        // Act
+        sut.save(&session).await.unwrap();
+        let actual = sut
+            .load(session.id().expose_secret())
+            .await
+            .unwrap()
+            .unwrap();
+
+        // Assert
+        assert_eq!(actual.user_id(), session.user_id());
+        assert_eq!(actual.csrf_token(), session.csrf_token());
+        assert_eq!(actual.flash_messages(), session.flash_messages());
+    }
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_overwrites_session_saved_again(#[future(awt)] pool: Pool<Postgres>) {
+    for sut in session_stores(&pool) {
+        // Arrange
+        let mut session = Session::start(TTL);
+        session.add_flash_message("Authentication failed".into());
+        sut.save(&session).await.unwrap();
+
+        // Act
+        session.take_flash_messages();
+        sut.save(&session).await.unwrap();
+
+        // Assert
+        let actual = sut
+            .load(session.id().expose_secret())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(actual.flash_messages().is_empty());
+    }
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_forgets_deleted_session(#[future(awt)] pool: Pool<Postgres>) {
+    for sut in session_stores(&pool) {
+        // Arrange
+        let session = logged_in_session(&pool).await;
+        sut.save(&session).await.unwrap();
+
+        // Act
+        sut.delete(session.id().expose_secret()).await.unwrap();
+
+        // Assert
+        let actual = sut.load(session.id().expose_secret()).await.unwrap();
+        assert!(actual.is_none());
+    }
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_does_not_load_expired_session(#[future(awt)] pool: Pool<Postgres>) {
+    for sut in session_stores(&pool) {
+        // Arrange
+        let session = Session::start(Duration::ZERO);
+
+        // Act
+        sut.save(&session).await.unwrap();
+
+        // Assert
+        let actual = sut.load(session.id().expose_secret()).await.unwrap();
+        assert!(actual.is_none());
+    }
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_stores_only_hash_of_session_id(#[future(awt)] pool: Pool<Postgres>) {
+    // Arrange
+    let sut = PostgresSessionStore::new(pool.clone());
+    let session = Session::start(TTL);
+
+    // Act
+    sut.save(&session).await.unwrap();
+
+    // Assert
+    let actual = sqlx::query_scalar!(
+        "select count(*) from sessions where id_hash = $1",
+        session.id().expose_secret()
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(actual, Some(0));
+}
@@ -0,0 +1,42 @@
+use std::net::Ipv4Addr;
+use std::net::SocketAddr;
+use std::net::SocketAddrV4;
+use std::sync::Arc;
+
+use axum::routing::get;
+use axum::Router;
+use sqlx::Pool;
+use sqlx::Postgres;
+use tokio::net::TcpListener;
+use zero2prod::assembly;
+use zero2prod::auth::domain::service::Authenticator;
+use zero2prod::auth::interface::extractor::AdminUser;
+
+// No production route is guarded yet, so the extractor is exercised through a minimal admin route
+pub async fn serve_admin_route(pool: Pool<Postgres>) -> SocketAddr {
+    let authenticator: Arc<dyn Authenticator> = Arc::new(assembly::assemble_authenticator(pool));
+    let router = Router::new()
+        .route(
+            "/admin",
+            get(|admin_user: AdminUser| async move { admin_user.user_id.to_string() }),
+        )
+        .with_state(authenticator);
+
+    let listener = TcpListener::bind(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 0))
+        .await
+        .unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, router).await });
+    address
+}
+
+pub async fn get_admin(
+    address: SocketAddr,
+    credentials: Option<(&str, &str)>,
+) -> reqwest::Response {
+    let mut request = reqwest::Client::new().get(format!("http://{}/admin", address));
+    if let Some((username, password)) = credentials {
+        request = request.basic_auth(username, Some(password));
+    }
+    request.send().await.unwrap()
+}
@@ -0,0 +1,52 @@
+use std::io::Write;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use tracing::subscriber::DefaultGuard;
+use tracing_bunyan_formatter::BunyanFormattingLayer;
+use tracing_bunyan_formatter::JsonStorageLayer;
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::EnvFilter;
+use tracing_subscriber::Registry;
+
+// Collects everything the production formatter would print, so that tests can inspect it
+#[derive(Clone, Default)]
+pub struct CapturedLogs(Arc<Mutex<Vec<u8>>>);
+
+impl CapturedLogs {
+    pub fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).into_owned()
+    }
+}
+
+impl Write for CapturedLogs {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a> MakeWriter<'a> for CapturedLogs {
+    type Writer = Self;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        self.clone()
+    }
+}
+
+// Subscriber is installed for the current thread only, which covers everything spawned on the
+// current-thread runtime of `#[tokio::test]`
+pub fn capture_logs() -> (DefaultGuard, CapturedLogs) {
+    let logs = CapturedLogs::default();
+    let subscriber = Registry::default()
+        .with(EnvFilter::new("trace"))
+        .with(JsonStorageLayer)
+        .with(BunyanFormattingLayer::new("zero2prod".into(), logs.clone()));
+
+    (tracing::subscriber::set_default(subscriber), logs)
+}
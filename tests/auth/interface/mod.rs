@@ -0,0 +1,4 @@
+pub mod admin_route;
+pub mod logs;
+mod specs_for_admin_user_extractor;
+mod specs_for_credential_redaction;
@@ -0,0 +1,84 @@
+use reqwest::header;
+use reqwest::StatusCode;
+use secrecy::ExposeSecret;
+use secrecy::SecretString;
+use sqlx::Pool;
+use sqlx::Postgres;
+
+use crate::auth::domain::model::password;
+use crate::auth::domain::model::username;
+use crate::auth::infrastructure::repository::save_user;
+use crate::auth::interface::admin_route::get_admin;
+use crate::auth::interface::admin_route::serve_admin_route;
+use crate::subscriber::infrastructure::repository::pool;
+
+#[rstest::rstest]
+#[tokio::test]
+async fn admin_route_accepts_valid_credentials(
+    #[future(awt)] pool: Pool<Postgres>,
+    username: String,
+    password: SecretString,
+) {
+    // Arrange
+    let user_id = save_user(&pool, &username, &password).await;
+    let address = serve_admin_route(pool).await;
+
+    // Act
+    let response = get_admin(address, Some((&username, password.expose_secret()))).await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.text().await.unwrap(), user_id.to_string());
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn admin_route_rejects_request_without_credentials(#[future(awt)] pool: Pool<Postgres>) {
+    // Arrange
+    let address = serve_admin_route(pool).await;
+
+    // Act
+    let response = get_admin(address, None).await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(
+        response.headers()[header::WWW_AUTHENTICATE],
+        r#"Basic realm="admin", charset="UTF-8""#
+    );
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn admin_route_rejects_wrong_password(
+    #[future(awt)] pool: Pool<Postgres>,
+    username: String,
+    password: SecretString,
+) {
+    // Arrange
+    save_user(&pool, &username, &password).await;
+    let address = serve_admin_route(pool).await;
+
+    // Act
+    let response = get_admin(address, Some((&username, "wrong-password"))).await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn admin_route_rejects_unknown_username(
+    #[future(awt)] pool: Pool<Postgres>,
+    username: String,
+    password: SecretString,
+) {
+    // Arrange
+    let address = serve_admin_route(pool).await;
+
+    // Act
+    let response = get_admin(address, Some((&username, password.expose_secret()))).await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
@@ -0,0 +1,42 @@
+use secrecy::ExposeSecret;
+use secrecy::SecretString;
+use sqlx::Pool;
+use sqlx::Postgres;
+
+use crate::auth::domain::model::password;
+use crate::auth::domain::model::username;
+use crate::auth::infrastructure::repository::find_password_hash_by_username;
+use crate::auth::infrastructure::repository::save_user;
+use crate::auth::interface::admin_route::get_admin;
+use crate::auth::interface::admin_route::serve_admin_route;
+use crate::auth::interface::logs::capture_logs;
+use crate::subscriber::infrastructure::repository::pool;
+
+#[rstest::rstest]
+#[tokio::test]
+async fn registration_and_authentication_never_log_password_or_its_hash(
+    #[future(awt)] pool: Pool<Postgres>,
+    username: String,
+    password: SecretString,
+) {
+    // Arrange
+    let (_guard, logs) = capture_logs();
+    let address = serve_admin_route(pool.clone()).await;
+
+    // Act
+    save_user(&pool, &username, &password).await;
+    get_admin(address, Some((&username, password.expose_secret()))).await;
+    get_admin(address, Some((&username, "wrong-password"))).await;
+
+    // Assert
+    let logs = logs.contents();
+    let password_hash = find_password_hash_by_username(&pool, &username).await;
+    assert!(
+        logs.contains("AUTHENTICATING USER"),
+        "spans must be captured"
+    );
+    assert!(logs.contains(&username));
+    assert!(!logs.contains(password.expose_secret()));
+    assert!(!logs.contains("wrong-password"));
+    assert!(!logs.contains(&password_hash));
+}
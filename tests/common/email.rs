@@ -0,0 +1,10 @@
+use zero2prod::common::email::EmailMessage;
+
+#[rstest::fixture]
+pub fn email_message() -> EmailMessage {
+    EmailMessage::new(
+        "hello!".into(),
+        "<p>click this link</p>".into(),
+        "click this link".into(),
+    )
+}
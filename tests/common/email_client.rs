@@ -0,0 +1,115 @@
+use reqwest::StatusCode;
+use secrecy::SecretString;
+use wiremock::matchers::method;
+use wiremock::matchers::path;
+use wiremock::Mock;
+use wiremock::MockServer;
+use wiremock::ResponseTemplate;
+use zero2prod::assembly::assemble_smtp_transport;
+use zero2prod::common::email_client::PostmarkEmailClient;
+use zero2prod::common::email_client::SmtpEmailClient;
+use zero2prod::configuration::get_configuration;
+use zero2prod::configuration::Environment;
+use zero2prod::configuration::SmtpConfiguration;
+use zero2prod::configuration::SmtpTls;
+
+use crate::common::smtp_server::SmtpServerStandIn;
+
+#[rstest::fixture]
+pub async fn email_server_and_client(
+    #[future(awt)]
+    #[from(email_server)]
+    server: MockServer,
+) -> (MockServer, PostmarkEmailClient) {
+    let client = postmark_email_client(&server);
+
+    (server, client)
+}
+
+#[rstest::fixture]
+pub async fn faulty_email_server_and_client(
+    #[future(awt)]
+    #[from(email_server)]
+    #[with(StatusCode::INTERNAL_SERVER_ERROR)]
+    server: MockServer,
+) -> (MockServer, PostmarkEmailClient) {
+    let client = postmark_email_client(&server);
+
+    (server, client)
+}
+
+#[rstest::fixture]
+pub async fn postmark_server_and_client(
+    #[default(ResponseTemplate::new(StatusCode::OK))] response: ResponseTemplate,
+) -> (MockServer, PostmarkEmailClient) {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/email"))
+        .respond_with(response)
+        .mount(&server)
+        .await;
+
+    let client = postmark_email_client(&server);
+
+    (server, client)
+}
+
+fn postmark_email_client(server: &MockServer) -> PostmarkEmailClient {
+    let configuration = get_configuration(Environment::Test).unwrap();
+
+    PostmarkEmailClient::new(
+        reqwest::Client::new(),
+        server.uri(),
+        configuration.subscriber.email.client.sender,
+        SecretString::from("SECRET_TOKEN"),
+        configuration.subscriber.email.client.timeout,
+    )
+}
+
+#[rstest::fixture]
+pub async fn smtp_server_and_client(
+    #[default(false)] reject_recipients: bool,
+    #[default(SmtpTls::None)] tls: SmtpTls,
+) -> (SmtpServerStandIn, SmtpEmailClient) {
+    let configuration = get_configuration(Environment::Test).unwrap();
+
+    let server = SmtpServerStandIn::start(reject_recipients).await;
+    let smtp_configuration = SmtpConfiguration {
+        host: server.host(),
+        port: server.port(),
+        username: "newsletter".into(),
+        password: SecretString::from("SECRET_SMTP_PASSWORD"),
+        tls,
+    };
+
+    let client = SmtpEmailClient::new(
+        assemble_smtp_transport(
+            &smtp_configuration,
+            configuration.subscriber.email.client.timeout,
+        ),
+        configuration.subscriber.email.client.sender,
+    );
+
+    (server, client)
+}
+
+#[rstest::fixture]
+pub async fn email_server(
+    #[default(StatusCode::OK)] response_status_code: StatusCode,
+) -> MockServer {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/email"))
+        .respond_with(ResponseTemplate::new(response_status_code))
+        .mount(&server)
+        .await;
+
+    server
+}
+
+pub async fn extract_first_received_request(
+    email_server: wiremock::MockServer,
+) -> wiremock::Request {
+    email_server.received_requests().await.unwrap()[0].clone()
+}
@@ -0,0 +1,7 @@
+pub mod email;
+pub mod email_client;
+pub mod smtp_server;
+mod specs_for_file_email_client;
+mod specs_for_in_memory_email_client;
+mod specs_for_postmark_email_client;
+mod specs_for_smtp_email_client;
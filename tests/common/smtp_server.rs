@@ -0,0 +1,130 @@
+use std::net::Ipv4Addr;
+use std::net::SocketAddr;
+use std::net::SocketAddrV4;
+use std::sync::Arc;
+
+use base64::prelude::BASE64_STANDARD;
+use base64::Engine;
+use tokio::io::AsyncBufReadExt;
+use tokio::io::AsyncWriteExt;
+use tokio::io::BufReader;
+use tokio::net::TcpListener;
+use tokio::net::TcpStream;
+use tokio::sync::RwLock;
+
+// Mail transaction accepted by the stand-in, kept in the order the client sent them
+#[derive(Clone, Debug, Default)]
+pub struct SmtpTransaction {
+    pub credentials: Option<(String, String)>,
+    pub sender: String,
+    pub recipients: Vec<String>,
+    pub data: String,
+}
+
+// Minimal in-process SMTP server that accepts AUTH PLAIN and records what it receives.
+// It never offers STARTTLS, so clients requiring TLS must refuse to talk to it.
+#[derive(Clone)]
+pub struct SmtpServerStandIn {
+    address: SocketAddr,
+    transactions: Arc<RwLock<Vec<SmtpTransaction>>>,
+}
+
+impl SmtpServerStandIn {
+    pub async fn start(reject_recipients: bool) -> Self {
+        let listener = TcpListener::bind(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 0))
+            .await
+            .unwrap();
+        let address = listener.local_addr().unwrap();
+        let transactions = Arc::new(RwLock::new(Vec::new()));
+
+        let server = Self {
+            address,
+            transactions,
+        };
+        let cloned = server.clone();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(cloned.clone().serve(stream, reject_recipients));
+            }
+        });
+
+        server
+    }
+
+    pub fn host(&self) -> String {
+        self.address.ip().to_string()
+    }
+
+    pub fn port(&self) -> u16 {
+        self.address.port()
+    }
+
+    pub async fn transactions(&self) -> Vec<SmtpTransaction> {
+        self.transactions.read().await.clone()
+    }
+
+    async fn serve(self, stream: TcpStream, reject_recipients: bool) {
+        let (reader, mut writer) = stream.into_split();
+        let mut lines = BufReader::new(reader).lines();
+        let mut transaction = SmtpTransaction::default();
+
+        writer.write_all(b"220 localhost ESMTP\r\n").await.unwrap();
+        while let Ok(Some(line)) = lines.next_line().await {
+            let command = line.to_ascii_uppercase();
+            let reply: &[u8] = if command.starts_with("EHLO") || command.starts_with("HELO") {
+                b"250-localhost\r\n250 AUTH PLAIN\r\n"
+            } else if command.starts_with("AUTH PLAIN ") {
+                let decoded = BASE64_STANDARD
+                    .decode(&line["AUTH PLAIN ".len()..])
+                    .unwrap();
+                let decoded = String::from_utf8(decoded).unwrap();
+                let mut parts = decoded.split('\0').skip(1);
+                transaction.credentials = Some((
+                    parts.next().unwrap_or_default().into(),
+                    parts.next().unwrap_or_default().into(),
+                ));
+                b"235 2.7.0 Authentication successful\r\n"
+            } else if command.starts_with("MAIL FROM:") {
+                transaction.sender = extract_address(&line);
+                b"250 OK\r\n"
+            } else if command.starts_with("RCPT TO:") {
+                if reject_recipients {
+                    b"550 5.1.1 Mailbox unavailable\r\n"
+                } else {
+                    transaction.recipients.push(extract_address(&line));
+                    b"250 OK\r\n"
+                }
+            } else if command == "DATA" {
+                writer
+                    .write_all(b"354 End data with <CR><LF>.<CR><LF>\r\n")
+                    .await
+                    .unwrap();
+                while let Ok(Some(line)) = lines.next_line().await {
+                    if line == "." {
+                        break;
+                    }
+                    transaction.data.push_str(&line);
+                    transaction.data.push_str("\r\n");
+                }
+                self.transactions
+                    .write()
+                    .await
+                    .push(std::mem::take(&mut transaction));
+                b"250 OK\r\n"
+            } else if command == "QUIT" {
+                writer.write_all(b"221 Bye\r\n").await.unwrap();
+                return;
+            } else {
+                b"250 OK\r\n"
+            };
+            writer.write_all(reply).await.unwrap();
+        }
+    }
+}
+
+fn extract_address(line: &str) -> String {
+    line.split_once('<')
+        .and_then(|(_, rest)| rest.split_once('>'))
+        .map(|(address, _)| address.into())
+        .unwrap_or_default()
+}
@@ -0,0 +1,72 @@
+use url::Url;
+use uuid::Uuid;
+use zero2prod::common::email::EmailClient;
+use zero2prod::common::email::EmailMessage;
+use zero2prod::common::email_client::FileEmailClient;
+use zero2prod::subscriber::domain::model::Subscriber;
+
+use crate::common::email::email_message;
+use crate::subscriber::domain::model::subscriber;
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_writes_each_email_as_eml_file_into_directory(
+    subscriber: Subscriber,
+    email_message: EmailMessage,
+) {
+    // Arrange
+    let directory = std::env::temp_dir().join(Uuid::now_v7().to_string());
+    let sut = FileEmailClient::new(directory.clone(), "test@gmail.com".into());
+
+    // Act
+    sut.send(subscriber.email(), &email_message).await.unwrap();
+    sut.send(subscriber.email(), &email_message).await.unwrap();
+
+    // Assert
+    let mut paths: Vec<_> = std::fs::read_dir(&directory)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    paths.sort();
+    assert_eq!(paths.len(), 2);
+    assert!(paths.iter().all(|path| path.extension().unwrap() == "eml"));
+
+    let actual = std::fs::read_to_string(&paths[0]).unwrap();
+    assert!(actual.contains("From: test@gmail.com"));
+    assert!(actual.contains(format!("To: {}", subscriber.email()).as_str()));
+    assert!(actual.contains("Subject: hello!"));
+    assert!(actual.contains("<p>click this link</p>"));
+
+    std::fs::remove_dir_all(directory).unwrap();
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_writes_one_click_unsubscribe_headers_if_message_has_unsubscribe_url(
+    subscriber: Subscriber,
+    email_message: EmailMessage,
+) {
+    // Arrange
+    let directory = std::env::temp_dir().join(Uuid::now_v7().to_string());
+    let sut = FileEmailClient::new(directory.clone(), "test@gmail.com".into());
+    let unsubscribe_url =
+        Url::parse("https://example.com/subscriptions/unsubscribe?token=abc").unwrap();
+    let email_message = email_message.with_unsubscribe_url(&unsubscribe_url);
+
+    // Act
+    sut.send(subscriber.email(), &email_message).await.unwrap();
+
+    // Assert
+    let path = std::fs::read_dir(&directory)
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap()
+        .path();
+    let actual = std::fs::read_to_string(path).unwrap();
+    assert!(actual
+        .contains("List-Unsubscribe: <https://example.com/subscriptions/unsubscribe?token=abc>"));
+    assert!(actual.contains("List-Unsubscribe-Post: List-Unsubscribe=One-Click"));
+
+    std::fs::remove_dir_all(directory).unwrap();
+}
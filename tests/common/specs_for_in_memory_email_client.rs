@@ -0,0 +1,30 @@
+use zero2prod::common::email::EmailClient;
+use zero2prod::common::email::EmailMessage;
+use zero2prod::common::email_client::InMemoryEmailClient;
+use zero2prod::common::email_client::InMemoryMailbox;
+use zero2prod::subscriber::domain::model::Subscriber;
+
+use crate::common::email::email_message;
+use crate::subscriber::domain::model::subscriber;
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_captures_emails_into_shared_mailbox(
+    subscriber: Subscriber,
+    email_message: EmailMessage,
+) {
+    // Arrange
+    let mailbox = InMemoryMailbox::new();
+    let sut = InMemoryEmailClient::new(mailbox.clone(), "test@gmail.com".into());
+
+    // Act
+    sut.send(subscriber.email(), &email_message).await.unwrap();
+
+    // Assert
+    let actual = mailbox.emails_to(subscriber.email()).await;
+    assert_eq!(actual.len(), 1);
+    assert_eq!(actual[0].sender, "test@gmail.com");
+    assert_eq!(actual[0].recipient, subscriber.email());
+    assert_eq!(actual[0].message, email_message);
+    assert!(mailbox.emails_to("unknown@gmail.com").await.is_empty());
+}
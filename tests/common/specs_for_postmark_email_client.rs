@@ -0,0 +1,144 @@
+use reqwest::StatusCode;
+use url::Url;
+use wiremock::MockServer;
+use wiremock::ResponseTemplate;
+use zero2prod::common::email::EmailClient;
+use zero2prod::common::email::EmailMessage;
+use zero2prod::common::email::Error;
+use zero2prod::common::email_client::PostmarkEmailClient;
+use zero2prod::common::email_client::PostmarkError;
+use zero2prod::subscriber::domain::model::Subscriber;
+
+use crate::common::email::email_message;
+use crate::common::email_client::extract_first_received_request;
+use crate::common::email_client::postmark_server_and_client;
+use crate::subscriber::domain::model::subscriber;
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_sends_html_and_text_bodies_with_server_token(
+    #[future(awt)] postmark_server_and_client: (MockServer, PostmarkEmailClient),
+    subscriber: Subscriber,
+    email_message: EmailMessage,
+) {
+    // Arrange
+    let (server, sut) = postmark_server_and_client;
+
+    // Act
+    let actual = sut.send(subscriber.email(), &email_message).await;
+
+    // Assert
+    assert!(actual.is_ok());
+
+    let request = extract_first_received_request(server).await;
+    let token = request
+        .headers
+        .get("X-Postmark-Server-Token")
+        .unwrap()
+        .to_str()
+        .unwrap();
+    assert_eq!(token, "SECRET_TOKEN");
+
+    let body: serde_json::Value = request.body_json().unwrap();
+    let expected = serde_json::json!({
+        "From": "test@gmail.com",
+        "To": subscriber.email(),
+        "Subject": "hello!",
+        "HtmlBody": "<p>click this link</p>",
+        "TextBody": "click this link",
+    });
+    assert_eq!(body, expected);
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_sends_one_click_unsubscribe_headers_if_message_has_unsubscribe_url(
+    #[future(awt)] postmark_server_and_client: (MockServer, PostmarkEmailClient),
+    subscriber: Subscriber,
+    email_message: EmailMessage,
+) {
+    // Arrange
+    let (server, sut) = postmark_server_and_client;
+    let unsubscribe_url =
+        Url::parse("https://example.com/subscriptions/unsubscribe?token=abc").unwrap();
+    let email_message = email_message.with_unsubscribe_url(&unsubscribe_url);
+
+    // Act
+    sut.send(subscriber.email(), &email_message).await.unwrap();
+
+    // Assert
+    let request = extract_first_received_request(server).await;
+    let body: serde_json::Value = request.body_json().unwrap();
+    let expected = serde_json::json!([
+        {
+            "Name": "List-Unsubscribe",
+            "Value": "<https://example.com/subscriptions/unsubscribe?token=abc>",
+        },
+        {
+            "Name": "List-Unsubscribe-Post",
+            "Value": "List-Unsubscribe=One-Click",
+        },
+    ]);
+    assert_eq!(body["Headers"], expected);
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_parses_error_code_from_rejected_request(
+    #[future(awt)]
+    #[with(ResponseTemplate::new(StatusCode::UNPROCESSABLE_ENTITY).set_body_json(
+        serde_json::json!({
+            "ErrorCode": 406,
+            "Message": "You tried to send to a recipient that has been marked as inactive.",
+        })
+    ))]
+    postmark_server_and_client: (MockServer, PostmarkEmailClient),
+    subscriber: Subscriber,
+    email_message: EmailMessage,
+) {
+    // Arrange
+    let (_server, sut) = postmark_server_and_client;
+
+    // Act
+    let actual = sut
+        .send(subscriber.email(), &email_message)
+        .await
+        .unwrap_err();
+
+    // Assert
+    let Error::Rejected(error) = actual else {
+        panic!("Unexpected error: {:?}", actual);
+    };
+    let cause = error.downcast_ref::<PostmarkError>().unwrap();
+    assert_eq!(cause.status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(cause.error_code, 406);
+    assert_eq!(
+        cause.message,
+        "You tried to send to a recipient that has been marked as inactive."
+    );
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_raises_failed_email_operation_error_if_error_response_is_unreadable(
+    #[future(awt)]
+    #[with(ResponseTemplate::new(StatusCode::INTERNAL_SERVER_ERROR))]
+    postmark_server_and_client: (MockServer, PostmarkEmailClient),
+    subscriber: Subscriber,
+    email_message: EmailMessage,
+) {
+    // Arrange
+    let (_server, sut) = postmark_server_and_client;
+
+    // Act
+    let actual = sut
+        .send(subscriber.email(), &email_message)
+        .await
+        .unwrap_err();
+
+    // Assert
+    let Error::OperationFailed(error) = actual else {
+        panic!("Unexpected error: {:?}", actual);
+    };
+    assert!(error.downcast_ref::<PostmarkError>().is_none());
+}
@@ -0,0 +1,89 @@
+use zero2prod::common::email::EmailClient;
+use zero2prod::common::email::EmailMessage;
+use zero2prod::common::email::Error;
+use zero2prod::common::email_client::SmtpEmailClient;
+use zero2prod::configuration::SmtpTls;
+use zero2prod::subscriber::domain::model::Subscriber;
+
+use crate::common::email::email_message;
+use crate::common::email_client::smtp_server_and_client;
+use crate::common::smtp_server::SmtpServerStandIn;
+use crate::subscriber::domain::model::subscriber;
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_authenticates_and_delivers_email_to_smtp_server(
+    #[future(awt)] smtp_server_and_client: (SmtpServerStandIn, SmtpEmailClient),
+    subscriber: Subscriber,
+    email_message: EmailMessage,
+) {
+    // Arrange
+    let (server, sut) = smtp_server_and_client;
+
+    // Act
+    let actual = sut.send(subscriber.email(), &email_message).await;
+
+    // Assert
+    assert!(actual.is_ok());
+
+    let transactions = server.transactions().await;
+    assert_eq!(transactions.len(), 1);
+    let transaction = &transactions[0];
+    assert_eq!(
+        transaction.credentials,
+        Some(("newsletter".into(), "SECRET_SMTP_PASSWORD".into()))
+    );
+    assert_eq!(transaction.sender, "test@gmail.com");
+    assert_eq!(transaction.recipients, vec![subscriber.email().to_owned()]);
+    assert!(transaction.data.contains("Subject: hello!"));
+    assert!(transaction.data.contains("Content-Type: text/plain"));
+    assert!(transaction.data.contains("Content-Type: text/html"));
+    assert!(transaction.data.contains("<p>click this link</p>"));
+    assert!(transaction.data.contains("click this link"));
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_raises_email_rejected_error_if_smtp_server_rejects_recipient(
+    #[future(awt)]
+    #[with(true)]
+    smtp_server_and_client: (SmtpServerStandIn, SmtpEmailClient),
+    subscriber: Subscriber,
+    email_message: EmailMessage,
+) {
+    // Arrange
+    let (server, sut) = smtp_server_and_client;
+
+    // Act
+    let actual = sut
+        .send(subscriber.email(), &email_message)
+        .await
+        .unwrap_err();
+
+    // Assert
+    assert!(matches!(actual, Error::Rejected(_)));
+    assert!(server.transactions().await.is_empty());
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_refuses_to_authenticate_over_plaintext_if_starttls_is_required(
+    #[future(awt)]
+    #[with(false, SmtpTls::StartTls)]
+    smtp_server_and_client: (SmtpServerStandIn, SmtpEmailClient),
+    subscriber: Subscriber,
+    email_message: EmailMessage,
+) {
+    // Arrange
+    let (server, sut) = smtp_server_and_client;
+
+    // Act
+    let actual = sut
+        .send(subscriber.email(), &email_message)
+        .await
+        .unwrap_err();
+
+    // Assert
+    assert!(matches!(actual, Error::OperationFailed(_)));
+    assert!(server.transactions().await.is_empty());
+}
@@ -1,4 +1,12 @@
+mod specs_for_admin_api_keys_api;
+mod specs_for_admin_dashboard_pages;
+mod specs_for_admin_login;
+mod specs_for_admin_subscribers_api;
+mod specs_for_admin_webhooks_api;
 mod specs_for_get_healthz_api;
 mod specs_for_get_subscriptions_confirm_api;
+mod specs_for_get_subscriptions_unsubscribe_api;
+mod specs_for_idempotency_key;
+mod specs_for_post_newsletters_api;
 mod specs_for_post_subscriptions_api;
 pub mod system;
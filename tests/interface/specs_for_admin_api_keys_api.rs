@@ -0,0 +1,201 @@
+use reqwest::StatusCode;
+use secrecy::ExposeSecret;
+use secrecy::SecretString;
+
+use crate::auth::domain::model::password;
+use crate::auth::domain::model::username;
+use crate::auth::infrastructure::repository::save_user;
+use crate::interface::system::system;
+use crate::interface::system::System;
+
+fn publishable_issue() -> serde_json::Value {
+    serde_json::json!({
+        "title": "Newsletter",
+        "content": { "html": "<p>Hi</p>", "text": "Hi" },
+    })
+}
+
+async fn mint(system: &System, credentials: (&str, &str), scopes: &[&str]) -> serde_json::Value {
+    system
+        .requestor
+        .post_admin_api_keys(
+            serde_json::json!({ "client_name": "billing", "scopes": scopes }),
+            credentials,
+        )
+        .await
+        .json()
+        .await
+        .unwrap()
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn admin_mints_api_key_usable_by_machine_client(
+    #[future(awt)] system: System,
+    username: String,
+    password: SecretString,
+) {
+    // Arrange
+    save_user(
+        &system.dependencies.subscriber_database_pool,
+        &username,
+        &password,
+    )
+    .await;
+    let credentials = (username.as_str(), password.expose_secret());
+
+    // Act
+    let response = system
+        .requestor
+        .post_admin_api_keys(
+            serde_json::json!({ "client_name": "billing", "scopes": ["newsletters:publish"] }),
+            credentials,
+        )
+        .await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["client_name"], "billing");
+    assert_eq!(body["scopes"], serde_json::json!(["newsletters:publish"]));
+
+    let key = body["key"].as_str().unwrap();
+    assert!(key.starts_with(body["visible_prefix"].as_str().unwrap()));
+    let published = system
+        .requestor
+        .post_newsletters_with_api_key(publishable_issue(), Some(key))
+        .await;
+    assert_eq!(published.status(), StatusCode::OK);
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn admin_lists_api_keys_without_revealing_them(
+    #[future(awt)] system: System,
+    username: String,
+    password: SecretString,
+) {
+    // Arrange
+    save_user(
+        &system.dependencies.subscriber_database_pool,
+        &username,
+        &password,
+    )
+    .await;
+    let credentials = (username.as_str(), password.expose_secret());
+    let minted = mint(&system, credentials, &["subscribers:read"]).await;
+
+    // Act
+    let response = system.requestor.get_admin_api_keys(credentials).await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.text().await.unwrap();
+    assert!(body.contains(minted["id"].as_str().unwrap()));
+    assert!(!body.contains(minted["key"].as_str().unwrap()));
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn revoked_api_key_is_rejected(
+    #[future(awt)] system: System,
+    username: String,
+    password: SecretString,
+) {
+    // Arrange
+    save_user(
+        &system.dependencies.subscriber_database_pool,
+        &username,
+        &password,
+    )
+    .await;
+    let credentials = (username.as_str(), password.expose_secret());
+    let minted = mint(&system, credentials, &["newsletters:publish"]).await;
+
+    // Act
+    let response = system
+        .requestor
+        .delete_admin_api_keys(minted["id"].as_str().unwrap(), credentials)
+        .await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    let published = system
+        .requestor
+        .post_newsletters_with_api_key(publishable_issue(), minted["key"].as_str())
+        .await;
+    assert_eq!(published.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn revoking_unknown_api_key_responds_not_found(
+    #[future(awt)] system: System,
+    username: String,
+    password: SecretString,
+) {
+    // Arrange
+    save_user(
+        &system.dependencies.subscriber_database_pool,
+        &username,
+        &password,
+    )
+    .await;
+
+    // Act
+    let response = system
+        .requestor
+        .delete_admin_api_keys(
+            &uuid::Uuid::now_v7().to_string(),
+            (&username, password.expose_secret()),
+        )
+        .await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn minting_api_key_with_unknown_scope_responds_bad_request(
+    #[future(awt)] system: System,
+    username: String,
+    password: SecretString,
+) {
+    // Arrange
+    save_user(
+        &system.dependencies.subscriber_database_pool,
+        &username,
+        &password,
+    )
+    .await;
+
+    // Act
+    let response = system
+        .requestor
+        .post_admin_api_keys(
+            serde_json::json!({ "client_name": "billing", "scopes": ["subscribers:delete"] }),
+            (&username, password.expose_secret()),
+        )
+        .await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn api_key_endpoints_require_admin_credentials(
+    #[future(awt)] system: System,
+    username: String,
+    password: SecretString,
+) {
+    // Act
+    let response = system
+        .requestor
+        .get_admin_api_keys((&username, password.expose_secret()))
+        .await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
@@ -0,0 +1,143 @@
+use reqwest::header;
+use reqwest::StatusCode;
+use secrecy::SecretString;
+use zero2prod::subscriber::domain::model::Email;
+use zero2prod::subscriber::domain::model::Name;
+
+use crate::auth::domain::model::password;
+use crate::auth::domain::model::username;
+use crate::auth::infrastructure::repository::save_user;
+use crate::interface::system::csrf_token;
+use crate::interface::system::log_in;
+use crate::interface::system::system;
+use crate::interface::system::System;
+use crate::subscriber::domain::model::email;
+use crate::subscriber::domain::model::name;
+
+async fn count_newsletter_issues(system: &System) -> Option<i64> {
+    sqlx::query_scalar!("select count(*) from newsletter_issues")
+        .fetch_one(&system.dependencies.subscriber_database_pool)
+        .await
+        .unwrap()
+}
+
+async fn log_in_as_operator(system: &System, username: &str, password: &SecretString) -> String {
+    save_user(
+        &system.dependencies.subscriber_database_pool,
+        username,
+        password,
+    )
+    .await;
+    log_in(system, username, password).await
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn operator_finds_subscriber_on_subscribers_page(
+    #[future(awt)] system: System,
+    username: String,
+    password: SecretString,
+    name: Name,
+    email: Email,
+) {
+    // Arrange
+    let session_id = log_in_as_operator(&system, &username, &password).await;
+    system
+        .requestor
+        .post_subscriptions(Some(name.as_ref().into()), Some(email.as_ref().into()))
+        .await;
+
+    // Act
+    let response = system
+        .requestor
+        .get_admin_dashboard_subscribers("status=Pending&email=", Some(&session_id))
+        .await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+    let page = response.text().await.unwrap();
+    assert!(page.contains(email.as_ref()));
+}
+
+#[rstest::rstest]
+#[case("/admin/dashboard/subscribers")]
+#[case("/admin/dashboard/newsletters")]
+#[tokio::test]
+async fn management_pages_redirect_anonymous_browser_to_login_form(
+    #[future(awt)] system: System,
+    #[case] path: &str,
+) {
+    // Act
+    let response = match path {
+        "/admin/dashboard/subscribers" => {
+            system
+                .requestor
+                .get_admin_dashboard_subscribers("", None)
+                .await
+        }
+        _ => system.requestor.get_admin_dashboard_newsletters(None).await,
+    };
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+    assert_eq!(response.headers()[header::LOCATION], "/login");
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn operator_publishes_newsletter_issue_from_form(
+    #[future(awt)] system: System,
+    username: String,
+    password: SecretString,
+) {
+    // Arrange
+    let session_id = log_in_as_operator(&system, &username, &password).await;
+    let form = system
+        .requestor
+        .get_admin_dashboard_newsletters(Some(&session_id))
+        .await;
+    let csrf_token = csrf_token(&form.text().await.unwrap());
+
+    // Act
+    let response = system
+        .requestor
+        .post_admin_dashboard_newsletters("Newsletter", &csrf_token, Some(&session_id))
+        .await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+    assert_eq!(
+        response.headers()[header::LOCATION],
+        "/admin/dashboard/newsletters"
+    );
+    assert_eq!(count_newsletter_issues(&system).await, Some(1));
+    let page = system
+        .requestor
+        .get_admin_dashboard_newsletters(Some(&session_id))
+        .await
+        .text()
+        .await
+        .unwrap();
+    assert!(page.contains("The newsletter issue has been published."));
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn publishing_without_matching_csrf_token_is_forbidden(
+    #[future(awt)] system: System,
+    username: String,
+    password: SecretString,
+) {
+    // Arrange
+    let session_id = log_in_as_operator(&system, &username, &password).await;
+
+    // Act
+    let response = system
+        .requestor
+        .post_admin_dashboard_newsletters("Newsletter", "forged", Some(&session_id))
+        .await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    assert_eq!(count_newsletter_issues(&system).await, Some(0));
+}
@@ -0,0 +1,239 @@
+use reqwest::header;
+use reqwest::StatusCode;
+use secrecy::ExposeSecret;
+use secrecy::SecretString;
+
+use crate::auth::domain::model::password;
+use crate::auth::domain::model::username;
+use crate::auth::infrastructure::repository::save_user;
+use crate::interface::system::csrf_token;
+use crate::interface::system::log_in;
+use crate::interface::system::open_login_form;
+use crate::interface::system::session_id;
+use crate::interface::system::system;
+use crate::interface::system::System;
+
+#[rstest::rstest]
+#[tokio::test]
+async fn login_form_starts_session_with_http_only_cookie(#[future(awt)] system: System) {
+    // Act
+    let response = system.requestor.get_login(None).await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+    let cookie = response.headers()[header::SET_COOKIE].to_str().unwrap();
+    assert!(cookie.contains("HttpOnly"));
+    assert!(cookie.contains("SameSite=Lax"));
+    assert!(!csrf_token(&response.text().await.unwrap()).is_empty());
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn login_with_valid_credentials_rotates_session_and_opens_dashboard(
+    #[future(awt)] system: System,
+    username: String,
+    password: SecretString,
+) {
+    // Arrange
+    save_user(
+        &system.dependencies.subscriber_database_pool,
+        &username,
+        &password,
+    )
+    .await;
+    let (anonymous_session_id, csrf_token) = open_login_form(&system).await;
+
+    // Act
+    let response = system
+        .requestor
+        .post_login(
+            &username,
+            password.expose_secret(),
+            &csrf_token,
+            Some(&anonymous_session_id),
+        )
+        .await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+    assert_eq!(response.headers()[header::LOCATION], "/admin/dashboard");
+    let session_id = session_id(&response).unwrap();
+    assert_ne!(session_id, anonymous_session_id);
+
+    let dashboard = system
+        .requestor
+        .get_admin_dashboard(Some(&session_id))
+        .await;
+    assert_eq!(dashboard.status(), StatusCode::OK);
+    let anonymous_dashboard = system
+        .requestor
+        .get_admin_dashboard(Some(&anonymous_session_id))
+        .await;
+    assert_eq!(anonymous_dashboard.status(), StatusCode::SEE_OTHER);
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn login_with_wrong_password_shows_flash_message_once(
+    #[future(awt)] system: System,
+    username: String,
+    password: SecretString,
+) {
+    // Arrange
+    save_user(
+        &system.dependencies.subscriber_database_pool,
+        &username,
+        &password,
+    )
+    .await;
+    let (session_id, csrf_token) = open_login_form(&system).await;
+
+    // Act
+    let response = system
+        .requestor
+        .post_login(&username, "wrong-password", &csrf_token, Some(&session_id))
+        .await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+    assert_eq!(response.headers()[header::LOCATION], "/login");
+
+    let first = system.requestor.get_login(Some(&session_id)).await;
+    assert!(first
+        .text()
+        .await
+        .unwrap()
+        .contains("Authentication failed"));
+    let second = system.requestor.get_login(Some(&session_id)).await;
+    assert!(!second
+        .text()
+        .await
+        .unwrap()
+        .contains("Authentication failed"));
+}
+
+#[rstest::rstest]
+#[case(Some("forged-token"))]
+#[case(None)]
+#[tokio::test]
+async fn login_without_matching_csrf_token_is_forbidden(
+    #[future(awt)] system: System,
+    username: String,
+    password: SecretString,
+    #[case] forged_csrf_token: Option<&str>,
+) {
+    // Arrange
+    save_user(
+        &system.dependencies.subscriber_database_pool,
+        &username,
+        &password,
+    )
+    .await;
+    let (session_id, _) = open_login_form(&system).await;
+
+    // Act
+    let response = system
+        .requestor
+        .post_login(
+            &username,
+            password.expose_secret(),
+            forged_csrf_token.unwrap_or_default(),
+            Some(&session_id),
+        )
+        .await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    assert!(self::session_id(&response).is_none());
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn dashboard_redirects_anonymous_browser_to_login_form(#[future(awt)] system: System) {
+    // Act
+    let response = system.requestor.get_admin_dashboard(None).await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+    assert_eq!(response.headers()[header::LOCATION], "/login");
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn logout_ends_session_and_says_goodbye(
+    #[future(awt)] system: System,
+    username: String,
+    password: SecretString,
+) {
+    // Arrange
+    save_user(
+        &system.dependencies.subscriber_database_pool,
+        &username,
+        &password,
+    )
+    .await;
+    let session_id = log_in(&system, &username, &password).await;
+    let dashboard = system
+        .requestor
+        .get_admin_dashboard(Some(&session_id))
+        .await;
+    let csrf_token = csrf_token(&dashboard.text().await.unwrap());
+
+    // Act
+    let response = system
+        .requestor
+        .post_logout(&csrf_token, Some(&session_id))
+        .await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+    assert_eq!(response.headers()[header::LOCATION], "/login");
+
+    let dashboard = system
+        .requestor
+        .get_admin_dashboard(Some(&session_id))
+        .await;
+    assert_eq!(dashboard.status(), StatusCode::SEE_OTHER);
+
+    let anonymous_session_id = self::session_id(&response).unwrap();
+    let login = system
+        .requestor
+        .get_login(Some(&anonymous_session_id))
+        .await;
+    assert!(login
+        .text()
+        .await
+        .unwrap()
+        .contains("You have successfully logged out."));
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn logout_without_matching_csrf_token_is_forbidden(
+    #[future(awt)] system: System,
+    username: String,
+    password: SecretString,
+) {
+    // Arrange
+    save_user(
+        &system.dependencies.subscriber_database_pool,
+        &username,
+        &password,
+    )
+    .await;
+    let session_id = log_in(&system, &username, &password).await;
+
+    // Act
+    let response = system
+        .requestor
+        .post_logout("forged-token", Some(&session_id))
+        .await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let dashboard = system
+        .requestor
+        .get_admin_dashboard(Some(&session_id))
+        .await;
+    assert_eq!(dashboard.status(), StatusCode::OK);
+}
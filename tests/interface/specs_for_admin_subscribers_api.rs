@@ -0,0 +1,308 @@
+use std::time::Duration;
+
+use reqwest::StatusCode;
+use secrecy::ExposeSecret;
+use secrecy::SecretString;
+use uuid::Uuid;
+use zero2prod::assembly;
+use zero2prod::auth::domain::model::Scope;
+use zero2prod::auth::domain::service::ApiKeyManager;
+use zero2prod::subscriber::domain::model::Email;
+use zero2prod::subscriber::domain::model::Name;
+
+use crate::auth::domain::model::password;
+use crate::auth::domain::model::username;
+use crate::auth::infrastructure::repository::save_user;
+use crate::interface::system::system;
+use crate::interface::system::System;
+use crate::subscriber::domain::model::email;
+use crate::subscriber::domain::model::name;
+
+async fn subscribe(system: &System, name: &Name, email: &Email) -> serde_json::Value {
+    system
+        .requestor
+        .post_subscriptions(Some(name.as_ref().into()), Some(email.as_ref().into()))
+        .await;
+    let body: serde_json::Value = system
+        .requestor
+        .get_admin_subscribers(&[("email", email.as_ref())])
+        .await
+        .json()
+        .await
+        .unwrap();
+    body["subscribers"][0].clone()
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_lists_subscribers_matching_filter(
+    #[future(awt)] system: System,
+    name: Name,
+    email: Email,
+) {
+    // Arrange
+    subscribe(&system, &name, &email).await;
+
+    // Act
+    let response = system
+        .requestor
+        .get_admin_subscribers(&[("email", email.as_ref()), ("status", "pending")])
+        .await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["subscribers"].as_array().unwrap().len(), 1);
+    assert_eq!(body["subscribers"][0]["name"], name.as_ref());
+    assert_eq!(body["subscribers"][0]["email"], email.as_ref());
+    assert_eq!(body["subscribers"][0]["display_email"], email.as_ref());
+    assert_eq!(body["subscribers"][0]["status"], "Pending");
+    assert!(body["next_cursor"].is_null());
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_pages_subscribers_with_cursor(
+    #[future(awt)] system: System,
+    #[from(email)] first: Email,
+    #[from(email)] second: Email,
+) {
+    // Arrange
+    subscribe(&system, &name(), &first).await;
+    subscribe(&system, &name(), &second).await;
+
+    // Act
+    let first_page: serde_json::Value = system
+        .requestor
+        .get_admin_subscribers(&[("limit", "1")])
+        .await
+        .json()
+        .await
+        .unwrap();
+    let cursor = first_page["next_cursor"].as_str().unwrap();
+    let second_page: serde_json::Value = system
+        .requestor
+        .get_admin_subscribers(&[("limit", "1"), ("cursor", cursor)])
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    // Assert
+    assert_eq!(first_page["subscribers"][0]["email"], first.as_ref());
+    assert_eq!(second_page["subscribers"][0]["email"], second.as_ref());
+    assert!(second_page["next_cursor"].is_null());
+}
+
+#[rstest::rstest]
+#[case(&[("status", "archived")])]
+#[case(&[("subscribed_since", "yesterday")])]
+#[case(&[("limit", "0")])]
+#[tokio::test]
+async fn sut_responds_status_bad_request_if_filter_is_invalid(
+    #[future(awt)] system: System,
+    #[case] query: &[(&str, &str)],
+) {
+    // Act
+    let response = system.requestor.get_admin_subscribers(query).await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_fetches_subscriber_by_id(#[future(awt)] system: System, name: Name, email: Email) {
+    // Arrange
+    let subscriber = subscribe(&system, &name, &email).await;
+    let id = subscriber["id"].as_str().unwrap();
+
+    // Act
+    let response = system.requestor.get_admin_subscribers_by_id(id).await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body, subscriber);
+}
+
+#[rstest::rstest]
+#[case(None, "Failed to find the subscriber.")]
+#[case(Some("ko-KR,ko;q=0.9,en;q=0.8"), "구독자를 찾을 수 없습니다.")]
+#[tokio::test]
+async fn sut_responds_status_not_found_if_subscriber_does_not_exist(
+    #[future(awt)] system: System,
+    #[case] accept_language: Option<&str>,
+    #[case] expected: &str,
+) {
+    // Arrange
+    let requestor = match accept_language {
+        Some(accept_language) => system.requestor.with_accept_language(accept_language),
+        None => system.requestor,
+    };
+
+    // Act
+    let response = requestor
+        .get_admin_subscribers_by_id(&Uuid::now_v7().to_string())
+        .await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let actual: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(actual["message"], expected);
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_responds_status_forbidden_if_api_key_lacks_read_scope(#[future(awt)] system: System) {
+    // Arrange
+    let (_, key) =
+        assembly::assemble_api_key_manager(system.dependencies.subscriber_database_pool.clone())
+            .mint("publisher", &[Scope::NewslettersPublish])
+            .await
+            .unwrap();
+    let requestor = system.requestor.with_api_key(key.expose_secret());
+
+    // Act
+    let listed = requestor.get_admin_subscribers(&[]).await;
+    let fetched = requestor
+        .get_admin_subscribers_by_id(&Uuid::now_v7().to_string())
+        .await;
+
+    // Assert
+    assert_eq!(listed.status(), StatusCode::FORBIDDEN);
+    assert_eq!(fetched.status(), StatusCode::FORBIDDEN);
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_registers_subscriber_who_still_has_to_confirm(
+    #[future(awt)] system: System,
+    name: Name,
+    email: Email,
+) {
+    // Act
+    let response = system
+        .requestor
+        .post_admin_subscribers(
+            serde_json::json!({ "name": name.as_ref(), "email": email.as_ref() }),
+        )
+        .await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::ACCEPTED);
+    let body: serde_json::Value = system
+        .requestor
+        .get_admin_subscribers(&[("email", email.as_ref())])
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(body["subscribers"][0]["status"], "Pending");
+    assert!(system
+        .dependencies
+        .wait_for_subscription_email(email.as_ref(), Duration::from_secs(5))
+        .await
+        .is_some());
+}
+
+#[rstest::rstest]
+#[case(None, "Email address must be valid.")]
+#[case(Some("ko-KR,ko;q=0.9,en;q=0.8"), "올바른 이메일 주소를 입력해 주세요.")]
+#[tokio::test]
+async fn sut_responds_status_bad_request_if_attribute_is_invalid(
+    #[future(awt)] system: System,
+    name: Name,
+    #[case] accept_language: Option<&str>,
+    #[case] expected: &str,
+) {
+    // Arrange
+    let requestor = match accept_language {
+        Some(accept_language) => system.requestor.with_accept_language(accept_language),
+        None => system.requestor,
+    };
+
+    // Act
+    let response = requestor
+        .post_admin_subscribers(
+            serde_json::json!({ "name": name.as_ref(), "email": "not-an-email" }),
+        )
+        .await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let actual: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(actual["message"], expected);
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_responds_status_forbidden_if_api_key_lacks_write_scope(
+    #[future(awt)] system: System,
+    name: Name,
+    email: Email,
+) {
+    // Arrange
+    let (_, key) =
+        assembly::assemble_api_key_manager(system.dependencies.subscriber_database_pool.clone())
+            .mint("reader", &[Scope::SubscribersRead])
+            .await
+            .unwrap();
+    let requestor = system.requestor.with_api_key(key.expose_secret());
+
+    // Act
+    let response = requestor
+        .post_admin_subscribers(
+            serde_json::json!({ "name": name.as_ref(), "email": email.as_ref() }),
+        )
+        .await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_lists_subscribers_for_operator_with_basic_credentials(
+    #[future(awt)] system: System,
+    username: String,
+    password: SecretString,
+    name: Name,
+    email: Email,
+) {
+    // Arrange
+    save_user(
+        &system.dependencies.subscriber_database_pool,
+        &username,
+        &password,
+    )
+    .await;
+    subscribe(&system, &name, &email).await;
+
+    // Act
+    let response = system
+        .requestor
+        .get_admin_subscribers_with_credentials(
+            &[("email", email.as_ref())],
+            (username.as_str(), password.expose_secret()),
+        )
+        .await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["subscribers"][0]["email"], email.as_ref());
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_responds_status_unauthorized_without_credentials(#[future(awt)] system: System) {
+    // Act
+    let response = system
+        .requestor
+        .get_admin_subscribers_with_credentials(&[], ("nobody", "wrong"))
+        .await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
@@ -0,0 +1,321 @@
+use std::time::Duration;
+use std::time::Instant;
+
+use chrono::Utc;
+use reqwest::StatusCode;
+use secrecy::ExposeSecret;
+use secrecy::SecretString;
+use uuid::Uuid;
+use wiremock::matchers::method;
+use wiremock::Mock;
+use wiremock::MockServer;
+use wiremock::Request;
+use wiremock::ResponseTemplate;
+use zero2prod::subscriber::domain::model::Email;
+use zero2prod::subscriber::domain::model::Name;
+use zero2prod::subscriber::domain::model::WebhookSignature;
+
+use crate::auth::domain::model::password;
+use crate::auth::domain::model::username;
+use crate::auth::infrastructure::repository::save_user;
+use crate::interface::system::system;
+use crate::interface::system::System;
+use crate::subscriber::domain::model::email;
+use crate::subscriber::domain::model::name;
+
+async fn receiver() -> MockServer {
+    let receiver = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&receiver)
+        .await;
+    receiver
+}
+
+async fn wait_for_webhook(receiver: &MockServer, timeout: Duration) -> Option<Request> {
+    let deadline = Instant::now() + timeout;
+    while Instant::now() < deadline {
+        if let Some(request) = receiver
+            .received_requests()
+            .await
+            .unwrap()
+            .into_iter()
+            .next()
+        {
+            return Some(request);
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    None
+}
+
+async fn register(system: &System, credentials: (&str, &str), url: &str) -> serde_json::Value {
+    system
+        .requestor
+        .post_admin_webhooks(serde_json::json!({ "url": url }), credentials)
+        .await
+        .json()
+        .await
+        .unwrap()
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn registered_endpoint_receives_signed_event_of_new_subscriber(
+    #[future(awt)] system: System,
+    username: String,
+    password: SecretString,
+    name: Name,
+    email: Email,
+) {
+    // Arrange
+    save_user(
+        &system.dependencies.subscriber_database_pool,
+        &username,
+        &password,
+    )
+    .await;
+    let credentials = (username.as_str(), password.expose_secret());
+    let receiver = receiver().await;
+
+    // Act
+    let response = system
+        .requestor
+        .post_admin_webhooks(serde_json::json!({ "url": receiver.uri() }), credentials)
+        .await;
+    let registered: serde_json::Value = response.json().await.unwrap();
+    system
+        .requestor
+        .post_subscriptions(Some(name.as_ref().into()), Some(email.as_ref().into()))
+        .await;
+
+    // Assert
+    let request = wait_for_webhook(&receiver, Duration::from_secs(5))
+        .await
+        .expect("Webhook was not delivered in time");
+    let header = |name: &str| request.headers.get(name).unwrap().to_str().unwrap();
+    let payload = String::from_utf8(request.body.clone()).unwrap();
+    let verified = WebhookSignature::verify(
+        &SecretString::from(registered["secret"].as_str().unwrap()),
+        &header("Webhook-Id").parse::<Uuid>().unwrap(),
+        header("Webhook-Timestamp").parse().unwrap(),
+        &payload,
+        header("Webhook-Signature"),
+        Utc::now(),
+    );
+    assert!(verified.is_ok());
+
+    let actual: serde_json::Value = serde_json::from_str(&payload).unwrap();
+    assert_eq!(actual["type"], "subscriber.registered");
+    assert_eq!(actual["data"]["email"], email.as_ref());
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn admin_inspects_delivery_log_of_endpoint(
+    #[future(awt)] system: System,
+    username: String,
+    password: SecretString,
+    name: Name,
+    email: Email,
+) {
+    // Arrange
+    save_user(
+        &system.dependencies.subscriber_database_pool,
+        &username,
+        &password,
+    )
+    .await;
+    let credentials = (username.as_str(), password.expose_secret());
+    let receiver = receiver().await;
+    let registered = register(&system, credentials, &receiver.uri()).await;
+    system
+        .requestor
+        .post_subscriptions(Some(name.as_ref().into()), Some(email.as_ref().into()))
+        .await;
+    wait_for_webhook(&receiver, Duration::from_secs(5))
+        .await
+        .expect("Webhook was not delivered in time");
+    // The delivery is marked once the worker commits, which follows the request shortly
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    // Act
+    let response = system
+        .requestor
+        .get_admin_webhooks_deliveries(registered["id"].as_str().unwrap(), credentials)
+        .await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+    let actual: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(actual[0]["event_type"], "subscriber.registered");
+    assert_eq!(actual[0]["attempts"], 1);
+    assert_eq!(actual[0]["last_status_code"], 200);
+    assert!(actual[0]["delivered_at"].is_string());
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn admin_lists_endpoints_without_revealing_secrets(
+    #[future(awt)] system: System,
+    username: String,
+    password: SecretString,
+) {
+    // Arrange
+    save_user(
+        &system.dependencies.subscriber_database_pool,
+        &username,
+        &password,
+    )
+    .await;
+    let credentials = (username.as_str(), password.expose_secret());
+    let registered = register(&system, credentials, "https://crm.example.com/hooks").await;
+
+    // Act
+    let response = system.requestor.get_admin_webhooks(credentials).await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.text().await.unwrap();
+    assert!(body.contains(registered["id"].as_str().unwrap()));
+    assert!(!body.contains(registered["secret"].as_str().unwrap()));
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn registering_endpoint_with_invalid_url_responds_bad_request(
+    #[future(awt)] system: System,
+    username: String,
+    password: SecretString,
+) {
+    // Arrange
+    save_user(
+        &system.dependencies.subscriber_database_pool,
+        &username,
+        &password,
+    )
+    .await;
+
+    // Act
+    let response = system
+        .requestor
+        .post_admin_webhooks(
+            serde_json::json!({ "url": "ftp://crm.example.com/hooks" }),
+            (&username, password.expose_secret()),
+        )
+        .await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn registering_endpoint_with_supplied_secret_keeps_it(
+    #[future(awt)] system: System,
+    username: String,
+    password: SecretString,
+) {
+    // Arrange
+    save_user(
+        &system.dependencies.subscriber_database_pool,
+        &username,
+        &password,
+    )
+    .await;
+    let secret = "whsec_shared-with-the-receiver-already";
+
+    // Act
+    let response = system
+        .requestor
+        .post_admin_webhooks(
+            serde_json::json!({ "url": "https://crm.example.com/hooks", "secret": secret }),
+            (&username, password.expose_secret()),
+        )
+        .await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let actual: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(actual["secret"], secret);
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn registering_endpoint_with_short_secret_responds_bad_request(
+    #[future(awt)] system: System,
+    username: String,
+    password: SecretString,
+) {
+    // Arrange
+    save_user(
+        &system.dependencies.subscriber_database_pool,
+        &username,
+        &password,
+    )
+    .await;
+
+    // Act
+    let response = system
+        .requestor
+        .post_admin_webhooks(
+            serde_json::json!({ "url": "https://crm.example.com/hooks", "secret": "short" }),
+            (&username, password.expose_secret()),
+        )
+        .await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn removing_endpoint_stops_listing_it(
+    #[future(awt)] system: System,
+    username: String,
+    password: SecretString,
+) {
+    // Arrange
+    save_user(
+        &system.dependencies.subscriber_database_pool,
+        &username,
+        &password,
+    )
+    .await;
+    let credentials = (username.as_str(), password.expose_secret());
+    let registered = register(&system, credentials, "https://crm.example.com/hooks").await;
+    let id = registered["id"].as_str().unwrap();
+
+    // Act
+    let response = system
+        .requestor
+        .delete_admin_webhooks(id, credentials)
+        .await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    let listed = system.requestor.get_admin_webhooks(credentials).await;
+    assert!(!listed.text().await.unwrap().contains(id));
+    let removed_again = system
+        .requestor
+        .delete_admin_webhooks(id, credentials)
+        .await;
+    assert_eq!(removed_again.status(), StatusCode::NOT_FOUND);
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn webhook_endpoints_require_admin_credentials(
+    #[future(awt)] system: System,
+    username: String,
+    password: SecretString,
+) {
+    // Act
+    let response = system
+        .requestor
+        .get_admin_webhooks((&username, password.expose_secret()))
+        .await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
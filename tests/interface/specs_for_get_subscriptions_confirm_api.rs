@@ -72,7 +72,7 @@ async fn sut_responds_status_bad_request_if_token_is_missing(
 #[tokio::test]
 async fn sut_responds_status_not_found_if_token_does_not_exist(token: String) {
     // Arrange
-    let command_executor_stub = faulty_command_executor_stub(Error::TokenNotFound(token.clone()));
+    let command_executor_stub = faulty_command_executor_stub(Error::TokenNotFound);
     let sut = SystemSurface::new(command_executor_stub).await;
 
     // Act
@@ -102,6 +102,71 @@ async fn sut_responds_status_not_found_if_subscriber_inferred_from_token_does_no
     assert!(matches!(actual, StatusCode::NOT_FOUND));
 }
 
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_responds_status_conflict_if_subscriber_keeps_being_modified_concurrently(
+    token: String,
+) {
+    // Arrange
+    let command_executor_stub =
+        faulty_command_executor_stub(Error::ConcurrencyConflict(Uuid::now_v7()));
+    let sut = SystemSurface::new(command_executor_stub).await;
+
+    // Act
+    let response = sut.requestor.get_subscriptions_confirm(Some(token)).await;
+
+    // Assert
+    let actual = response.status();
+    assert!(matches!(actual, StatusCode::CONFLICT));
+}
+
+#[rstest::rstest]
+#[case(Error::TokenExpired)]
+#[case(Error::TokenAlreadyUsed)]
+#[tokio::test]
+async fn sut_responds_status_gone_if_token_is_expired_or_already_used(
+    #[case] error: Error,
+    token: String,
+) {
+    // Arrange
+    let command_executor_stub = faulty_command_executor_stub(error);
+    let sut = SystemSurface::new(command_executor_stub).await;
+
+    // Act
+    let response = sut.requestor.get_subscriptions_confirm(Some(token)).await;
+
+    // Assert
+    let actual = response.status();
+    assert!(matches!(actual, StatusCode::GONE));
+}
+
+#[rstest::rstest]
+#[case(None, "The token has expired.")]
+#[case(Some("en-GB,en;q=0.9"), "The token has expired.")]
+#[case(Some("ko-KR,ko;q=0.9,en;q=0.8"), "토큰이 만료되었습니다.")]
+#[case(Some("fr-FR"), "The token has expired.")]
+#[tokio::test]
+async fn sut_responds_message_in_locale_of_accept_language(
+    #[case] accept_language: Option<&str>,
+    #[case] expected: &str,
+    token: String,
+) {
+    // Arrange
+    let command_executor_stub = faulty_command_executor_stub(Error::TokenExpired);
+    let sut = SystemSurface::new(command_executor_stub).await;
+    let requestor = match accept_language {
+        Some(accept_language) => sut.requestor.with_accept_language(accept_language),
+        None => sut.requestor,
+    };
+
+    // Act
+    let response = requestor.get_subscriptions_confirm(Some(token)).await;
+
+    // Assert
+    let actual = response.json::<serde_json::Value>().await.unwrap();
+    assert_eq!(actual["message"], expected);
+}
+
 async fn parse_confirm_subscription_command(
     spy: &CommandExecutorSpy,
 ) -> ConfirmSubscriptionCommand {
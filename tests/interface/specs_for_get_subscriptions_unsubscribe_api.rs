@@ -0,0 +1,155 @@
+use reqwest::StatusCode;
+use uuid::Uuid;
+use zero2prod::subscriber::domain::error::Error;
+use zero2prod::subscriber::domain::service::UnsubscribeCommand;
+
+use crate::interface::system::SystemSurface;
+use crate::subscriber::domain::model::token;
+use crate::subscriber::domain::service::command_executor_spy;
+use crate::subscriber::domain::service::faulty_command_executor_stub;
+use crate::subscriber::domain::service::CommandExecutorSpy;
+use crate::subscriber::domain::service::CommandExecutorStub;
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_asks_for_confirmation_without_unsubscribing_when_link_is_followed(
+    command_executor_spy: CommandExecutorSpy,
+    token: String,
+) {
+    // Arrange
+    let sut = SystemSurface::new(command_executor_spy.clone()).await;
+
+    // Act
+    let response = sut
+        .requestor
+        .get_subscriptions_unsubscribe(Some(token))
+        .await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.text().await.unwrap();
+    assert!(body.contains(r#"<form method="post">"#));
+    assert!(command_executor_spy.command().await.is_none());
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_delivers_one_click_unsubscribe_command_to_command_executor_correctly(
+    command_executor_spy: CommandExecutorSpy,
+    token: String,
+) {
+    // Arrange
+    let sut = SystemSurface::new(command_executor_spy.clone()).await;
+
+    // Act
+    let response = sut
+        .requestor
+        .post_subscriptions_unsubscribe(Some(token.clone()))
+        .await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let actual = parse_unsubscribe_command(&command_executor_spy).await;
+    assert_eq!(actual.token(), token);
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_responds_status_bad_request_if_token_is_missing(
+    #[from(command_executor_spy)] command_executor_dummy: CommandExecutorSpy,
+) {
+    // Arrange
+    let sut = SystemSurface::new(command_executor_dummy).await;
+
+    // Act
+    let response = sut.requestor.get_subscriptions_unsubscribe(None).await;
+
+    // Assert
+    let actual = response.status();
+    assert!(matches!(actual, StatusCode::BAD_REQUEST));
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_responds_status_bad_request_if_token_is_missing_from_one_click_request(
+    #[from(command_executor_spy)] command_executor_dummy: CommandExecutorSpy,
+) {
+    // Arrange
+    let sut = SystemSurface::new(command_executor_dummy).await;
+
+    // Act
+    let response = sut.requestor.post_subscriptions_unsubscribe(None).await;
+
+    // Assert
+    let actual = response.status();
+    assert!(matches!(actual, StatusCode::BAD_REQUEST));
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_responds_status_bad_request_if_token_is_invalid(token: String) {
+    // Arrange
+    let command_executor_stub = faulty_command_executor_stub(Error::TokenInvalid);
+    let sut = SystemSurface::new(command_executor_stub).await;
+
+    // Act
+    let response = sut
+        .requestor
+        .post_subscriptions_unsubscribe(Some(token))
+        .await;
+
+    // Assert
+    let actual = response.status();
+    assert!(matches!(actual, StatusCode::BAD_REQUEST));
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_responds_status_conflict_if_subscriber_keeps_being_modified_concurrently(
+    token: String,
+) {
+    // Arrange
+    let command_executor_stub =
+        faulty_command_executor_stub(Error::ConcurrencyConflict(Uuid::now_v7()));
+    let sut = SystemSurface::new(command_executor_stub).await;
+
+    // Act
+    let response = sut
+        .requestor
+        .post_subscriptions_unsubscribe(Some(token))
+        .await;
+
+    // Assert
+    let actual = response.status();
+    assert!(matches!(actual, StatusCode::CONFLICT));
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_responds_status_internal_server_error_if_unexpected_error_occurs(
+    #[from(faulty_command_executor_stub)] command_executor_stub: CommandExecutorStub,
+    token: String,
+) {
+    // Arrange
+    let sut = SystemSurface::new(command_executor_stub).await;
+
+    // Act
+    let response = sut
+        .requestor
+        .post_subscriptions_unsubscribe(Some(token))
+        .await;
+
+    // Assert
+    let actual = response.status();
+    assert!(matches!(actual, StatusCode::INTERNAL_SERVER_ERROR));
+}
+
+async fn parse_unsubscribe_command(spy: &CommandExecutorSpy) -> UnsubscribeCommand {
+    spy.command()
+        .await
+        .unwrap()
+        .as_unsubscribe()
+        .unwrap()
+        .clone()
+}
@@ -0,0 +1,253 @@
+use reqwest::StatusCode;
+use secrecy::ExposeSecret;
+use secrecy::SecretString;
+use uuid::Uuid;
+use zero2prod::subscriber::domain::model::Email;
+use zero2prod::subscriber::domain::model::Name;
+
+use crate::auth::domain::model::password;
+use crate::auth::domain::model::username;
+use crate::auth::infrastructure::repository::save_user;
+use crate::interface::system::system;
+use crate::interface::system::System;
+use crate::interface::system::SystemSurface;
+use crate::subscriber::domain::model::email;
+use crate::subscriber::domain::model::name;
+use crate::subscriber::domain::service::faulty_command_executor_stub;
+use crate::subscriber::domain::service::CommandExecutorStub;
+
+async fn count_subscription_tokens(system: &System) -> Option<i64> {
+    sqlx::query_scalar!("select count(*) from subscription_tokens")
+        .fetch_one(&system.dependencies.subscriber_database_pool)
+        .await
+        .unwrap()
+}
+
+// Lets the same address subscribe again without waiting for the reissue interval to elapse
+async fn backdate_subscription_tokens(system: &System) {
+    sqlx::query!("update subscription_tokens set created_at = created_at - interval '1 hour'")
+        .execute(&system.dependencies.subscriber_database_pool)
+        .await
+        .unwrap();
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn repeated_request_with_same_idempotency_key_replays_first_response(
+    #[future(awt)] system: System,
+    name: Name,
+    email: Email,
+) {
+    // Arrange
+    let requestor = system
+        .requestor
+        .with_idempotency_key(&Uuid::new_v4().to_string());
+    let first = requestor
+        .post_subscriptions(Some(name.as_ref().into()), Some(email.as_ref().into()))
+        .await;
+    let first_status = first.status();
+    let first_body = first.text().await.unwrap();
+
+    // Act
+    let second = requestor
+        .post_subscriptions(Some(name.as_ref().into()), Some(email.as_ref().into()))
+        .await;
+
+    // Assert
+    assert_eq!(second.status(), first_status);
+    assert_eq!(second.headers().get("Idempotent-Replayed").unwrap(), "true");
+    assert_eq!(second.text().await.unwrap(), first_body);
+    assert_eq!(count_subscription_tokens(&system).await, Some(1));
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn requests_with_different_idempotency_keys_are_handled_separately(
+    #[future(awt)] system: System,
+    name: Name,
+    email: Email,
+) {
+    // Arrange
+    system
+        .requestor
+        .with_idempotency_key(&Uuid::new_v4().to_string())
+        .post_subscriptions(Some(name.as_ref().into()), Some(email.as_ref().into()))
+        .await;
+    backdate_subscription_tokens(&system).await;
+
+    // Act
+    let response = system
+        .requestor
+        .with_idempotency_key(&Uuid::new_v4().to_string())
+        .post_subscriptions(Some(name.as_ref().into()), Some(email.as_ref().into()))
+        .await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers().get("Idempotent-Replayed").is_none());
+    assert_eq!(count_subscription_tokens(&system).await, Some(2));
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn concurrent_requests_with_same_idempotency_key_are_handled_once(
+    #[future(awt)] system: System,
+    name: Name,
+    email: Email,
+) {
+    // Arrange
+    let requestor = system
+        .requestor
+        .with_idempotency_key(&Uuid::new_v4().to_string());
+
+    // Act
+    let (first, second) = tokio::join!(
+        requestor.post_subscriptions(Some(name.as_ref().into()), Some(email.as_ref().into())),
+        requestor.post_subscriptions(Some(name.as_ref().into()), Some(email.as_ref().into())),
+    );
+
+    // Assert
+    // The duplicate waits for the first to complete and gets its response replayed
+    assert_eq!(first.status(), StatusCode::OK);
+    assert_eq!(second.status(), StatusCode::OK);
+    let replayed = [&first, &second]
+        .iter()
+        .filter(|response| response.headers().get("Idempotent-Replayed").is_some())
+        .count();
+    assert_eq!(replayed, 1);
+    assert_eq!(first.text().await.unwrap(), second.text().await.unwrap());
+    assert_eq!(count_subscription_tokens(&system).await, Some(1));
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn request_reusing_idempotency_key_for_another_body_is_rejected(
+    #[future(awt)] system: System,
+    name: Name,
+    email: Email,
+) {
+    // Arrange
+    let requestor = system
+        .requestor
+        .with_idempotency_key(&Uuid::new_v4().to_string());
+    requestor
+        .post_subscriptions(Some(name.as_ref().into()), Some(email.as_ref().into()))
+        .await;
+
+    // Act
+    let response = requestor
+        .post_subscriptions(Some("another".into()), Some(email.as_ref().into()))
+        .await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    assert!(response.headers().get("Idempotent-Replayed").is_none());
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn client_error_response_is_not_replayed_for_same_idempotency_key(
+    #[future(awt)] system: System,
+    name: Name,
+    email: Email,
+) {
+    // Arrange
+    let requestor = system
+        .requestor
+        .with_idempotency_key(&Uuid::new_v4().to_string());
+    let rejected = requestor
+        .post_subscriptions(Some(name.as_ref().into()), Some("not-an-email".into()))
+        .await;
+    assert_eq!(rejected.status(), StatusCode::BAD_REQUEST);
+
+    // Act
+    let response = requestor
+        .post_subscriptions(Some(name.as_ref().into()), Some(email.as_ref().into()))
+        .await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers().get("Idempotent-Replayed").is_none());
+    assert_eq!(count_subscription_tokens(&system).await, Some(1));
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn minted_api_key_is_never_replayed_for_same_idempotency_key(
+    #[future(awt)] system: System,
+    username: String,
+    password: SecretString,
+) {
+    // Arrange
+    save_user(
+        &system.dependencies.subscriber_database_pool,
+        &username,
+        &password,
+    )
+    .await;
+    let credentials = (username.as_str(), password.expose_secret());
+    let requestor = system
+        .requestor
+        .with_idempotency_key(&Uuid::new_v4().to_string());
+    let body = serde_json::json!({ "client_name": "billing", "scopes": ["newsletters:publish"] });
+    let first: serde_json::Value = requestor
+        .post_admin_api_keys(body.clone(), credentials)
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    // Act
+    let response = requestor.post_admin_api_keys(body, credentials).await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::CREATED);
+    assert!(response.headers().get("Idempotent-Replayed").is_none());
+    let second: serde_json::Value = response.json().await.unwrap();
+    assert_ne!(first["key"], second["key"]);
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn server_error_response_is_not_replayed_for_same_idempotency_key(
+    faulty_command_executor_stub: CommandExecutorStub,
+    name: Name,
+    email: Email,
+) {
+    // Arrange
+    let surface = SystemSurface::new(faulty_command_executor_stub).await;
+    let requestor = surface
+        .requestor
+        .with_idempotency_key(&Uuid::new_v4().to_string());
+    requestor
+        .post_subscriptions(Some(name.as_ref().into()), Some(email.as_ref().into()))
+        .await;
+
+    // Act
+    let response = requestor
+        .post_subscriptions(Some(name.as_ref().into()), Some(email.as_ref().into()))
+        .await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    assert!(response.headers().get("Idempotent-Replayed").is_none());
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn request_with_too_long_idempotency_key_is_rejected(
+    #[future(awt)] system: System,
+    name: Name,
+    email: Email,
+) {
+    // Act
+    let response = system
+        .requestor
+        .with_idempotency_key(&"k".repeat(256))
+        .post_subscriptions(Some(name.as_ref().into()), Some(email.as_ref().into()))
+        .await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(count_subscription_tokens(&system).await, Some(0));
+}
@@ -0,0 +1,211 @@
+use std::time::Duration;
+
+use reqwest::StatusCode;
+use secrecy::ExposeSecret;
+use sqlx::Pool;
+use sqlx::Postgres;
+use zero2prod::assembly;
+use zero2prod::auth::domain::model::Scope;
+use zero2prod::auth::domain::service::ApiKeyManager;
+use zero2prod::newsletter::domain::error::Error;
+use zero2prod::newsletter::domain::service::PublishIssueCommand;
+use zero2prod::subscriber::domain::model::Email;
+use zero2prod::subscriber::domain::model::Name;
+
+use crate::interface::system::system;
+use crate::interface::system::System;
+use crate::interface::system::SystemSurface;
+use crate::newsletter::domain::model::html_content;
+use crate::newsletter::domain::model::text_content;
+use crate::newsletter::domain::model::title;
+use crate::newsletter::domain::service::command_executor_spy;
+use crate::newsletter::domain::service::faulty_command_executor_stub;
+use crate::newsletter::domain::service::CommandExecutorSpy;
+use crate::subscriber::domain::model::email;
+use crate::subscriber::domain::model::name;
+use crate::subscriber::infrastructure::repository::pool;
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_delivers_publish_issue_command_to_command_executor_correctly(
+    command_executor_spy: CommandExecutorSpy,
+    title: String,
+    text_content: String,
+    #[with(text_content.clone())] html_content: String,
+) {
+    // Arrange
+    let sut = SystemSurface::for_newsletter(command_executor_spy.clone()).await;
+
+    // Act
+    let response = sut
+        .requestor
+        .post_newsletters(serde_json::json!({
+            "title": title,
+            "content": { "html": html_content, "text": text_content },
+        }))
+        .await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let actual = parse_publish_issue_command(&command_executor_spy).await;
+    assert_eq!(actual.title(), title);
+    assert_eq!(actual.html_content(), html_content);
+    assert_eq!(actual.text_content(), text_content);
+}
+
+#[rstest::rstest]
+#[case(serde_json::json!({ "content": { "html": "<p>Hi</p>", "text": "Hi" } }))]
+#[case(serde_json::json!({ "title": "Newsletter", "content": { "text": "Hi" } }))]
+#[case(serde_json::json!({ "title": "Newsletter" }))]
+#[tokio::test]
+async fn sut_responds_status_unprocessable_entity_if_mandatory_field_is_missing(
+    command_executor_spy: CommandExecutorSpy,
+    #[case] body: serde_json::Value,
+) {
+    // Arrange
+    let sut = SystemSurface::for_newsletter(command_executor_spy).await;
+
+    // Act
+    let response = sut.requestor.post_newsletters(body).await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[rstest::rstest]
+#[case(Error::InvariantViolated("Title cannot be empty".into()), StatusCode::BAD_REQUEST)]
+#[case(Error::RepositoryOperationFailed(anyhow::anyhow!("")), StatusCode::INTERNAL_SERVER_ERROR)]
+#[tokio::test]
+async fn sut_responds_error_status_if_issue_cannot_be_published(
+    #[case] error: Error,
+    #[case] expected: StatusCode,
+) {
+    // Arrange
+    let sut = SystemSurface::for_newsletter(faulty_command_executor_stub(error)).await;
+
+    // Act
+    let response = sut
+        .requestor
+        .post_newsletters(serde_json::json!({
+            "title": title(),
+            "content": { "html": html_content(text_content()), "text": text_content() },
+        }))
+        .await;
+
+    // Assert
+    assert_eq!(response.status(), expected);
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_delivers_issue_to_subscriber_who_has_confirmed(
+    #[future(awt)] system: System,
+    name: Name,
+    email: Email,
+    title: String,
+) {
+    // Arrange
+    system
+        .requestor
+        .post_subscriptions(Some(name.as_ref().into()), Some(email.as_ref().into()))
+        .await;
+    let confirmation = system
+        .dependencies
+        .wait_for_subscription_email(email.as_ref(), Duration::from_secs(3))
+        .await
+        .expect("Confirmation email has not been delivered");
+    let token = confirmation
+        .message
+        .text_body()
+        .split("token=")
+        .nth(1)
+        .and_then(|rest| rest.split_whitespace().next())
+        .unwrap()
+        .to_string();
+    system
+        .requestor
+        .get_subscriptions_confirm(Some(token))
+        .await;
+
+    // Act
+    let response = system
+        .requestor
+        .post_newsletters(serde_json::json!({
+            "title": title,
+            "content": { "html": "<p>Hello</p>", "text": "Hello" },
+        }))
+        .await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let actual = system
+        .dependencies
+        .wait_for_email_with_subject(email.as_ref(), &title, Duration::from_secs(3))
+        .await;
+    assert!(actual.is_some(), "Newsletter issue has not been delivered");
+}
+
+async fn parse_publish_issue_command(spy: &CommandExecutorSpy) -> PublishIssueCommand {
+    spy.command()
+        .await
+        .unwrap()
+        .as_publish_issue()
+        .unwrap()
+        .clone()
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_responds_status_unauthorized_if_api_key_is_missing(
+    command_executor_spy: CommandExecutorSpy,
+) {
+    // Arrange
+    let sut = SystemSurface::for_newsletter(command_executor_spy).await;
+
+    // Act
+    let response = sut
+        .requestor
+        .post_newsletters_with_api_key(
+            serde_json::json!({
+                "title": "Newsletter",
+                "content": { "html": "<p>Hi</p>", "text": "Hi" },
+            }),
+            None,
+        )
+        .await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(response.headers()["WWW-Authenticate"], "Bearer");
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_responds_status_forbidden_if_api_key_lacks_publish_scope(
+    command_executor_spy: CommandExecutorSpy,
+    #[future(awt)] pool: Pool<Postgres>,
+) {
+    // Arrange
+    let sut = SystemSurface::for_newsletter(command_executor_spy).await;
+    let (_, key) = assembly::assemble_api_key_manager(pool)
+        .mint("reporting", &[Scope::SubscribersRead])
+        .await
+        .unwrap();
+
+    // Act
+    let response = sut
+        .requestor
+        .post_newsletters_with_api_key(
+            serde_json::json!({
+                "title": "Newsletter",
+                "content": { "html": "<p>Hi</p>", "text": "Hi" },
+            }),
+            Some(key.expose_secret()),
+        )
+        .await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
@@ -1,7 +1,6 @@
 use std::time::Duration;
 
 use reqwest::StatusCode;
-use wiremock::ResponseTemplate;
 use zero2prod::subscriber::domain::model::Email;
 use zero2prod::subscriber::domain::model::Name;
 
@@ -36,6 +35,36 @@ async fn subscription_returns_status_ok_with_valid_form_data(
     assert_eq!(actual.email, email.as_ref());
 }
 
+#[rstest::rstest]
+#[tokio::test]
+async fn subscription_returns_status_ok_when_email_is_submitted_twice(
+    #[future(awt)] system: System,
+    name: Name,
+    email: Email,
+) {
+    // Arrange
+    system
+        .requestor
+        .post_subscriptions(Some(name.as_ref().into()), Some(email.as_ref().into()))
+        .await;
+
+    // Act
+    let response = system
+        .requestor
+        .post_subscriptions(Some(name.as_ref().into()), Some(email.as_ref().into()))
+        .await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let pool = system.dependencies.subscriber_database_pool;
+    let actual = sqlx::query_scalar!("select count(*) from subscribers")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(actual, Some(1));
+}
+
 #[rstest::rstest]
 #[case(None, Some(email().as_ref().into()))]
 #[case(Some(name().as_ref().into()), None)]
@@ -55,25 +84,105 @@ async fn subscription_returns_status_unprocessable_entity_when_mandatory_field_i
 
 #[rstest::rstest]
 #[tokio::test]
-async fn sut_returns_status_internal_server_error_when_email_client_does_not_respond_in_3_seconds(
+async fn sut_delivers_confirmation_email_in_background(
     #[future(awt)] system: System,
     name: Name,
     email: Email,
 ) {
-    // Arrange
-    system
+    // Act
+    let _ = system
+        .requestor
+        .post_subscriptions(Some(name.as_ref().into()), Some(email.as_ref().into()))
+        .await;
+
+    // Assert
+    let actual = system
         .dependencies
-        .reset_subscription_email_server(Some(
-            ResponseTemplate::new(StatusCode::OK).set_delay(Duration::from_secs(4)),
-        ))
+        .wait_for_subscription_email(email.as_ref(), Duration::from_secs(3))
+        .await
+        .expect("Confirmation email has not been delivered");
+    assert!(actual
+        .message
+        .text_body()
+        .contains("/subscriptions/confirm?token="));
+}
+
+#[rstest::rstest]
+#[case(None, Some("ko-KR,ko;q=0.9,en;q=0.8"), "ko")]
+#[case(Some("ko"), Some("en-US,en;q=0.9"), "ko")]
+#[case(Some("fr"), Some("ko"), "ko")]
+#[case(None, None, "en")]
+#[tokio::test]
+async fn subscription_stores_locale_from_form_or_accept_language(
+    #[future(awt)] system: System,
+    name: Name,
+    email: Email,
+    #[case] locale: Option<&str>,
+    #[case] accept_language: Option<&str>,
+    #[case] expected: &str,
+) {
+    // Arrange
+    let requestor = match accept_language {
+        Some(accept_language) => system.requestor.with_accept_language(accept_language),
+        None => system.requestor,
+    };
+
+    // Act
+    let response = requestor
+        .post_subscriptions_with_locale(
+            Some(name.as_ref().into()),
+            Some(email.as_ref().into()),
+            locale.map(Into::into),
+        )
         .await;
 
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let pool = system.dependencies.subscriber_database_pool;
+    let actual = sqlx::query_scalar!("select locale from subscribers")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(actual, expected);
+}
+
+#[rstest::rstest]
+#[case(
+    Some("en-US,en;q=0.9"),
+    "",
+    "alice@example.com",
+    "Name cannot be empty."
+)]
+#[case(
+    Some("ko-KR,ko;q=0.9,en;q=0.8"),
+    "",
+    "alice@example.com",
+    "이름을 입력해 주세요."
+)]
+#[case(None, "Alice", "alice", "Email address must be valid.")]
+#[case(Some("ko"), "Alice", "alice", "올바른 이메일 주소를 입력해 주세요.")]
+#[tokio::test]
+async fn subscription_returns_localized_message_when_attribute_is_invalid(
+    #[future(awt)] system: System,
+    #[case] accept_language: Option<&str>,
+    #[case] name: &str,
+    #[case] email: &str,
+    #[case] expected: &str,
+) {
+    // Arrange
+    let requestor = match accept_language {
+        Some(accept_language) => system.requestor.with_accept_language(accept_language),
+        None => system.requestor,
+    };
+
     // Act
-    let response = system
-        .requestor
-        .post_subscriptions(Some(name.as_ref().into()), Some(email.as_ref().into()))
+    let response = requestor
+        .post_subscriptions(Some(name.into()), Some(email.into()))
         .await;
 
     // Assert
-    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let actual: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(actual["message"], expected);
 }
@@ -1,11 +1,13 @@
 use std::net::Ipv4Addr;
 use std::net::SocketAddr;
 use std::net::SocketAddrV4;
+use std::time::Duration;
+use std::time::Instant;
 
 use reqwest::header;
 use reqwest::Response;
-use reqwest::StatusCode;
 use secrecy::ExposeSecret;
+use secrecy::SecretString;
 use sqlx::Connection;
 use sqlx::Executor;
 use sqlx::PgConnection;
@@ -13,15 +15,16 @@ use sqlx::Pool;
 use sqlx::Postgres;
 use tokio::net::TcpListener;
 use uuid::Uuid;
-use wiremock::matchers::method;
-use wiremock::matchers::path;
-use wiremock::Mock;
-use wiremock::MockServer;
-use wiremock::ResponseTemplate;
 use zero2prod::assembly;
 use zero2prod::assembly::get_database_connection_string;
+use zero2prod::auth::domain::model::Scope;
+use zero2prod::auth::domain::service::ApiKeyManager;
+use zero2prod::common::email_client::CapturedEmail;
+use zero2prod::common::email_client::ConfiguredEmailClient;
+use zero2prod::common::email_client::InMemoryMailbox;
 use zero2prod::configuration;
 use zero2prod::interface;
+use zero2prod::newsletter;
 use zero2prod::subscriber;
 
 pub struct System {
@@ -62,50 +65,130 @@ impl System {
         // Create subscriber database dependency
         let subscriber_database_pool =
             assembly::get_database_pool(&configuration.subscriber.database).await;
+        let unit_of_work = assembly::assemble_unit_of_work(subscriber_database_pool.clone());
         let subscriber_repository =
-            assembly::assemble_subscriber_repository(subscriber_database_pool.clone());
-        let subscription_token_repository =
-            assembly::assemble_subscription_token_repository(subscriber_database_pool.clone());
+            assembly::assemble_subscriber_repository(&configuration.subscriber.concurrency_control);
+        let subscription_token_repository = assembly::assemble_subscription_token_repository(
+            &configuration.subscriber.subscription_token,
+        );
+        let outbox_repository = assembly::assemble_outbox_repository();
         sqlx::migrate!("./migrations")
             .run(&subscriber_database_pool)
             .await
             .unwrap();
 
         // Create subscriber email dependency
-        let subscription_email_server = MockServer::start().await;
-        Mock::given(method("POST"))
-            .and(path("/email"))
-            .respond_with(ResponseTemplate::new(StatusCode::OK))
-            .mount(&subscription_email_server)
-            .await;
-        configuration.subscriber.email.server.url = subscription_email_server.uri();
         let subscription_email_client =
             assembly::assemble_subscription_email_client(&configuration.subscriber.email);
+        let ConfiguredEmailClient::InMemory(in_memory_email_client) = &subscription_email_client
+        else {
+            panic!("Test configuration must use the in-memory email backend");
+        };
+        let subscription_mailbox = in_memory_email_client.mailbox().clone();
 
         // Create dependencies
         let dependencies = SystemDependencies {
             subscriber_database_pool,
-            subscription_email_server,
+            subscription_mailbox,
         };
 
+        // Run subscriber aggregate's outbox dispatcher
+        tokio::spawn(subscriber::interface::dispatcher::run(
+            unit_of_work.clone(),
+            subscriber_repository.clone(),
+            outbox_repository,
+            subscription_email_client.clone(),
+            assembly::assemble_outbox_retry_policy(&configuration.subscriber.outbox),
+            configuration.subscriber.outbox.batch_size,
+            configuration.subscriber.outbox.interval,
+        ));
+
+        // Run subscriber aggregate's webhook worker
+        tokio::spawn(subscriber::interface::webhook_worker::run(
+            unit_of_work.clone(),
+            assembly::assemble_webhook_endpoint_repository(),
+            assembly::assemble_webhook_delivery_repository(),
+            assembly::assemble_webhook_client(&configuration.subscriber.webhook),
+            assembly::assemble_webhook_retry_policy(&configuration.subscriber.webhook),
+            configuration.subscriber.webhook.batch_size,
+            configuration.subscriber.webhook.interval,
+        ));
+
+        // Assemble subscriber aggregate's query executor
+        let subscriber_query_executor = subscriber::domain::service::new_query_executor(
+            unit_of_work.clone(),
+            subscriber_repository.clone(),
+        );
+
         // Assemble subscriber aggregate's command executor
         let subscriber_command_executor = subscriber::domain::service::new_command_executor(
+            unit_of_work,
             subscriber_repository,
             subscription_token_repository,
+            assembly::assemble_event_publisher(
+                &configuration.subscriber,
+                &configuration.application,
+            ),
+            configuration.subscriber.unsubscribe.key.clone(),
+        );
+
+        // Run newsletter aggregate's delivery worker
+        let newsletter_unit_of_work = assembly::assemble_newsletter_unit_of_work(
+            dependencies.subscriber_database_pool.clone(),
+        );
+        let newsletter_issue_repository = assembly::assemble_newsletter_issue_repository();
+        let issue_delivery_repository = assembly::assemble_issue_delivery_repository();
+        tokio::spawn(newsletter::interface::worker::run(
+            newsletter_unit_of_work.clone(),
+            newsletter_issue_repository.clone(),
+            issue_delivery_repository.clone(),
+            assembly::assemble_recipient_repository(),
             subscription_email_client,
+            assembly::assemble_issue_delivery_retry_policy(&configuration.newsletter.delivery),
+            assembly::assemble_link_builder(&configuration.application),
+            configuration.subscriber.unsubscribe.key.clone(),
+            configuration.newsletter.delivery.batch_size,
+            configuration.newsletter.delivery.interval,
+        ));
+
+        // Assemble newsletter aggregate's command executor
+        let newsletter_command_executor = newsletter::domain::service::new_command_executor(
+            newsletter_unit_of_work,
+            newsletter_issue_repository,
+            issue_delivery_repository,
         );
 
         // Set up listener and client
         let listener = TcpListener::bind(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 0))
             .await
             .unwrap();
+        let api_key_manager =
+            assembly::assemble_api_key_manager(dependencies.subscriber_database_pool.clone());
         let requestor = SystemRequestor {
             url: listener.local_addr().unwrap(),
             client: reqwest::Client::new(),
+            api_key: mint_api_key_with_every_scope(&api_key_manager).await,
         };
 
         // Run a server
-        tokio::spawn(interface::run(listener, subscriber_command_executor));
+        tokio::spawn(interface::run(
+            listener,
+            subscriber_command_executor,
+            subscriber_query_executor,
+            newsletter_command_executor,
+            assembly::assemble_idempotency_store(dependencies.subscriber_database_pool.clone()),
+            assembly::assemble_authenticator(dependencies.subscriber_database_pool.clone()),
+            api_key_manager,
+            assembly::assemble_session_store(
+                &configuration.auth.session,
+                dependencies.subscriber_database_pool.clone(),
+            ),
+            assembly::assemble_session_cookie(&configuration.auth.session),
+            assembly::assemble_webhook_manager(
+                &configuration.subscriber.webhook,
+                dependencies.subscriber_database_pool.clone(),
+            ),
+        ));
 
         // Return test system
         System {
@@ -118,6 +201,8 @@ impl System {
 pub struct SystemRequestor {
     pub url: SocketAddr,
     pub client: reqwest::Client,
+    // Key granted every scope, sent by requests of machine clients
+    pub api_key: String,
 }
 
 impl SystemRequestor {
@@ -125,10 +210,61 @@ impl SystemRequestor {
         self.client.get(self.url("/healthz")).send().await.unwrap()
     }
 
+    pub fn with_accept_language(&self, accept_language: &str) -> Self {
+        let mut headers = header::HeaderMap::new();
+        headers.insert(
+            header::ACCEPT_LANGUAGE,
+            header::HeaderValue::from_str(accept_language).unwrap(),
+        );
+
+        Self {
+            url: self.url,
+            api_key: self.api_key.clone(),
+            client: reqwest::Client::builder()
+                .default_headers(headers)
+                .build()
+                .unwrap(),
+        }
+    }
+
+    pub fn with_api_key(&self, api_key: &str) -> Self {
+        Self {
+            url: self.url,
+            client: self.client.clone(),
+            api_key: api_key.into(),
+        }
+    }
+
+    pub fn with_idempotency_key(&self, idempotency_key: &str) -> Self {
+        let mut headers = header::HeaderMap::new();
+        headers.insert(
+            "Idempotency-Key",
+            header::HeaderValue::from_str(idempotency_key).unwrap(),
+        );
+
+        Self {
+            url: self.url,
+            api_key: self.api_key.clone(),
+            client: reqwest::Client::builder()
+                .default_headers(headers)
+                .build()
+                .unwrap(),
+        }
+    }
+
     pub async fn post_subscriptions(
         &self,
         name: Option<String>,
         email: Option<String>,
+    ) -> Response {
+        self.post_subscriptions_with_locale(name, email, None).await
+    }
+
+    pub async fn post_subscriptions_with_locale(
+        &self,
+        name: Option<String>,
+        email: Option<String>,
+        locale: Option<String>,
     ) -> Response {
         let mut body = String::new();
         if let Some(name) = name {
@@ -137,6 +273,9 @@ impl SystemRequestor {
         if let Some(email) = email {
             body.push_str(format!("&email={}", &urlencoding::encode(&email)).as_str());
         };
+        if let Some(locale) = locale {
+            body.push_str(format!("&locale={}", &urlencoding::encode(&locale)).as_str());
+        };
         body = body.trim_start_matches("&").to_string();
 
         self.client
@@ -148,6 +287,144 @@ impl SystemRequestor {
             .unwrap()
     }
 
+    pub async fn post_newsletters(&self, body: serde_json::Value) -> Response {
+        self.post_newsletters_with_api_key(body, Some(&self.api_key))
+            .await
+    }
+
+    pub async fn post_newsletters_with_api_key(
+        &self,
+        body: serde_json::Value,
+        api_key: Option<&str>,
+    ) -> Response {
+        let mut request_builder = self.client.post(self.url("/newsletters")).json(&body);
+        if let Some(api_key) = api_key {
+            request_builder = request_builder.bearer_auth(api_key);
+        }
+
+        request_builder.send().await.unwrap()
+    }
+
+    pub async fn post_admin_api_keys(
+        &self,
+        body: serde_json::Value,
+        credentials: (&str, &str),
+    ) -> Response {
+        self.client
+            .post(self.url("/admin/api-keys"))
+            .basic_auth(credentials.0, Some(credentials.1))
+            .json(&body)
+            .send()
+            .await
+            .unwrap()
+    }
+
+    pub async fn get_admin_api_keys(&self, credentials: (&str, &str)) -> Response {
+        self.client
+            .get(self.url("/admin/api-keys"))
+            .basic_auth(credentials.0, Some(credentials.1))
+            .send()
+            .await
+            .unwrap()
+    }
+
+    pub async fn delete_admin_api_keys(&self, id: &str, credentials: (&str, &str)) -> Response {
+        self.client
+            .delete(self.url(&format!("/admin/api-keys/{}", id)))
+            .basic_auth(credentials.0, Some(credentials.1))
+            .send()
+            .await
+            .unwrap()
+    }
+
+    pub async fn post_admin_webhooks(
+        &self,
+        body: serde_json::Value,
+        credentials: (&str, &str),
+    ) -> Response {
+        self.client
+            .post(self.url("/admin/webhooks"))
+            .basic_auth(credentials.0, Some(credentials.1))
+            .json(&body)
+            .send()
+            .await
+            .unwrap()
+    }
+
+    pub async fn get_admin_webhooks(&self, credentials: (&str, &str)) -> Response {
+        self.client
+            .get(self.url("/admin/webhooks"))
+            .basic_auth(credentials.0, Some(credentials.1))
+            .send()
+            .await
+            .unwrap()
+    }
+
+    pub async fn delete_admin_webhooks(&self, id: &str, credentials: (&str, &str)) -> Response {
+        self.client
+            .delete(self.url(&format!("/admin/webhooks/{}", id)))
+            .basic_auth(credentials.0, Some(credentials.1))
+            .send()
+            .await
+            .unwrap()
+    }
+
+    pub async fn get_admin_webhooks_deliveries(
+        &self,
+        id: &str,
+        credentials: (&str, &str),
+    ) -> Response {
+        self.client
+            .get(self.url(&format!("/admin/webhooks/{}/deliveries", id)))
+            .basic_auth(credentials.0, Some(credentials.1))
+            .send()
+            .await
+            .unwrap()
+    }
+
+    pub async fn get_admin_subscribers(&self, query: &[(&str, &str)]) -> Response {
+        self.client
+            .get(self.url("/admin/subscribers"))
+            .bearer_auth(&self.api_key)
+            .query(query)
+            .send()
+            .await
+            .unwrap()
+    }
+
+    pub async fn get_admin_subscribers_with_credentials(
+        &self,
+        query: &[(&str, &str)],
+        credentials: (&str, &str),
+    ) -> Response {
+        self.client
+            .get(self.url("/admin/subscribers"))
+            .basic_auth(credentials.0, Some(credentials.1))
+            .query(query)
+            .send()
+            .await
+            .unwrap()
+    }
+
+    pub async fn post_admin_subscribers(&self, body: serde_json::Value) -> Response {
+        self.client
+            .post(self.url("/admin/subscribers"))
+            .bearer_auth(&self.api_key)
+            .json(&body)
+            .send()
+            .await
+            .unwrap()
+    }
+
+    pub async fn get_admin_subscribers_by_id(&self, id: &str) -> Response {
+        self.client
+            .get(self.url(&format!("/admin/subscribers/{}", id)))
+            .bearer_auth(&self.api_key)
+            .send()
+            .await
+            .unwrap()
+    }
+
     pub async fn get_subscriptions_confirm(&self, token: Option<String>) -> Response {
         let mut request_builder = self.client.get(self.url("/subscriptions/confirm"));
         if let Some(token) = token {
@@ -157,6 +434,119 @@ impl SystemRequestor {
         request_builder.send().await.unwrap()
     }
 
+    pub async fn get_subscriptions_unsubscribe(&self, token: Option<String>) -> Response {
+        let mut request_builder = self.client.get(self.url("/subscriptions/unsubscribe"));
+        if let Some(token) = token {
+            request_builder = request_builder.query(&[("token", token)])
+        }
+
+        request_builder.send().await.unwrap()
+    }
+
+    pub async fn post_subscriptions_unsubscribe(&self, token: Option<String>) -> Response {
+        let mut request_builder = self
+            .client
+            .post(self.url("/subscriptions/unsubscribe"))
+            .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .body("List-Unsubscribe=One-Click");
+        if let Some(token) = token {
+            request_builder = request_builder.query(&[("token", token)])
+        }
+
+        request_builder.send().await.unwrap()
+    }
+
+    pub async fn get_login(&self, session_id: Option<&str>) -> Response {
+        self.browse(self.browser().get(self.url("/login")), session_id)
+            .await
+    }
+
+    pub async fn post_login(
+        &self,
+        username: &str,
+        password: &str,
+        csrf_token: &str,
+        session_id: Option<&str>,
+    ) -> Response {
+        let request_builder = self.browser().post(self.url("/login")).form(&[
+            ("username", username),
+            ("password", password),
+            ("csrf_token", csrf_token),
+        ]);
+        self.browse(request_builder, session_id).await
+    }
+
+    pub async fn post_logout(&self, csrf_token: &str, session_id: Option<&str>) -> Response {
+        let request_builder = self
+            .browser()
+            .post(self.url("/logout"))
+            .form(&[("csrf_token", csrf_token)]);
+        self.browse(request_builder, session_id).await
+    }
+
+    pub async fn get_admin_dashboard(&self, session_id: Option<&str>) -> Response {
+        self.browse(self.browser().get(self.url("/admin/dashboard")), session_id)
+            .await
+    }
+
+    pub async fn get_admin_dashboard_subscribers(
+        &self,
+        query: &str,
+        session_id: Option<&str>,
+    ) -> Response {
+        let request_builder = self
+            .browser()
+            .get(self.url(&format!("/admin/dashboard/subscribers?{}", query)));
+        self.browse(request_builder, session_id).await
+    }
+
+    pub async fn get_admin_dashboard_newsletters(&self, session_id: Option<&str>) -> Response {
+        self.browse(
+            self.browser().get(self.url("/admin/dashboard/newsletters")),
+            session_id,
+        )
+        .await
+    }
+
+    pub async fn post_admin_dashboard_newsletters(
+        &self,
+        title: &str,
+        csrf_token: &str,
+        session_id: Option<&str>,
+    ) -> Response {
+        let request_builder = self
+            .browser()
+            .post(self.url("/admin/dashboard/newsletters"))
+            .form(&[
+                ("title", title),
+                ("html_content", "<p>Hi</p>"),
+                ("text_content", "Hi"),
+                ("csrf_token", csrf_token),
+            ]);
+        self.browse(request_builder, session_id).await
+    }
+
+    // Browser pages answer with redirects, which tests inspect instead of following
+    fn browser(&self) -> reqwest::Client {
+        reqwest::Client::builder()
+            .redirect(reqwest::redirect::Policy::none())
+            .build()
+            .unwrap()
+    }
+
+    async fn browse(
+        &self,
+        mut request_builder: reqwest::RequestBuilder,
+        session_id: Option<&str>,
+    ) -> Response {
+        if let Some(session_id) = session_id {
+            request_builder =
+                request_builder.header(header::COOKIE, format!("session_id={}", session_id));
+        }
+
+        request_builder.send().await.unwrap()
+    }
+
     fn url(&self, path: &str) -> String {
         format!("http://{}/{}", self.url, path.trim_start_matches("/"))
     }
@@ -164,25 +554,103 @@ impl SystemRequestor {
 
 pub struct SystemDependencies {
     pub subscriber_database_pool: Pool<Postgres>,
-    pub subscription_email_server: MockServer,
+    pub subscription_mailbox: InMemoryMailbox,
 }
 
 impl SystemDependencies {
-    pub async fn reset_subscription_email_server(
+    pub async fn wait_for_subscription_email(
         &self,
-        response_template: Option<ResponseTemplate>,
-    ) {
-        self.subscription_email_server.reset().await;
+        recipient: &str,
+        timeout: Duration,
+    ) -> Option<CapturedEmail> {
+        let deadline = Instant::now() + timeout;
+        while Instant::now() < deadline {
+            let emails = self.subscription_mailbox.emails_to(recipient).await;
+            if let Some(email) = emails.into_iter().next() {
+                return Some(email);
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        None
+    }
 
-        let response_template = response_template.unwrap_or(ResponseTemplate::new(StatusCode::OK));
-        Mock::given(method("POST"))
-            .and(path("/email"))
-            .respond_with(response_template)
-            .mount(&self.subscription_email_server)
-            .await;
+    pub async fn wait_for_email_with_subject(
+        &self,
+        recipient: &str,
+        subject: &str,
+        timeout: Duration,
+    ) -> Option<CapturedEmail> {
+        let deadline = Instant::now() + timeout;
+        while Instant::now() < deadline {
+            let emails = self.subscription_mailbox.emails_to(recipient).await;
+            if let Some(email) = emails
+                .into_iter()
+                .find(|email| email.message.subject() == subject)
+            {
+                return Some(email);
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        None
     }
 }
 
+async fn mint_api_key_with_every_scope(api_key_manager: &impl ApiKeyManager) -> String {
+    let (_, key) = api_key_manager
+        .mint(
+            "system-test",
+            &[
+                Scope::SubscribersRead,
+                Scope::SubscribersWrite,
+                Scope::NewslettersPublish,
+            ],
+        )
+        .await
+        .unwrap();
+    key.expose_secret().into()
+}
+
+// Opens the login form as a fresh browser, returning the session it was given and its CSRF token
+pub async fn open_login_form(system: &System) -> (String, String) {
+    let response = system.requestor.get_login(None).await;
+    let session_id = session_id(&response).unwrap();
+    let csrf_token = csrf_token(&response.text().await.unwrap());
+    (session_id, csrf_token)
+}
+
+pub async fn log_in(system: &System, username: &str, password: &SecretString) -> String {
+    let (anonymous_session_id, csrf_token) = open_login_form(system).await;
+    let response = system
+        .requestor
+        .post_login(
+            username,
+            password.expose_secret(),
+            &csrf_token,
+            Some(&anonymous_session_id),
+        )
+        .await;
+    session_id(&response).unwrap()
+}
+
+// Session identifier the response asked the browser to keep
+pub fn session_id(response: &Response) -> Option<String> {
+    response
+        .headers()
+        .get_all(header::SET_COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .find_map(|cookie| cookie.strip_prefix("session_id="))
+        .map(|cookie| cookie.split(';').next().unwrap().to_owned())
+}
+
+// CSRF token embedded as a hidden input of the rendered form
+pub fn csrf_token(html: &str) -> String {
+    let (_, rest) = html
+        .split_once(r#"name="csrf_token" value=""#)
+        .expect("Page must contain a CSRF token");
+    rest.split('"').next().unwrap().to_owned()
+}
+
 #[rstest::fixture]
 pub async fn system() -> System {
     System::new().await
@@ -195,16 +663,58 @@ pub struct SystemSurface {
 impl SystemSurface {
     pub async fn new(
         subscriber_command_executor: impl subscriber::domain::service::CommandExecutor,
+    ) -> Self {
+        Self::with(
+            subscriber_command_executor,
+            crate::newsletter::domain::service::CommandExecutorSpy::new(),
+        )
+        .await
+    }
+
+    pub async fn for_newsletter(
+        newsletter_command_executor: impl newsletter::domain::service::CommandExecutor,
+    ) -> Self {
+        Self::with(
+            crate::subscriber::domain::service::CommandExecutorSpy::new(),
+            newsletter_command_executor,
+        )
+        .await
+    }
+
+    async fn with(
+        subscriber_command_executor: impl subscriber::domain::service::CommandExecutor,
+        newsletter_command_executor: impl newsletter::domain::service::CommandExecutor,
     ) -> Self {
         let listener = TcpListener::bind(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 0))
             .await
             .unwrap();
+        let configuration =
+            configuration::get_configuration(configuration::Environment::Test).unwrap();
+        let pool = assembly::get_database_pool(&configuration.subscriber.database).await;
+        let api_key_manager = assembly::assemble_api_key_manager(pool.clone());
         let requestor = SystemRequestor {
             url: listener.local_addr().unwrap(),
             client: reqwest::Client::new(),
+            api_key: mint_api_key_with_every_scope(&api_key_manager).await,
         };
 
-        tokio::spawn(interface::run(listener, subscriber_command_executor));
+        tokio::spawn(interface::run(
+            listener,
+            subscriber_command_executor,
+            subscriber::domain::service::new_query_executor(
+                assembly::assemble_unit_of_work(pool.clone()),
+                assembly::assemble_subscriber_repository(
+                    &configuration.subscriber.concurrency_control,
+                ),
+            ),
+            newsletter_command_executor,
+            assembly::assemble_idempotency_store(pool.clone()),
+            assembly::assemble_authenticator(pool.clone()),
+            api_key_manager,
+            assembly::assemble_session_store(&configuration.auth.session, pool.clone()),
+            assembly::assemble_session_cookie(&configuration.auth.session),
+            assembly::assemble_webhook_manager(&configuration.subscriber.webhook, pool),
+        ));
 
         SystemSurface { requestor }
     }
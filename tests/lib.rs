@@ -1,2 +1,5 @@
+pub mod auth;
+pub mod common;
 mod interface;
+pub mod newsletter;
 pub mod subscriber;
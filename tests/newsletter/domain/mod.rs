@@ -0,0 +1,4 @@
+pub mod model;
+pub mod service;
+mod specs_for_newsletter_issue_delivery;
+mod specs_for_publish_issue_command_executor;
@@ -0,0 +1,18 @@
+use fake::faker::lorem::en::Paragraph;
+use fake::faker::lorem::en::Sentence;
+use fake::Fake;
+
+#[rstest::fixture]
+pub fn title() -> String {
+    Sentence(3..6).fake()
+}
+
+#[rstest::fixture]
+pub fn text_content() -> String {
+    Paragraph(1..3).fake()
+}
+
+#[rstest::fixture]
+pub fn html_content(text_content: String) -> String {
+    format!("<p>{}</p>", text_content)
+}
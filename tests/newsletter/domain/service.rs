@@ -0,0 +1,90 @@
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use tokio::sync::RwLock;
+use zero2prod::newsletter::domain::error::Error;
+use zero2prod::newsletter::domain::service::Command;
+use zero2prod::newsletter::domain::service::CommandExecutor;
+use zero2prod::newsletter::domain::service::PublishIssueCommand;
+
+use crate::newsletter::domain::model::html_content;
+use crate::newsletter::domain::model::text_content;
+use crate::newsletter::domain::model::title;
+
+#[rstest::fixture]
+pub fn publish_issue_command(
+    title: String,
+    #[from(text_content)] text: String,
+    #[from(html_content)] html: String,
+) -> Command {
+    PublishIssueCommand::new(title, html, text).into()
+}
+
+#[derive(Clone)]
+pub struct CommandExecutorSpy {
+    command: Arc<RwLock<Option<Command>>>,
+}
+
+#[allow(clippy::new_without_default)]
+impl CommandExecutorSpy {
+    pub fn new() -> Self {
+        CommandExecutorSpy {
+            command: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    pub async fn command(&self) -> Option<Command> {
+        self.command.read().await.clone()
+    }
+}
+
+#[async_trait::async_trait]
+impl CommandExecutor for CommandExecutorSpy {
+    async fn execute(&self, command: Command) -> Result<(), Error> {
+        *self.command.write().await = Some(command);
+        Ok(())
+    }
+}
+
+#[rstest::fixture]
+pub fn command_executor_spy() -> CommandExecutorSpy {
+    CommandExecutorSpy::new()
+}
+
+#[derive(Clone)]
+pub struct CommandExecutorStub {
+    error: Arc<Option<Error>>,
+}
+
+impl CommandExecutorStub {
+    pub fn new(error: Option<Error>) -> Self {
+        CommandExecutorStub {
+            error: Arc::new(error),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl CommandExecutor for CommandExecutorStub {
+    async fn execute(&self, _: Command) -> Result<(), Error> {
+        let error = &*self.error;
+        if let Some(error) = error {
+            return match error {
+                Error::InvariantViolated(message) => Err(Error::InvariantViolated(message.into())),
+                Error::RepositoryOperationFailed(_) => {
+                    Err(Error::RepositoryOperationFailed(anyhow!("")))
+                }
+                Error::EmailOperationFailed(_) => Err(Error::EmailOperationFailed(anyhow!(""))),
+                Error::FailedUnexpectedly(_) => Err(Error::FailedUnexpectedly(anyhow!(""))),
+            };
+        };
+        Ok(())
+    }
+}
+
+#[rstest::fixture]
+pub fn faulty_command_executor_stub(
+    #[default(Error::FailedUnexpectedly(anyhow!("")))] error: Error,
+) -> CommandExecutorStub {
+    CommandExecutorStub::new(Some(error))
+}
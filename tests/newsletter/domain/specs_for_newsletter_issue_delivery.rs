@@ -0,0 +1,365 @@
+use std::time::Duration;
+
+use reqwest::StatusCode;
+use sqlx::Pool;
+use sqlx::Postgres;
+use url::Url;
+use wiremock::MockServer;
+use wiremock::ResponseTemplate;
+use zero2prod::common::email::EmailClient;
+use zero2prod::common::email_client::InMemoryEmailClient;
+use zero2prod::common::email_client::InMemoryMailbox;
+use zero2prod::common::email_client::PostmarkEmailClient;
+use zero2prod::common::link::UnsubscribeToken;
+use zero2prod::common::retry::RetryPolicy;
+use zero2prod::newsletter::domain::infrastructure::IssueDeliveryRepository;
+use zero2prod::newsletter::domain::infrastructure::UnitOfWork;
+use zero2prod::newsletter::domain::service::deliver_newsletter_issues;
+use zero2prod::newsletter::domain::service::new_command_executor;
+use zero2prod::newsletter::domain::service::Command;
+use zero2prod::newsletter::infrastructure::repository::SqlxIssueDeliveryRepository;
+use zero2prod::newsletter::infrastructure::unit_of_work::SqlxUnitOfWork;
+use zero2prod::subscriber::domain::model::Subscriber;
+
+use crate::common::email_client::faulty_email_server_and_client;
+use crate::common::email_client::postmark_server_and_client;
+use crate::newsletter::domain::service::publish_issue_command;
+use crate::newsletter::infrastructure::repository::find_parked_issue_deliveries;
+use crate::newsletter::infrastructure::repository::find_queued_issue_deliveries;
+use crate::newsletter::infrastructure::repository::issue_delivery_repository;
+use crate::newsletter::infrastructure::repository::newsletter_issue_repository;
+use crate::newsletter::infrastructure::repository::recipient_repository;
+use crate::newsletter::infrastructure::repository::save_subscribers;
+use crate::newsletter::infrastructure::repository::unsubscribe_subscriber;
+use crate::subscriber::domain::model::link_builder;
+use crate::subscriber::domain::model::subscriber;
+use crate::subscriber::domain::model::unsubscribe_key;
+use crate::subscriber::infrastructure::repository::isolated_pool;
+
+#[rstest::fixture]
+fn retry_policy() -> RetryPolicy {
+    RetryPolicy::new(3, Duration::from_secs(60), Duration::from_secs(3600))
+}
+
+#[rstest::fixture]
+fn email_client() -> InMemoryEmailClient {
+    InMemoryEmailClient::new(InMemoryMailbox::new(), "newsletter@example.com".into())
+}
+
+#[rstest::fixture]
+fn confirmed_subscriber(mut subscriber: Subscriber) -> Subscriber {
+    subscriber.confirm().unwrap();
+    subscriber
+}
+
+async fn publish(pool: &Pool<Postgres>, recipient: &Subscriber, command: Command) {
+    save_subscribers(pool, std::slice::from_ref(recipient)).await;
+    let executor = new_command_executor(
+        SqlxUnitOfWork::new(pool.clone()),
+        newsletter_issue_repository(),
+        issue_delivery_repository(),
+    );
+    executor(command).await.unwrap();
+}
+
+async fn deliver(
+    pool: &Pool<Postgres>,
+    email_client: &impl EmailClient,
+    retry_policy: &RetryPolicy,
+) -> usize {
+    deliver_newsletter_issues(
+        &SqlxUnitOfWork::new(pool.clone()),
+        &newsletter_issue_repository(),
+        &issue_delivery_repository(),
+        &recipient_repository(),
+        email_client,
+        retry_policy,
+        &link_builder(),
+        &unsubscribe_key(),
+        10,
+    )
+    .await
+    .unwrap()
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_sends_queued_issue_and_removes_it_from_queue(
+    #[future(awt)] isolated_pool: Pool<Postgres>,
+    email_client: InMemoryEmailClient,
+    retry_policy: RetryPolicy,
+    confirmed_subscriber: Subscriber,
+    publish_issue_command: Command,
+) {
+    // Arrange
+    publish(
+        &isolated_pool,
+        &confirmed_subscriber,
+        publish_issue_command.clone(),
+    )
+    .await;
+
+    // Act
+    let actual = deliver(&isolated_pool, &email_client, &retry_policy).await;
+
+    // Assert
+    assert_eq!(actual, 1);
+
+    let emails = email_client.mailbox().emails().await;
+    assert_eq!(emails.len(), 1);
+    assert_eq!(emails[0].recipient, confirmed_subscriber.email());
+    let command = publish_issue_command.as_publish_issue().unwrap();
+    assert_eq!(emails[0].message.subject(), command.title());
+    assert!(emails[0]
+        .message
+        .html_body()
+        .starts_with(command.html_content()));
+    assert!(emails[0]
+        .message
+        .text_body()
+        .starts_with(command.text_content()));
+
+    assert!(find_queued_issue_deliveries(&isolated_pool)
+        .await
+        .is_empty());
+    assert!(find_parked_issue_deliveries(&isolated_pool)
+        .await
+        .is_empty());
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_sends_issue_with_unsubscribe_link_of_recipient(
+    #[future(awt)] isolated_pool: Pool<Postgres>,
+    email_client: InMemoryEmailClient,
+    retry_policy: RetryPolicy,
+    confirmed_subscriber: Subscriber,
+    publish_issue_command: Command,
+) {
+    // Arrange
+    publish(&isolated_pool, &confirmed_subscriber, publish_issue_command).await;
+
+    // Act
+    deliver(&isolated_pool, &email_client, &retry_policy).await;
+
+    // Assert
+    let emails = email_client.mailbox().emails().await;
+    let unsubscribe_url = emails[0].message.unsubscribe_url().unwrap();
+    let token = Url::parse(unsubscribe_url)
+        .unwrap()
+        .query_pairs()
+        .find_map(|(key, value)| (key == "token").then(|| value.into_owned()))
+        .unwrap();
+    let token = UnsubscribeToken::verify(&token, &unsubscribe_key()).unwrap();
+    assert_eq!(token.subscriber_id(), confirmed_subscriber.id());
+    assert!(emails[0].message.html_body().contains(unsubscribe_url));
+    assert!(emails[0].message.text_body().contains(unsubscribe_url));
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_reschedules_delivery_if_email_server_fails_transiently(
+    #[future(awt)] isolated_pool: Pool<Postgres>,
+    #[future(awt)] faulty_email_server_and_client: (MockServer, PostmarkEmailClient),
+    retry_policy: RetryPolicy,
+    confirmed_subscriber: Subscriber,
+    publish_issue_command: Command,
+) {
+    // Arrange
+    let (_email_server, email_client) = faulty_email_server_and_client;
+    publish(&isolated_pool, &confirmed_subscriber, publish_issue_command).await;
+
+    // Act
+    deliver(&isolated_pool, &email_client, &retry_policy).await;
+
+    // Assert
+    let actual = find_queued_issue_deliveries(&isolated_pool).await;
+    assert_eq!(actual.len(), 1);
+    assert_eq!(actual[0].attempts, 1);
+    assert!(actual[0].last_error.is_some());
+
+    // Backoff keeps the delivery from being retried immediately
+    assert_eq!(
+        deliver(&isolated_pool, &email_client, &retry_policy).await,
+        0
+    );
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_parks_delivery_if_email_is_rejected_permanently(
+    #[future(awt)] isolated_pool: Pool<Postgres>,
+    #[future(awt)]
+    #[with(ResponseTemplate::new(StatusCode::UNPROCESSABLE_ENTITY).set_body_json(
+        serde_json::json!({
+            "ErrorCode": 406,
+            "Message": "You tried to send to a recipient that has been marked as inactive.",
+        })
+    ))]
+    postmark_server_and_client: (MockServer, PostmarkEmailClient),
+    retry_policy: RetryPolicy,
+    confirmed_subscriber: Subscriber,
+    publish_issue_command: Command,
+) {
+    // Arrange
+    let (_email_server, email_client) = postmark_server_and_client;
+    publish(&isolated_pool, &confirmed_subscriber, publish_issue_command).await;
+
+    // Act
+    deliver(&isolated_pool, &email_client, &retry_policy).await;
+
+    // Assert
+    assert!(find_queued_issue_deliveries(&isolated_pool)
+        .await
+        .is_empty());
+
+    let actual = find_parked_issue_deliveries(&isolated_pool).await;
+    assert_eq!(actual.len(), 1);
+    assert_eq!(&actual[0].subscriber_id, confirmed_subscriber.id());
+    assert_eq!(actual[0].attempts, 1);
+    assert!(actual[0].last_error.as_ref().unwrap().contains("inactive"));
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_parks_delivery_if_attempts_are_exhausted(
+    #[future(awt)] isolated_pool: Pool<Postgres>,
+    #[future(awt)] faulty_email_server_and_client: (MockServer, PostmarkEmailClient),
+    confirmed_subscriber: Subscriber,
+    publish_issue_command: Command,
+) {
+    // Arrange
+    let (_email_server, email_client) = faulty_email_server_and_client;
+    let retry_policy = RetryPolicy::new(1, Duration::from_secs(60), Duration::from_secs(3600));
+    publish(&isolated_pool, &confirmed_subscriber, publish_issue_command).await;
+
+    // Act
+    deliver(&isolated_pool, &email_client, &retry_policy).await;
+
+    // Assert
+    assert!(find_queued_issue_deliveries(&isolated_pool)
+        .await
+        .is_empty());
+    assert_eq!(find_parked_issue_deliveries(&isolated_pool).await.len(), 1);
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_drops_delivery_if_subscriber_has_unsubscribed_since_publishing(
+    #[future(awt)] isolated_pool: Pool<Postgres>,
+    email_client: InMemoryEmailClient,
+    retry_policy: RetryPolicy,
+    confirmed_subscriber: Subscriber,
+    publish_issue_command: Command,
+) {
+    // Arrange
+    publish(&isolated_pool, &confirmed_subscriber, publish_issue_command).await;
+    unsubscribe_subscriber(&isolated_pool, &confirmed_subscriber).await;
+
+    // Act
+    deliver(&isolated_pool, &email_client, &retry_policy).await;
+
+    // Assert
+    assert!(email_client.mailbox().emails().await.is_empty());
+    assert!(find_queued_issue_deliveries(&isolated_pool)
+        .await
+        .is_empty());
+    assert!(find_parked_issue_deliveries(&isolated_pool)
+        .await
+        .is_empty());
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_skips_deliveries_locked_by_another_worker(
+    #[future(awt)] isolated_pool: Pool<Postgres>,
+    issue_delivery_repository: SqlxIssueDeliveryRepository,
+    email_client: InMemoryEmailClient,
+    retry_policy: RetryPolicy,
+    confirmed_subscriber: Subscriber,
+    publish_issue_command: Command,
+) {
+    // Arrange
+    publish(&isolated_pool, &confirmed_subscriber, publish_issue_command).await;
+    let unit_of_work = SqlxUnitOfWork::new(isolated_pool.clone());
+    let mut other_worker = unit_of_work.begin().await.unwrap();
+    let locked = issue_delivery_repository
+        .find_due(&mut other_worker, 10)
+        .await
+        .unwrap();
+    assert_eq!(locked.len(), 1);
+
+    // Act
+    let actual = deliver(&isolated_pool, &email_client, &retry_policy).await;
+
+    // Assert
+    assert_eq!(actual, 0);
+    assert!(email_client.mailbox().emails().await.is_empty());
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_does_not_keep_issue_delivery_locked_while_sending_it(
+    #[future(awt)] isolated_pool: Pool<Postgres>,
+    #[future(awt)]
+    #[with(ResponseTemplate::new(StatusCode::OK).set_delay(Duration::from_millis(500)))]
+    postmark_server_and_client: (MockServer, PostmarkEmailClient),
+    retry_policy: RetryPolicy,
+    confirmed_subscriber: Subscriber,
+    publish_issue_command: Command,
+) {
+    // Arrange
+    let (_email_server, email_client) = postmark_server_and_client;
+    publish(&isolated_pool, &confirmed_subscriber, publish_issue_command).await;
+
+    // Act
+    let (delivered, locked) = tokio::join!(
+        deliver(&isolated_pool, &email_client, &retry_policy),
+        async {
+            tokio::time::sleep(Duration::from_millis(250)).await;
+            sqlx::query!(
+                "select subscriber_id from issue_delivery_queue where subscriber_id = $1 for update nowait",
+                confirmed_subscriber.id(),
+            )
+            .fetch_one(&isolated_pool)
+            .await
+        },
+    );
+
+    // Assert
+    assert_eq!(delivered, 1);
+    assert!(locked.is_ok());
+    assert!(find_queued_issue_deliveries(&isolated_pool)
+        .await
+        .is_empty());
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_leaves_delivery_being_sent_by_another_worker_alone(
+    #[future(awt)] isolated_pool: Pool<Postgres>,
+    #[future(awt)]
+    #[with(ResponseTemplate::new(StatusCode::OK).set_delay(Duration::from_millis(500)))]
+    postmark_server_and_client: (MockServer, PostmarkEmailClient),
+    email_client: InMemoryEmailClient,
+    retry_policy: RetryPolicy,
+    confirmed_subscriber: Subscriber,
+    publish_issue_command: Command,
+) {
+    // Arrange
+    let (_email_server, slow_email_client) = postmark_server_and_client;
+    publish(&isolated_pool, &confirmed_subscriber, publish_issue_command).await;
+
+    // Act
+    let (_, actual) = tokio::join!(
+        deliver(&isolated_pool, &slow_email_client, &retry_policy),
+        async {
+            tokio::time::sleep(Duration::from_millis(250)).await;
+            deliver(&isolated_pool, &email_client, &retry_policy).await
+        },
+    );
+
+    // Assert
+    assert_eq!(actual, 0);
+    assert!(email_client.mailbox().emails().await.is_empty());
+}
@@ -0,0 +1,113 @@
+use sqlx::Pool;
+use sqlx::Postgres;
+use zero2prod::newsletter::domain::error::Error;
+use zero2prod::newsletter::domain::service::new_command_executor;
+use zero2prod::newsletter::domain::service::Command;
+use zero2prod::newsletter::domain::service::PublishIssueCommand;
+use zero2prod::newsletter::infrastructure::repository::SqlxIssueDeliveryRepository;
+use zero2prod::newsletter::infrastructure::repository::SqlxNewsletterIssueRepository;
+use zero2prod::newsletter::infrastructure::unit_of_work::SqlxUnitOfWork;
+
+use crate::newsletter::domain::model::html_content;
+use crate::newsletter::domain::model::text_content;
+use crate::newsletter::domain::service::publish_issue_command as command;
+use crate::newsletter::infrastructure::repository::find_newsletter_issue_ids_by_title;
+use crate::newsletter::infrastructure::repository::find_queued_issue_deliveries;
+use crate::newsletter::infrastructure::repository::issue_delivery_repository;
+use crate::newsletter::infrastructure::repository::newsletter_issue_repository;
+use crate::newsletter::infrastructure::repository::save_subscribers;
+use crate::subscriber::domain::model::subscriber;
+use crate::subscriber::infrastructure::repository::isolated_pool;
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_queues_delivery_for_confirmed_subscribers_only(
+    #[future(awt)] isolated_pool: Pool<Postgres>,
+    newsletter_issue_repository: SqlxNewsletterIssueRepository,
+    issue_delivery_repository: SqlxIssueDeliveryRepository,
+    command: Command,
+) {
+    // Arrange
+    let mut confirmed = subscriber::default();
+    confirmed.confirm().unwrap();
+    let pending = subscriber::default();
+    let mut unsubscribed = subscriber::default();
+    unsubscribed.unsubscribe().unwrap();
+    save_subscribers(&isolated_pool, &[confirmed.clone(), pending, unsubscribed]).await;
+
+    let sut = new_command_executor(
+        SqlxUnitOfWork::new(isolated_pool.clone()),
+        newsletter_issue_repository,
+        issue_delivery_repository,
+    );
+
+    // Act
+    let actual = sut(command).await;
+
+    // Assert
+    assert!(actual.is_ok());
+
+    let deliveries = find_queued_issue_deliveries(&isolated_pool).await;
+    assert_eq!(deliveries.len(), 1);
+    assert_eq!(&deliveries[0].subscriber_id, confirmed.id());
+    assert_eq!(deliveries[0].attempts, 0);
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_stores_published_issue(
+    #[future(awt)] isolated_pool: Pool<Postgres>,
+    newsletter_issue_repository: SqlxNewsletterIssueRepository,
+    issue_delivery_repository: SqlxIssueDeliveryRepository,
+    command: Command,
+) {
+    // Arrange
+    let sut = new_command_executor(
+        SqlxUnitOfWork::new(isolated_pool.clone()),
+        newsletter_issue_repository,
+        issue_delivery_repository,
+    );
+
+    // Act
+    sut(command.clone()).await.unwrap();
+
+    // Assert
+    let title = command.as_publish_issue().unwrap().title();
+    let actual = find_newsletter_issue_ids_by_title(&isolated_pool, title).await;
+    assert_eq!(actual.len(), 1);
+}
+
+#[rstest::rstest]
+#[case("", html_content(text_content()), text_content())]
+#[case("Weekly digest", "".into(), text_content())]
+#[case("Weekly digest", html_content(text_content()), " ".into())]
+#[tokio::test]
+async fn sut_raises_invariant_violated_error_if_title_or_content_is_blank(
+    #[future(awt)] isolated_pool: Pool<Postgres>,
+    newsletter_issue_repository: SqlxNewsletterIssueRepository,
+    issue_delivery_repository: SqlxIssueDeliveryRepository,
+    #[case] title: &str,
+    #[case] html_content: String,
+    #[case] text_content: String,
+) {
+    // Arrange
+    let mut confirmed = subscriber::default();
+    confirmed.confirm().unwrap();
+    save_subscribers(&isolated_pool, &[confirmed]).await;
+
+    let sut = new_command_executor(
+        SqlxUnitOfWork::new(isolated_pool.clone()),
+        newsletter_issue_repository,
+        issue_delivery_repository,
+    );
+    let command = PublishIssueCommand::new(title.into(), html_content, text_content);
+
+    // Act
+    let actual = sut(command.into()).await;
+
+    // Assert
+    assert!(matches!(actual, Err(Error::InvariantViolated(_))));
+    assert!(find_queued_issue_deliveries(&isolated_pool)
+        .await
+        .is_empty());
+}
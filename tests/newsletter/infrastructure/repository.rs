@@ -0,0 +1,85 @@
+use sqlx::Pool;
+use sqlx::Postgres;
+use uuid::Uuid;
+use zero2prod::newsletter::infrastructure::repository::SqlxIssueDeliveryRepository;
+use zero2prod::newsletter::infrastructure::repository::SqlxNewsletterIssueRepository;
+use zero2prod::newsletter::infrastructure::repository::SqlxRecipientRepository;
+use zero2prod::subscriber::domain::infrastructure::SubscriberRepository;
+use zero2prod::subscriber::domain::infrastructure::UnitOfWork;
+use zero2prod::subscriber::domain::model::Subscriber;
+use zero2prod::subscriber::infrastructure::unit_of_work::SqlxUnitOfWork as SubscriberUnitOfWork;
+
+use crate::subscriber::infrastructure::repository::subscriber_repository;
+
+#[rstest::fixture]
+pub fn newsletter_issue_repository() -> SqlxNewsletterIssueRepository {
+    SqlxNewsletterIssueRepository::new()
+}
+
+#[rstest::fixture]
+pub fn issue_delivery_repository() -> SqlxIssueDeliveryRepository {
+    SqlxIssueDeliveryRepository::new()
+}
+
+#[rstest::fixture]
+pub fn recipient_repository() -> SqlxRecipientRepository {
+    SqlxRecipientRepository::new()
+}
+
+pub async fn save_subscribers(pool: &Pool<Postgres>, subscribers: &[Subscriber]) {
+    let unit_of_work = SubscriberUnitOfWork::new(pool.clone());
+    let mut transaction = unit_of_work.begin().await.unwrap();
+    for subscriber in subscribers {
+        subscriber_repository()
+            .save(&mut transaction, subscriber)
+            .await
+            .unwrap();
+    }
+    unit_of_work.commit(transaction).await.unwrap();
+}
+
+pub async fn unsubscribe_subscriber(pool: &Pool<Postgres>, subscriber: &Subscriber) {
+    let unit_of_work = SubscriberUnitOfWork::new(pool.clone());
+    let mut transaction = unit_of_work.begin().await.unwrap();
+    subscriber_repository()
+        .modify_by_id(&mut transaction, subscriber.id(), |mut subscriber| {
+            subscriber.unsubscribe().unwrap();
+            subscriber
+        })
+        .await
+        .unwrap();
+    unit_of_work.commit(transaction).await.unwrap();
+}
+
+pub async fn find_newsletter_issue_ids_by_title(pool: &Pool<Postgres>, title: &str) -> Vec<Uuid> {
+    sqlx::query_scalar!("select id from newsletter_issues where title = $1", title)
+        .fetch_all(pool)
+        .await
+        .unwrap()
+}
+
+pub struct QueuedIssueDelivery {
+    pub subscriber_id: Uuid,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+}
+
+pub async fn find_queued_issue_deliveries(pool: &Pool<Postgres>) -> Vec<QueuedIssueDelivery> {
+    sqlx::query_as!(
+        QueuedIssueDelivery,
+        "select subscriber_id, attempts, last_error from issue_delivery_queue",
+    )
+    .fetch_all(pool)
+    .await
+    .unwrap()
+}
+
+pub async fn find_parked_issue_deliveries(pool: &Pool<Postgres>) -> Vec<QueuedIssueDelivery> {
+    sqlx::query_as!(
+        QueuedIssueDelivery,
+        "select subscriber_id, attempts, last_error from issue_delivery_dead_letters",
+    )
+    .fetch_all(pool)
+    .await
+    .unwrap()
+}
@@ -1,4 +1,10 @@
 pub mod model;
 pub mod service;
 mod specs_for_confirm_subscription_command_executor;
+mod specs_for_confirmation_email_publisher;
+mod specs_for_outbox_dispatcher;
 mod specs_for_subscribe_command_executor;
+mod specs_for_subscriber_query_executor;
+mod specs_for_unsubscribe_command_executor;
+mod specs_for_webhook_dispatcher;
+mod specs_for_webhook_event_publisher;
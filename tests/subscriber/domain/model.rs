@@ -1,7 +1,15 @@
+use chrono::DateTime;
+use chrono::TimeDelta;
+use chrono::Utc;
 use fake::faker::internet::en::SafeEmail as FakeEmail;
 use fake::faker::name::en::Name as FakeName;
 use fake::Fake;
+use secrecy::SecretString;
 use uuid::Uuid;
+use zero2prod::common::link::LinkBuilder;
+use zero2prod::common::locale::Locale;
+use zero2prod::configuration::get_configuration;
+use zero2prod::configuration::Environment;
 use zero2prod::subscriber::domain::model::Email;
 use zero2prod::subscriber::domain::model::Name;
 use zero2prod::subscriber::domain::model::Subscriber;
@@ -9,16 +17,24 @@ use zero2prod::subscriber::domain::model::SubscriptionToken;
 use zero2prod::subscriber::infrastructure::repository::SubscriptionTokenDataModel;
 
 #[rstest::fixture]
-pub fn subscriber(name: Name, email: Email) -> Subscriber {
-    Subscriber::create(name.as_ref(), email.as_ref()).unwrap()
+pub fn subscriber(name: Name, email: Email, locale: Locale) -> Subscriber {
+    Subscriber::create(name.as_ref(), email.as_ref(), locale).unwrap()
 }
 
 #[rstest::fixture]
 pub fn subscription_token(
     #[default(Uuid::now_v7().to_string())] token: String,
     #[default(Uuid::now_v7())] subscriber_id: Uuid,
+    #[default(Utc::now() + TimeDelta::days(1))] expires_at: DateTime<Utc>,
 ) -> SubscriptionToken {
-    SubscriptionTokenDataModel::new(token, subscriber_id).into()
+    SubscriptionTokenDataModel::new(
+        token,
+        subscriber_id,
+        Utc::now().naive_utc(),
+        expires_at.naive_utc(),
+        None,
+    )
+    .into()
 }
 
 #[rstest::fixture]
@@ -26,12 +42,39 @@ pub fn name() -> Name {
     Name::parse(FakeName().fake::<String>().as_str()).unwrap()
 }
 
+// Faked addresses repeat across runs against the shared database, so a unique prefix keeps
+// re-subscription from turning a fresh subscriber into an existing one
 #[rstest::fixture]
 pub fn email() -> Email {
-    Email::parse(FakeEmail().fake::<String>().as_str()).unwrap()
+    let email = format!(
+        "{}.{}",
+        Uuid::now_v7().simple(),
+        FakeEmail().fake::<String>()
+    );
+    Email::parse(email.as_str()).unwrap()
+}
+
+#[rstest::fixture]
+pub fn locale() -> Locale {
+    Locale::default()
 }
 
 #[rstest::fixture]
 pub fn token() -> String {
     Uuid::now_v7().into()
 }
+
+#[rstest::fixture]
+pub fn unsubscribe_key() -> SecretString {
+    get_configuration(Environment::Test)
+        .unwrap()
+        .subscriber
+        .unsubscribe
+        .key
+}
+
+#[rstest::fixture]
+pub fn link_builder() -> LinkBuilder {
+    let configuration = get_configuration(Environment::Test).unwrap();
+    LinkBuilder::new(configuration.application.base_url)
+}
@@ -2,6 +2,7 @@ use std::sync::Arc;
 
 use anyhow::anyhow;
 use tokio::sync::RwLock;
+use zero2prod::common::locale::Locale;
 use zero2prod::subscriber::domain::error::Error;
 use zero2prod::subscriber::domain::model::Email;
 use zero2prod::subscriber::domain::model::Name;
@@ -9,14 +10,16 @@ use zero2prod::subscriber::domain::service::Command;
 use zero2prod::subscriber::domain::service::CommandExecutor;
 use zero2prod::subscriber::domain::service::ConfirmSubscriptionCommand;
 use zero2prod::subscriber::domain::service::SubscribeCommand;
+use zero2prod::subscriber::domain::service::UnsubscribeCommand;
 
 use crate::subscriber::domain::model::email;
+use crate::subscriber::domain::model::locale;
 use crate::subscriber::domain::model::name;
 use crate::subscriber::domain::model::token;
 
 #[rstest::fixture]
-pub fn subscribe_command(name: Name, email: Email) -> Command {
-    SubscribeCommand::new(name.as_ref().into(), email.as_ref().into()).into()
+pub fn subscribe_command(name: Name, email: Email, locale: Locale) -> Command {
+    SubscribeCommand::new(name.as_ref().into(), email.as_ref().into(), locale).into()
 }
 
 #[rstest::fixture]
@@ -26,6 +29,7 @@ pub fn subscribe_commands(#[default(5)] size: usize) -> Vec<Command> {
             Command::from(SubscribeCommand::new(
                 name().as_ref().into(),
                 email().as_ref().into(),
+                locale(),
             ))
         })
         .collect()
@@ -36,6 +40,11 @@ pub fn confirm_subscription_command(token: String) -> Command {
     ConfirmSubscriptionCommand::new(token).into()
 }
 
+#[rstest::fixture]
+pub fn unsubscribe_command(token: String) -> Command {
+    UnsubscribeCommand::new(token).into()
+}
+
 #[derive(Clone)]
 pub struct CommandExecutorSpy {
     command: Arc<RwLock<Option<Command>>>,
@@ -88,12 +97,27 @@ impl CommandExecutor for CommandExecutorStub {
         if let Some(error) = error {
             return match error {
                 Error::InvariantViolated(message) => Err(Error::InvariantViolated(message.into())),
-                Error::TokenNotFound(message) => Err(Error::TokenNotFound(message.into())),
+                Error::NameEmpty => Err(Error::NameEmpty),
+                Error::NameTooLong => Err(Error::NameTooLong),
+                Error::NameHasForbiddenCharacters => Err(Error::NameHasForbiddenCharacters),
+                Error::EmailInvalid => Err(Error::EmailInvalid),
+                Error::TokenNotFound => Err(Error::TokenNotFound),
+                Error::TokenExpired => Err(Error::TokenExpired),
+                Error::TokenAlreadyUsed => Err(Error::TokenAlreadyUsed),
+                Error::TokenInvalid => Err(Error::TokenInvalid),
                 Error::SubscriberNotFound(id) => Err(Error::SubscriberNotFound(*id)),
+                Error::SubscriberAlreadyExists(email) => {
+                    Err(Error::SubscriberAlreadyExists(email.into()))
+                }
+                Error::ConcurrencyConflict(id) => Err(Error::ConcurrencyConflict(*id)),
+                Error::WebhookEndpointNotFound(id) => Err(Error::WebhookEndpointNotFound(*id)),
+                Error::SignatureInvalid(message) => Err(Error::SignatureInvalid(message.into())),
                 Error::RepositoryOperationFailed(_) => {
                     Err(Error::RepositoryOperationFailed(anyhow!("")))
                 }
                 Error::EmailOperationFailed(_) => Err(Error::EmailOperationFailed(anyhow!(""))),
+                Error::EmailRejected(_) => Err(Error::EmailRejected(anyhow!(""))),
+                Error::WebhookOperationFailed(_) => Err(Error::WebhookOperationFailed(anyhow!(""))),
                 Error::FailedUnexpectedly(_) => Err(Error::FailedUnexpectedly(anyhow!(""))),
             };
         };
@@ -1,46 +1,75 @@
+use chrono::TimeDelta;
+use chrono::Utc;
 use uuid::Uuid;
 use zero2prod::subscriber::domain::error::Error;
-use zero2prod::subscriber::domain::infrastructure::SubscriberRepository;
-use zero2prod::subscriber::domain::infrastructure::SubscriptionTokenRepository;
 use zero2prod::subscriber::domain::model::Status;
 use zero2prod::subscriber::domain::model::Subscriber;
+use zero2prod::subscriber::domain::model::SubscriberEvent;
 use zero2prod::subscriber::domain::service::new_command_executor;
 use zero2prod::subscriber::domain::service::Command;
+use zero2prod::subscriber::domain::service::ConfirmationEmailPublisher;
+use zero2prod::subscriber::infrastructure::repository::SqlxOutboxRepository;
 use zero2prod::subscriber::infrastructure::repository::SqlxSubscriberRepository;
 use zero2prod::subscriber::infrastructure::repository::SqlxSubscriptionTokenRepository;
+use zero2prod::subscriber::infrastructure::repository::SubscriberDataModel;
+use zero2prod::subscriber::infrastructure::unit_of_work::SqlxUnitOfWork;
 
+use crate::subscriber::domain::model::link_builder;
 use crate::subscriber::domain::model::subscriber;
 use crate::subscriber::domain::model::subscription_token;
 use crate::subscriber::domain::model::token;
+use crate::subscriber::domain::model::unsubscribe_key;
 use crate::subscriber::domain::service::confirm_subscription_command as command;
 use crate::subscriber::domain::service::confirm_subscription_command;
-use crate::subscriber::infrastructure::email_client::email_client_double;
-use crate::subscriber::infrastructure::email_client::EmailClientDouble;
+use crate::subscriber::infrastructure::email_renderer::email_renderer;
+use crate::subscriber::infrastructure::event_publisher::event_publisher_spy;
+use crate::subscriber::infrastructure::event_publisher::EventPublisherSpy;
 use crate::subscriber::infrastructure::repository::find_subscriber_by_email;
+use crate::subscriber::infrastructure::repository::find_subscription_token_by_subscriber_id;
+use crate::subscriber::infrastructure::repository::outbox_repository;
+use crate::subscriber::infrastructure::repository::save_subscriber;
+use crate::subscriber::infrastructure::repository::save_subscription_token;
 use crate::subscriber::infrastructure::repository::subscriber_repository;
 use crate::subscriber::infrastructure::repository::subscription_token_repository;
+use crate::subscriber::infrastructure::repository::unit_of_work;
 
 #[rstest::rstest]
 #[tokio::test]
 async fn sut_changes_subscriber_status_as_confirmed_if_token_exists(
-    #[future(awt)] subscriber_repository: SqlxSubscriberRepository,
-    #[future(awt)] subscription_token_repository: SqlxSubscriptionTokenRepository,
-    #[future(awt)]
-    #[from(email_client_double)]
-    dummy: EmailClientDouble,
+    #[future(awt)] unit_of_work: SqlxUnitOfWork,
+    subscriber_repository: SqlxSubscriberRepository,
+    subscription_token_repository: SqlxSubscriptionTokenRepository,
+    outbox_repository: SqlxOutboxRepository,
     subscriber: Subscriber,
     token: String,
 ) {
     // Arrange
-    let subscription_token = subscription_token(token.clone(), *subscriber.id());
-    subscriber_repository.save(&subscriber).await.unwrap();
-    subscription_token_repository
-        .save(&subscription_token)
-        .await
-        .unwrap();
+    let subscription_token = subscription_token(
+        token.clone(),
+        *subscriber.id(),
+        Utc::now() + TimeDelta::days(1),
+    );
+    save_subscriber(&subscriber).await;
+    save_subscription_token(&subscription_token).await;
 
     let command = confirm_subscription_command(token);
-    let sut = new_command_executor(subscriber_repository, subscription_token_repository, dummy);
+    let sut = new_command_executor(
+        unit_of_work,
+        subscriber_repository.clone(),
+        subscription_token_repository.clone(),
+        (
+            event_publisher_spy(),
+            ConfirmationEmailPublisher::new(
+                subscriber_repository,
+                subscription_token_repository,
+                outbox_repository,
+                email_renderer(),
+                link_builder(),
+                unsubscribe_key(),
+            ),
+        ),
+        unsubscribe_key(),
+    );
 
     // Act
     sut(command).await.unwrap();
@@ -53,42 +82,73 @@ async fn sut_changes_subscriber_status_as_confirmed_if_token_exists(
 #[rstest::rstest]
 #[tokio::test]
 async fn sut_raises_token_not_found_error_if_token_does_not_exist(
-    #[future(awt)] subscriber_repository: SqlxSubscriberRepository,
-    #[future(awt)] subscription_token_repository: SqlxSubscriptionTokenRepository,
-    #[future(awt)]
-    #[from(email_client_double)]
-    dummy: EmailClientDouble,
+    #[future(awt)] unit_of_work: SqlxUnitOfWork,
+    subscriber_repository: SqlxSubscriberRepository,
+    subscription_token_repository: SqlxSubscriptionTokenRepository,
+    outbox_repository: SqlxOutboxRepository,
     command: Command,
 ) {
     // Arrange
-    let sut = new_command_executor(subscriber_repository, subscription_token_repository, dummy);
+    let sut = new_command_executor(
+        unit_of_work,
+        subscriber_repository.clone(),
+        subscription_token_repository.clone(),
+        (
+            event_publisher_spy(),
+            ConfirmationEmailPublisher::new(
+                subscriber_repository,
+                subscription_token_repository,
+                outbox_repository,
+                email_renderer(),
+                link_builder(),
+                unsubscribe_key(),
+            ),
+        ),
+        unsubscribe_key(),
+    );
 
     // Act
     let actual = sut(command).await.unwrap_err();
 
     // Assert
-    assert!(matches!(actual, Error::TokenNotFound(_)));
+    assert!(matches!(actual, Error::TokenNotFound));
 }
 
 #[rstest::rstest]
 #[tokio::test]
 async fn sut_raises_subscriber_not_found_error_if_subscriber_does_not_exist(
-    #[future(awt)] subscriber_repository: SqlxSubscriberRepository,
-    #[future(awt)] subscription_token_repository: SqlxSubscriptionTokenRepository,
-    #[future(awt)]
-    #[from(email_client_double)]
-    dummy: EmailClientDouble,
+    #[future(awt)] unit_of_work: SqlxUnitOfWork,
+    subscriber_repository: SqlxSubscriberRepository,
+    subscription_token_repository: SqlxSubscriptionTokenRepository,
+    outbox_repository: SqlxOutboxRepository,
     token: String,
 ) {
     // Arrange
-    let subscription_token = subscription_token(token.clone(), Uuid::now_v7());
-    subscription_token_repository
-        .save(&subscription_token)
-        .await
-        .unwrap();
+    let subscription_token = subscription_token(
+        token.clone(),
+        Uuid::now_v7(),
+        Utc::now() + TimeDelta::days(1),
+    );
+    save_subscription_token(&subscription_token).await;
 
     let command = confirm_subscription_command(token);
-    let sut = new_command_executor(subscriber_repository, subscription_token_repository, dummy);
+    let sut = new_command_executor(
+        unit_of_work,
+        subscriber_repository.clone(),
+        subscription_token_repository.clone(),
+        (
+            event_publisher_spy(),
+            ConfirmationEmailPublisher::new(
+                subscriber_repository,
+                subscription_token_repository,
+                outbox_repository,
+                email_renderer(),
+                link_builder(),
+                unsubscribe_key(),
+            ),
+        ),
+        unsubscribe_key(),
+    );
 
     // Act
     let actual = sut(command).await.unwrap_err();
@@ -96,3 +156,408 @@ async fn sut_raises_subscriber_not_found_error_if_subscriber_does_not_exist(
     // Assert
     assert!(matches!(actual, Error::SubscriberNotFound(_)));
 }
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_marks_token_as_used_after_confirmation(
+    #[future(awt)] unit_of_work: SqlxUnitOfWork,
+    subscriber_repository: SqlxSubscriberRepository,
+    subscription_token_repository: SqlxSubscriptionTokenRepository,
+    outbox_repository: SqlxOutboxRepository,
+    subscriber: Subscriber,
+    token: String,
+) {
+    // Arrange
+    let subscription_token = subscription_token(
+        token.clone(),
+        *subscriber.id(),
+        Utc::now() + TimeDelta::days(1),
+    );
+    save_subscriber(&subscriber).await;
+    save_subscription_token(&subscription_token).await;
+
+    let command = confirm_subscription_command(token);
+    let sut = new_command_executor(
+        unit_of_work,
+        subscriber_repository.clone(),
+        subscription_token_repository.clone(),
+        (
+            event_publisher_spy(),
+            ConfirmationEmailPublisher::new(
+                subscriber_repository,
+                subscription_token_repository,
+                outbox_repository,
+                email_renderer(),
+                link_builder(),
+                unsubscribe_key(),
+            ),
+        ),
+        unsubscribe_key(),
+    );
+
+    // Act
+    sut(command).await.unwrap();
+
+    // Assert
+    let actual = find_subscription_token_by_subscriber_id(subscriber.id()).await;
+    assert!(actual.used_at().is_some());
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_raises_token_already_used_error_if_token_is_used_twice(
+    #[future(awt)] unit_of_work: SqlxUnitOfWork,
+    subscriber_repository: SqlxSubscriberRepository,
+    subscription_token_repository: SqlxSubscriptionTokenRepository,
+    outbox_repository: SqlxOutboxRepository,
+    subscriber: Subscriber,
+    token: String,
+) {
+    // Arrange
+    let subscription_token = subscription_token(
+        token.clone(),
+        *subscriber.id(),
+        Utc::now() + TimeDelta::days(1),
+    );
+    save_subscriber(&subscriber).await;
+    save_subscription_token(&subscription_token).await;
+
+    let command = confirm_subscription_command(token);
+    let sut = new_command_executor(
+        unit_of_work,
+        subscriber_repository.clone(),
+        subscription_token_repository.clone(),
+        (
+            event_publisher_spy(),
+            ConfirmationEmailPublisher::new(
+                subscriber_repository,
+                subscription_token_repository,
+                outbox_repository,
+                email_renderer(),
+                link_builder(),
+                unsubscribe_key(),
+            ),
+        ),
+        unsubscribe_key(),
+    );
+    sut(command.clone()).await.unwrap();
+
+    // Act
+    let actual = sut(command).await.unwrap_err();
+
+    // Assert
+    assert!(matches!(actual, Error::TokenAlreadyUsed));
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_lets_only_one_of_concurrent_confirmations_use_token(
+    #[future(awt)] unit_of_work: SqlxUnitOfWork,
+    subscriber_repository: SqlxSubscriberRepository,
+    subscription_token_repository: SqlxSubscriptionTokenRepository,
+    outbox_repository: SqlxOutboxRepository,
+    subscriber: Subscriber,
+    token: String,
+) {
+    // Arrange
+    let subscription_token = subscription_token(
+        token.clone(),
+        *subscriber.id(),
+        Utc::now() + TimeDelta::days(1),
+    );
+    save_subscriber(&subscriber).await;
+    save_subscription_token(&subscription_token).await;
+
+    let command = confirm_subscription_command(token);
+    let sut = new_command_executor(
+        unit_of_work,
+        subscriber_repository.clone(),
+        subscription_token_repository.clone(),
+        (
+            event_publisher_spy(),
+            ConfirmationEmailPublisher::new(
+                subscriber_repository,
+                subscription_token_repository,
+                outbox_repository,
+                email_renderer(),
+                link_builder(),
+                unsubscribe_key(),
+            ),
+        ),
+        unsubscribe_key(),
+    );
+
+    // Act
+    let (first, second) = tokio::join!(sut(command.clone()), sut(command));
+
+    // Assert
+    let results = [first, second];
+    assert_eq!(results.iter().filter(|result| result.is_ok()).count(), 1);
+    assert!(results
+        .iter()
+        .any(|result| matches!(result, Err(Error::TokenAlreadyUsed))));
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_raises_token_expired_error_and_keeps_subscriber_pending_if_token_has_expired(
+    #[future(awt)] unit_of_work: SqlxUnitOfWork,
+    subscriber_repository: SqlxSubscriberRepository,
+    subscription_token_repository: SqlxSubscriptionTokenRepository,
+    outbox_repository: SqlxOutboxRepository,
+    subscriber: Subscriber,
+    token: String,
+) {
+    // Arrange
+    let subscription_token = subscription_token(
+        token.clone(),
+        *subscriber.id(),
+        Utc::now() - TimeDelta::minutes(1),
+    );
+    save_subscriber(&subscriber).await;
+    save_subscription_token(&subscription_token).await;
+
+    let command = confirm_subscription_command(token);
+    let sut = new_command_executor(
+        unit_of_work,
+        subscriber_repository.clone(),
+        subscription_token_repository.clone(),
+        (
+            event_publisher_spy(),
+            ConfirmationEmailPublisher::new(
+                subscriber_repository,
+                subscription_token_repository,
+                outbox_repository,
+                email_renderer(),
+                link_builder(),
+                unsubscribe_key(),
+            ),
+        ),
+        unsubscribe_key(),
+    );
+
+    // Act
+    let actual = sut(command).await.unwrap_err();
+
+    // Assert
+    assert!(matches!(actual, Error::TokenExpired));
+
+    let subscriber = find_subscriber_by_email(subscriber.email()).await;
+    assert!(matches!(subscriber.status(), Status::Pending));
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_publishes_subscription_confirmed_event(
+    #[future(awt)] unit_of_work: SqlxUnitOfWork,
+    subscriber_repository: SqlxSubscriberRepository,
+    subscription_token_repository: SqlxSubscriptionTokenRepository,
+    outbox_repository: SqlxOutboxRepository,
+    event_publisher_spy: EventPublisherSpy,
+    subscriber: Subscriber,
+    token: String,
+) {
+    // Arrange
+    let subscription_token = subscription_token(
+        token.clone(),
+        *subscriber.id(),
+        Utc::now() + TimeDelta::days(1),
+    );
+    save_subscriber(&subscriber).await;
+    save_subscription_token(&subscription_token).await;
+
+    let command = confirm_subscription_command(token);
+    let sut = new_command_executor(
+        unit_of_work,
+        subscriber_repository.clone(),
+        subscription_token_repository.clone(),
+        (
+            event_publisher_spy.clone(),
+            ConfirmationEmailPublisher::new(
+                subscriber_repository,
+                subscription_token_repository,
+                outbox_repository,
+                email_renderer(),
+                link_builder(),
+                unsubscribe_key(),
+            ),
+        ),
+        unsubscribe_key(),
+    );
+
+    // Act
+    sut(command).await.unwrap();
+
+    // Assert
+    let actual = event_publisher_spy.events().await;
+    assert_eq!(actual.len(), 1);
+    assert!(matches!(
+        actual[0],
+        SubscriberEvent::SubscriptionConfirmed { subscriber_id, .. } if subscriber_id == *subscriber.id()
+    ));
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_records_when_subscriber_has_been_confirmed(
+    #[future(awt)] unit_of_work: SqlxUnitOfWork,
+    subscriber_repository: SqlxSubscriberRepository,
+    subscription_token_repository: SqlxSubscriptionTokenRepository,
+    outbox_repository: SqlxOutboxRepository,
+    subscriber: Subscriber,
+    token: String,
+) {
+    // Arrange
+    let subscription_token = subscription_token(
+        token.clone(),
+        *subscriber.id(),
+        Utc::now() + TimeDelta::days(1),
+    );
+    save_subscriber(&subscriber).await;
+    save_subscription_token(&subscription_token).await;
+
+    let command = confirm_subscription_command(token);
+    let sut = new_command_executor(
+        unit_of_work,
+        subscriber_repository.clone(),
+        subscription_token_repository.clone(),
+        (
+            event_publisher_spy(),
+            ConfirmationEmailPublisher::new(
+                subscriber_repository,
+                subscription_token_repository,
+                outbox_repository,
+                email_renderer(),
+                link_builder(),
+                unsubscribe_key(),
+            ),
+        ),
+        unsubscribe_key(),
+    );
+    let before = Utc::now();
+
+    // Act
+    sut(command).await.unwrap();
+
+    // Assert
+    let actual = find_subscriber_by_email(subscriber.email()).await;
+    let confirmed_at = *actual.confirmed_at().unwrap();
+    assert!(confirmed_at >= before - TimeDelta::milliseconds(1));
+    assert!(confirmed_at <= Utc::now());
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_succeeds_without_changes_if_subscriber_has_already_been_confirmed(
+    #[future(awt)] unit_of_work: SqlxUnitOfWork,
+    subscriber_repository: SqlxSubscriberRepository,
+    subscription_token_repository: SqlxSubscriptionTokenRepository,
+    outbox_repository: SqlxOutboxRepository,
+    event_publisher_spy: EventPublisherSpy,
+    subscriber: Subscriber,
+) {
+    // Arrange
+    let first_token = Uuid::now_v7().to_string();
+    let second_token = Uuid::now_v7().to_string();
+    save_subscriber(&subscriber).await;
+    for token in [&first_token, &second_token] {
+        save_subscription_token(&subscription_token(
+            token.clone(),
+            *subscriber.id(),
+            Utc::now() + TimeDelta::days(1),
+        ))
+        .await;
+    }
+
+    let sut = new_command_executor(
+        unit_of_work,
+        subscriber_repository.clone(),
+        subscription_token_repository.clone(),
+        (
+            event_publisher_spy.clone(),
+            ConfirmationEmailPublisher::new(
+                subscriber_repository,
+                subscription_token_repository,
+                outbox_repository,
+                email_renderer(),
+                link_builder(),
+                unsubscribe_key(),
+            ),
+        ),
+        unsubscribe_key(),
+    );
+    sut(confirm_subscription_command(first_token))
+        .await
+        .unwrap();
+    let confirmed = find_subscriber_by_email(subscriber.email()).await;
+
+    // Act
+    let actual = sut(confirm_subscription_command(second_token)).await;
+
+    // Assert
+    assert!(actual.is_ok());
+    let subscriber = find_subscriber_by_email(subscriber.email()).await;
+    assert!(matches!(subscriber.status(), Status::Confirmed));
+    assert_eq!(subscriber.confirmed_at(), confirmed.confirmed_at());
+    assert_eq!(event_publisher_spy.events().await.len(), 1);
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_confirms_subscriber_in_unexpected_status_and_uses_token(
+    #[future(awt)] unit_of_work: SqlxUnitOfWork,
+    subscriber_repository: SqlxSubscriberRepository,
+    subscription_token_repository: SqlxSubscriptionTokenRepository,
+    outbox_repository: SqlxOutboxRepository,
+    subscriber: Subscriber,
+    token: String,
+) {
+    // Arrange
+    let subscriber: Subscriber = SubscriberDataModel::new(
+        *subscriber.id(),
+        subscriber.name().into(),
+        subscriber.email().into(),
+        subscriber.display_email().into(),
+        subscriber.subscribed_at().naive_utc(),
+        "Bounced".into(),
+        None,
+        subscriber.locale().as_ref().into(),
+    )
+    .into();
+    let subscription_token = subscription_token(
+        token.clone(),
+        *subscriber.id(),
+        Utc::now() + TimeDelta::days(1),
+    );
+    save_subscriber(&subscriber).await;
+    save_subscription_token(&subscription_token).await;
+
+    let command = confirm_subscription_command(token);
+    let sut = new_command_executor(
+        unit_of_work,
+        subscriber_repository.clone(),
+        subscription_token_repository.clone(),
+        (
+            event_publisher_spy(),
+            ConfirmationEmailPublisher::new(
+                subscriber_repository,
+                subscription_token_repository,
+                outbox_repository,
+                email_renderer(),
+                link_builder(),
+                unsubscribe_key(),
+            ),
+        ),
+        unsubscribe_key(),
+    );
+
+    // Act
+    let actual = sut(command).await;
+
+    // Assert
+    assert!(actual.is_ok());
+    let subscriber = find_subscriber_by_email(subscriber.email()).await;
+    assert!(matches!(subscriber.status(), Status::Confirmed));
+    let subscription_token = find_subscription_token_by_subscriber_id(subscriber.id()).await;
+    assert!(subscription_token.used_at().is_some());
+}
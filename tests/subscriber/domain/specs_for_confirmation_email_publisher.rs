@@ -0,0 +1,128 @@
+use chrono::Utc;
+use zero2prod::subscriber::domain::infrastructure::EventPublisher;
+use zero2prod::subscriber::domain::infrastructure::SubscriberRepository;
+use zero2prod::subscriber::domain::infrastructure::UnitOfWork;
+use zero2prod::subscriber::domain::model::Subscriber;
+use zero2prod::subscriber::domain::model::SubscriberEvent;
+use zero2prod::subscriber::domain::service::ConfirmationEmailPublisher;
+use zero2prod::subscriber::infrastructure::email_renderer::MinijinjaEmailRenderer;
+use zero2prod::subscriber::infrastructure::repository::SqlxOutboxRepository;
+use zero2prod::subscriber::infrastructure::repository::SqlxSubscriberRepository;
+use zero2prod::subscriber::infrastructure::repository::SqlxSubscriptionTokenRepository;
+use zero2prod::subscriber::infrastructure::unit_of_work::SqlxUnitOfWork;
+
+use crate::subscriber::domain::model::link_builder;
+use crate::subscriber::domain::model::subscriber;
+use crate::subscriber::domain::model::unsubscribe_key;
+use crate::subscriber::infrastructure::email_renderer::email_renderer;
+use crate::subscriber::infrastructure::repository::count_outbox_messages_by_subscriber_id;
+use crate::subscriber::infrastructure::repository::count_subscription_tokens_by_subscriber_id;
+use crate::subscriber::infrastructure::repository::find_outbox_message_by_subscriber_id;
+use crate::subscriber::infrastructure::repository::outbox_repository;
+use crate::subscriber::infrastructure::repository::subscriber_repository;
+use crate::subscriber::infrastructure::repository::subscription_token_repository;
+use crate::subscriber::infrastructure::repository::unit_of_work;
+
+type Sut = ConfirmationEmailPublisher<
+    SqlxSubscriberRepository,
+    SqlxSubscriptionTokenRepository,
+    SqlxOutboxRepository,
+    MinijinjaEmailRenderer,
+>;
+
+#[rstest::fixture]
+fn sut() -> Sut {
+    ConfirmationEmailPublisher::new(
+        subscriber_repository(),
+        subscription_token_repository(),
+        outbox_repository(),
+        email_renderer(),
+        link_builder(),
+        unsubscribe_key(),
+    )
+}
+
+async fn publish(
+    unit_of_work: &SqlxUnitOfWork,
+    sut: &Sut,
+    subscriber: &Subscriber,
+    event: &SubscriberEvent,
+) {
+    let mut transaction = unit_of_work.begin().await.unwrap();
+    subscriber_repository()
+        .save(&mut transaction, subscriber)
+        .await
+        .unwrap();
+    sut.publish(&mut transaction, event).await.unwrap();
+    unit_of_work.commit(transaction).await.unwrap();
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_queues_confirmation_email_with_fresh_token_on_registration(
+    #[future(awt)] unit_of_work: SqlxUnitOfWork,
+    sut: Sut,
+    mut subscriber: Subscriber,
+) {
+    // Arrange
+    let event = subscriber.take_events().remove(0);
+
+    // Act
+    publish(&unit_of_work, &sut, &subscriber, &event).await;
+
+    // Assert
+    assert_eq!(
+        count_subscription_tokens_by_subscriber_id(subscriber.id()).await,
+        1
+    );
+    assert_eq!(
+        count_outbox_messages_by_subscriber_id(subscriber.id()).await,
+        1
+    );
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_queues_confirmation_email_with_unsubscribe_url_for_list_unsubscribe_header(
+    #[future(awt)] unit_of_work: SqlxUnitOfWork,
+    sut: Sut,
+    mut subscriber: Subscriber,
+) {
+    // Arrange
+    let event = subscriber.take_events().remove(0);
+
+    // Act
+    publish(&unit_of_work, &sut, &subscriber, &event).await;
+
+    // Assert
+    let actual = find_outbox_message_by_subscriber_id(subscriber.id()).await;
+    let unsubscribe_url = actual.message().unwrap().unsubscribe_url().unwrap();
+    assert!(unsubscribe_url.contains("/subscriptions/unsubscribe?token="));
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_queues_nothing_on_other_events(
+    #[future(awt)] unit_of_work: SqlxUnitOfWork,
+    sut: Sut,
+    subscriber: Subscriber,
+) {
+    // Arrange
+    let event = SubscriberEvent::SubscriberUnsubscribed {
+        subscriber_id: *subscriber.id(),
+        occurred_at: Utc::now(),
+    };
+
+    // Act
+    publish(&unit_of_work, &sut, &subscriber, &event).await;
+
+    // Assert
+    assert_eq!(
+        count_subscription_tokens_by_subscriber_id(subscriber.id()).await,
+        0
+    );
+    assert_eq!(
+        count_outbox_messages_by_subscriber_id(subscriber.id()).await,
+        0
+    );
+}
@@ -0,0 +1,482 @@
+use std::time::Duration;
+
+use chrono::Utc;
+use reqwest::StatusCode;
+use sqlx::Pool;
+use sqlx::Postgres;
+use url::Url;
+use wiremock::ResponseTemplate;
+use zero2prod::common::email::EmailMessage;
+use zero2prod::common::email_client::PostmarkEmailClient;
+use zero2prod::common::retry::RetryPolicy;
+use zero2prod::subscriber::domain::infrastructure::OutboxRepository;
+use zero2prod::subscriber::domain::infrastructure::SubscriberRepository;
+use zero2prod::subscriber::domain::infrastructure::UnitOfWork;
+use zero2prod::subscriber::domain::model::OutboxMessage;
+use zero2prod::subscriber::domain::model::Subscriber;
+use zero2prod::subscriber::domain::service::dispatch_outbox_messages;
+use zero2prod::subscriber::infrastructure::repository::SqlxOutboxRepository;
+use zero2prod::subscriber::infrastructure::repository::SqlxSubscriberRepository;
+use zero2prod::subscriber::infrastructure::unit_of_work::SqlxUnitOfWork;
+
+use crate::common::email::email_message;
+use crate::common::email_client::email_server_and_client;
+use crate::common::email_client::extract_first_received_request;
+use crate::common::email_client::faulty_email_server_and_client;
+use crate::common::email_client::postmark_server_and_client;
+use crate::subscriber::domain::model::subscriber;
+use crate::subscriber::infrastructure::repository::find_outbox_message_by_subscriber_id_in;
+use crate::subscriber::infrastructure::repository::isolated_pool;
+use crate::subscriber::infrastructure::repository::outbox_repository;
+use crate::subscriber::infrastructure::repository::subscriber_repository;
+
+#[rstest::fixture]
+fn retry_policy() -> RetryPolicy {
+    RetryPolicy::new(3, Duration::from_secs(60), Duration::from_secs(3600))
+}
+
+async fn save_outbox_message(
+    pool: &Pool<Postgres>,
+    subscriber: &Subscriber,
+    email_message: EmailMessage,
+) -> OutboxMessage {
+    let unit_of_work = SqlxUnitOfWork::new(pool.clone());
+    let mut transaction = unit_of_work.begin().await.unwrap();
+    let outbox_message = OutboxMessage::create(*subscriber.id(), email_message);
+    subscriber_repository()
+        .save(&mut transaction, subscriber)
+        .await
+        .unwrap();
+    outbox_repository()
+        .save(&mut transaction, &outbox_message)
+        .await
+        .unwrap();
+    unit_of_work.commit(transaction).await.unwrap();
+    outbox_message
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_sends_sending_email_request_with_authorization_token_to_email_server_correctly(
+    #[future(awt)] isolated_pool: Pool<Postgres>,
+    subscriber_repository: SqlxSubscriberRepository,
+    outbox_repository: SqlxOutboxRepository,
+    #[future(awt)] email_server_and_client: (wiremock::MockServer, PostmarkEmailClient),
+    retry_policy: RetryPolicy,
+    subscriber: Subscriber,
+) {
+    // Arrange
+    let (email_server, email_client) = email_server_and_client;
+    save_outbox_message(&isolated_pool, &subscriber, email_message()).await;
+    let unit_of_work = SqlxUnitOfWork::new(isolated_pool);
+
+    // Act
+    let _ = dispatch_outbox_messages(
+        &unit_of_work,
+        &subscriber_repository,
+        &outbox_repository,
+        &email_client,
+        &retry_policy,
+        10,
+    )
+    .await;
+
+    // Assert
+    let request = extract_first_received_request(email_server).await;
+    let actual = request
+        .headers
+        .get("X-Postmark-Server-Token")
+        .unwrap()
+        .to_str()
+        .unwrap();
+    let expected = "SECRET_TOKEN";
+    assert_eq!(actual, expected);
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_sends_sending_email_request_body_to_email_server_correctly(
+    #[future(awt)] isolated_pool: Pool<Postgres>,
+    subscriber_repository: SqlxSubscriberRepository,
+    outbox_repository: SqlxOutboxRepository,
+    #[future(awt)] email_server_and_client: (wiremock::MockServer, PostmarkEmailClient),
+    retry_policy: RetryPolicy,
+    subscriber: Subscriber,
+) {
+    // Arrange
+    let (email_server, email_client) = email_server_and_client;
+    save_outbox_message(&isolated_pool, &subscriber, email_message()).await;
+    let unit_of_work = SqlxUnitOfWork::new(isolated_pool);
+
+    // Act
+    let _ = dispatch_outbox_messages(
+        &unit_of_work,
+        &subscriber_repository,
+        &outbox_repository,
+        &email_client,
+        &retry_policy,
+        10,
+    )
+    .await;
+
+    // Assert
+    let request = extract_first_received_request(email_server).await;
+    let actual: serde_json::Value = request.body_json().unwrap();
+    let expected = serde_json::json!({
+        "From": "test@gmail.com",
+        "To": subscriber.email(),
+        "Subject": "hello!",
+        "HtmlBody": "<p>click this link</p>",
+        "TextBody": "click this link",
+    });
+    assert_eq!(actual, expected);
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_marks_outbox_message_as_delivered_if_email_server_accepts_it(
+    #[future(awt)] isolated_pool: Pool<Postgres>,
+    subscriber_repository: SqlxSubscriberRepository,
+    outbox_repository: SqlxOutboxRepository,
+    #[future(awt)] email_server_and_client: (wiremock::MockServer, PostmarkEmailClient),
+    retry_policy: RetryPolicy,
+    subscriber: Subscriber,
+) {
+    // Arrange
+    let (_email_server, email_client) = email_server_and_client;
+    save_outbox_message(&isolated_pool, &subscriber, email_message()).await;
+    let unit_of_work = SqlxUnitOfWork::new(isolated_pool.clone());
+
+    // Act
+    let actual = dispatch_outbox_messages(
+        &unit_of_work,
+        &subscriber_repository,
+        &outbox_repository,
+        &email_client,
+        &retry_policy,
+        10,
+    )
+    .await
+    .unwrap();
+
+    // Assert
+    assert_eq!(actual, 1);
+    let outbox_message =
+        find_outbox_message_by_subscriber_id_in(&isolated_pool, subscriber.id()).await;
+    assert!(outbox_message.delivered_at().is_some());
+    assert_eq!(outbox_message.attempts(), 1);
+    assert!(outbox_message.last_error().is_none());
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_discards_body_of_outbox_message_once_delivered(
+    #[future(awt)] isolated_pool: Pool<Postgres>,
+    subscriber_repository: SqlxSubscriberRepository,
+    outbox_repository: SqlxOutboxRepository,
+    #[future(awt)] email_server_and_client: (wiremock::MockServer, PostmarkEmailClient),
+    retry_policy: RetryPolicy,
+    subscriber: Subscriber,
+) {
+    // Arrange
+    let (_email_server, email_client) = email_server_and_client;
+    save_outbox_message(&isolated_pool, &subscriber, email_message()).await;
+    let unit_of_work = SqlxUnitOfWork::new(isolated_pool.clone());
+
+    // Act
+    dispatch_outbox_messages(
+        &unit_of_work,
+        &subscriber_repository,
+        &outbox_repository,
+        &email_client,
+        &retry_policy,
+        10,
+    )
+    .await
+    .unwrap();
+
+    // Assert
+    let actual = find_outbox_message_by_subscriber_id_in(&isolated_pool, subscriber.id()).await;
+    assert!(actual.message().is_none());
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_sends_list_unsubscribe_header_kept_with_outbox_message(
+    #[future(awt)] isolated_pool: Pool<Postgres>,
+    subscriber_repository: SqlxSubscriberRepository,
+    outbox_repository: SqlxOutboxRepository,
+    #[future(awt)] email_server_and_client: (wiremock::MockServer, PostmarkEmailClient),
+    retry_policy: RetryPolicy,
+    subscriber: Subscriber,
+) {
+    // Arrange
+    let (email_server, email_client) = email_server_and_client;
+    let unsubscribe_url =
+        Url::parse("http://127.0.0.1/subscriptions/unsubscribe?token=abc").unwrap();
+    save_outbox_message(
+        &isolated_pool,
+        &subscriber,
+        email_message().with_unsubscribe_url(&unsubscribe_url),
+    )
+    .await;
+    let unit_of_work = SqlxUnitOfWork::new(isolated_pool);
+
+    // Act
+    dispatch_outbox_messages(
+        &unit_of_work,
+        &subscriber_repository,
+        &outbox_repository,
+        &email_client,
+        &retry_policy,
+        10,
+    )
+    .await
+    .unwrap();
+
+    // Assert
+    let request = extract_first_received_request(email_server).await;
+    let actual: serde_json::Value = request.body_json().unwrap();
+    assert_eq!(
+        actual["Headers"][0],
+        serde_json::json!({"Name": "List-Unsubscribe", "Value": format!("<{}>", unsubscribe_url)}),
+    );
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_does_not_keep_outbox_message_locked_while_sending_it(
+    #[future(awt)] isolated_pool: Pool<Postgres>,
+    subscriber_repository: SqlxSubscriberRepository,
+    outbox_repository: SqlxOutboxRepository,
+    #[future(awt)]
+    #[with(ResponseTemplate::new(StatusCode::OK).set_delay(Duration::from_millis(500)))]
+    postmark_server_and_client: (wiremock::MockServer, PostmarkEmailClient),
+    retry_policy: RetryPolicy,
+    subscriber: Subscriber,
+) {
+    // Arrange
+    let (_email_server, email_client) = postmark_server_and_client;
+    let outbox_message = save_outbox_message(&isolated_pool, &subscriber, email_message()).await;
+    let unit_of_work = SqlxUnitOfWork::new(isolated_pool.clone());
+
+    // Act
+    let (dispatched, locked) = tokio::join!(
+        dispatch_outbox_messages(
+            &unit_of_work,
+            &subscriber_repository,
+            &outbox_repository,
+            &email_client,
+            &retry_policy,
+            10,
+        ),
+        async {
+            tokio::time::sleep(Duration::from_millis(250)).await;
+            sqlx::query!(
+                "select id from outbox where id = $1 for update nowait",
+                outbox_message.id(),
+            )
+            .fetch_one(&isolated_pool)
+            .await
+        },
+    );
+
+    // Assert
+    assert_eq!(dispatched.unwrap(), 1);
+    assert!(locked.is_ok());
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_schedules_retry_with_backoff_if_email_server_responds_with_internal_server_error(
+    #[future(awt)] isolated_pool: Pool<Postgres>,
+    subscriber_repository: SqlxSubscriberRepository,
+    outbox_repository: SqlxOutboxRepository,
+    #[future(awt)] faulty_email_server_and_client: (wiremock::MockServer, PostmarkEmailClient),
+    retry_policy: RetryPolicy,
+    subscriber: Subscriber,
+) {
+    // Arrange
+    let (_email_server, email_client) = faulty_email_server_and_client;
+    save_outbox_message(&isolated_pool, &subscriber, email_message()).await;
+    let unit_of_work = SqlxUnitOfWork::new(isolated_pool.clone());
+
+    // Act
+    let result = dispatch_outbox_messages(
+        &unit_of_work,
+        &subscriber_repository,
+        &outbox_repository,
+        &email_client,
+        &retry_policy,
+        10,
+    )
+    .await;
+
+    // Assert
+    assert!(result.is_ok());
+    let actual = find_outbox_message_by_subscriber_id_in(&isolated_pool, subscriber.id()).await;
+    assert!(actual.delivered_at().is_none());
+    assert_eq!(actual.attempts(), 1);
+    assert!(actual.last_error().is_some());
+    assert!(*actual.next_attempt_at() > Utc::now() + retry_policy.backoff(1) / 2);
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_does_not_retry_outbox_message_before_its_backoff_elapses(
+    #[future(awt)] isolated_pool: Pool<Postgres>,
+    subscriber_repository: SqlxSubscriberRepository,
+    outbox_repository: SqlxOutboxRepository,
+    #[future(awt)] faulty_email_server_and_client: (wiremock::MockServer, PostmarkEmailClient),
+    retry_policy: RetryPolicy,
+    subscriber: Subscriber,
+) {
+    // Arrange
+    let (email_server, email_client) = faulty_email_server_and_client;
+    save_outbox_message(&isolated_pool, &subscriber, email_message()).await;
+    let unit_of_work = SqlxUnitOfWork::new(isolated_pool.clone());
+    dispatch_outbox_messages(
+        &unit_of_work,
+        &subscriber_repository,
+        &outbox_repository,
+        &email_client,
+        &retry_policy,
+        10,
+    )
+    .await
+    .unwrap();
+
+    // Act
+    let actual = dispatch_outbox_messages(
+        &unit_of_work,
+        &subscriber_repository,
+        &outbox_repository,
+        &email_client,
+        &retry_policy,
+        10,
+    )
+    .await
+    .unwrap();
+
+    // Assert
+    assert_eq!(actual, 0);
+    assert_eq!(email_server.received_requests().await.unwrap().len(), 1);
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_gives_up_on_outbox_message_after_max_attempts(
+    #[future(awt)] isolated_pool: Pool<Postgres>,
+    subscriber_repository: SqlxSubscriberRepository,
+    outbox_repository: SqlxOutboxRepository,
+    #[future(awt)] faulty_email_server_and_client: (wiremock::MockServer, PostmarkEmailClient),
+    subscriber: Subscriber,
+) {
+    // Arrange
+    let (email_server, email_client) = faulty_email_server_and_client;
+    let retry_policy = RetryPolicy::new(2, Duration::ZERO, Duration::ZERO);
+    save_outbox_message(&isolated_pool, &subscriber, email_message()).await;
+    let unit_of_work = SqlxUnitOfWork::new(isolated_pool.clone());
+    for _ in 0..retry_policy.max_attempts() {
+        dispatch_outbox_messages(
+            &unit_of_work,
+            &subscriber_repository,
+            &outbox_repository,
+            &email_client,
+            &retry_policy,
+            10,
+        )
+        .await
+        .unwrap();
+    }
+
+    // Act
+    let actual = dispatch_outbox_messages(
+        &unit_of_work,
+        &subscriber_repository,
+        &outbox_repository,
+        &email_client,
+        &retry_policy,
+        10,
+    )
+    .await
+    .unwrap();
+
+    // Assert
+    assert_eq!(actual, 0);
+    assert_eq!(email_server.received_requests().await.unwrap().len(), 2);
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_dead_letters_outbox_message_and_discards_its_body_after_max_attempts(
+    #[future(awt)] isolated_pool: Pool<Postgres>,
+    subscriber_repository: SqlxSubscriberRepository,
+    outbox_repository: SqlxOutboxRepository,
+    #[future(awt)] faulty_email_server_and_client: (wiremock::MockServer, PostmarkEmailClient),
+    subscriber: Subscriber,
+) {
+    // Arrange
+    let (_email_server, email_client) = faulty_email_server_and_client;
+    let retry_policy = RetryPolicy::new(2, Duration::ZERO, Duration::ZERO);
+    save_outbox_message(&isolated_pool, &subscriber, email_message()).await;
+    let unit_of_work = SqlxUnitOfWork::new(isolated_pool.clone());
+
+    // Act
+    for _ in 0..retry_policy.max_attempts() {
+        dispatch_outbox_messages(
+            &unit_of_work,
+            &subscriber_repository,
+            &outbox_repository,
+            &email_client,
+            &retry_policy,
+            10,
+        )
+        .await
+        .unwrap();
+    }
+
+    // Assert
+    let actual = find_outbox_message_by_subscriber_id_in(&isolated_pool, subscriber.id()).await;
+    assert!(actual.delivered_at().is_none());
+    assert!(actual.dead_lettered_at().is_some());
+    assert!(actual.message().is_none());
+    assert!(actual.last_error().is_some());
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_dead_letters_outbox_message_at_once_if_email_server_rejects_it(
+    #[future(awt)] isolated_pool: Pool<Postgres>,
+    subscriber_repository: SqlxSubscriberRepository,
+    outbox_repository: SqlxOutboxRepository,
+    #[future(awt)]
+    #[from(postmark_server_and_client)]
+    #[with(ResponseTemplate::new(StatusCode::UNPROCESSABLE_ENTITY))]
+    rejecting_email_server_and_client: (wiremock::MockServer, PostmarkEmailClient),
+    retry_policy: RetryPolicy,
+    subscriber: Subscriber,
+) {
+    // Arrange
+    let (_email_server, email_client) = rejecting_email_server_and_client;
+    save_outbox_message(&isolated_pool, &subscriber, email_message()).await;
+    let unit_of_work = SqlxUnitOfWork::new(isolated_pool.clone());
+
+    // Act
+    dispatch_outbox_messages(
+        &unit_of_work,
+        &subscriber_repository,
+        &outbox_repository,
+        &email_client,
+        &retry_policy,
+        10,
+    )
+    .await
+    .unwrap();
+
+    // Assert
+    let actual = find_outbox_message_by_subscriber_id_in(&isolated_pool, subscriber.id()).await;
+    assert_eq!(actual.attempts(), 1);
+    assert!(actual.dead_lettered_at().is_some());
+    assert!(actual.message().is_none());
+}
@@ -1,42 +1,91 @@
 use std::collections::HashSet;
 
 use fake::Fake;
+use sqlx::Pool;
+use sqlx::Postgres;
+use zero2prod::common::link::UnsubscribeToken;
+use zero2prod::common::locale::Locale;
 use zero2prod::subscriber::domain::error::Error;
+use zero2prod::subscriber::domain::infrastructure::SubscriptionTokenRepository;
 use zero2prod::subscriber::domain::model::Email;
+use zero2prod::subscriber::domain::model::Name;
 use zero2prod::subscriber::domain::model::Status;
+use zero2prod::subscriber::domain::model::Subscriber;
+use zero2prod::subscriber::domain::model::SubscriberEvent;
 use zero2prod::subscriber::domain::service::new_command_executor;
 use zero2prod::subscriber::domain::service::Command;
+use zero2prod::subscriber::domain::service::CommandExecutorFuncion;
+use zero2prod::subscriber::domain::service::ConfirmationEmailPublisher;
 use zero2prod::subscriber::domain::service::SubscribeCommand;
-use zero2prod::subscriber::infrastructure::email_client::FakeEmailClient;
-use zero2prod::subscriber::infrastructure::repository::SqlxSubscriberRepository;
-use zero2prod::subscriber::infrastructure::repository::SqlxSubscriptionTokenRepository;
+use zero2prod::subscriber::infrastructure::unit_of_work::SqlxTransaction;
+use zero2prod::subscriber::infrastructure::unit_of_work::SqlxUnitOfWork;
 
 use crate::subscriber::domain::model::email;
+use crate::subscriber::domain::model::link_builder;
+use crate::subscriber::domain::model::locale;
+use crate::subscriber::domain::model::name;
+use crate::subscriber::domain::model::subscriber;
+use crate::subscriber::domain::model::unsubscribe_key;
 use crate::subscriber::domain::service::subscribe_command as command;
 use crate::subscriber::domain::service::subscribe_commands as commands;
-use crate::subscriber::infrastructure::email_client::email_client_double;
-use crate::subscriber::infrastructure::email_client::email_server_and_client;
-use crate::subscriber::infrastructure::email_client::extract_first_received_request;
-use crate::subscriber::infrastructure::email_client::faulty_email_server_and_client;
-use crate::subscriber::infrastructure::email_client::EmailClientDouble;
+use crate::subscriber::infrastructure::email_renderer::email_renderer;
+use crate::subscriber::infrastructure::event_publisher::event_publisher_spy;
+use crate::subscriber::infrastructure::event_publisher::EventPublisherSpy;
+use crate::subscriber::infrastructure::repository::backdate_subscription_tokens_by_subscriber_id;
+use crate::subscriber::infrastructure::repository::count_outbox_messages_by_subscriber_id;
+use crate::subscriber::infrastructure::repository::count_subscription_tokens_by_subscriber_id;
+use crate::subscriber::infrastructure::repository::faulty_subscription_token_repository_stub;
+use crate::subscriber::infrastructure::repository::find_outbox_message_by_subscriber_id;
 use crate::subscriber::infrastructure::repository::find_subscriber_by_email;
 use crate::subscriber::infrastructure::repository::find_subscription_token_by_subscriber_id;
+use crate::subscriber::infrastructure::repository::find_subscription_token_by_token;
+use crate::subscriber::infrastructure::repository::outbox_repository;
+use crate::subscriber::infrastructure::repository::pool;
+use crate::subscriber::infrastructure::repository::save_subscriber;
 use crate::subscriber::infrastructure::repository::subscriber_repository;
 use crate::subscriber::infrastructure::repository::subscription_token_repository;
+use crate::subscriber::infrastructure::repository::unit_of_work;
+use crate::subscriber::infrastructure::repository::FaultySubscriptionTokenRepositoryStub;
+
+type Sut = CommandExecutorFuncion;
+
+// Wires the executor to the database, taking the collaborators specs override
+fn new_sut(
+    unit_of_work: SqlxUnitOfWork,
+    subscription_token_repository: impl SubscriptionTokenRepository<Transaction = SqlxTransaction>,
+    event_publisher_spy: EventPublisherSpy,
+) -> Sut {
+    new_command_executor(
+        unit_of_work,
+        subscriber_repository(),
+        subscription_token_repository.clone(),
+        (
+            event_publisher_spy,
+            ConfirmationEmailPublisher::new(
+                subscriber_repository(),
+                subscription_token_repository,
+                outbox_repository(),
+                email_renderer(),
+                link_builder(),
+                unsubscribe_key(),
+            ),
+        ),
+        unsubscribe_key(),
+    )
+}
+
+#[rstest::fixture]
+async fn sut(#[future(awt)] unit_of_work: SqlxUnitOfWork) -> Sut {
+    new_sut(
+        unit_of_work,
+        subscription_token_repository(),
+        event_publisher_spy(),
+    )
+}
 
 #[rstest::rstest]
 #[tokio::test]
-async fn sut_stores_new_subscribers_correctly(
-    #[future(awt)] subscriber_repository: SqlxSubscriberRepository,
-    #[future(awt)] subscription_token_repository: SqlxSubscriptionTokenRepository,
-    #[future(awt)]
-    #[from(email_client_double)]
-    dummy: EmailClientDouble,
-    command: Command,
-) {
-    // Arrange
-    let sut = new_command_executor(subscriber_repository, subscription_token_repository, dummy);
-
+async fn sut_stores_new_subscribers_correctly(#[future(awt)] sut: Sut, command: Command) {
     // Act
     let actual = sut(command.clone()).await;
 
@@ -53,17 +102,7 @@ async fn sut_stores_new_subscribers_correctly(
 
 #[rstest::rstest]
 #[tokio::test]
-async fn sut_generates_token_to_validate_email_address(
-    #[future(awt)] subscriber_repository: SqlxSubscriberRepository,
-    #[future(awt)] subscription_token_repository: SqlxSubscriptionTokenRepository,
-    #[future(awt)]
-    #[from(email_client_double)]
-    dummy: EmailClientDouble,
-    command: Command,
-) {
-    // Arrange
-    let sut = new_command_executor(subscriber_repository, subscription_token_repository, dummy);
-
+async fn sut_generates_token_to_validate_email_address(#[future(awt)] sut: Sut, command: Command) {
     // Act
     let _ = sut(command.clone()).await;
 
@@ -77,15 +116,10 @@ async fn sut_generates_token_to_validate_email_address(
 #[rstest::rstest]
 #[tokio::test]
 async fn sut_generates_randomised_token_for_each_subscription(
-    #[future(awt)] subscriber_repository: SqlxSubscriberRepository,
-    #[future(awt)] subscription_token_repository: SqlxSubscriptionTokenRepository,
-    #[future(awt)]
-    #[from(email_client_double)]
-    dummy: EmailClientDouble,
+    #[future(awt)] sut: Sut,
     commands: Vec<Command>,
 ) {
     // Arrange
-    let sut = new_command_executor(subscriber_repository, subscription_token_repository, dummy);
     let mut tokens = Vec::new();
 
     // Act
@@ -105,114 +139,408 @@ async fn sut_generates_randomised_token_for_each_subscription(
     assert_eq!(set_of_tokens.len(), tokens.len());
 }
 
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_does_not_store_subscriber_if_subscription_token_fails_to_be_saved(
+    #[future(awt)] pool: Pool<Postgres>,
+    #[future(awt)] unit_of_work: SqlxUnitOfWork,
+    #[from(faulty_subscription_token_repository_stub)]
+    subscription_token_repository: FaultySubscriptionTokenRepositoryStub,
+    command: Command,
+) {
+    // Arrange
+    let sut = new_sut(
+        unit_of_work,
+        subscription_token_repository,
+        event_publisher_spy(),
+    );
+
+    // Act
+    let actual = sut(command.clone()).await;
+
+    // Assert
+    assert!(matches!(
+        actual.unwrap_err(),
+        Error::RepositoryOperationFailed(_)
+    ));
+
+    let count = sqlx::query_scalar!(
+        "select count(*) from subscribers where email = $1",
+        command.as_subscribe().unwrap().email(),
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(count, Some(0));
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_reissues_token_and_resends_confirmation_if_subscriber_is_pending(
+    #[future(awt)] sut: Sut,
+    command: Command,
+) {
+    // Arrange
+    sut(command.clone()).await.unwrap();
+    let subscriber = find_subscriber_by_email(command.as_subscribe().unwrap().email()).await;
+    backdate_subscription_tokens_by_subscriber_id(subscriber.id()).await;
+
+    // Act
+    let actual = sut(command.clone()).await;
+
+    // Assert
+    assert!(actual.is_ok());
+    let subscriber = find_subscriber_by_email(command.as_subscribe().unwrap().email()).await;
+    assert!(matches!(subscriber.status(), Status::Pending));
+    assert_eq!(
+        count_subscription_tokens_by_subscriber_id(subscriber.id()).await,
+        2
+    );
+    assert_eq!(
+        count_outbox_messages_by_subscriber_id(subscriber.id()).await,
+        2
+    );
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_does_not_reissue_token_if_one_has_just_been_issued(
+    #[future(awt)] sut: Sut,
+    command: Command,
+) {
+    // Arrange
+    sut(command.clone()).await.unwrap();
+
+    // Act
+    let actual = sut(command.clone()).await;
+
+    // Assert
+    assert!(actual.is_ok());
+    let subscriber = find_subscriber_by_email(command.as_subscribe().unwrap().email()).await;
+    assert_eq!(
+        count_subscription_tokens_by_subscriber_id(subscriber.id()).await,
+        1
+    );
+    assert_eq!(
+        count_outbox_messages_by_subscriber_id(subscriber.id()).await,
+        1
+    );
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_stores_single_subscriber_if_same_address_subscribes_concurrently(
+    #[future(awt)] sut: Sut,
+    command: Command,
+) {
+    // Act
+    let (first, second) = tokio::join!(sut(command.clone()), sut(command.clone()));
+
+    // Assert
+    assert!(first.is_ok());
+    assert!(second.is_ok());
+    let subscriber = find_subscriber_by_email(command.as_subscribe().unwrap().email()).await;
+    assert_eq!(
+        count_subscription_tokens_by_subscriber_id(subscriber.id()).await,
+        1
+    );
+    assert_eq!(
+        count_outbox_messages_by_subscriber_id(subscriber.id()).await,
+        1
+    );
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_stores_normalized_email_address_along_with_address_as_given(
+    #[future(awt)] sut: Sut,
+    name: Name,
+    email: Email,
+    locale: Locale,
+) {
+    // Arrange
+    let given_email = email.as_ref().to_uppercase();
+    let command: Command =
+        SubscribeCommand::new(name.as_ref().into(), format!(" {} ", given_email), locale).into();
+
+    // Act
+    sut(command).await.unwrap();
+
+    // Assert
+    let actual = find_subscriber_by_email(email.as_ref()).await;
+    let (local_part, domain) = given_email.rsplit_once('@').unwrap();
+    assert_eq!(
+        actual.email(),
+        format!("{}@{}", local_part, domain.to_lowercase())
+    );
+    assert_eq!(actual.display_email(), given_email);
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_treats_email_addresses_differing_only_by_case_as_same_subscriber(
+    #[future(awt)] sut: Sut,
+    name: Name,
+    email: Email,
+    locale: Locale,
+) {
+    // Arrange
+    sut(SubscribeCommand::new(name.as_ref().into(), email.as_ref().into(), locale).into())
+        .await
+        .unwrap();
+    backdate_subscription_tokens_by_subscriber_id(
+        find_subscriber_by_email(email.as_ref()).await.id(),
+    )
+    .await;
+
+    // Act
+    let actual =
+        sut(
+            SubscribeCommand::new(name.as_ref().into(), email.as_ref().to_uppercase(), locale)
+                .into(),
+        )
+        .await;
+
+    // Assert
+    assert!(actual.is_ok());
+    let subscriber = find_subscriber_by_email(email.as_ref()).await;
+    assert_eq!(subscriber.display_email(), email.as_ref());
+    assert_eq!(
+        count_subscription_tokens_by_subscriber_id(subscriber.id()).await,
+        2
+    );
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_succeeds_silently_if_subscriber_is_already_confirmed(
+    #[future(awt)] sut: Sut,
+    mut subscriber: Subscriber,
+) {
+    // Arrange
+    subscriber.confirm().unwrap();
+    save_subscriber(&subscriber).await;
+    let command =
+        SubscribeCommand::new(name().as_ref().into(), subscriber.email().into(), locale());
+
+    // Act
+    let actual = sut(command.into()).await;
+
+    // Assert
+    assert!(actual.is_ok());
+    let subscriber = find_subscriber_by_email(subscriber.email()).await;
+    assert!(matches!(subscriber.status(), Status::Confirmed));
+    assert_eq!(
+        count_subscription_tokens_by_subscriber_id(subscriber.id()).await,
+        0
+    );
+    assert_eq!(
+        count_outbox_messages_by_subscriber_id(subscriber.id()).await,
+        0
+    );
+}
+
 #[rstest::rstest]
 #[tokio::test]
 async fn sut_raises_invalid_attributes_error_if_name_is_longer_than_256(
-    #[future(awt)] subscriber_repository: SqlxSubscriberRepository,
-    #[future(awt)] subscription_token_repository: SqlxSubscriptionTokenRepository,
-    #[future(awt)]
-    #[from(email_client_double)]
-    dummy: EmailClientDouble,
+    #[future(awt)] sut: Sut,
     email: Email,
 ) {
     // Arrange
-    let sut = new_command_executor(subscriber_repository, subscription_token_repository, dummy);
     let name = (0..(256..1024).fake::<u32>())
         .map(|_| "X")
         .collect::<String>();
-    let command = Command::from(SubscribeCommand::new(name, email.as_ref().into()));
+    let command = Command::from(SubscribeCommand::new(name, email.as_ref().into(), locale()));
 
     // Act
     let actual = sut(command).await;
 
     // Assert
     assert!(actual.is_err());
-    assert!(matches!(actual.unwrap_err(), Error::InvariantViolated(_)));
+    assert!(matches!(actual.unwrap_err(), Error::NameTooLong));
 }
 
 #[rstest::rstest]
 #[tokio::test]
-async fn sut_sends_sending_email_request_with_authorization_token_to_email_server_correctly(
-    #[future(awt)] subscriber_repository: SqlxSubscriberRepository,
-    #[future(awt)] subscription_token_repository: SqlxSubscriptionTokenRepository,
-    #[future(awt)] email_server_and_client: (wiremock::MockServer, FakeEmailClient),
+async fn sut_stores_confirmation_email_in_outbox_instead_of_sending_it(
+    #[future(awt)] sut: Sut,
     command: Command,
 ) {
-    // Arrange
-    let (email_server, email_client) = email_server_and_client;
-    let sut = new_command_executor(
-        subscriber_repository,
-        subscription_token_repository,
-        email_client.clone(),
-    );
+    // Act
+    let _ = sut(command.clone()).await;
+
+    // Assert
+    let subscriber = find_subscriber_by_email(command.as_subscribe().unwrap().email()).await;
+    let actual = find_outbox_message_by_subscriber_id(subscriber.id()).await;
+    assert_eq!(actual.attempts(), 0);
+    assert!(actual.delivered_at().is_none());
+
+    let token = extract_token_from_content(actual.message().unwrap().text_body());
+    let subscription_token = find_subscription_token_by_token(&token).await.unwrap();
+    assert_eq!(subscription_token.subscriber_id(), subscriber.id());
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_renders_confirmation_email_with_html_and_text_parts(
+    #[future(awt)] sut: Sut,
+    command: Command,
+) {
+    // Act
+    sut(command.clone()).await.unwrap();
 
+    // Assert
+    let subscriber = find_subscriber_by_email(command.as_subscribe().unwrap().email()).await;
+    let actual = find_outbox_message_by_subscriber_id(subscriber.id()).await;
+    let token = extract_token_from_content(actual.message().unwrap().text_body());
+    let confirmation_url = link_builder().confirm_subscription(&token);
+
+    assert!(actual
+        .message()
+        .unwrap()
+        .subject()
+        .contains("Zero2Prod Newsletter"));
+    assert!(actual
+        .message()
+        .unwrap()
+        .text_body()
+        .contains(confirmation_url.as_str()));
+    assert!(confirmation_url
+        .as_str()
+        .starts_with("http://127.0.0.1:8080/subscriptions/confirm?token="));
+    // HTML part is auto-escaped, so only the token is compared verbatim
+    assert!(actual.message().unwrap().html_body().contains("<a href="));
+    assert!(actual.message().unwrap().html_body().contains(&token));
+    assert!(actual
+        .message()
+        .unwrap()
+        .text_body()
+        .contains(command.as_subscribe().unwrap().name()));
+    let unsubscribe_url = link_builder()
+        .unsubscribe(&UnsubscribeToken::issue(*subscriber.id(), &unsubscribe_key()).token());
+    assert!(actual
+        .message()
+        .unwrap()
+        .text_body()
+        .contains(unsubscribe_url.as_str()));
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_renders_confirmation_email_in_locale_of_subscriber(
+    #[future(awt)] sut: Sut,
+    #[with(name(), email(), Locale::Ko)] command: Command,
+) {
     // Act
-    let _ = sut(command).await;
+    sut(command.clone()).await.unwrap();
 
     // Assert
-    let request = extract_first_received_request(email_server).await;
-    let actual = request
-        .headers
-        .get("X-Postmark-Server-Token")
+    let subscriber = find_subscriber_by_email(command.as_subscribe().unwrap().email()).await;
+    assert_eq!(subscriber.locale(), Locale::Ko);
+
+    let actual = find_outbox_message_by_subscriber_id(subscriber.id()).await;
+    assert!(actual
+        .message()
         .unwrap()
-        .to_str()
-        .unwrap();
-    let expected = "SECRET_TOKEN";
-    assert_eq!(actual, expected);
+        .subject()
+        .contains("구독을 확인해 주세요"));
+    assert!(actual
+        .message()
+        .unwrap()
+        .html_body()
+        .contains("구독 확인하기"));
+    assert!(actual
+        .message()
+        .unwrap()
+        .text_body()
+        .contains("/subscriptions/confirm?token="));
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_stores_only_hash_of_token(#[future(awt)] sut: Sut, command: Command) {
+    // Act
+    let _ = sut(command.clone()).await;
+
+    // Assert
+    let subscriber = find_subscriber_by_email(command.as_subscribe().unwrap().email()).await;
+    let outbox_message = find_outbox_message_by_subscriber_id(subscriber.id()).await;
+    let token = extract_token_from_content(outbox_message.message().unwrap().text_body());
+    let actual = find_subscription_token_by_subscriber_id(subscriber.id()).await;
+    assert_ne!(actual.token(), token);
+    assert!(!actual.token().contains(&token));
 }
 
 #[rstest::rstest]
 #[tokio::test]
-async fn sut_sends_sending_email_request_body_to_email_server_correctly(
-    #[future(awt)] subscriber_repository: SqlxSubscriberRepository,
-    #[future(awt)] subscription_token_repository: SqlxSubscriptionTokenRepository,
-    #[future(awt)] email_server_and_client: (wiremock::MockServer, FakeEmailClient),
+async fn sut_publishes_subscriber_registered_event_for_new_subscriber(
+    #[future(awt)] unit_of_work: SqlxUnitOfWork,
+    event_publisher_spy: EventPublisherSpy,
     command: Command,
 ) {
     // Arrange
-    let (email_server, email_client) = email_server_and_client;
-    let sut = new_command_executor(
-        subscriber_repository,
-        subscription_token_repository,
-        email_client.clone(),
+    let sut = new_sut(
+        unit_of_work,
+        subscription_token_repository(),
+        event_publisher_spy.clone(),
     );
 
     // Act
-    let _ = sut(command.clone()).await;
+    sut(command.clone()).await.unwrap();
 
     // Assert
     let subscriber = find_subscriber_by_email(command.as_subscribe().unwrap().email()).await;
-    let subscription_token = find_subscription_token_by_subscriber_id(subscriber.id()).await;
-    let request = extract_first_received_request(email_server).await;
-    let actual: serde_json::Value = request.body_json().unwrap();
-    let expected = serde_json::json!({
-        "From": "test@gmail.com",
-        "To": command.as_subscribe().unwrap().email(),
-        "Subject": "hello!",
-        "Content": format!("click this link: .. {} ..", subscription_token.token()).as_str(),
-    });
-    assert_eq!(actual, expected);
+    let actual = event_publisher_spy.events().await;
+    assert_eq!(actual.len(), 1);
+    assert!(matches!(
+        actual[0],
+        SubscriberEvent::SubscriberRegistered { subscriber_id, .. } if subscriber_id == *subscriber.id()
+    ));
 }
 
 #[rstest::rstest]
 #[tokio::test]
-async fn sut_raises_failed_email_operation_error_if_email_server_responds_with_internal_server_error(
-    #[future(awt)] subscriber_repository: SqlxSubscriberRepository,
-    #[future(awt)] subscription_token_repository: SqlxSubscriptionTokenRepository,
-    #[future(awt)] faulty_email_server_and_client: (wiremock::MockServer, FakeEmailClient),
-    command: Command,
+async fn sut_publishes_confirmation_reissued_event_when_pending_subscriber_subscribes_again(
+    #[future(awt)] unit_of_work: SqlxUnitOfWork,
+    event_publisher_spy: EventPublisherSpy,
+    subscriber: Subscriber,
 ) {
     // Arrange
-    let (_, email_client) = faulty_email_server_and_client;
-    let sut = new_command_executor(
-        subscriber_repository,
-        subscription_token_repository,
-        email_client.clone(),
+    save_subscriber(&subscriber).await;
+    let command = Command::from(SubscribeCommand::new(
+        subscriber.name().into(),
+        subscriber.email().into(),
+        subscriber.locale(),
+    ));
+    let sut = new_sut(
+        unit_of_work,
+        subscription_token_repository(),
+        event_publisher_spy.clone(),
     );
 
     // Act
-    let actual = sut(command).await.unwrap_err();
+    sut(command).await.unwrap();
 
     // Assert
-    assert!(matches!(actual, Error::EmailOperationFailed(_)));
+    let actual = event_publisher_spy.events().await;
+    assert_eq!(actual.len(), 1);
+    assert!(matches!(
+        actual[0],
+        SubscriberEvent::ConfirmationReissued { subscriber_id, .. } if subscriber_id == *subscriber.id()
+    ));
+    assert_eq!(
+        count_outbox_messages_by_subscriber_id(subscriber.id()).await,
+        1
+    );
+}
+
+fn extract_token_from_content(content: &str) -> String {
+    content
+        .split_once("token=")
+        .unwrap()
+        .1
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .next()
+        .unwrap()
+        .into()
 }
@@ -0,0 +1,211 @@
+use chrono::DateTime;
+use chrono::TimeDelta;
+use chrono::Utc;
+use sqlx::Pool;
+use sqlx::Postgres;
+use uuid::Uuid;
+use zero2prod::subscriber::domain::error::Error;
+use zero2prod::subscriber::domain::model::Status;
+use zero2prod::subscriber::domain::model::Subscriber;
+use zero2prod::subscriber::domain::model::SubscriberFilter;
+use zero2prod::subscriber::domain::service::new_query_executor;
+use zero2prod::subscriber::domain::service::GetSubscriberQuery;
+use zero2prod::subscriber::domain::service::ListSubscribersQuery;
+use zero2prod::subscriber::domain::service::QueryExecutor;
+use zero2prod::subscriber::infrastructure::repository::SqlxSubscriberRepository;
+use zero2prod::subscriber::infrastructure::repository::SubscriberDataModel;
+use zero2prod::subscriber::infrastructure::unit_of_work::SqlxUnitOfWork;
+
+use crate::subscriber::domain::model::email;
+use crate::subscriber::domain::model::name;
+use crate::subscriber::domain::model::subscriber;
+use crate::subscriber::infrastructure::repository::isolated_pool;
+use crate::subscriber::infrastructure::repository::save_subscriber_in;
+use crate::subscriber::infrastructure::repository::subscriber_repository;
+
+fn subscriber_with(email: &str, status: Status, subscribed_at: DateTime<Utc>) -> Subscriber {
+    SubscriberDataModel::new(
+        Uuid::now_v7(),
+        name().as_ref().into(),
+        email.into(),
+        email.into(),
+        subscribed_at.naive_utc(),
+        status.as_ref().into(),
+        None,
+        "en".into(),
+    )
+    .into()
+}
+
+async fn save_subscribers(pool: &Pool<Postgres>, subscribers: &[Subscriber]) {
+    for subscriber in subscribers {
+        save_subscriber_in(pool, subscriber).await;
+    }
+}
+
+fn ids(subscribers: &[Subscriber]) -> Vec<Uuid> {
+    subscribers
+        .iter()
+        .map(|subscriber| *subscriber.id())
+        .collect()
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_pages_through_subscribers_in_order_of_ids(
+    #[future(awt)] isolated_pool: Pool<Postgres>,
+    subscriber_repository: SqlxSubscriberRepository,
+) {
+    // Arrange
+    let subscribers: Vec<_> = (0..5)
+        .map(|_| subscriber_with(email().as_ref(), Status::Pending, Utc::now()))
+        .collect();
+    save_subscribers(&isolated_pool, &subscribers).await;
+    let sut = new_query_executor(SqlxUnitOfWork::new(isolated_pool), subscriber_repository);
+
+    // Act
+    let first = sut
+        .list_subscribers(ListSubscribersQuery::new(
+            SubscriberFilter::default(),
+            None,
+            2,
+        ))
+        .await
+        .unwrap();
+    let second = sut
+        .list_subscribers(ListSubscribersQuery::new(
+            SubscriberFilter::default(),
+            first.next_cursor().copied(),
+            2,
+        ))
+        .await
+        .unwrap();
+    let last = sut
+        .list_subscribers(ListSubscribersQuery::new(
+            SubscriberFilter::default(),
+            second.next_cursor().copied(),
+            2,
+        ))
+        .await
+        .unwrap();
+
+    // Assert
+    assert_eq!(ids(first.subscribers()), ids(&subscribers[0..2]));
+    assert_eq!(ids(second.subscribers()), ids(&subscribers[2..4]));
+    assert_eq!(ids(last.subscribers()), ids(&subscribers[4..]));
+    assert!(last.next_cursor().is_none());
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_filters_subscribers_by_status_email_and_subscription_date(
+    #[future(awt)] isolated_pool: Pool<Postgres>,
+    subscriber_repository: SqlxSubscriberRepository,
+) {
+    // Arrange
+    let now = Utc::now();
+    let expected = subscriber_with(
+        "ada@example.com",
+        Status::Confirmed,
+        now - TimeDelta::days(2),
+    );
+    save_subscribers(
+        &isolated_pool,
+        &[
+            expected.clone(),
+            subscriber_with("ada@example.org", Status::Pending, now - TimeDelta::days(2)),
+            subscriber_with(
+                "ADA@example.net",
+                Status::Confirmed,
+                now - TimeDelta::days(9),
+            ),
+            subscriber_with(
+                "grace@example.com",
+                Status::Confirmed,
+                now - TimeDelta::days(2),
+            ),
+        ],
+    )
+    .await;
+    let filter = SubscriberFilter::new(
+        Some(Status::Confirmed),
+        Some("Ada@".into()),
+        Some(now - TimeDelta::days(3)),
+        Some(now),
+    );
+    let sut = new_query_executor(SqlxUnitOfWork::new(isolated_pool), subscriber_repository);
+
+    // Act
+    let actual = sut
+        .list_subscribers(ListSubscribersQuery::new(filter, None, 10))
+        .await
+        .unwrap();
+
+    // Assert
+    assert_eq!(ids(actual.subscribers()), vec![*expected.id()]);
+    assert!(actual.next_cursor().is_none());
+}
+
+#[rstest::rstest]
+#[case(0)]
+#[case(101)]
+#[tokio::test]
+async fn sut_raises_invariant_violated_error_if_limit_is_out_of_range(
+    #[future(awt)] isolated_pool: Pool<Postgres>,
+    subscriber_repository: SqlxSubscriberRepository,
+    #[case] limit: i64,
+) {
+    // Arrange
+    let sut = new_query_executor(SqlxUnitOfWork::new(isolated_pool), subscriber_repository);
+
+    // Act
+    let actual = sut
+        .list_subscribers(ListSubscribersQuery::new(
+            SubscriberFilter::default(),
+            None,
+            limit,
+        ))
+        .await;
+
+    // Assert
+    assert!(matches!(actual, Err(Error::InvariantViolated(_))));
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_gets_subscriber_by_id(
+    #[future(awt)] isolated_pool: Pool<Postgres>,
+    subscriber_repository: SqlxSubscriberRepository,
+    subscriber: Subscriber,
+) {
+    // Arrange
+    save_subscriber_in(&isolated_pool, &subscriber).await;
+    let sut = new_query_executor(SqlxUnitOfWork::new(isolated_pool), subscriber_repository);
+
+    // Act
+    let actual = sut
+        .get_subscriber(GetSubscriberQuery::new(*subscriber.id()))
+        .await
+        .unwrap();
+
+    // Assert
+    assert_eq!(actual.id(), subscriber.id());
+    assert_eq!(actual.email(), subscriber.email());
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_raises_subscriber_not_found_error_if_subscriber_does_not_exist(
+    #[future(awt)] isolated_pool: Pool<Postgres>,
+    subscriber_repository: SqlxSubscriberRepository,
+) {
+    // Arrange
+    let id = Uuid::now_v7();
+    let sut = new_query_executor(SqlxUnitOfWork::new(isolated_pool), subscriber_repository);
+
+    // Act
+    let actual = sut.get_subscriber(GetSubscriberQuery::new(id)).await;
+
+    // Assert
+    assert!(matches!(actual, Err(Error::SubscriberNotFound(actual)) if actual == id));
+}
@@ -0,0 +1,368 @@
+use uuid::Uuid;
+use zero2prod::common::link::UnsubscribeToken;
+use zero2prod::subscriber::domain::error::Error;
+use zero2prod::subscriber::domain::model::Status;
+use zero2prod::subscriber::domain::model::Subscriber;
+use zero2prod::subscriber::domain::model::SubscriberEvent;
+use zero2prod::subscriber::domain::model::SubscriptionToken;
+use zero2prod::subscriber::domain::service::new_command_executor;
+use zero2prod::subscriber::domain::service::Command;
+use zero2prod::subscriber::domain::service::ConfirmationEmailPublisher;
+use zero2prod::subscriber::domain::service::MAX_ATTEMPTS_ON_CONCURRENCY_CONFLICT;
+use zero2prod::subscriber::infrastructure::repository::SqlxOutboxRepository;
+use zero2prod::subscriber::infrastructure::repository::SqlxSubscriberRepository;
+use zero2prod::subscriber::infrastructure::repository::SqlxSubscriptionTokenRepository;
+use zero2prod::subscriber::infrastructure::unit_of_work::SqlxUnitOfWork;
+
+use crate::subscriber::domain::model::link_builder;
+use crate::subscriber::domain::model::subscriber;
+use crate::subscriber::domain::model::unsubscribe_key;
+use crate::subscriber::domain::service::confirm_subscription_command;
+use crate::subscriber::domain::service::unsubscribe_command;
+use crate::subscriber::domain::service::unsubscribe_command as command;
+use crate::subscriber::infrastructure::email_renderer::email_renderer;
+use crate::subscriber::infrastructure::event_publisher::event_publisher_spy;
+use crate::subscriber::infrastructure::event_publisher::EventPublisherSpy;
+use crate::subscriber::infrastructure::repository::find_subscriber_by_email;
+use crate::subscriber::infrastructure::repository::outbox_repository;
+use crate::subscriber::infrastructure::repository::save_subscriber;
+use crate::subscriber::infrastructure::repository::save_subscription_token;
+use crate::subscriber::infrastructure::repository::subscriber_repository;
+use crate::subscriber::infrastructure::repository::subscription_token_repository;
+use crate::subscriber::infrastructure::repository::unit_of_work;
+use crate::subscriber::infrastructure::repository::ConflictingSubscriberRepositoryStub;
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_changes_subscriber_status_as_unsubscribed_if_token_is_signed_correctly(
+    #[future(awt)] unit_of_work: SqlxUnitOfWork,
+    subscriber_repository: SqlxSubscriberRepository,
+    subscription_token_repository: SqlxSubscriptionTokenRepository,
+    outbox_repository: SqlxOutboxRepository,
+    mut subscriber: Subscriber,
+) {
+    // Arrange
+    subscriber.confirm().unwrap();
+    save_subscriber(&subscriber).await;
+
+    let token = UnsubscribeToken::issue(*subscriber.id(), &unsubscribe_key());
+    let command = unsubscribe_command(token.token());
+    let sut = new_command_executor(
+        unit_of_work,
+        subscriber_repository.clone(),
+        subscription_token_repository.clone(),
+        (
+            event_publisher_spy(),
+            ConfirmationEmailPublisher::new(
+                subscriber_repository,
+                subscription_token_repository,
+                outbox_repository,
+                email_renderer(),
+                link_builder(),
+                unsubscribe_key(),
+            ),
+        ),
+        unsubscribe_key(),
+    );
+
+    // Act
+    sut(command).await.unwrap();
+
+    // Assert
+    let actual = find_subscriber_by_email(subscriber.email()).await;
+    assert!(matches!(actual.status(), Status::Unsubscribed));
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_invalidates_confirmation_links_sent_before_unsubscribing(
+    #[future(awt)] unit_of_work: SqlxUnitOfWork,
+    subscriber_repository: SqlxSubscriberRepository,
+    subscription_token_repository: SqlxSubscriptionTokenRepository,
+    outbox_repository: SqlxOutboxRepository,
+    subscriber: Subscriber,
+) {
+    // Arrange
+    save_subscriber(&subscriber).await;
+    let subscription_token = SubscriptionToken::create(*subscriber.id());
+    save_subscription_token(&subscription_token).await;
+
+    let token = UnsubscribeToken::issue(*subscriber.id(), &unsubscribe_key());
+    let sut = new_command_executor(
+        unit_of_work,
+        subscriber_repository.clone(),
+        subscription_token_repository.clone(),
+        (
+            event_publisher_spy(),
+            ConfirmationEmailPublisher::new(
+                subscriber_repository,
+                subscription_token_repository,
+                outbox_repository,
+                email_renderer(),
+                link_builder(),
+                unsubscribe_key(),
+            ),
+        ),
+        unsubscribe_key(),
+    );
+    sut(unsubscribe_command(token.token())).await.unwrap();
+
+    // Act
+    let actual = sut(confirm_subscription_command(
+        subscription_token.token().into(),
+    ))
+    .await
+    .unwrap_err();
+
+    // Assert
+    assert!(matches!(actual, Error::TokenNotFound));
+    let subscriber = find_subscriber_by_email(subscriber.email()).await;
+    assert!(matches!(subscriber.status(), Status::Unsubscribed));
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_raises_token_invalid_error_if_token_is_not_signed(
+    #[future(awt)] unit_of_work: SqlxUnitOfWork,
+    subscriber_repository: SqlxSubscriberRepository,
+    subscription_token_repository: SqlxSubscriptionTokenRepository,
+    outbox_repository: SqlxOutboxRepository,
+    command: Command,
+) {
+    // Arrange
+    let sut = new_command_executor(
+        unit_of_work,
+        subscriber_repository.clone(),
+        subscription_token_repository.clone(),
+        (
+            event_publisher_spy(),
+            ConfirmationEmailPublisher::new(
+                subscriber_repository,
+                subscription_token_repository,
+                outbox_repository,
+                email_renderer(),
+                link_builder(),
+                unsubscribe_key(),
+            ),
+        ),
+        unsubscribe_key(),
+    );
+
+    // Act
+    let actual = sut(command).await.unwrap_err();
+
+    // Assert
+    assert!(matches!(actual, Error::TokenInvalid));
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_raises_token_invalid_error_if_token_is_signed_for_another_subscriber(
+    #[future(awt)] unit_of_work: SqlxUnitOfWork,
+    subscriber_repository: SqlxSubscriberRepository,
+    subscription_token_repository: SqlxSubscriptionTokenRepository,
+    outbox_repository: SqlxOutboxRepository,
+    subscriber: Subscriber,
+) {
+    // Arrange
+    save_subscriber(&subscriber).await;
+
+    let token = UnsubscribeToken::issue(Uuid::now_v7(), &unsubscribe_key()).token();
+    let (_, signature) = token.split_once('.').unwrap();
+    let command = unsubscribe_command(format!("{}.{}", subscriber.id(), signature));
+    let sut = new_command_executor(
+        unit_of_work,
+        subscriber_repository.clone(),
+        subscription_token_repository.clone(),
+        (
+            event_publisher_spy(),
+            ConfirmationEmailPublisher::new(
+                subscriber_repository,
+                subscription_token_repository,
+                outbox_repository,
+                email_renderer(),
+                link_builder(),
+                unsubscribe_key(),
+            ),
+        ),
+        unsubscribe_key(),
+    );
+
+    // Act
+    let actual = sut(command).await.unwrap_err();
+
+    // Assert
+    assert!(matches!(actual, Error::TokenInvalid));
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_raises_subscriber_not_found_error_if_subscriber_does_not_exist(
+    #[future(awt)] unit_of_work: SqlxUnitOfWork,
+    subscriber_repository: SqlxSubscriberRepository,
+    subscription_token_repository: SqlxSubscriptionTokenRepository,
+    outbox_repository: SqlxOutboxRepository,
+) {
+    // Arrange
+    let token = UnsubscribeToken::issue(Uuid::now_v7(), &unsubscribe_key());
+    let command = unsubscribe_command(token.token());
+    let sut = new_command_executor(
+        unit_of_work,
+        subscriber_repository.clone(),
+        subscription_token_repository.clone(),
+        (
+            event_publisher_spy(),
+            ConfirmationEmailPublisher::new(
+                subscriber_repository,
+                subscription_token_repository,
+                outbox_repository,
+                email_renderer(),
+                link_builder(),
+                unsubscribe_key(),
+            ),
+        ),
+        unsubscribe_key(),
+    );
+
+    // Act
+    let actual = sut(command).await.unwrap_err();
+
+    // Assert
+    assert!(matches!(actual, Error::SubscriberNotFound(_)));
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_publishes_subscriber_unsubscribed_event(
+    #[future(awt)] unit_of_work: SqlxUnitOfWork,
+    subscriber_repository: SqlxSubscriberRepository,
+    subscription_token_repository: SqlxSubscriptionTokenRepository,
+    outbox_repository: SqlxOutboxRepository,
+    event_publisher_spy: EventPublisherSpy,
+    subscriber: Subscriber,
+) {
+    // Arrange
+    save_subscriber(&subscriber).await;
+
+    let token = UnsubscribeToken::issue(*subscriber.id(), &unsubscribe_key());
+    let command = unsubscribe_command(token.token());
+    let sut = new_command_executor(
+        unit_of_work,
+        subscriber_repository.clone(),
+        subscription_token_repository.clone(),
+        (
+            event_publisher_spy.clone(),
+            ConfirmationEmailPublisher::new(
+                subscriber_repository,
+                subscription_token_repository,
+                outbox_repository,
+                email_renderer(),
+                link_builder(),
+                unsubscribe_key(),
+            ),
+        ),
+        unsubscribe_key(),
+    );
+
+    // Act
+    sut(command).await.unwrap();
+
+    // Assert
+    let actual = event_publisher_spy.events().await;
+    assert_eq!(actual.len(), 1);
+    assert!(matches!(
+        actual[0],
+        SubscriberEvent::SubscriberUnsubscribed { subscriber_id, .. } if subscriber_id == *subscriber.id()
+    ));
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_retries_command_if_subscriber_is_modified_concurrently(
+    #[future(awt)] unit_of_work: SqlxUnitOfWork,
+    subscription_token_repository: SqlxSubscriptionTokenRepository,
+    outbox_repository: SqlxOutboxRepository,
+    subscriber: Subscriber,
+) {
+    // Arrange
+    save_subscriber(&subscriber).await;
+
+    let subscriber_repository =
+        ConflictingSubscriberRepositoryStub::new(MAX_ATTEMPTS_ON_CONCURRENCY_CONFLICT - 1);
+    let token = UnsubscribeToken::issue(*subscriber.id(), &unsubscribe_key());
+    let command = unsubscribe_command(token.token());
+    let sut = new_command_executor(
+        unit_of_work,
+        subscriber_repository.clone(),
+        subscription_token_repository.clone(),
+        (
+            event_publisher_spy(),
+            ConfirmationEmailPublisher::new(
+                subscriber_repository.clone(),
+                subscription_token_repository,
+                outbox_repository,
+                email_renderer(),
+                link_builder(),
+                unsubscribe_key(),
+            ),
+        ),
+        unsubscribe_key(),
+    );
+
+    // Act
+    sut(command).await.unwrap();
+
+    // Assert
+    let actual = find_subscriber_by_email(subscriber.email()).await;
+    assert!(matches!(actual.status(), Status::Unsubscribed));
+    assert_eq!(
+        subscriber_repository.attempts(),
+        MAX_ATTEMPTS_ON_CONCURRENCY_CONFLICT
+    );
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_raises_concurrency_conflict_error_if_every_attempt_conflicts(
+    #[future(awt)] unit_of_work: SqlxUnitOfWork,
+    subscription_token_repository: SqlxSubscriptionTokenRepository,
+    outbox_repository: SqlxOutboxRepository,
+    subscriber: Subscriber,
+) {
+    // Arrange
+    save_subscriber(&subscriber).await;
+
+    let subscriber_repository =
+        ConflictingSubscriberRepositoryStub::new(MAX_ATTEMPTS_ON_CONCURRENCY_CONFLICT);
+    let token = UnsubscribeToken::issue(*subscriber.id(), &unsubscribe_key());
+    let command = unsubscribe_command(token.token());
+    let sut = new_command_executor(
+        unit_of_work,
+        subscriber_repository.clone(),
+        subscription_token_repository.clone(),
+        (
+            event_publisher_spy(),
+            ConfirmationEmailPublisher::new(
+                subscriber_repository.clone(),
+                subscription_token_repository,
+                outbox_repository,
+                email_renderer(),
+                link_builder(),
+                unsubscribe_key(),
+            ),
+        ),
+        unsubscribe_key(),
+    );
+
+    // Act
+    let actual = sut(command).await.unwrap_err();
+
+    // Assert
+    assert!(matches!(actual, Error::ConcurrencyConflict(_)));
+    assert_eq!(
+        subscriber_repository.attempts(),
+        MAX_ATTEMPTS_ON_CONCURRENCY_CONFLICT
+    );
+    let subscriber = find_subscriber_by_email(subscriber.email()).await;
+    assert!(matches!(subscriber.status(), Status::Pending));
+}
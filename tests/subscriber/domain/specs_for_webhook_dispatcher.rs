@@ -0,0 +1,413 @@
+use std::time::Duration;
+
+use chrono::Utc;
+use sqlx::Pool;
+use sqlx::Postgres;
+use wiremock::matchers::method;
+use wiremock::Mock;
+use wiremock::MockServer;
+use wiremock::ResponseTemplate;
+use zero2prod::common::retry::RetryPolicy;
+use zero2prod::subscriber::domain::infrastructure::SubscriberRepository;
+use zero2prod::subscriber::domain::infrastructure::UnitOfWork;
+use zero2prod::subscriber::domain::infrastructure::WebhookDeliveryRepository;
+use zero2prod::subscriber::domain::infrastructure::WebhookEndpointRepository;
+use zero2prod::subscriber::domain::model::Subscriber;
+use zero2prod::subscriber::domain::model::WebhookDelivery;
+use zero2prod::subscriber::domain::model::WebhookEndpoint;
+use zero2prod::subscriber::domain::model::WebhookSignature;
+use zero2prod::subscriber::domain::service::dispatch_webhook_deliveries;
+use zero2prod::subscriber::infrastructure::repository::SqlxWebhookDeliveryRepository;
+use zero2prod::subscriber::infrastructure::repository::SqlxWebhookEndpointRepository;
+use zero2prod::subscriber::infrastructure::unit_of_work::SqlxUnitOfWork;
+use zero2prod::subscriber::infrastructure::webhook_client::ReqwestWebhookClient;
+
+use crate::subscriber::domain::model::subscriber;
+use crate::subscriber::infrastructure::repository::find_webhook_deliveries_by_endpoint_id_in;
+use crate::subscriber::infrastructure::repository::isolated_pool;
+use crate::subscriber::infrastructure::repository::subscriber_repository;
+use crate::subscriber::infrastructure::repository::webhook_delivery_repository;
+use crate::subscriber::infrastructure::repository::webhook_endpoint_repository;
+
+#[rstest::fixture]
+fn retry_policy() -> RetryPolicy {
+    RetryPolicy::new(3, Duration::from_secs(60), Duration::from_secs(3600))
+}
+
+#[rstest::fixture]
+fn webhook_client() -> ReqwestWebhookClient {
+    ReqwestWebhookClient::new(Duration::from_secs(1), true)
+}
+
+async fn receiver_responding_with(status_code: u16) -> MockServer {
+    let receiver = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(status_code))
+        .mount(&receiver)
+        .await;
+    receiver
+}
+
+async fn save_webhook_delivery(
+    pool: &Pool<Postgres>,
+    url: &str,
+    mut subscriber: Subscriber,
+) -> (WebhookEndpoint, WebhookDelivery) {
+    let unit_of_work = SqlxUnitOfWork::new(pool.clone());
+    let mut transaction = unit_of_work.begin().await.unwrap();
+    let webhook_endpoint = WebhookEndpoint::register(url, None).unwrap();
+    let event = subscriber.take_events().remove(0);
+    let webhook_delivery = WebhookDelivery::create(*webhook_endpoint.id(), &event, &subscriber);
+    subscriber_repository()
+        .save(&mut transaction, &subscriber)
+        .await
+        .unwrap();
+    webhook_endpoint_repository()
+        .save(&mut transaction, &webhook_endpoint)
+        .await
+        .unwrap();
+    webhook_delivery_repository()
+        .save(&mut transaction, &webhook_delivery)
+        .await
+        .unwrap();
+    unit_of_work.commit(transaction).await.unwrap();
+    (webhook_endpoint, webhook_delivery)
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_sends_payload_with_signature_verifiable_by_shared_secret(
+    #[future(awt)] isolated_pool: Pool<Postgres>,
+    webhook_endpoint_repository: SqlxWebhookEndpointRepository,
+    webhook_delivery_repository: SqlxWebhookDeliveryRepository,
+    webhook_client: ReqwestWebhookClient,
+    retry_policy: RetryPolicy,
+    subscriber: Subscriber,
+) {
+    // Arrange
+    let receiver = receiver_responding_with(200).await;
+    let (webhook_endpoint, webhook_delivery) =
+        save_webhook_delivery(&isolated_pool, &receiver.uri(), subscriber.clone()).await;
+    let unit_of_work = SqlxUnitOfWork::new(isolated_pool);
+
+    // Act
+    dispatch_webhook_deliveries(
+        &unit_of_work,
+        &webhook_endpoint_repository,
+        &webhook_delivery_repository,
+        &webhook_client,
+        &retry_policy,
+        10,
+    )
+    .await
+    .unwrap();
+
+    // Assert
+    let requests = receiver.received_requests().await.unwrap();
+    let request = requests.first().unwrap();
+    let header = |name: &str| request.headers.get(name).unwrap().to_str().unwrap();
+    assert_eq!(header("Webhook-Id"), webhook_delivery.id().to_string());
+    let payload = String::from_utf8(request.body.clone()).unwrap();
+    let verified = WebhookSignature::verify(
+        webhook_endpoint.secret(),
+        webhook_delivery.id(),
+        header("Webhook-Timestamp").parse().unwrap(),
+        &payload,
+        header("Webhook-Signature"),
+        Utc::now(),
+    );
+    assert!(verified.is_ok());
+
+    let actual: serde_json::Value = serde_json::from_str(&payload).unwrap();
+    assert_eq!(actual["type"], "subscriber.registered");
+    assert_eq!(actual["data"]["email"], subscriber.email());
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_marks_webhook_delivery_as_delivered_if_receiver_accepts_it(
+    #[future(awt)] isolated_pool: Pool<Postgres>,
+    webhook_endpoint_repository: SqlxWebhookEndpointRepository,
+    webhook_delivery_repository: SqlxWebhookDeliveryRepository,
+    webhook_client: ReqwestWebhookClient,
+    retry_policy: RetryPolicy,
+    subscriber: Subscriber,
+) {
+    // Arrange
+    let receiver = receiver_responding_with(204).await;
+    let (webhook_endpoint, _) =
+        save_webhook_delivery(&isolated_pool, &receiver.uri(), subscriber).await;
+    let unit_of_work = SqlxUnitOfWork::new(isolated_pool.clone());
+
+    // Act
+    let actual = dispatch_webhook_deliveries(
+        &unit_of_work,
+        &webhook_endpoint_repository,
+        &webhook_delivery_repository,
+        &webhook_client,
+        &retry_policy,
+        10,
+    )
+    .await
+    .unwrap();
+
+    // Assert
+    assert_eq!(actual, 1);
+    let webhook_deliveries =
+        find_webhook_deliveries_by_endpoint_id_in(&isolated_pool, webhook_endpoint.id()).await;
+    let webhook_delivery = webhook_deliveries.first().unwrap();
+    assert!(webhook_delivery.delivered_at().is_some());
+    assert_eq!(webhook_delivery.attempts(), 1);
+    assert_eq!(webhook_delivery.last_status_code(), Some(204));
+    assert!(webhook_delivery.last_error().is_none());
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_schedules_retry_with_status_code_if_receiver_responds_with_internal_server_error(
+    #[future(awt)] isolated_pool: Pool<Postgres>,
+    webhook_endpoint_repository: SqlxWebhookEndpointRepository,
+    webhook_delivery_repository: SqlxWebhookDeliveryRepository,
+    webhook_client: ReqwestWebhookClient,
+    retry_policy: RetryPolicy,
+    subscriber: Subscriber,
+) {
+    // Arrange
+    let receiver = receiver_responding_with(500).await;
+    let (webhook_endpoint, _) =
+        save_webhook_delivery(&isolated_pool, &receiver.uri(), subscriber).await;
+    let unit_of_work = SqlxUnitOfWork::new(isolated_pool.clone());
+
+    // Act
+    let result = dispatch_webhook_deliveries(
+        &unit_of_work,
+        &webhook_endpoint_repository,
+        &webhook_delivery_repository,
+        &webhook_client,
+        &retry_policy,
+        10,
+    )
+    .await;
+
+    // Assert
+    assert!(result.is_ok());
+    let webhook_deliveries =
+        find_webhook_deliveries_by_endpoint_id_in(&isolated_pool, webhook_endpoint.id()).await;
+    let actual = webhook_deliveries.first().unwrap();
+    assert!(actual.delivered_at().is_none());
+    assert_eq!(actual.attempts(), 1);
+    assert_eq!(actual.last_status_code(), Some(500));
+    assert!(actual.last_error().is_some());
+    assert!(*actual.next_attempt_at() > Utc::now() + retry_policy.backoff(1) / 2);
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_schedules_retry_without_status_code_if_receiver_is_unreachable(
+    #[future(awt)] isolated_pool: Pool<Postgres>,
+    webhook_endpoint_repository: SqlxWebhookEndpointRepository,
+    webhook_delivery_repository: SqlxWebhookDeliveryRepository,
+    webhook_client: ReqwestWebhookClient,
+    retry_policy: RetryPolicy,
+    subscriber: Subscriber,
+) {
+    // Arrange
+    // Nothing listens on the port, so the connection is refused
+    let (webhook_endpoint, _) =
+        save_webhook_delivery(&isolated_pool, "http://127.0.0.1:9", subscriber).await;
+    let unit_of_work = SqlxUnitOfWork::new(isolated_pool.clone());
+
+    // Act
+    let result = dispatch_webhook_deliveries(
+        &unit_of_work,
+        &webhook_endpoint_repository,
+        &webhook_delivery_repository,
+        &webhook_client,
+        &retry_policy,
+        10,
+    )
+    .await;
+
+    // Assert
+    assert!(result.is_ok());
+    let webhook_deliveries =
+        find_webhook_deliveries_by_endpoint_id_in(&isolated_pool, webhook_endpoint.id()).await;
+    let actual = webhook_deliveries.first().unwrap();
+    assert!(actual.delivered_at().is_none());
+    assert_eq!(actual.attempts(), 1);
+    assert_eq!(actual.last_status_code(), None);
+    assert!(actual.last_error().is_some());
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_gives_up_on_webhook_delivery_after_max_attempts(
+    #[future(awt)] isolated_pool: Pool<Postgres>,
+    webhook_endpoint_repository: SqlxWebhookEndpointRepository,
+    webhook_delivery_repository: SqlxWebhookDeliveryRepository,
+    webhook_client: ReqwestWebhookClient,
+    subscriber: Subscriber,
+) {
+    // Arrange
+    let receiver = receiver_responding_with(503).await;
+    let retry_policy = RetryPolicy::new(2, Duration::ZERO, Duration::ZERO);
+    save_webhook_delivery(&isolated_pool, &receiver.uri(), subscriber).await;
+    let unit_of_work = SqlxUnitOfWork::new(isolated_pool.clone());
+    for _ in 0..retry_policy.max_attempts() {
+        dispatch_webhook_deliveries(
+            &unit_of_work,
+            &webhook_endpoint_repository,
+            &webhook_delivery_repository,
+            &webhook_client,
+            &retry_policy,
+            10,
+        )
+        .await
+        .unwrap();
+    }
+
+    // Act
+    let actual = dispatch_webhook_deliveries(
+        &unit_of_work,
+        &webhook_endpoint_repository,
+        &webhook_delivery_repository,
+        &webhook_client,
+        &retry_policy,
+        10,
+    )
+    .await
+    .unwrap();
+
+    // Assert
+    assert_eq!(actual, 0);
+    assert_eq!(receiver.received_requests().await.unwrap().len(), 2);
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_does_not_keep_webhook_delivery_locked_while_sending_it(
+    #[future(awt)] isolated_pool: Pool<Postgres>,
+    webhook_endpoint_repository: SqlxWebhookEndpointRepository,
+    webhook_delivery_repository: SqlxWebhookDeliveryRepository,
+    webhook_client: ReqwestWebhookClient,
+    retry_policy: RetryPolicy,
+    subscriber: Subscriber,
+) {
+    // Arrange
+    let receiver = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(204).set_delay(Duration::from_millis(500)))
+        .mount(&receiver)
+        .await;
+    let (_, webhook_delivery) =
+        save_webhook_delivery(&isolated_pool, &receiver.uri(), subscriber).await;
+    let unit_of_work = SqlxUnitOfWork::new(isolated_pool.clone());
+
+    // Act
+    let (dispatched, locked) = tokio::join!(
+        dispatch_webhook_deliveries(
+            &unit_of_work,
+            &webhook_endpoint_repository,
+            &webhook_delivery_repository,
+            &webhook_client,
+            &retry_policy,
+            10,
+        ),
+        async {
+            tokio::time::sleep(Duration::from_millis(250)).await;
+            sqlx::query!(
+                "select id from webhook_deliveries where id = $1 for update nowait",
+                webhook_delivery.id(),
+            )
+            .fetch_one(&isolated_pool)
+            .await
+        },
+    );
+
+    // Assert
+    assert_eq!(dispatched.unwrap(), 1);
+    assert!(locked.is_ok());
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_does_not_follow_redirect_of_receiver(
+    #[future(awt)] isolated_pool: Pool<Postgres>,
+    webhook_endpoint_repository: SqlxWebhookEndpointRepository,
+    webhook_delivery_repository: SqlxWebhookDeliveryRepository,
+    webhook_client: ReqwestWebhookClient,
+    retry_policy: RetryPolicy,
+    subscriber: Subscriber,
+) {
+    // Arrange
+    let target = receiver_responding_with(204).await;
+    let receiver = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(307).insert_header("Location", target.uri().as_str()))
+        .mount(&receiver)
+        .await;
+    let (webhook_endpoint, _) =
+        save_webhook_delivery(&isolated_pool, &receiver.uri(), subscriber).await;
+    let unit_of_work = SqlxUnitOfWork::new(isolated_pool.clone());
+
+    // Act
+    dispatch_webhook_deliveries(
+        &unit_of_work,
+        &webhook_endpoint_repository,
+        &webhook_delivery_repository,
+        &webhook_client,
+        &retry_policy,
+        10,
+    )
+    .await
+    .unwrap();
+
+    // Assert
+    assert!(target.received_requests().await.unwrap().is_empty());
+    let webhook_deliveries =
+        find_webhook_deliveries_by_endpoint_id_in(&isolated_pool, webhook_endpoint.id()).await;
+    let actual = webhook_deliveries.first().unwrap();
+    assert!(actual.delivered_at().is_none());
+    assert_eq!(actual.last_status_code(), Some(307));
+}
+
+#[rstest::rstest]
+#[case("127.0.0.1")]
+#[case("localhost")]
+#[tokio::test]
+async fn sut_does_not_send_to_loopback_address_unless_allowed(
+    #[future(awt)] isolated_pool: Pool<Postgres>,
+    webhook_endpoint_repository: SqlxWebhookEndpointRepository,
+    webhook_delivery_repository: SqlxWebhookDeliveryRepository,
+    retry_policy: RetryPolicy,
+    subscriber: Subscriber,
+    #[case] host: &str,
+) {
+    // Arrange
+    let receiver = receiver_responding_with(204).await;
+    let url = format!("http://{}:{}", host, receiver.address().port());
+    let (webhook_endpoint, _) = save_webhook_delivery(&isolated_pool, &url, subscriber).await;
+    let unit_of_work = SqlxUnitOfWork::new(isolated_pool.clone());
+    let webhook_client = ReqwestWebhookClient::new(Duration::from_secs(1), false);
+
+    // Act
+    dispatch_webhook_deliveries(
+        &unit_of_work,
+        &webhook_endpoint_repository,
+        &webhook_delivery_repository,
+        &webhook_client,
+        &retry_policy,
+        10,
+    )
+    .await
+    .unwrap();
+
+    // Assert
+    assert!(receiver.received_requests().await.unwrap().is_empty());
+    let webhook_deliveries =
+        find_webhook_deliveries_by_endpoint_id_in(&isolated_pool, webhook_endpoint.id()).await;
+    let actual = webhook_deliveries.first().unwrap();
+    assert!(actual.delivered_at().is_none());
+    assert_eq!(actual.last_status_code(), None);
+    assert!(actual.last_error().is_some());
+}
@@ -0,0 +1,136 @@
+use sqlx::Pool;
+use sqlx::Postgres;
+use zero2prod::subscriber::domain::infrastructure::EventPublisher;
+use zero2prod::subscriber::domain::infrastructure::SubscriberRepository;
+use zero2prod::subscriber::domain::infrastructure::UnitOfWork;
+use zero2prod::subscriber::domain::infrastructure::WebhookEndpointRepository;
+use zero2prod::subscriber::domain::model::Subscriber;
+use zero2prod::subscriber::domain::model::SubscriberEvent;
+use zero2prod::subscriber::domain::model::WebhookEndpoint;
+use zero2prod::subscriber::domain::service::WebhookEventPublisher;
+use zero2prod::subscriber::infrastructure::repository::SqlxSubscriberRepository;
+use zero2prod::subscriber::infrastructure::repository::SqlxWebhookDeliveryRepository;
+use zero2prod::subscriber::infrastructure::repository::SqlxWebhookEndpointRepository;
+use zero2prod::subscriber::infrastructure::unit_of_work::SqlxUnitOfWork;
+
+use crate::subscriber::domain::model::subscriber;
+use crate::subscriber::infrastructure::repository::find_webhook_deliveries_by_endpoint_id_in;
+use crate::subscriber::infrastructure::repository::isolated_pool;
+use crate::subscriber::infrastructure::repository::subscriber_repository;
+use crate::subscriber::infrastructure::repository::webhook_delivery_repository;
+use crate::subscriber::infrastructure::repository::webhook_endpoint_repository;
+
+type Sut = WebhookEventPublisher<
+    SqlxSubscriberRepository,
+    SqlxWebhookEndpointRepository,
+    SqlxWebhookDeliveryRepository,
+>;
+
+#[rstest::fixture]
+fn sut() -> Sut {
+    WebhookEventPublisher::new(
+        subscriber_repository(),
+        webhook_endpoint_repository(),
+        webhook_delivery_repository(),
+    )
+}
+
+async fn publish(
+    pool: &Pool<Postgres>,
+    sut: &Sut,
+    subscriber: &Subscriber,
+    event: &SubscriberEvent,
+) -> Vec<WebhookEndpoint> {
+    let unit_of_work = SqlxUnitOfWork::new(pool.clone());
+    let mut transaction = unit_of_work.begin().await.unwrap();
+    subscriber_repository()
+        .save(&mut transaction, subscriber)
+        .await
+        .unwrap();
+    let mut webhook_endpoints = Vec::new();
+    for url in [
+        "https://crm.example.com/hooks",
+        "https://example.org/events",
+    ] {
+        let webhook_endpoint = WebhookEndpoint::register(url, None).unwrap();
+        webhook_endpoint_repository()
+            .save(&mut transaction, &webhook_endpoint)
+            .await
+            .unwrap();
+        webhook_endpoints.push(webhook_endpoint);
+    }
+    sut.publish(&mut transaction, event).await.unwrap();
+    unit_of_work.commit(transaction).await.unwrap();
+    webhook_endpoints
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_queues_delivery_of_registration_for_every_endpoint(
+    #[future(awt)] isolated_pool: Pool<Postgres>,
+    sut: Sut,
+    mut subscriber: Subscriber,
+) {
+    // Arrange
+    let event = subscriber.take_events().remove(0);
+
+    // Act
+    let webhook_endpoints = publish(&isolated_pool, &sut, &subscriber, &event).await;
+
+    // Assert
+    for webhook_endpoint in webhook_endpoints.iter() {
+        let actual =
+            find_webhook_deliveries_by_endpoint_id_in(&isolated_pool, webhook_endpoint.id()).await;
+        assert_eq!(actual.len(), 1);
+        assert_eq!(actual[0].event_type(), "subscriber.registered");
+        assert_eq!(actual[0].attempts(), 0);
+    }
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_does_not_queue_delivery_of_unsubscription(
+    #[future(awt)] isolated_pool: Pool<Postgres>,
+    sut: Sut,
+    mut subscriber: Subscriber,
+) {
+    // Arrange
+    subscriber.unsubscribe().unwrap();
+    let event = subscriber.take_events().pop().unwrap();
+
+    // Act
+    let webhook_endpoints = publish(&isolated_pool, &sut, &subscriber, &event).await;
+
+    // Assert
+    let actual =
+        find_webhook_deliveries_by_endpoint_id_in(&isolated_pool, webhook_endpoints[0].id()).await;
+    assert!(actual.is_empty());
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_does_not_queue_another_delivery_when_subscriber_registers_again(
+    #[future(awt)] isolated_pool: Pool<Postgres>,
+    sut: Sut,
+    mut subscriber: Subscriber,
+) {
+    // Arrange
+    let registered = subscriber.take_events().remove(0);
+    let webhook_endpoints = publish(&isolated_pool, &sut, &subscriber, &registered).await;
+    subscriber.register_again();
+    let reissued = subscriber.take_events().remove(0);
+
+    // Act
+    let unit_of_work = SqlxUnitOfWork::new(isolated_pool.clone());
+    let mut transaction = unit_of_work.begin().await.unwrap();
+    sut.publish(&mut transaction, &reissued).await.unwrap();
+    unit_of_work.commit(transaction).await.unwrap();
+
+    // Assert
+    for webhook_endpoint in webhook_endpoints.iter() {
+        let actual =
+            find_webhook_deliveries_by_endpoint_id_in(&isolated_pool, webhook_endpoint.id()).await;
+        assert_eq!(actual.len(), 1);
+        assert_eq!(actual[0].event_type(), "subscriber.registered");
+    }
+}
@@ -0,0 +1,9 @@
+use zero2prod::configuration::get_configuration;
+use zero2prod::configuration::Environment;
+use zero2prod::subscriber::infrastructure::email_renderer::MinijinjaEmailRenderer;
+
+#[rstest::fixture]
+pub fn email_renderer() -> MinijinjaEmailRenderer {
+    let configuration = get_configuration(Environment::Test).unwrap();
+    MinijinjaEmailRenderer::new(configuration.subscriber.email.template.sender_name)
+}
@@ -0,0 +1,44 @@
+use std::sync::Arc;
+
+use tokio::sync::RwLock;
+use zero2prod::subscriber::domain::error::Error;
+use zero2prod::subscriber::domain::infrastructure::EventPublisher;
+use zero2prod::subscriber::domain::model::SubscriberEvent;
+use zero2prod::subscriber::infrastructure::unit_of_work::SqlxTransaction;
+
+#[derive(Clone)]
+pub struct EventPublisherSpy {
+    events: Arc<RwLock<Vec<SubscriberEvent>>>,
+}
+
+#[allow(clippy::new_without_default)]
+impl EventPublisherSpy {
+    pub fn new() -> Self {
+        EventPublisherSpy {
+            events: Arc::new(RwLock::new(Vec::new())),
+        }
+    }
+
+    pub async fn events(&self) -> Vec<SubscriberEvent> {
+        self.events.read().await.clone()
+    }
+}
+
+#[async_trait::async_trait]
+impl EventPublisher for EventPublisherSpy {
+    type Transaction = SqlxTransaction;
+
+    async fn publish(
+        &self,
+        _: &mut Self::Transaction,
+        event: &SubscriberEvent,
+    ) -> Result<(), Error> {
+        self.events.write().await.push(event.clone());
+        Ok(())
+    }
+}
+
+#[rstest::fixture]
+pub fn event_publisher_spy() -> EventPublisherSpy {
+    EventPublisherSpy::new()
+}
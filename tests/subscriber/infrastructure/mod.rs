@@ -1,2 +1,5 @@
-pub mod email_client;
+pub mod email_renderer;
+pub mod event_publisher;
 pub mod repository;
+mod specs_for_subscriber_repository;
+mod specs_for_subscription_token_repository;
@@ -1,15 +1,42 @@
+use std::sync::atomic::AtomicU32;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use chrono::DateTime;
+use chrono::Utc;
+use secrecy::ExposeSecret;
+use sqlx::Connection;
+use sqlx::Executor;
+use sqlx::PgConnection;
 use sqlx::Pool;
 use sqlx::Postgres;
 use uuid::Uuid;
+use zero2prod::assembly::get_database_connection_string;
 use zero2prod::assembly::get_database_pool;
 use zero2prod::configuration::get_configuration;
 use zero2prod::configuration::Environment;
+use zero2prod::subscriber::domain::error::Error;
+use zero2prod::subscriber::domain::infrastructure::SubscriberRepository;
+use zero2prod::subscriber::domain::infrastructure::SubscriptionTokenRepository;
+use zero2prod::subscriber::domain::infrastructure::UnitOfWork;
+use zero2prod::subscriber::domain::infrastructure::WebhookDeliveryRepository;
+use zero2prod::subscriber::domain::model::OutboxMessage;
 use zero2prod::subscriber::domain::model::Subscriber;
+use zero2prod::subscriber::domain::model::SubscriberFilter;
 use zero2prod::subscriber::domain::model::SubscriptionToken;
+use zero2prod::subscriber::domain::model::WebhookDelivery;
+use zero2prod::subscriber::infrastructure::repository::ConcurrencyControl;
+use zero2prod::subscriber::infrastructure::repository::OutboxDataModel;
+use zero2prod::subscriber::infrastructure::repository::SqlxOutboxRepository;
 use zero2prod::subscriber::infrastructure::repository::SqlxSubscriberRepository;
 use zero2prod::subscriber::infrastructure::repository::SqlxSubscriptionTokenRepository;
+use zero2prod::subscriber::infrastructure::repository::SqlxWebhookDeliveryRepository;
+use zero2prod::subscriber::infrastructure::repository::SqlxWebhookEndpointRepository;
 use zero2prod::subscriber::infrastructure::repository::SubscriberDataModel;
 use zero2prod::subscriber::infrastructure::repository::SubscriptionTokenDataModel;
+use zero2prod::subscriber::infrastructure::unit_of_work::SqlxTransaction;
+use zero2prod::subscriber::infrastructure::unit_of_work::SqlxUnitOfWork;
 
 #[rstest::fixture]
 pub async fn pool() -> Pool<Postgres> {
@@ -18,44 +45,389 @@ pub async fn pool() -> Pool<Postgres> {
 }
 
 #[rstest::fixture]
-pub async fn subscriber_repository(
-    #[future(awt)] pool: Pool<Postgres>,
-) -> SqlxSubscriberRepository {
-    SqlxSubscriberRepository::new(pool)
+pub async fn unit_of_work(#[future(awt)] pool: Pool<Postgres>) -> SqlxUnitOfWork {
+    SqlxUnitOfWork::new(pool)
+}
+
+#[rstest::fixture]
+pub fn subscriber_repository() -> SqlxSubscriberRepository {
+    SqlxSubscriberRepository::default()
+}
+
+#[rstest::fixture]
+pub fn optimistic_subscriber_repository() -> SqlxSubscriberRepository {
+    SqlxSubscriberRepository::new(ConcurrencyControl::Optimistic)
+}
+
+pub async fn save_subscriber(subscriber: &Subscriber) {
+    save_subscriber_in(&pool().await, subscriber).await;
+}
+
+pub async fn save_subscriber_in(pool: &Pool<Postgres>, subscriber: &Subscriber) {
+    let unit_of_work = SqlxUnitOfWork::new(pool.clone());
+    let mut transaction = unit_of_work.begin().await.unwrap();
+    subscriber_repository()
+        .save(&mut transaction, subscriber)
+        .await
+        .unwrap();
+    unit_of_work.commit(transaction).await.unwrap();
 }
 
 pub async fn find_subscriber_by_email(email: &str) -> Subscriber {
     let pool = pool().await;
     let row = sqlx::query!(
-        "select id, name, email, subscribed_at, status from subscribers where email = $1",
+        "select id, name, email, display_email, subscribed_at, status, confirmed_at, locale from subscribers where lower(email) = lower($1)",
         email,
     )
     .fetch_one(&pool)
     .await
     .unwrap();
 
-    let data_model =
-        SubscriberDataModel::new(row.id, row.name, row.email, row.subscribed_at, row.status);
+    let data_model = SubscriberDataModel::new(
+        row.id,
+        row.name,
+        row.email,
+        row.display_email,
+        row.subscribed_at,
+        row.status,
+        row.confirmed_at,
+        row.locale,
+    );
     data_model.into()
 }
 
+pub async fn find_subscriber_version_by_id(subscriber_id: &Uuid) -> i32 {
+    let pool = pool().await;
+    sqlx::query_scalar!(
+        "select version from subscribers where id = $1",
+        subscriber_id,
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap()
+}
+
 #[rstest::fixture]
-pub async fn subscription_token_repository(
-    #[future(awt)] pool: Pool<Postgres>,
-) -> SqlxSubscriptionTokenRepository {
-    SqlxSubscriptionTokenRepository::new(pool)
+pub fn subscription_token_repository() -> SqlxSubscriptionTokenRepository {
+    let configuration = get_configuration(Environment::Test).unwrap();
+    SqlxSubscriptionTokenRepository::new(configuration.subscriber.subscription_token.key)
+}
+
+pub async fn save_subscription_token(subscription_token: &SubscriptionToken) {
+    let unit_of_work = unit_of_work(pool()).await;
+    let mut transaction = unit_of_work.begin().await.unwrap();
+    subscription_token_repository()
+        .save(&mut transaction, subscription_token)
+        .await
+        .unwrap();
+    unit_of_work.commit(transaction).await.unwrap();
 }
 
+pub async fn find_subscription_token_by_token(token: &str) -> Option<SubscriptionToken> {
+    let unit_of_work = unit_of_work(pool()).await;
+    let mut transaction = unit_of_work.begin().await.unwrap();
+    subscription_token_repository()
+        .find_by_token(&mut transaction, token)
+        .await
+        .unwrap()
+}
+
+// Returned token is the stored hash, since raw tokens are never persisted
 pub async fn find_subscription_token_by_subscriber_id(subscriber_id: &Uuid) -> SubscriptionToken {
     let pool = pool().await;
     let row = sqlx::query!(
-        "select subscriber_id, token from subscription_tokens where subscriber_id = $1",
+        "select subscriber_id, token_hash, created_at, expires_at, used_at from subscription_tokens where subscriber_id = $1",
+        subscriber_id,
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+
+    let data_model = SubscriptionTokenDataModel::new(
+        row.token_hash,
+        row.subscriber_id,
+        row.created_at,
+        row.expires_at,
+        row.used_at,
+    );
+    data_model.into()
+}
+
+// Lets specs subscribe again without waiting for the reissue interval to elapse
+pub async fn backdate_subscription_tokens_by_subscriber_id(subscriber_id: &Uuid) {
+    let pool = pool().await;
+    sqlx::query!(
+        "update subscription_tokens set created_at = created_at - interval '1 hour' where subscriber_id = $1",
+        subscriber_id,
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+}
+
+pub async fn count_subscription_tokens_by_subscriber_id(subscriber_id: &Uuid) -> i64 {
+    let pool = pool().await;
+    sqlx::query_scalar!(
+        "select count(*) from subscription_tokens where subscriber_id = $1",
         subscriber_id,
     )
     .fetch_one(&pool)
     .await
+    .unwrap()
+    .unwrap()
+}
+
+#[rstest::fixture]
+pub fn outbox_repository() -> SqlxOutboxRepository {
+    SqlxOutboxRepository::new()
+}
+
+#[rstest::fixture]
+pub fn webhook_endpoint_repository() -> SqlxWebhookEndpointRepository {
+    SqlxWebhookEndpointRepository::new()
+}
+
+#[rstest::fixture]
+pub fn webhook_delivery_repository() -> SqlxWebhookDeliveryRepository {
+    SqlxWebhookDeliveryRepository::new()
+}
+
+pub async fn find_webhook_deliveries_by_endpoint_id_in(
+    pool: &Pool<Postgres>,
+    endpoint_id: &Uuid,
+) -> Vec<WebhookDelivery> {
+    let unit_of_work = SqlxUnitOfWork::new(pool.clone());
+    let mut transaction = unit_of_work.begin().await.unwrap();
+    let webhook_deliveries = webhook_delivery_repository()
+        .find_by_endpoint_id(&mut transaction, endpoint_id, 100)
+        .await
+        .unwrap();
+    unit_of_work.commit(transaction).await.unwrap();
+    webhook_deliveries
+}
+
+pub async fn find_outbox_message_by_subscriber_id(subscriber_id: &Uuid) -> OutboxMessage {
+    find_outbox_message_by_subscriber_id_in(&pool().await, subscriber_id).await
+}
+
+pub async fn find_outbox_message_by_subscriber_id_in(
+    pool: &Pool<Postgres>,
+    subscriber_id: &Uuid,
+) -> OutboxMessage {
+    let row = sqlx::query!(
+        "select id, subscriber_id, subject, html_body, text_body, unsubscribe_url, created_at, attempts, next_attempt_at, delivered_at, dead_lettered_at, last_error from outbox where subscriber_id = $1",
+        subscriber_id,
+    )
+    .fetch_one(pool)
+    .await
     .unwrap();
 
-    let data_model = SubscriptionTokenDataModel::new(row.token, row.subscriber_id);
+    let data_model = OutboxDataModel::new(
+        row.id,
+        row.subscriber_id,
+        row.subject,
+        row.html_body,
+        row.text_body,
+        row.unsubscribe_url,
+        row.created_at,
+        row.attempts,
+        row.next_attempt_at,
+        row.delivered_at,
+        row.dead_lettered_at,
+        row.last_error,
+    );
     data_model.into()
 }
+
+pub async fn count_outbox_messages_by_subscriber_id(subscriber_id: &Uuid) -> i64 {
+    let pool = pool().await;
+    sqlx::query_scalar!(
+        "select count(*) from outbox where subscriber_id = $1",
+        subscriber_id,
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap()
+    .unwrap()
+}
+
+// Creates a database of its own for specs that must not see rows written by other specs
+#[rstest::fixture]
+pub async fn isolated_pool() -> Pool<Postgres> {
+    isolated_pool_migrated_before(i64::MAX).await
+}
+
+// Creates a database of its own left at the schema preceding the given migration, as databases
+// which have still to run a one-off command before that migration are
+pub async fn isolated_pool_migrated_before(version: i64) -> Pool<Postgres> {
+    let mut configuration = get_configuration(Environment::Test).unwrap();
+    configuration.subscriber.database.connection.database = Uuid::now_v7().into();
+
+    let connection_string = get_database_connection_string(&configuration.subscriber.database);
+    let (connection_string_without_database, _) =
+        connection_string.expose_secret().rsplit_once("/").unwrap();
+    let mut connection = PgConnection::connect(connection_string_without_database)
+        .await
+        .unwrap();
+    connection
+        .execute(
+            format!(
+                r#"CREATE DATABASE "{}";"#,
+                configuration.subscriber.database.connection.database
+            )
+            .as_str(),
+        )
+        .await
+        .unwrap();
+
+    let pool = get_database_pool(&configuration.subscriber.database).await;
+    let mut migrator = sqlx::migrate!("./migrations");
+    migrator.migrations = migrator
+        .migrations
+        .iter()
+        .filter(|migration| migration.version < version)
+        .cloned()
+        .collect::<Vec<_>>()
+        .into();
+    migrator.run(&pool).await.unwrap();
+    pool
+}
+
+#[derive(Clone)]
+pub struct FaultySubscriptionTokenRepositoryStub;
+
+#[async_trait::async_trait]
+impl SubscriptionTokenRepository for FaultySubscriptionTokenRepositoryStub {
+    type Transaction = SqlxTransaction;
+
+    async fn save(&self, _: &mut Self::Transaction, _: &SubscriptionToken) -> Result<(), Error> {
+        Err(Error::RepositoryOperationFailed(anyhow!("")))
+    }
+
+    async fn find_by_token(
+        &self,
+        _: &mut Self::Transaction,
+        _: &str,
+    ) -> Result<Option<SubscriptionToken>, Error> {
+        Err(Error::RepositoryOperationFailed(anyhow!("")))
+    }
+
+    async fn find_latest_issued_at_by_subscriber_id(
+        &self,
+        _: &mut Self::Transaction,
+        _: &Uuid,
+    ) -> Result<Option<DateTime<Utc>>, Error> {
+        Err(Error::RepositoryOperationFailed(anyhow!("")))
+    }
+
+    async fn modify_by_token<F>(
+        &self,
+        _: &mut Self::Transaction,
+        _: &str,
+        _: F,
+    ) -> Result<(), Error>
+    where
+        F: FnOnce(SubscriptionToken) -> SubscriptionToken + Send + Sync,
+    {
+        Err(Error::RepositoryOperationFailed(anyhow!("")))
+    }
+
+    async fn remove_unused_by_subscriber_id(
+        &self,
+        _: &mut Self::Transaction,
+        _: &Uuid,
+    ) -> Result<(), Error> {
+        Err(Error::RepositoryOperationFailed(anyhow!("")))
+    }
+}
+
+#[rstest::fixture]
+pub fn faulty_subscription_token_repository_stub() -> FaultySubscriptionTokenRepositoryStub {
+    FaultySubscriptionTokenRepositoryStub
+}
+
+// Loses the race against a concurrent modification for the given number of modifications
+// before delegating to the optimistic repository
+#[derive(Clone)]
+pub struct ConflictingSubscriberRepositoryStub {
+    repository: SqlxSubscriberRepository,
+    conflicts: Arc<AtomicU32>,
+    attempts: Arc<AtomicU32>,
+}
+
+impl ConflictingSubscriberRepositoryStub {
+    pub fn new(conflicts: u32) -> Self {
+        Self {
+            repository: SqlxSubscriberRepository::new(ConcurrencyControl::Optimistic),
+            conflicts: Arc::new(AtomicU32::new(conflicts)),
+            attempts: Arc::new(AtomicU32::new(0)),
+        }
+    }
+
+    pub fn attempts(&self) -> u32 {
+        self.attempts.load(Ordering::SeqCst)
+    }
+}
+
+#[async_trait::async_trait]
+impl SubscriberRepository for ConflictingSubscriberRepositoryStub {
+    type Transaction = SqlxTransaction;
+
+    async fn save(
+        &self,
+        transaction: &mut Self::Transaction,
+        subscriber: &Subscriber,
+    ) -> Result<(), Error> {
+        self.repository.save(transaction, subscriber).await
+    }
+
+    async fn find_by_id(
+        &self,
+        transaction: &mut Self::Transaction,
+        id: &Uuid,
+    ) -> Result<Option<Subscriber>, Error> {
+        self.repository.find_by_id(transaction, id).await
+    }
+
+    async fn find_by_email(
+        &self,
+        transaction: &mut Self::Transaction,
+        email: &str,
+    ) -> Result<Option<Subscriber>, Error> {
+        self.repository.find_by_email(transaction, email).await
+    }
+
+    async fn find_page(
+        &self,
+        transaction: &mut Self::Transaction,
+        filter: &SubscriberFilter,
+        cursor: Option<&Uuid>,
+        limit: i64,
+    ) -> Result<Vec<Subscriber>, Error> {
+        self.repository
+            .find_page(transaction, filter, cursor, limit)
+            .await
+    }
+
+    async fn modify_by_id<F>(
+        &self,
+        transaction: &mut Self::Transaction,
+        id: &Uuid,
+        modifier: F,
+    ) -> Result<(), Error>
+    where
+        F: FnOnce(Subscriber) -> Subscriber + Send + Sync,
+    {
+        self.attempts.fetch_add(1, Ordering::SeqCst);
+        let conflicted = self
+            .conflicts
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+            .is_ok();
+        if conflicted {
+            return Err(Error::ConcurrencyConflict(*id));
+        }
+        self.repository
+            .modify_by_id(transaction, id, modifier)
+            .await
+    }
+}
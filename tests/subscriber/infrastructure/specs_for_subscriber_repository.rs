@@ -0,0 +1,248 @@
+use sqlx::Pool;
+use sqlx::Postgres;
+use tokio::runtime::Handle;
+use uuid::Uuid;
+use zero2prod::subscriber::domain::error::Error;
+use zero2prod::subscriber::domain::infrastructure::SubscriberRepository;
+use zero2prod::subscriber::domain::infrastructure::UnitOfWork;
+use zero2prod::subscriber::domain::model::Email;
+use zero2prod::subscriber::domain::model::Status;
+use zero2prod::subscriber::domain::model::Subscriber;
+use zero2prod::subscriber::infrastructure::repository::SqlxSubscriberRepository;
+use zero2prod::subscriber::infrastructure::unit_of_work::SqlxUnitOfWork;
+
+use crate::subscriber::domain::model::subscriber;
+use crate::subscriber::infrastructure::repository::find_subscriber_by_email;
+use crate::subscriber::infrastructure::repository::find_subscriber_version_by_id;
+use crate::subscriber::infrastructure::repository::isolated_pool;
+use crate::subscriber::infrastructure::repository::optimistic_subscriber_repository;
+use crate::subscriber::infrastructure::repository::pool;
+use crate::subscriber::infrastructure::repository::save_subscriber;
+use crate::subscriber::infrastructure::repository::subscriber_repository;
+use crate::subscriber::infrastructure::repository::unit_of_work;
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_increments_version_when_modifying_subscriber_with_lock(
+    #[future(awt)] unit_of_work: SqlxUnitOfWork,
+    #[from(subscriber_repository)] sut: SqlxSubscriberRepository,
+    subscriber: Subscriber,
+) {
+    // Arrange
+    save_subscriber(&subscriber).await;
+    let mut transaction = unit_of_work.begin().await.unwrap();
+
+    // Act
+    sut.modify_by_id(&mut transaction, subscriber.id(), |mut subscriber| {
+        subscriber.confirm().unwrap();
+        subscriber
+    })
+    .await
+    .unwrap();
+    unit_of_work.commit(transaction).await.unwrap();
+
+    // Assert
+    let actual = find_subscriber_version_by_id(subscriber.id()).await;
+    assert_eq!(actual, 1);
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_modifies_subscriber_if_nobody_else_has_modified_it_since_it_was_read(
+    #[future(awt)] unit_of_work: SqlxUnitOfWork,
+    #[from(optimistic_subscriber_repository)] sut: SqlxSubscriberRepository,
+    subscriber: Subscriber,
+) {
+    // Arrange
+    save_subscriber(&subscriber).await;
+    let mut transaction = unit_of_work.begin().await.unwrap();
+
+    // Act
+    sut.modify_by_id(&mut transaction, subscriber.id(), |mut subscriber| {
+        subscriber.confirm().unwrap();
+        subscriber
+    })
+    .await
+    .unwrap();
+    unit_of_work.commit(transaction).await.unwrap();
+
+    // Assert
+    let actual = find_subscriber_by_email(subscriber.email()).await;
+    assert!(matches!(actual.status(), Status::Confirmed));
+    assert_eq!(find_subscriber_version_by_id(subscriber.id()).await, 1);
+}
+
+#[rstest::rstest]
+#[tokio::test(flavor = "multi_thread")]
+async fn sut_raises_concurrency_conflict_error_if_subscriber_has_been_modified_since_it_was_read(
+    #[future(awt)] pool: Pool<Postgres>,
+    #[future(awt)] unit_of_work: SqlxUnitOfWork,
+    #[from(optimistic_subscriber_repository)] sut: SqlxSubscriberRepository,
+    subscriber: Subscriber,
+) {
+    // Arrange
+    save_subscriber(&subscriber).await;
+    let mut transaction = unit_of_work.begin().await.unwrap();
+
+    // Act
+    let actual = sut
+        .modify_by_id(&mut transaction, subscriber.id(), |mut subscriber| {
+            // Another writer commits between the read and the write of this modification
+            tokio::task::block_in_place(|| {
+                Handle::current().block_on(async {
+                    sqlx::query!(
+                        "update subscribers set version = version + 1 where id = $1",
+                        subscriber.id(),
+                    )
+                    .execute(&pool)
+                    .await
+                    .unwrap();
+                })
+            });
+            subscriber.confirm().unwrap();
+            subscriber
+        })
+        .await
+        .unwrap_err();
+
+    // Assert
+    assert!(matches!(actual, Error::ConcurrencyConflict(id) if id == *subscriber.id()));
+    drop(transaction);
+    let subscriber = find_subscriber_by_email(subscriber.email()).await;
+    assert!(matches!(subscriber.status(), Status::Pending));
+}
+
+// Inserts a subscriber the way databases normalized before domains were encoded keep them, i.e.
+// with the domain of the address as given lowercased
+async fn insert_legacy_subscriber(
+    pool: &Pool<Postgres>,
+    display_email: &str,
+    status: &str,
+    subscribed_days_ago: i32,
+) -> Uuid {
+    let id = Uuid::new_v4();
+    sqlx::query!(
+        "insert into subscribers (id, name, email, display_email, subscribed_at, status) values ($1, 'alice', regexp_replace($2, '@[^@]*$', '') || lower(substring($2 from '@[^@]*$')), $2, now() - make_interval(days => $3), $4)",
+        id,
+        display_email,
+        subscribed_days_ago,
+        status,
+    )
+    .execute(pool)
+    .await
+    .unwrap();
+    id
+}
+
+async fn normalize_legacy_emails(pool: &Pool<Postgres>, sut: &SqlxSubscriberRepository) -> u64 {
+    let unit_of_work = SqlxUnitOfWork::new(pool.clone());
+    let mut transaction = unit_of_work.begin().await.unwrap();
+    let count = sut.normalize_legacy_emails(&mut transaction).await.unwrap();
+    unit_of_work.commit(transaction).await.unwrap();
+    count
+}
+
+async fn find_subscriber_by_email_in(pool: &Pool<Postgres>, email: &str) -> Option<Subscriber> {
+    let unit_of_work = SqlxUnitOfWork::new(pool.clone());
+    let mut transaction = unit_of_work.begin().await.unwrap();
+    subscriber_repository()
+        .find_by_email(&mut transaction, email)
+        .await
+        .unwrap()
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_normalizes_legacy_emails_with_internationalized_domains_as_email_parse_does(
+    #[future(awt)] isolated_pool: Pool<Postgres>,
+    #[from(subscriber_repository)] sut: SqlxSubscriberRepository,
+) {
+    // Arrange
+    let display_emails = [
+        "alice@BÜCHER.example",
+        "bob@ＢＵＣＨＥＲ.example",
+        "carol@ⓑücher.example",
+        "dave@테스트.example",
+    ];
+    let mut ids = Vec::new();
+    for display_email in display_emails {
+        ids.push(insert_legacy_subscriber(&isolated_pool, display_email, "Pending", 0).await);
+    }
+
+    // Act
+    let actual = normalize_legacy_emails(&isolated_pool, &sut).await;
+
+    // Assert
+    assert_eq!(actual, display_emails.len() as u64);
+    for (id, display_email) in ids.iter().zip(display_emails) {
+        let email = Email::parse(display_email).unwrap();
+        let subscriber = find_subscriber_by_email_in(&isolated_pool, email.as_ref())
+            .await
+            .unwrap();
+        assert_eq!(subscriber.id(), id);
+        assert_eq!(subscriber.display_email(), display_email);
+    }
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_merges_subscribers_sharing_legacy_email_keeping_confirmed_one(
+    #[future(awt)] isolated_pool: Pool<Postgres>,
+    #[from(subscriber_repository)] sut: SqlxSubscriberRepository,
+) {
+    // Arrange
+    let pending_id =
+        insert_legacy_subscriber(&isolated_pool, "alice@xn--bcher-kva.example", "Pending", 0).await;
+    sqlx::query!(
+        "insert into subscription_tokens (token_hash, subscriber_id, created_at, expires_at) values ('hash', $1, now(), now() + interval '1 day')",
+        pending_id,
+    )
+    .execute(&isolated_pool)
+    .await
+    .unwrap();
+    let confirmed_id =
+        insert_legacy_subscriber(&isolated_pool, "alice@Bücher.example", "Confirmed", 1).await;
+
+    // Act
+    normalize_legacy_emails(&isolated_pool, &sut).await;
+
+    // Assert
+    let actual = find_subscriber_by_email_in(&isolated_pool, "alice@xn--bcher-kva.example")
+        .await
+        .unwrap();
+    assert_eq!(actual.id(), &confirmed_id);
+    assert!(matches!(actual.status(), Status::Confirmed));
+    let count = sqlx::query_scalar!("select count(*) from subscribers")
+        .fetch_one(&isolated_pool)
+        .await
+        .unwrap();
+    assert_eq!(count, Some(1));
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_keeps_merged_subscriber_unsubscribed_when_any_legacy_email_opted_out(
+    #[future(awt)] isolated_pool: Pool<Postgres>,
+    #[from(subscriber_repository)] sut: SqlxSubscriberRepository,
+) {
+    // Arrange
+    let confirmed_id =
+        insert_legacy_subscriber(&isolated_pool, "Alice@bücher.example", "Confirmed", 1).await;
+    insert_legacy_subscriber(
+        &isolated_pool,
+        "alice@xn--bcher-kva.example",
+        "Unsubscribed",
+        0,
+    )
+    .await;
+
+    // Act
+    normalize_legacy_emails(&isolated_pool, &sut).await;
+
+    // Assert
+    let actual = find_subscriber_by_email_in(&isolated_pool, "alice@xn--bcher-kva.example")
+        .await
+        .unwrap();
+    assert_eq!(actual.id(), &confirmed_id);
+    assert!(matches!(actual.status(), Status::Unsubscribed));
+}
@@ -0,0 +1,195 @@
+use chrono::TimeDelta;
+use chrono::Utc;
+use hmac::Hmac;
+use hmac::Mac;
+use secrecy::ExposeSecret;
+use sha2::Sha256;
+use sqlx::Pool;
+use sqlx::Postgres;
+use zero2prod::configuration::get_configuration;
+use zero2prod::configuration::Environment;
+use zero2prod::subscriber::domain::infrastructure::SubscriberRepository;
+use zero2prod::subscriber::domain::infrastructure::SubscriptionTokenRepository;
+use zero2prod::subscriber::domain::infrastructure::UnitOfWork;
+use zero2prod::subscriber::domain::model::Subscriber;
+use zero2prod::subscriber::infrastructure::repository::SqlxSubscriptionTokenRepository;
+use zero2prod::subscriber::infrastructure::unit_of_work::SqlxUnitOfWork;
+
+use crate::subscriber::domain::model::subscriber;
+use crate::subscriber::domain::model::subscription_token;
+use crate::subscriber::domain::model::token;
+use crate::subscriber::infrastructure::repository::isolated_pool_migrated_before;
+use crate::subscriber::infrastructure::repository::pool;
+use crate::subscriber::infrastructure::repository::save_subscriber;
+use crate::subscriber::infrastructure::repository::save_subscription_token;
+use crate::subscriber::infrastructure::repository::subscriber_repository;
+use crate::subscriber::infrastructure::repository::subscription_token_repository;
+use crate::subscriber::infrastructure::repository::unit_of_work;
+
+// Only the keyed hash of a token is stored, so that read access to the table is not enough to
+// confirm subscriptions
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_stores_hex_encoded_hmac_sha256_of_token(
+    #[future(awt)] pool: Pool<Postgres>,
+    subscriber: Subscriber,
+    token: String,
+) {
+    // Arrange
+    let key = get_configuration(Environment::Test)
+        .unwrap()
+        .subscriber
+        .subscription_token
+        .key;
+    let mut mac = Hmac::<Sha256>::new_from_slice(key.expose_secret().as_bytes()).unwrap();
+    mac.update(token.as_bytes());
+    let expected = hex::encode(mac.finalize().into_bytes());
+
+    save_subscriber(&subscriber).await;
+
+    // Act
+    save_subscription_token(&subscription_token(
+        token,
+        *subscriber.id(),
+        Utc::now() + TimeDelta::days(1),
+    ))
+    .await;
+
+    // Assert
+    let actual = sqlx::query_scalar!(
+        "select token_hash from subscription_tokens where subscriber_id = $1",
+        subscriber.id(),
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(actual, expected);
+}
+
+const FINALIZE_TOKEN_HASHES_MIGRATION: i64 = 20250220090000;
+
+// Saves a subscriber and a raw token the way releases storing raw tokens did
+async fn save_legacy_subscription_token(
+    pool: &Pool<Postgres>,
+    subscriber: &Subscriber,
+    token: &str,
+) {
+    let unit_of_work = SqlxUnitOfWork::new(pool.clone());
+    let mut transaction = unit_of_work.begin().await.unwrap();
+    subscriber_repository()
+        .save(&mut transaction, subscriber)
+        .await
+        .unwrap();
+    unit_of_work.commit(transaction).await.unwrap();
+    sqlx::query(
+        "insert into subscription_tokens (token, subscriber_id, created_at, expires_at) values ($1, $2, now(), now() + interval '1 day')",
+    )
+    .bind(token)
+    .bind(subscriber.id())
+    .execute(pool)
+    .await
+    .unwrap();
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_hashes_legacy_raw_tokens_so_that_they_are_still_found_once_migrations_finish(
+    #[from(subscription_token_repository)] sut: SqlxSubscriptionTokenRepository,
+    subscriber: Subscriber,
+    token: String,
+) {
+    // Arrange
+    let pool = isolated_pool_migrated_before(FINALIZE_TOKEN_HASHES_MIGRATION).await;
+    save_legacy_subscription_token(&pool, &subscriber, &token).await;
+    let unit_of_work = SqlxUnitOfWork::new(pool.clone());
+
+    // Act
+    let mut transaction = unit_of_work.begin().await.unwrap();
+    let actual = sut.hash_legacy_tokens(&mut transaction).await.unwrap();
+    unit_of_work.commit(transaction).await.unwrap();
+
+    // Assert
+    assert_eq!(actual, 1);
+    sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+    let mut transaction = unit_of_work.begin().await.unwrap();
+    let subscription_token = sut
+        .find_by_token(&mut transaction, &token)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(subscription_token.subscriber_id(), subscriber.id());
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_leaves_raw_tokens_to_be_hashed_before_they_are_dropped(
+    subscriber: Subscriber,
+    token: String,
+) {
+    // Arrange
+    let pool = isolated_pool_migrated_before(FINALIZE_TOKEN_HASHES_MIGRATION).await;
+    save_legacy_subscription_token(&pool, &subscriber, &token).await;
+
+    // Act
+    let actual = sqlx::migrate!("./migrations").run(&pool).await;
+
+    // Assert
+    assert!(actual.is_err());
+    let count: i64 =
+        sqlx::query_scalar("select count(*) from subscription_tokens where token = $1")
+            .bind(&token)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(count, 1);
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_finds_when_latest_token_of_subscriber_has_been_issued(
+    #[future(awt)] unit_of_work: SqlxUnitOfWork,
+    #[from(subscription_token_repository)] sut: SqlxSubscriptionTokenRepository,
+    subscriber: Subscriber,
+) {
+    // Arrange
+    save_subscriber(&subscriber).await;
+    let expires_at = Utc::now() + TimeDelta::days(1);
+    let earlier = subscription_token(token(), *subscriber.id(), expires_at);
+    let latest = subscription_token(token(), *subscriber.id(), expires_at);
+    save_subscription_token(&earlier).await;
+    save_subscription_token(&latest).await;
+
+    // Act
+    let mut transaction = unit_of_work.begin().await.unwrap();
+    let actual = sut
+        .find_latest_issued_at_by_subscriber_id(&mut transaction, subscriber.id())
+        .await
+        .unwrap();
+
+    // Assert
+    assert_eq!(
+        actual.unwrap().timestamp_micros(),
+        latest.created_at().timestamp_micros()
+    );
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn sut_finds_nothing_if_no_token_has_been_issued_to_subscriber(
+    #[future(awt)] unit_of_work: SqlxUnitOfWork,
+    #[from(subscription_token_repository)] sut: SqlxSubscriptionTokenRepository,
+    subscriber: Subscriber,
+) {
+    // Arrange
+    save_subscriber(&subscriber).await;
+
+    // Act
+    let mut transaction = unit_of_work.begin().await.unwrap();
+    let actual = sut
+        .find_latest_issued_at_by_subscriber_id(&mut transaction, subscriber.id())
+        .await
+        .unwrap();
+
+    // Assert
+    assert!(actual.is_none());
+}